@@ -0,0 +1,219 @@
+//! Serialization helpers for bytes written as text.
+//!
+//! The helpers work with `Vec<u8>` and `[u8; N]` fields. Arrays are
+//! deserialized only if the length matches. Formats that are not
+//! human-readable, such as bincode, store the bytes as they are.
+
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    Deserializer,
+};
+use std::fmt;
+
+/// Serialize or deserialize bytes as a hex string, like `"00ff1a"`.
+///
+/// Upper-case digits are accepted. Bytes separated by `:` or `-`,
+/// like MAC addresses, are also accepted, while bytes are always
+/// written without separators.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::hex_bytes;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyDevice {
+///     #[serde(with = "hex_bytes")]
+///     serial: Vec<u8>,
+///     #[serde(with = "hex_bytes")]
+///     mac: [u8; 6],
+/// }
+///
+/// let json = r#"{ "serial": "00FF1a", "mac": "f8:dc:7a:00:12:9e" }"#;
+/// let device: MyDevice = serde_json::from_str(json).unwrap();
+/// assert_eq!(device.serial, [0x00, 0xff, 0x1a]);
+/// assert_eq!(device.mac, [0xf8, 0xdc, 0x7a, 0x00, 0x12, 0x9e]);
+///
+/// let json = serde_json::to_string(&device).unwrap();
+/// assert_eq!(json, r#"{"serial":"00ff1a","mac":"f8dc7a00129e"}"#);
+///
+/// // The MAC address is too short.
+/// let json = r#"{ "serial": "00", "mac": "f8:dc:7a:00:12" }"#;
+/// assert!(serde_json::from_str::<MyDevice>(json).is_err());
+/// ```
+pub mod hex_bytes {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+    use std::fmt::Write as _;
+
+    pub fn serialize<S, T>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: AsRef<[u8]> + ?Sized,
+    {
+        let bytes = bytes.as_ref();
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(bytes);
+        }
+        serializer.serialize_str(&encode(bytes))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        let bytes = if deserializer.is_human_readable() {
+            let text = String::deserialize(deserializer)?;
+            decode(&text).map_err(D::Error::custom)?
+        } else {
+            super::deserialize_raw(deserializer)?
+        };
+        super::convert(bytes)
+    }
+
+    /// Encodes the bytes to lower-case hex digits.
+    pub fn encode(bytes: &[u8]) -> String {
+        let mut text = String::with_capacity(bytes.len() * 2);
+        for byte in bytes {
+            write!(text, "{byte:02x}").unwrap();
+        }
+        text
+    }
+
+    /// Decodes hex digits, optionally with bytes separated by `:` or
+    /// `-`.
+    pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+        let error = || format!("'{text}' is not a valid hex string");
+        let parse = |digits: &str| -> Result<u8, String> {
+            if digits.len() != 2 || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                return Err(error());
+            }
+            u8::from_str_radix(digits, 16).map_err(|_| error())
+        };
+
+        if let Some(sep) = text.chars().find(|&c| c == ':' || c == '-') {
+            return text.split(sep).map(parse).collect();
+        }
+        if !text.len().is_multiple_of(2) || !text.is_ascii() {
+            return Err(error());
+        }
+        (0..text.len())
+            .step_by(2)
+            .map(|index| parse(&text[index..index + 2]))
+            .collect()
+    }
+}
+
+/// Serialize or deserialize bytes as a standard base64 string with
+/// padding, like `"AP8a"`.
+///
+/// It requires the `with-base64` feature.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::base64_bytes;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyDevice {
+///     #[serde(with = "base64_bytes")]
+///     firmware_key: [u8; 3],
+///     #[serde(with = "base64_bytes")]
+///     blob: Vec<u8>,
+/// }
+///
+/// let json = r#"{ "firmware_key": "AP8a", "blob": "aGVsbG8=" }"#;
+/// let device: MyDevice = serde_json::from_str(json).unwrap();
+/// assert_eq!(device.firmware_key, [0x00, 0xff, 0x1a]);
+/// assert_eq!(device.blob, b"hello");
+///
+/// let json = serde_json::to_string(&device).unwrap();
+/// assert_eq!(json, r#"{"firmware_key":"AP8a","blob":"aGVsbG8="}"#);
+///
+/// let json = r#"{ "firmware_key": "AP8", "blob": "" }"#;
+/// assert!(serde_json::from_str::<MyDevice>(json).is_err());
+/// ```
+#[cfg(feature = "with-base64")]
+pub mod base64_bytes {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S, T>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: AsRef<[u8]> + ?Sized,
+    {
+        let bytes = bytes.as_ref();
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(bytes);
+        }
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        let bytes = if deserializer.is_human_readable() {
+            let text = String::deserialize(deserializer)?;
+            BASE64.decode(&text).map_err(|err| {
+                D::Error::custom(format!("'{text}' is not a valid base64 string: {err}"))
+            })?
+        } else {
+            super::deserialize_raw(deserializer)?
+        };
+        super::convert(bytes)
+    }
+}
+
+/// Converts the bytes to the target type, which fails if the length
+/// of an array does not match.
+fn convert<T, E>(bytes: Vec<u8>) -> Result<T, E>
+where
+    T: TryFrom<Vec<u8>>,
+    E: Error,
+{
+    let len = bytes.len();
+    T::try_from(bytes).map_err(|_| E::custom(format!("unexpected length of {len} bytes")))
+}
+
+/// Deserializes bytes, or a sequence of bytes, from formats that are
+/// not human-readable.
+fn deserialize_raw<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("bytes")
+        }
+
+        fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Vec<u8>, E>
+        where
+            E: Error,
+        {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_byte_buf<E>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E>
+        where
+            E: Error,
+        {
+            Ok(bytes)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<u8>, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+
+    deserializer.deserialize_byte_buf(BytesVisitor)
+}
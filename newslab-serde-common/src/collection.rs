@@ -0,0 +1,204 @@
+//! Serialization helpers for validated sequences.
+//!
+//! Invalid sequences are rejected on both serialization and
+//! deserialization, except for [sorted_vec::sort], which sorts them.
+
+/// Serialize or deserialize a non-empty sequence into a `Vec`.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::non_empty_vec;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyRig {
+///     #[serde(with = "non_empty_vec")]
+///     sensors: Vec<String>,
+/// }
+///
+/// let rig: MyRig = serde_json::from_str(r#"{ "sensors": ["lidar_top"] }"#).unwrap();
+/// assert_eq!(rig.sensors, ["lidar_top"]);
+///
+/// assert!(serde_json::from_str::<MyRig>(r#"{ "sensors": [] }"#).is_err());
+/// ```
+pub mod non_empty_vec {
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    pub fn serialize<S, T>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        if values.is_empty() {
+            return Err(S::Error::custom("sequence must not be empty"));
+        }
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        if values.is_empty() {
+            return Err(D::Error::custom("sequence must not be empty"));
+        }
+        Ok(values)
+    }
+}
+
+/// Serialize or deserialize a sequence without duplicates into a
+/// `Vec`. The order of the elements is kept.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::unique_vec;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyRig {
+///     #[serde(with = "unique_vec")]
+///     sensors: Vec<String>,
+/// }
+///
+/// let json = r#"{ "sensors": ["lidar_top", "camera_front"] }"#;
+/// let rig: MyRig = serde_json::from_str(json).unwrap();
+/// assert_eq!(rig.sensors, ["lidar_top", "camera_front"]);
+///
+/// let json = r#"{ "sensors": ["lidar_top", "camera_front", "lidar_top"] }"#;
+/// assert!(serde_json::from_str::<MyRig>(json).is_err());
+/// ```
+pub mod unique_vec {
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{collections::HashSet, hash::Hash};
+
+    /// Finds the index of the first element that duplicates an
+    /// earlier one.
+    fn find_duplicate<T>(values: &[T]) -> Option<usize>
+    where
+        T: Eq + Hash,
+    {
+        let mut seen = HashSet::with_capacity(values.len());
+        values.iter().position(|value| !seen.insert(value))
+    }
+
+    fn error_message(index: usize) -> String {
+        format!("sequence must not contain duplicates, but the element at {index} is repeated")
+    }
+
+    pub fn serialize<S, T>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize + Eq + Hash,
+    {
+        if let Some(index) = find_duplicate(values) {
+            return Err(S::Error::custom(error_message(index)));
+        }
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + Eq + Hash,
+    {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        if let Some(index) = find_duplicate(&values) {
+            return Err(D::Error::custom(error_message(index)));
+        }
+        Ok(values)
+    }
+}
+
+/// Serialize or deserialize a sequence in ascending order into a
+/// `Vec`. Equal elements are allowed.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::sorted_vec;
+/// #[derive(Serialize, Deserialize)]
+/// struct MySchedule {
+///     #[serde(with = "sorted_vec")]
+///     checkpoints: Vec<u32>,
+///     #[serde(with = "sorted_vec::sort")]
+///     sensors: Vec<String>,
+/// }
+///
+/// let json = r#"{ "checkpoints": [10, 20, 20, 30], "sensors": ["radar", "camera"] }"#;
+/// let schedule: MySchedule = serde_json::from_str(json).unwrap();
+/// assert_eq!(schedule.sensors, ["camera", "radar"]);
+///
+/// let json = r#"{ "checkpoints": [10, 30, 20], "sensors": [] }"#;
+/// assert!(serde_json::from_str::<MySchedule>(json).is_err());
+/// ```
+pub mod sorted_vec {
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    /// Finds the index of the first element that is less than the
+    /// previous one.
+    fn find_unsorted<T>(values: &[T]) -> Option<usize>
+    where
+        T: Ord,
+    {
+        values
+            .windows(2)
+            .position(|pair| pair[0] > pair[1])
+            .map(|index| index + 1)
+    }
+
+    fn error_message(index: usize) -> String {
+        format!("sequence must be in ascending order, but the element at {index} is out of order")
+    }
+
+    pub fn serialize<S, T>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize + Ord,
+    {
+        if let Some(index) = find_unsorted(values) {
+            return Err(S::Error::custom(error_message(index)));
+        }
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + Ord,
+    {
+        let values = Vec::<T>::deserialize(deserializer)?;
+        if let Some(index) = find_unsorted(&values) {
+            return Err(D::Error::custom(error_message(index)));
+        }
+        Ok(values)
+    }
+
+    /// Serialization helper that sorts the sequence in ascending
+    /// order instead of rejecting it.
+    pub mod sort {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S, T>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            T: Serialize + Ord,
+        {
+            let mut values: Vec<_> = values.iter().collect();
+            values.sort();
+            values.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+        where
+            D: Deserializer<'de>,
+            T: Deserialize<'de> + Ord,
+        {
+            let mut values = Vec::<T>::deserialize(deserializer)?;
+            values.sort();
+            Ok(values)
+        }
+    }
+}
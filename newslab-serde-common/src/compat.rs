@@ -0,0 +1,64 @@
+//! Representation versions and the policy to emit older ones.
+//!
+//! The policy is shared by the helper crates, so that one call to
+//! [with_representation] selects the representation of every type.
+//! Each version is recorded in the fixture corpus of the
+//! `newslab-serde` crate, and the data of every earlier version keeps
+//! being accepted.
+//!
+//! ```rust
+//! # use newslab_serde_common::compat::{self, Representation};
+//! assert_eq!(compat::representation(), Representation::V2);
+//!
+//! let version = compat::with_representation(Representation::V1, compat::representation);
+//! assert_eq!(version, Representation::V1);
+//! assert_eq!(compat::representation(), Representation::V2);
+//! ```
+
+use std::cell::Cell;
+
+/// The versions of serialized representations, from the oldest to
+/// the newest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[non_exhaustive]
+pub enum Representation {
+    /// Quantities are strings with units in all formats.
+    V0,
+    /// The frozen 1.0 representation. Formats that are not
+    /// human-readable store bare numbers in the base units.
+    V1,
+    /// Formats that are not human-readable store fractions as
+    /// `(is_negative, num, deno)` tuples instead of strings, and
+    /// durations as they are instead of floating seconds.
+    #[default]
+    V2,
+}
+
+thread_local! {
+    static CURRENT: Cell<Representation> = const { Cell::new(Representation::V2) };
+}
+
+/// Gets the representation used by the helper modules on the current
+/// thread.
+pub fn representation() -> Representation {
+    CURRENT.with(Cell::get)
+}
+
+/// Runs the function with the representation used by the helper
+/// modules on the current thread. The previous representation is
+/// restored afterwards, even if the function panics.
+pub fn with_representation<F, R>(representation: Representation, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Restore(Representation);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|current| current.replace(representation)));
+    f()
+}
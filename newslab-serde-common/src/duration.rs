@@ -0,0 +1,241 @@
+//! Serialization helper for [Duration] in the humantime syntax.
+//!
+//! A duration is a sequence of integers with units, optionally
+//! separated by whitespace, like `"1h 30m"`, `"250ms"` or `"2d"`.
+//! Accepted units are
+//!
+//! - `ns`, `nsec`
+//! - `us`, `µs`, `usec`
+//! - `ms`, `msec`, `millis`
+//! - `s`, `sec`, `secs`, `second`, `seconds`
+//! - `m`, `min`, `mins`, `minute`, `minutes`
+//! - `h`, `hr`, `hrs`, `hour`, `hours`
+//! - `d`, `day`, `days`
+//! - `w`, `week`, `weeks`
+//!
+//! Durations are serialized compactly in days, hours, minutes,
+//! seconds and sub-second units, like `"1h30m"`. Formats that are not
+//! human-readable, such as bincode, store the [Duration] as is.
+//!
+//! ```rust
+//! # use newslab_serde_common::duration;
+//! # use serde::{Serialize, Deserialize};
+//! # use std::time::Duration;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyService {
+//!     #[serde(with = "duration")]
+//!     retention: Duration,
+//!     #[serde(with = "duration")]
+//!     timeout: Duration,
+//!     #[serde(with = "duration::opt", default)]
+//!     grace: Option<Duration>,
+//! }
+//!
+//! let json = r#"{ "retention": "2d", "timeout": "1h 30m", "grace": "1.5s" }"#;
+//! assert!(serde_json::from_str::<MyService>(json).is_err());
+//!
+//! let json = r#"{ "retention": "2d", "timeout": "1h 30m", "grace": "1s 500ms" }"#;
+//! let service: MyService = serde_json::from_str(json).unwrap();
+//! assert_eq!(service.timeout, Duration::from_secs(5400));
+//! assert_eq!(service.grace, Some(Duration::from_millis(1500)));
+//!
+//! let json = serde_json::to_string(&service).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"retention":"2d","timeout":"1h30m","grace":"1s500ms"}"#
+//! );
+//! ```
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display, Write as _},
+    time::Duration,
+};
+
+/// The units in nanoseconds along with the accepted symbols. The
+/// first symbol is used in formatting.
+const UNITS: &[(u64, &[&str])] = &[
+    (1, &["ns", "nsec"]),
+    (1_000, &["us", "µs", "μs", "usec"]),
+    (1_000_000, &["ms", "msec", "millis"]),
+    (1_000_000_000, &["s", "sec", "secs", "second", "seconds"]),
+    (60_000_000_000, &["m", "min", "mins", "minute", "minutes"]),
+    (3_600_000_000_000, &["h", "hr", "hrs", "hour", "hours"]),
+    (86_400_000_000_000, &["d", "day", "days"]),
+    (604_800_000_000_000, &["w", "week", "weeks"]),
+];
+
+pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        return duration.serialize(serializer);
+    }
+    format(duration).serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        return Duration::deserialize(deserializer);
+    }
+    let text = String::deserialize(deserializer)?;
+    parse(&text).map_err(D::Error::custom)
+}
+
+/// Serialization helper for optional durations.
+pub mod opt {
+    use super::*;
+
+    pub fn serialize<S>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Wrapper<'a>(#[serde(with = "super")] &'a Duration);
+
+        duration.as_ref().map(Wrapper).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Wrapper(#[serde(with = "super")] Duration);
+
+        let duration = Option::<Wrapper>::deserialize(deserializer)?;
+        Ok(duration.map(|Wrapper(duration)| duration))
+    }
+}
+
+/// Parses a duration in the humantime syntax, like `"1h 30m"`.
+pub fn parse(text: &str) -> Result<Duration, ParseDurationError> {
+    let error = |kind| ParseDurationError {
+        text: text.to_string(),
+        kind,
+    };
+
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return Err(error(ParseDurationErrorKind::Empty));
+    }
+
+    let mut total = Duration::ZERO;
+    while !rest.is_empty() {
+        let digits_end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (digits, tail) = rest.split_at(digits_end);
+        if digits.is_empty() || tail.starts_with('.') {
+            return Err(error(ParseDurationErrorKind::InvalidNumber));
+        }
+
+        let tail = tail.trim_start();
+        let symbol_end = tail
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(tail.len());
+        let (symbol, tail) = tail.split_at(symbol_end);
+        if symbol.is_empty() {
+            return Err(error(ParseDurationErrorKind::MissingUnit));
+        }
+
+        let &(unit_nanos, _) = UNITS
+            .iter()
+            .find(|(_, symbols)| symbols.contains(&symbol))
+            .ok_or_else(|| error(ParseDurationErrorKind::UnknownUnit(symbol.to_string())))?;
+
+        let count: u64 = digits
+            .parse()
+            .map_err(|_| error(ParseDurationErrorKind::Overflow))?;
+        let nanos = u128::from(count) * u128::from(unit_nanos);
+        let secs = u64::try_from(nanos / 1_000_000_000)
+            .map_err(|_| error(ParseDurationErrorKind::Overflow))?;
+        let part = Duration::new(secs, (nanos % 1_000_000_000) as u32);
+        total = total
+            .checked_add(part)
+            .ok_or_else(|| error(ParseDurationErrorKind::Overflow))?;
+
+        rest = tail.trim_start();
+    }
+
+    Ok(total)
+}
+
+/// Formats a duration compactly, like `"1h30m"`. A zero duration is
+/// written as `"0s"`.
+pub fn format(duration: &Duration) -> String {
+    if duration.is_zero() {
+        return "0s".to_string();
+    }
+
+    let mut nanos = duration.as_nanos();
+    let mut text = String::new();
+    // Weeks are skipped, so that long durations are written in days.
+    for &(unit_nanos, symbols) in UNITS.iter().rev().skip(1) {
+        let unit_nanos = u128::from(unit_nanos);
+        let symbol = symbols[0];
+        let count = nanos / unit_nanos;
+        if count > 0 {
+            write!(text, "{count}{symbol}").unwrap();
+            nanos %= unit_nanos;
+        }
+    }
+    text
+}
+
+/// The error returned by [parse].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDurationError {
+    text: String,
+    kind: ParseDurationErrorKind,
+}
+
+impl ParseDurationError {
+    /// Gets the text that fails to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn kind(&self) -> &ParseDurationErrorKind {
+        &self.kind
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDurationErrorKind {
+    Empty,
+    InvalidNumber,
+    MissingUnit,
+    UnknownUnit(String),
+    Overflow,
+}
+
+impl Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, kind } = self;
+        match kind {
+            ParseDurationErrorKind::Empty => write!(f, "the duration is empty"),
+            ParseDurationErrorKind::InvalidNumber => {
+                write!(
+                    f,
+                    "unable to parse '{text}' as a duration, \
+                     which must be integers with units, for example, '1h 30m'"
+                )
+            }
+            ParseDurationErrorKind::MissingUnit => {
+                write!(f, "a number in the duration '{text}' has no unit")
+            }
+            ParseDurationErrorKind::UnknownUnit(unit) => {
+                write!(f, "unknown unit '{unit}' in the duration '{text}'")
+            }
+            ParseDurationErrorKind::Overflow => write!(f, "the duration '{text}' is too large"),
+        }
+    }
+}
+
+impl StdError for ParseDurationError {}
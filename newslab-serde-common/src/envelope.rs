@@ -0,0 +1,400 @@
+//! Signed and optionally encrypted payloads.
+//!
+//! An [Envelope] carries a payload along with the Ed25519 public key
+//! of the signer and the signature. It is deserialized by
+//! [Envelope::deserialize_trusted], which rejects the envelope unless
+//! the signer is one of the trusted keys and the signature matches,
+//! so that an edited file is rejected even if it is signed again with
+//! another key.
+//!
+//! The payload is signed in the canonical JSON form, where object
+//! keys are sorted and whitespaces are removed. Encrypted payloads
+//! are sealed with AES-256-GCM and the ciphertext is signed.
+//!
+//! ```rust
+//! # use newslab_serde_common::envelope::{Envelope, SigningKey};
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct MyCalibration {
+//!     camera: String,
+//!     focal: f64,
+//! }
+//!
+//! let key = SigningKey::from_bytes(&[7; 32]);
+//! let trusted = [key.verifying_key()];
+//! let calib = MyCalibration {
+//!     camera: "front".to_string(),
+//!     focal: 640.0,
+//! };
+//!
+//! let envelope = Envelope::sign(calib, &key).unwrap();
+//! let json = serde_json::to_string(&envelope).unwrap();
+//!
+//! let parse = |json: &str| {
+//!     let mut deserializer = serde_json::Deserializer::from_str(json);
+//!     Envelope::<MyCalibration>::deserialize_trusted(&mut deserializer, &trusted)
+//! };
+//! let envelope = parse(&json).unwrap();
+//! assert_eq!(envelope.payload().unwrap().focal, 640.0);
+//!
+//! // A tampered payload is rejected.
+//! assert!(parse(&json.replace("640.0", "641.0")).is_err());
+//!
+//! // A payload signed again with an untrusted key is rejected.
+//! let mut calib = envelope.into_payload().unwrap();
+//! calib.focal = 641.0;
+//! let forged = Envelope::sign(calib, &SigningKey::from_bytes(&[8; 32])).unwrap();
+//! assert!(parse(&serde_json::to_string(&forged).unwrap()).is_err());
+//! ```
+//!
+//! A payload is sealed with a shared secret. The plaintext is wiped
+//! from memory once it is encrypted or parsed.
+//!
+//! ```rust
+//! # use newslab_serde_common::envelope::{Envelope, SigningKey};
+//! # use std::collections::BTreeMap;
+//! let key = SigningKey::from_bytes(&[7; 32]);
+//! let secret = [42; 32];
+//! let rates = BTreeMap::from([("lidar".to_string(), 10)]);
+//!
+//! let envelope = Envelope::seal(rates, &key, &secret).unwrap();
+//! let json = serde_json::to_string(&envelope).unwrap();
+//! assert!(!json.contains("lidar"));
+//!
+//! let mut deserializer = serde_json::Deserializer::from_str(&json);
+//! let mut envelope: Envelope<BTreeMap<String, u32>> =
+//!     Envelope::deserialize_trusted(&mut deserializer, &[key.verifying_key()]).unwrap();
+//! assert!(envelope.is_encrypted());
+//! assert!(envelope.payload().is_none());
+//! assert!(envelope.decrypt(&[0; 32]).is_err());
+//! assert_eq!(envelope.decrypt(&secret).unwrap()["lidar"], 10);
+//! ```
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Signer as _};
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+};
+use zeroize::Zeroizing;
+
+/// The AES-256-GCM key to encrypt payloads.
+pub type EncryptionKey = [u8; 32];
+
+/// The prefix of signed messages of plain payloads.
+const PLAIN_CONTEXT: &[u8] = b"newslab-serde/envelope/v1/plain\n";
+
+/// The prefix of signed messages of encrypted payloads.
+const SEALED_CONTEXT: &[u8] = b"newslab-serde/envelope/v1/sealed\n";
+
+/// A signed payload, which is optionally encrypted.
+#[derive(Debug)]
+pub struct Envelope<T> {
+    raw: EnvelopeUnchecked,
+    signer: VerifyingKey,
+    payload: Option<T>,
+}
+
+impl<T> Envelope<T>
+where
+    T: Serialize,
+{
+    /// Signs the payload with the key.
+    pub fn sign(payload: T, key: &SigningKey) -> Result<Self, EnvelopeError> {
+        let value = canonicalize(&serde_json::to_value(&payload)?);
+        let message = [PLAIN_CONTEXT, &serde_json::to_vec(&value)?].concat();
+
+        Ok(Self {
+            raw: EnvelopeUnchecked {
+                payload: Some(value),
+                sealed: None,
+                signer: Base64(key.verifying_key().to_bytes().to_vec()),
+                signature: Base64(key.sign(&message).to_bytes().to_vec()),
+            },
+            signer: key.verifying_key(),
+            payload: Some(payload),
+        })
+    }
+
+    /// Encrypts the payload with the `secret` and signs the
+    /// ciphertext with the key.
+    pub fn seal(
+        payload: T,
+        key: &SigningKey,
+        secret: &EncryptionKey,
+    ) -> Result<Self, EnvelopeError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(&canonicalize(&serde_json::to_value(
+            &payload,
+        )?))?);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(secret.into())
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| EnvelopeError::Cipher)?;
+        let sealed = Sealed {
+            nonce: Base64(nonce.to_vec()),
+            ciphertext: Base64(ciphertext),
+        };
+        let message = sealed.message();
+
+        Ok(Self {
+            raw: EnvelopeUnchecked {
+                payload: None,
+                sealed: Some(sealed),
+                signer: Base64(key.verifying_key().to_bytes().to_vec()),
+                signature: Base64(key.sign(&message).to_bytes().to_vec()),
+            },
+            signer: key.verifying_key(),
+            payload: Some(payload),
+        })
+    }
+}
+
+impl<T> Envelope<T> {
+    /// Gets the public key of the signer.
+    pub fn signer(&self) -> &VerifyingKey {
+        &self.signer
+    }
+
+    /// Checks if the payload is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.raw.sealed.is_some()
+    }
+
+    /// Gets the payload. It returns `None` if the payload is
+    /// encrypted and not yet decrypted.
+    pub fn payload(&self) -> Option<&T> {
+        self.payload.as_ref()
+    }
+
+    /// Takes the payload like [payload](Self::payload).
+    pub fn into_payload(self) -> Option<T> {
+        self.payload
+    }
+}
+
+impl<T> Envelope<T>
+where
+    T: DeserializeOwned,
+{
+    /// Deserializes an envelope, which is rejected unless the signer
+    /// is one of the `trusted` keys and the signature matches.
+    pub fn deserialize_trusted<'de, D>(
+        deserializer: D,
+        trusted: &[VerifyingKey],
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = EnvelopeUnchecked::deserialize(deserializer)?;
+        Self::verify(raw, trusted).map_err(D::Error::custom)
+    }
+
+    /// Decrypts the payload with the `secret`. The payload is kept so
+    /// that later calls return it without decryption.
+    pub fn decrypt(&mut self, secret: &EncryptionKey) -> Result<&T, EnvelopeError> {
+        let payload = match self.payload.take() {
+            Some(payload) => payload,
+            None => self.sealed().open(secret)?,
+        };
+        Ok(self.payload.insert(payload))
+    }
+
+    fn sealed(&self) -> &Sealed {
+        self.raw
+            .sealed
+            .as_ref()
+            .expect("a plain envelope always has the payload")
+    }
+
+    fn verify(raw: EnvelopeUnchecked, trusted: &[VerifyingKey]) -> Result<Self, EnvelopeError> {
+        let signer: [u8; 32] = raw
+            .signer
+            .0
+            .as_slice()
+            .try_into()
+            .map_err(|_| EnvelopeError::Malformed("the signer key must have 32 bytes"))?;
+        let signer = VerifyingKey::from_bytes(&signer).map_err(|_| EnvelopeError::InvalidSigner)?;
+        if !trusted.contains(&signer) {
+            return Err(EnvelopeError::UntrustedSigner);
+        }
+        let signature = Signature::from_slice(&raw.signature.0)
+            .map_err(|_| EnvelopeError::Malformed("the signature must have 64 bytes"))?;
+
+        let message = match (&raw.payload, &raw.sealed) {
+            (Some(value), None) => {
+                [PLAIN_CONTEXT, &serde_json::to_vec(&canonicalize(value))?].concat()
+            }
+            (None, Some(sealed)) => {
+                if sealed.nonce.0.len() != 12 {
+                    return Err(EnvelopeError::Malformed("the nonce must have 12 bytes"));
+                }
+                sealed.message()
+            }
+            _ => {
+                return Err(EnvelopeError::Malformed(
+                    "exactly one of payload and sealed must be specified",
+                ))
+            }
+        };
+        signer
+            .verify_strict(&message, &signature)
+            .map_err(|_| EnvelopeError::InvalidSignature)?;
+
+        let payload = raw
+            .payload
+            .as_ref()
+            .map(|value| T::deserialize(value))
+            .transpose()?;
+
+        Ok(Self {
+            raw,
+            signer,
+            payload,
+        })
+    }
+}
+
+impl<T> Serialize for Envelope<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.raw.serialize(serializer)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EnvelopeUnchecked {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payload: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sealed: Option<Sealed>,
+    signer: Base64,
+    signature: Base64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Sealed {
+    nonce: Base64,
+    ciphertext: Base64,
+}
+
+impl Sealed {
+    /// Builds the signed message.
+    fn message(&self) -> Vec<u8> {
+        [SEALED_CONTEXT, &self.nonce.0, &self.ciphertext.0].concat()
+    }
+
+    fn open<T>(&self, secret: &EncryptionKey) -> Result<T, EnvelopeError>
+    where
+        T: DeserializeOwned,
+    {
+        let plaintext = Aes256Gcm::new(secret.into())
+            .decrypt(
+                Nonce::from_slice(&self.nonce.0),
+                self.ciphertext.0.as_slice(),
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| EnvelopeError::Cipher)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+/// Bytes serialized as a base64 string.
+#[derive(Debug)]
+struct Base64(Vec<u8>);
+
+impl Serialize for Base64 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        BASE64.encode(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Base64 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        let text = String::deserialize(deserializer)?;
+        let bytes = BASE64.decode(text).map_err(D::Error::custom)?;
+        Ok(Self(bytes))
+    }
+}
+
+/// Sorts object keys recursively, so that the serialized text does
+/// not depend on the key order.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(values.iter().map(canonicalize).collect()),
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let map: Map<_, _> = entries
+                .into_iter()
+                .map(|(key, value)| (key.clone(), canonicalize(value)))
+                .collect();
+            Value::Object(map)
+        }
+        value => value.clone(),
+    }
+}
+
+/// The error returned by [Envelope] operations.
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// The payload cannot be converted from or to JSON.
+    Json(serde_json::Error),
+    /// The envelope is not well-formed.
+    Malformed(&'static str),
+    /// The signer key is not a valid Ed25519 public key.
+    InvalidSigner,
+    /// The signature does not match the payload.
+    InvalidSignature,
+    /// The signer is not one of the trusted keys.
+    UntrustedSigner,
+    /// The payload cannot be encrypted or decrypted with the key.
+    Cipher,
+}
+
+impl Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid payload: {err}"),
+            Self::Malformed(reason) => write!(f, "malformed envelope: {reason}"),
+            Self::InvalidSigner => write!(f, "the signer key is not a valid Ed25519 public key"),
+            Self::InvalidSignature => write!(f, "the signature does not match the payload"),
+            Self::UntrustedSigner => write!(f, "the signer is not trusted"),
+            Self::Cipher => write!(f, "unable to encrypt or decrypt the payload"),
+        }
+    }
+}
+
+impl StdError for EnvelopeError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for EnvelopeError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
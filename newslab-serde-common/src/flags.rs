@@ -0,0 +1,341 @@
+//! Serialization helpers for sets of bit flags defined by the
+//! `bitflags` crate.
+//!
+//! Flags are written as arrays of names in human-readable formats,
+//! and as raw bits otherwise.
+
+use bitflags::Flags;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, marker::PhantomData};
+
+/// The policy on unknown flag names and bits.
+#[derive(Clone, Copy)]
+enum Unknown {
+    Reject,
+    Ignore,
+}
+
+fn known_names<F: Flags>() -> String {
+    let names: Vec<_> = F::FLAGS
+        .iter()
+        .map(|flag| flag.name())
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.join(", ")
+}
+
+fn from_name<F: Flags>(name: &str, unknown: Unknown) -> Result<F, String> {
+    let name = name.trim();
+    if let Some(flag) = F::from_name(name) {
+        return Ok(flag);
+    }
+    match unknown {
+        Unknown::Reject => Err(format!(
+            "unknown flag '{name}', expected one of {}",
+            known_names::<F>()
+        )),
+        Unknown::Ignore => {
+            log::warn!("unknown flag '{name}' is ignored");
+            Ok(F::empty())
+        }
+    }
+}
+
+fn from_bits<F: Flags>(bits: F::Bits, unknown: Unknown) -> Result<F, String> {
+    match unknown {
+        Unknown::Reject => {
+            F::from_bits(bits).ok_or_else(|| "the bits contain unknown flags".to_string())
+        }
+        Unknown::Ignore => {
+            let flags = F::from_bits_truncate(bits);
+            if flags.bits() != bits {
+                log::warn!("unknown bits of the flags are ignored");
+            }
+            Ok(flags)
+        }
+    }
+}
+
+fn names<F: Flags>(flags: &F, unknown: Unknown) -> Result<Vec<&'static str>, String> {
+    if F::from_bits(flags.bits()).is_none() {
+        match unknown {
+            Unknown::Reject => return Err("the flags contain unknown bits".to_string()),
+            Unknown::Ignore => log::warn!("unknown bits of the flags are not written"),
+        }
+    }
+    Ok(flags.iter_names().map(|(name, _)| name).collect())
+}
+
+fn serialize<F, S>(
+    flags: &F,
+    serializer: S,
+    unknown: Unknown,
+    as_pipe: bool,
+) -> Result<S::Ok, S::Error>
+where
+    F: Flags,
+    F::Bits: Serialize,
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        let flags: F = from_bits(flags.bits(), unknown).map_err(S::Error::custom)?;
+        return flags.bits().serialize(serializer);
+    }
+    let names = names(flags, unknown).map_err(S::Error::custom)?;
+    if as_pipe {
+        serializer.serialize_str(&names.join("|"))
+    } else {
+        names.serialize(serializer)
+    }
+}
+
+fn deserialize<'de, F, D>(deserializer: D, unknown: Unknown) -> Result<F, D::Error>
+where
+    F: Flags,
+    F::Bits: Deserialize<'de> + TryFrom<u64>,
+    D: Deserializer<'de>,
+{
+    if !deserializer.is_human_readable() {
+        let bits = F::Bits::deserialize(deserializer)?;
+        return from_bits(bits, unknown).map_err(de::Error::custom);
+    }
+    deserializer.deserialize_any(FlagsVisitor {
+        unknown,
+        _phantom: PhantomData,
+    })
+}
+
+/// Visits an array of names, a pipe-separated string of names or an
+/// integer of raw bits.
+struct FlagsVisitor<F> {
+    unknown: Unknown,
+    _phantom: PhantomData<F>,
+}
+
+impl<'de, F> Visitor<'de> for FlagsVisitor<F>
+where
+    F: Flags,
+    F::Bits: TryFrom<u64>,
+{
+    type Value = F;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .write_str("an array of flag names, a string like \"READ|WRITE\" or an integer of bits")
+    }
+
+    fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        text.split('|')
+            .filter(|name| !name.trim().is_empty())
+            .try_fold(F::empty(), |flags, name| {
+                let flag = from_name(name, self.unknown).map_err(E::custom)?;
+                Ok(flags.union(flag))
+            })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut flags = F::empty();
+        while let Some(name) = seq.next_element::<String>()? {
+            let flag = from_name(&name, self.unknown).map_err(de::Error::custom)?;
+            flags = flags.union(flag);
+        }
+        Ok(flags)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let bits = F::Bits::try_from(value)
+            .map_err(|_| E::custom(format!("the bits {value:#x} are out of range")))?;
+        from_bits(bits, self.unknown)
+            .map_err(|err| E::custom(format!("invalid bits {value:#x}: {err}")))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let value = u64::try_from(value)
+            .map_err(|_| E::custom(format!("the bits {value} must not be negative")))?;
+        self.visit_u64(value)
+    }
+}
+
+/// Serialize or deserialize a set of bit flags as an array of names,
+/// like `["READ", "WRITE"]`, rejecting unknown names and bits.
+///
+/// A pipe-separated string like `"READ|WRITE"` and an integer of raw
+/// bits, as in older configs, are also accepted on deserialization.
+/// The [pipe](flag_names::pipe) variant writes the string form, and
+/// the [lenient](flag_names::lenient) variant ignores unknown flags
+/// with a warning.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::flag_names;
+/// bitflags::bitflags! {
+///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///     pub struct Caps: u32 {
+///         const READ = 1;
+///         const WRITE = 1 << 1;
+///         const EXEC = 1 << 2;
+///     }
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct MyDevice {
+///     #[serde(with = "flag_names")]
+///     caps: Caps,
+/// }
+///
+/// let device: MyDevice = serde_json::from_str(r#"{ "caps": ["READ", "WRITE"] }"#).unwrap();
+/// assert_eq!(device.caps, Caps::READ | Caps::WRITE);
+/// assert_eq!(serde_json::to_string(&device).unwrap(), r#"{"caps":["READ","WRITE"]}"#);
+///
+/// let device: MyDevice = serde_json::from_str(r#"{ "caps": "READ | EXEC" }"#).unwrap();
+/// assert_eq!(device.caps, Caps::READ | Caps::EXEC);
+///
+/// let device: MyDevice = serde_json::from_str(r#"{ "caps": 3 }"#).unwrap();
+/// assert_eq!(device.caps, Caps::READ | Caps::WRITE);
+///
+/// assert!(serde_json::from_str::<MyDevice>(r#"{ "caps": ["DELETE"] }"#).is_err());
+/// assert!(serde_json::from_str::<MyDevice>(r#"{ "caps": 8 }"#).is_err());
+///
+/// let device = MyDevice { caps: Caps::from_bits_retain(9) };
+/// assert!(serde_json::to_string(&device).is_err());
+/// ```
+pub mod flag_names {
+    use super::Unknown;
+    use bitflags::Flags;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, F>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        F: Flags,
+        F::Bits: Serialize,
+    {
+        super::serialize(flags, serializer, Unknown::Reject, false)
+    }
+
+    pub fn deserialize<'de, D, F>(deserializer: D) -> Result<F, D::Error>
+    where
+        D: Deserializer<'de>,
+        F: Flags,
+        F::Bits: Deserialize<'de> + TryFrom<u64>,
+    {
+        super::deserialize(deserializer, Unknown::Reject)
+    }
+
+    /// Serialize a set of bit flags as a pipe-separated string, like
+    /// `"READ|WRITE"`. All forms are accepted on deserialization.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::flag_names;
+    /// bitflags::bitflags! {
+    ///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    ///     pub struct Caps: u8 {
+    ///         const READ = 1;
+    ///         const WRITE = 1 << 1;
+    ///     }
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyDevice {
+    ///     #[serde(with = "flag_names::pipe")]
+    ///     caps: Caps,
+    /// }
+    ///
+    /// let device = MyDevice { caps: Caps::all() };
+    /// assert_eq!(serde_json::to_string(&device).unwrap(), r#"{"caps":"READ|WRITE"}"#);
+    ///
+    /// let device = MyDevice { caps: Caps::empty() };
+    /// assert_eq!(serde_json::to_string(&device).unwrap(), r#"{"caps":""}"#);
+    /// ```
+    pub mod pipe {
+        use super::Unknown;
+        use bitflags::Flags;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S, F>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            F: Flags,
+            F::Bits: Serialize,
+        {
+            super::super::serialize(flags, serializer, Unknown::Reject, true)
+        }
+
+        pub fn deserialize<'de, D, F>(deserializer: D) -> Result<F, D::Error>
+        where
+            D: Deserializer<'de>,
+            F: Flags,
+            F::Bits: Deserialize<'de> + TryFrom<u64>,
+        {
+            super::deserialize(deserializer)
+        }
+    }
+
+    /// Serialize or deserialize a set of bit flags like
+    /// [flag_names](super::flag_names), which ignores unknown names
+    /// and bits with a warning instead of failing.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::flag_names;
+    /// bitflags::bitflags! {
+    ///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    ///     pub struct Caps: u32 {
+    ///         const READ = 1;
+    ///         const WRITE = 1 << 1;
+    ///     }
+    /// }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyDevice {
+    ///     #[serde(with = "flag_names::lenient")]
+    ///     caps: Caps,
+    /// }
+    ///
+    /// let device: MyDevice = serde_json::from_str(r#"{ "caps": ["READ", "DELETE"] }"#).unwrap();
+    /// assert_eq!(device.caps, Caps::READ);
+    ///
+    /// let device: MyDevice = serde_json::from_str(r#"{ "caps": 6 }"#).unwrap();
+    /// assert_eq!(device.caps, Caps::WRITE);
+    /// ```
+    pub mod lenient {
+        use super::Unknown;
+        use bitflags::Flags;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S, F>(flags: &F, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+            F: Flags,
+            F::Bits: Serialize,
+        {
+            super::super::serialize(flags, serializer, Unknown::Ignore, false)
+        }
+
+        pub fn deserialize<'de, D, F>(deserializer: D) -> Result<F, D::Error>
+        where
+            D: Deserializer<'de>,
+            F: Flags,
+            F::Bits: Deserialize<'de> + TryFrom<u64>,
+        {
+            super::super::deserialize(deserializer, Unknown::Ignore)
+        }
+    }
+}
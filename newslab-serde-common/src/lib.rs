@@ -1,12 +1,62 @@
 //! Data ser/deserialization library for well-known concepts.
 
-pub use serde_bound::{optional_range, range};
+mod bytes;
+mod collection;
+pub mod compat;
+pub mod duration;
+#[cfg(feature = "with-envelope")]
+pub mod envelope;
+#[cfg(feature = "with-bitflags")]
+mod flags;
+#[cfg(feature = "with-load")]
+pub mod load;
+pub mod net;
+mod number;
+pub mod path;
+#[cfg(feature = "with-regex")]
+pub mod pattern_string;
+mod range_set;
+mod remap_table;
+pub mod schema;
+pub mod subtree;
+pub mod timestamp;
+pub mod version;
+#[cfg(feature = "with-watch")]
+pub mod watch;
+
+#[cfg(feature = "with-base64")]
+pub use bytes::base64_bytes;
+pub use bytes::hex_bytes;
+pub use collection::{non_empty_vec, sorted_vec, unique_vec};
+#[cfg(feature = "with-bitflags")]
+pub use flags::flag_names;
+#[cfg(feature = "with-url")]
+pub use net::url;
+pub use net::{ip_addr, socket_addr};
+pub use number::{
+    bounded, non_negative_f64, non_zero_u32, non_zero_u64, percentage, positive_f64, probability,
+    saturating_u16, saturating_u32, saturating_u8,
+};
+pub use range_set::{InvalidRangeSetError, RangeSet};
+pub use remap_table::{InvalidRemapTableError, RemapTable};
+pub use serde_bound::{optional_range, ordered_range, range};
 mod serde_bound {
-    use serde::{Deserialize, Serialize};
-    use std::ops::{Bound, Bound::*};
+    use serde::{
+        de::{value::MapAccessDeserializer, Error as _, MapAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{
+        cmp::Ordering,
+        fmt::{self, Display},
+        marker::PhantomData,
+        ops::{Bound, Bound::*},
+        str::FromStr,
+    };
+
+    pub(crate) type Range<T> = (Bound<T>, Bound<T>);
 
     #[derive(Serialize, Deserialize)]
-    struct SerializedBound<T> {
+    pub(crate) struct SerializedBound<T> {
         #[serde(rename = ">")]
         pub min: Option<T>,
         #[serde(rename = ">=")]
@@ -65,8 +115,160 @@ mod serde_bound {
         }
     }
 
+    /// Formats the range in the interval notation, like `"(-10, 5]"`.
+    /// An unbounded end is left empty.
+    fn format_range<T>((lower, upper): &Range<T>) -> String
+    where
+        T: Display,
+    {
+        let lower = match lower {
+            Unbounded => "(".to_string(),
+            Included(val) => format!("[{val}"),
+            Excluded(val) => format!("({val}"),
+        };
+        let upper = match upper {
+            Unbounded => ")".to_string(),
+            Included(val) => format!("{val}]"),
+            Excluded(val) => format!("{val})"),
+        };
+        format!("{lower}, {upper}")
+    }
+
+    /// Parses a range in the interval notation, like `"(-10, 5]"`, or
+    /// comparisons separated by a comma, like `">= 3, < 7"`.
+    fn parse_range<T>(text: &str) -> Result<Range<T>, String>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let parse = |val: &str| -> Result<T, String> {
+            val.trim()
+                .parse()
+                .map_err(|err| format!("invalid bound '{}' in range '{text}': {err}", val.trim()))
+        };
+        let trimmed = text.trim();
+
+        if trimmed.starts_with(['(', '[']) {
+            let (lower, upper) = trimmed[1..]
+                .strip_suffix([')', ']'])
+                .and_then(|inner| inner.split_once(','))
+                .ok_or_else(|| format!("range '{text}' must be in '(min, max)' format"))?;
+            let lower = match lower.trim() {
+                "" => Unbounded,
+                val if trimmed.starts_with('[') => Included(parse(val)?),
+                val => Excluded(parse(val)?),
+            };
+            let upper = match upper.trim() {
+                "" => Unbounded,
+                val if trimmed.ends_with(']') => Included(parse(val)?),
+                val => Excluded(parse(val)?),
+            };
+            return Ok((lower, upper));
+        }
+
+        let mut lower = None;
+        let mut upper = None;
+        for cond in trimmed.split(',') {
+            let cond = cond.trim();
+            let (slot, bound) = if let Some(val) = cond.strip_prefix(">=") {
+                (&mut lower, Included(parse(val)?))
+            } else if let Some(val) = cond.strip_prefix('>') {
+                (&mut lower, Excluded(parse(val)?))
+            } else if let Some(val) = cond.strip_prefix("<=") {
+                (&mut upper, Included(parse(val)?))
+            } else if let Some(val) = cond.strip_prefix('<') {
+                (&mut upper, Excluded(parse(val)?))
+            } else {
+                return Err(format!(
+                    "'{cond}' in range '{text}' must start with '>', '>=', '<' or '<='"
+                ));
+            };
+            if slot.replace(bound).is_some() {
+                return Err(format!("range '{text}' has more than one bound on a side"));
+            }
+        }
+
+        Ok((lower.unwrap_or(Unbounded), upper.unwrap_or(Unbounded)))
+    }
+
+    /// A range deserialized from either the object or the string form.
+    pub(crate) struct AnyRange<T>(pub Range<T>);
+
+    impl<'de, T> Deserialize<'de> for AnyRange<T>
+    where
+        T: Deserialize<'de> + FromStr,
+        T::Err: Display,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct RangeVisitor<T>(PhantomData<T>);
+
+            impl<'de, T> Visitor<'de> for RangeVisitor<T>
+            where
+                T: Deserialize<'de> + FromStr,
+                T::Err: Display,
+            {
+                type Value = Range<T>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a range object or a string like \"(-10, 5]\"")
+                }
+
+                fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    parse_range(text).map_err(E::custom)
+                }
+
+                fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: MapAccess<'de>,
+                {
+                    SerializedBound::deserialize(MapAccessDeserializer::new(map))?
+                        .into_bound()
+                        .map_err(A::Error::custom)
+                }
+            }
+
+            let range = if deserializer.is_human_readable() {
+                deserializer.deserialize_any(RangeVisitor(PhantomData))?
+            } else {
+                SerializedBound::<T>::deserialize(deserializer)?
+                    .into_bound()
+                    .map_err(D::Error::custom)?
+            };
+            Ok(Self(range))
+        }
+    }
+
+    /// A range serialized in the string form for human-readable
+    /// formats, or in the object form otherwise.
+    struct CompactRange<'a, T>(&'a Range<T>);
+
+    impl<T> Serialize for CompactRange<'_, T>
+    where
+        T: Serialize + Display,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            if serializer.is_human_readable() {
+                serializer.serialize_str(&format_range(self.0))
+            } else {
+                SerializedBound::from_bound(self.0).serialize(serializer)
+            }
+        }
+    }
+
     /// Serialize or deserialize arbitrary ranges.
     ///
+    /// The [compact](range::compact) variant also accepts and writes
+    /// the string form.
+    ///
     /// ```rust
     /// # use std::ops::Bound;
     /// # use serde::{Serialize, Deserialize};
@@ -117,9 +319,164 @@ mod serde_bound {
             let raw = SerializedBound::<T>::deserialize(deserializer)?;
             raw.into_bound().map_err(D::Error::custom)
         }
+
+        /// Serialize ranges as strings in the interval notation, like
+        /// `"(-10, 5]"`.
+        ///
+        /// Human-readable formats accept both the object form and the
+        /// string form, either in the interval notation or as
+        /// comparisons like `">= 3, < 7"`. An unbounded end is left
+        /// empty, like `"[0, )"`. Formats that are not human-readable
+        /// use the object form.
+        ///
+        /// ```rust
+        /// # use std::ops::Bound;
+        /// # use serde::{Serialize, Deserialize};
+        /// # use newslab_serde_common::range;
+        /// #[derive(Serialize, Deserialize)]
+        /// struct MyRange {
+        ///     #[serde(with = "range::compact")]
+        ///     range: (Bound<i32>, Bound<i32>),
+        /// }
+        ///
+        /// let my_range = MyRange {
+        ///     range: (Bound::Included(0), Bound::Unbounded),
+        /// };
+        /// let json = serde_json::to_string(&my_range).unwrap();
+        /// assert_eq!(json, r#"{"range":"[0, )"}"#);
+        ///
+        /// let my_range: MyRange = serde_json::from_str(r#"{ "range": "(-10, 5]" }"#).unwrap();
+        /// assert_eq!(my_range.range, (Bound::Excluded(-10), Bound::Included(5)));
+        ///
+        /// let my_range: MyRange = serde_json::from_str(r#"{ "range": ">= 3, < 7" }"#).unwrap();
+        /// assert_eq!(my_range.range, (Bound::Included(3), Bound::Excluded(7)));
+        ///
+        /// let my_range: MyRange = serde_json::from_str(r#"{ "range": { ">=": 3 } }"#).unwrap();
+        /// assert_eq!(my_range.range, (Bound::Included(3), Bound::Unbounded));
+        ///
+        /// // Both bounds are lower bounds.
+        /// assert!(serde_json::from_str::<MyRange>(r#"{ "range": "> 3, >= 7" }"#).is_err());
+        /// ```
+        pub mod compact {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+            use std::{fmt::Display, ops::Bound, str::FromStr};
+
+            use super::super::{AnyRange, CompactRange};
+
+            pub fn serialize<S, T>(
+                bound: &(Bound<T>, Bound<T>),
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                T: Serialize + Display,
+                S: Serializer,
+            {
+                CompactRange(bound).serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<(Bound<T>, Bound<T>), D::Error>
+            where
+                T: Deserialize<'de> + FromStr,
+                T::Err: Display,
+                D: Deserializer<'de>,
+            {
+                AnyRange::deserialize(deserializer).map(|AnyRange(range)| range)
+            }
+        }
+    }
+
+    /// Checks that the lower bound is below the upper bound, so that
+    /// the range is not empty.
+    fn check_order<T>((lower, upper): &Range<T>) -> Result<(), String>
+    where
+        T: PartialOrd + Display,
+    {
+        let (lop, min) = match lower {
+            Unbounded => return Ok(()),
+            Included(min) => (">=", min),
+            Excluded(min) => (">", min),
+        };
+        let (uop, max) = match upper {
+            Unbounded => return Ok(()),
+            Included(max) => ("<=", max),
+            Excluded(max) => ("<", max),
+        };
+
+        let is_closed = matches!((lower, upper), (Included(_), Included(_)));
+        match min.partial_cmp(max) {
+            Some(Ordering::Less) => {}
+            Some(Ordering::Equal) if is_closed => {}
+            _ if is_closed => {
+                return Err(format!(
+                    "the lower bound '{lop}' {min} must not be greater than the upper bound '{uop}' {max}"
+                ));
+            }
+            _ => {
+                return Err(format!(
+                    "the lower bound '{lop}' {min} must be less than the upper bound '{uop}' {max}, or the range is empty"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize or deserialize non-empty ranges like [range], which
+    /// rejects ranges whose lower bound is above the upper bound.
+    ///
+    /// ```rust
+    /// # use std::ops::Bound;
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::ordered_range;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyRange {
+    ///     #[serde(with = "ordered_range")]
+    ///     range: (Bound<i32>, Bound<i32>),
+    /// }
+    ///
+    /// let my_range: MyRange = serde_json::from_str(r#"{ "range": { ">=": 5, "<=": 5 } }"#).unwrap();
+    /// assert_eq!(my_range.range, (Bound::Included(5), Bound::Included(5)));
+    ///
+    /// let err = serde_json::from_str::<MyRange>(r#"{ "range": { ">": 10, "<": 5 } }"#)
+    ///     .err()
+    ///     .unwrap();
+    /// assert!(err.to_string().starts_with("the lower bound '>' 10 must be less than the upper bound '<' 5"));
+    ///
+    /// // The exclusive range is empty.
+    /// assert!(serde_json::from_str::<MyRange>(r#"{ "range": { ">=": 5, "<": 5 } }"#).is_err());
+    /// ```
+    pub mod ordered_range {
+        use serde::{
+            de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+        };
+        use std::{fmt::Display, ops::Bound};
+
+        use super::check_order;
+
+        pub fn serialize<S, T>(
+            bound: &(Bound<T>, Bound<T>),
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            T: Serialize + PartialOrd + Display,
+            S: Serializer,
+        {
+            check_order(bound).map_err(S::Error::custom)?;
+            super::range::serialize(bound, serializer)
+        }
+
+        pub fn deserialize<'de, D, T>(deserializer: D) -> Result<(Bound<T>, Bound<T>), D::Error>
+        where
+            T: Deserialize<'de> + PartialOrd + Display,
+            D: Deserializer<'de>,
+        {
+            let range = super::range::deserialize(deserializer)?;
+            check_order(&range).map_err(D::Error::custom)?;
+            Ok(range)
+        }
     }
 
-    /// Optionally serialize or deserialize arbitrary ranges.
+    /// Optionally serialize or deserialize arbitrary ranges like
+    /// [range](super::range).
     ///
     /// ```rust
     /// # use std::ops::Bound;
@@ -145,14 +502,13 @@ mod serde_bound {
     ///     my_range.range,
     ///     Some((Bound::Excluded(-10.0), Bound::Included(5.0)))
     /// );
+    ///
     /// ```
     pub mod optional_range {
         use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
         use std::ops::Bound;
 
-        use super::SerializedBound;
-
-        type Range<T> = (Bound<T>, Bound<T>);
+        use super::{Range, SerializedBound};
 
         pub fn serialize<S, T>(
             bound: &Option<(Bound<T>, Bound<T>)>,
@@ -179,6 +535,55 @@ mod serde_bound {
                 .map_err(D::Error::custom)?;
             Ok(bound)
         }
+
+        /// Serialize ranges as strings in the interval notation like
+        /// [range::compact](super::range::compact), which also accepts
+        /// the string form.
+        ///
+        /// ```rust
+        /// # use std::ops::Bound;
+        /// # use serde::{Serialize, Deserialize};
+        /// # use newslab_serde_common::optional_range;
+        /// #[derive(Serialize, Deserialize)]
+        /// struct MyRange {
+        ///     #[serde(with = "optional_range::compact")]
+        ///     range: Option<(Bound<f32>, Bound<f32>)>,
+        /// }
+        ///
+        /// let my_range: MyRange = serde_json::from_str(r#"{ "range": "(, 5]" }"#).unwrap();
+        /// assert_eq!(
+        ///     my_range.range,
+        ///     Some((Bound::Unbounded, Bound::Included(5.0)))
+        /// );
+        /// assert_eq!(serde_json::to_string(&my_range).unwrap(), r#"{"range":"(, 5]"}"#);
+        /// ```
+        pub mod compact {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+            use std::{fmt::Display, ops::Bound, str::FromStr};
+
+            use super::super::{AnyRange, CompactRange, Range};
+
+            pub fn serialize<S, T>(
+                bound: &Option<(Bound<T>, Bound<T>)>,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                T: Serialize + Display,
+                S: Serializer,
+            {
+                bound.as_ref().map(CompactRange).serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Range<T>>, D::Error>
+            where
+                T: Deserialize<'de> + FromStr,
+                T::Err: Display,
+                D: Deserializer<'de>,
+            {
+                let bound = Option::<AnyRange<T>>::deserialize(deserializer)?;
+                Ok(bound.map(|AnyRange(range)| range))
+            }
+        }
     }
 }
 
@@ -0,0 +1,466 @@
+//! Loading configuration files into deserializable types.
+//!
+//! The format is detected by the file extension, `.json`, `.yaml` or
+//! `.yml`, and by the content if the extension is unknown. Errors
+//! carry the file path and, for syntax and data errors, the line and
+//! column.
+//!
+//! YAML support requires the `with-yaml` feature, and the `*_async`
+//! variants require the `with-tokio` feature. Files can be watched
+//! for changes with the `watch` module, which requires the
+//! `with-watch` feature.
+//!
+//! ```rust
+//! # use newslab_serde_common::load::{load_file, load_json_file};
+//! # use serde::Deserialize;
+//! #[derive(Debug, Deserialize)]
+//! struct MyConfig {
+//!     name: String,
+//!     rate: u32,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("newslab-serde-load-doc");
+//! std::fs::create_dir_all(&dir).unwrap();
+//!
+//! let path = dir.join("config.json");
+//! std::fs::write(&path, r#"{ "name": "lidar", "rate": 10 }"#).unwrap();
+//! let config: MyConfig = load_file(&path).unwrap();
+//! assert_eq!(config.rate, 10);
+//!
+//! // The error names the file and the location.
+//! let path = dir.join("broken.json");
+//! std::fs::write(&path, "{\n  \"name\": \"lidar\",\n  \"rate\": \"fast\"\n}").unwrap();
+//! let err = load_json_file::<MyConfig, _>(&path).unwrap_err();
+//! assert_eq!(err.path(), path);
+//! assert_eq!(err.line(), Some(3));
+//! assert!(err.to_string().starts_with(&format!("{}:3:", path.display())));
+//! ```
+
+use crate::subtree::get_path;
+use serde::de::DeserializeOwned;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error as StdError,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The file formats supported by the loaders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    Json,
+    #[cfg(feature = "with-yaml")]
+    Yaml,
+}
+
+impl Format {
+    /// Detects the format by the file extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            #[cfg(feature = "with-yaml")]
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format by the content. Text starting with `{` or
+    /// `[` is JSON and the rest is YAML.
+    pub fn from_content(text: &str) -> Option<Self> {
+        match text.trim_start().chars().next() {
+            Some('{' | '[') => Some(Self::Json),
+            #[cfg(feature = "with-yaml")]
+            _ => Some(Self::Yaml),
+            #[cfg(not(feature = "with-yaml"))]
+            _ => None,
+        }
+    }
+
+    /// Deserializes the text in this format. The `path` is used in
+    /// error messages.
+    pub fn parse<T>(self, path: impl AsRef<Path>, text: &str) -> Result<T, LoadError>
+    where
+        T: DeserializeOwned,
+    {
+        self.parse_path(path, text, "")
+    }
+
+    /// Deserializes the subtree at the dotted `key_path` of the text
+    /// in this format, like [get_path]. The `path` is used in error
+    /// messages.
+    pub fn parse_path<T>(
+        self,
+        path: impl AsRef<Path>,
+        text: &str,
+        key_path: &str,
+    ) -> Result<T, LoadError>
+    where
+        T: DeserializeOwned,
+    {
+        let path = path.as_ref();
+        match self {
+            Self::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(text);
+                get_path(&mut deserializer, key_path)
+                    .and_then(|value| deserializer.end().map(|()| value))
+                    .map_err(|err| {
+                        let location = (err.line() > 0).then(|| (err.line(), err.column()));
+                        LoadError::new(path, location, LoadErrorKind::Json(err))
+                    })
+            }
+            #[cfg(feature = "with-yaml")]
+            Self::Yaml => {
+                get_path(serde_yaml::Deserializer::from_str(text), key_path).map_err(|err| {
+                    let location = err.location().map(|loc| (loc.line(), loc.column()));
+                    LoadError::new(path, location, LoadErrorKind::Yaml(err))
+                })
+            }
+        }
+    }
+}
+
+/// Loads a file and detects its format by the extension, or by the
+/// content if the extension is unknown.
+pub fn load_file<T, P>(path: P) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let text = read_file(path)?;
+    detect(path, &text)?.parse(path, &text)
+}
+
+/// Loads the subtree at the dotted `key_path` of a file, like
+/// [get_path]. The format is detected as in [load_file].
+///
+/// ```rust
+/// # use newslab_serde_common::load::load_file_path;
+/// let path = std::env::temp_dir().join("newslab-serde-load-path-doc.json");
+/// std::fs::write(&path, r#"{ "sensors": { "lidar": { "rate": 10 } } }"#).unwrap();
+/// let rate: u32 = load_file_path(&path, "sensors.lidar.rate").unwrap();
+/// assert_eq!(rate, 10);
+/// ```
+pub fn load_file_path<T, P>(path: P, key_path: &str) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let text = read_file(path)?;
+    detect(path, &text)?.parse_path(path, &text, key_path)
+}
+
+/// Loads a JSON file regardless of the extension.
+pub fn load_json_file<T, P>(path: P) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    Format::Json.parse(path, &read_file(path)?)
+}
+
+/// Loads a YAML file regardless of the extension.
+///
+/// ```rust
+/// # #[cfg(feature = "with-yaml")]
+/// # {
+/// # use newslab_serde_common::load::load_yaml_file;
+/// # use std::collections::BTreeMap;
+/// let path = std::env::temp_dir().join("newslab-serde-load-yaml-doc.txt");
+/// std::fs::write(&path, "lidar: 10\ncamera: 30\n").unwrap();
+/// let rates: BTreeMap<String, u32> = load_yaml_file(&path).unwrap();
+/// assert_eq!(rates["camera"], 30);
+///
+/// std::fs::write(&path, "lidar: 10\ncamera: fast\n").unwrap();
+/// let err = load_yaml_file::<BTreeMap<String, u32>, _>(&path).unwrap_err();
+/// assert_eq!(err.line(), Some(2));
+/// # }
+/// ```
+#[cfg(feature = "with-yaml")]
+pub fn load_yaml_file<T, P>(path: P) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    Format::Yaml.parse(path, &read_file(path)?)
+}
+
+/// Loads all files matching the glob pattern, keyed by their file
+/// names without extensions. The format of each file is detected as
+/// in [load_file].
+///
+/// ```rust
+/// # use newslab_serde_common::load::{load_dir, load_dir_by, LoadErrorKind};
+/// # use serde::Deserialize;
+/// #[derive(Debug, Deserialize)]
+/// struct MyCamera {
+///     name: Option<String>,
+///     rate: u32,
+/// }
+///
+/// let dir = std::env::temp_dir().join("newslab-serde-load-dir-doc");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join("front.json"), r#"{ "name": "cam0", "rate": 30 }"#).unwrap();
+/// std::fs::write(dir.join("rear.json"), r#"{ "rate": 15 }"#).unwrap();
+/// let pattern = format!("{}/*.json", dir.display());
+///
+/// let cameras = load_dir::<MyCamera>(&pattern).unwrap();
+/// assert_eq!(cameras["rear"].rate, 15);
+///
+/// // Key by the name field and fall back to the file name.
+/// let cameras = load_dir_by(&pattern, |camera: &MyCamera| camera.name.clone()).unwrap();
+/// assert_eq!(cameras["cam0"].rate, 30);
+/// assert_eq!(cameras["rear"].rate, 15);
+///
+/// // Two files claim the same key.
+/// let err = load_dir_by(&pattern, |_: &MyCamera| Some("cam".to_string())).unwrap_err();
+/// assert!(matches!(err.kind(), LoadErrorKind::DuplicateKey { .. }));
+/// ```
+pub fn load_dir<T>(pattern: &str) -> Result<BTreeMap<String, T>, LoadError>
+where
+    T: DeserializeOwned,
+{
+    load_dir_by(pattern, |_| None)
+}
+
+/// Loads all files matching the glob pattern, keyed by the name
+/// returned by `key`. Files for which `key` returns `None` are keyed
+/// by their file names without extensions. It fails if two files
+/// have the same key.
+pub fn load_dir_by<T, F>(pattern: &str, key: F) -> Result<BTreeMap<String, T>, LoadError>
+where
+    T: DeserializeOwned,
+    F: Fn(&T) -> Option<String>,
+{
+    let paths = glob::glob(pattern)
+        .map_err(|err| LoadError::new(Path::new(pattern), None, LoadErrorKind::Pattern(err)))?;
+
+    let mut values = BTreeMap::new();
+    let mut sources: HashMap<String, PathBuf> = HashMap::new();
+
+    for path in paths {
+        let path = path.map_err(|err| {
+            let path = err.path().to_path_buf();
+            LoadError::new(&path, None, LoadErrorKind::Io(err.into()))
+        })?;
+        if !path.is_file() {
+            continue;
+        }
+
+        let value: T = load_file(&path)?;
+        let name = match key(&value) {
+            Some(name) => name,
+            None => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+
+        if let Some(first) = sources.get(&name) {
+            let kind = LoadErrorKind::DuplicateKey {
+                key: name,
+                first: first.clone(),
+            };
+            return Err(LoadError::new(&path, None, kind));
+        }
+        sources.insert(name.clone(), path);
+        values.insert(name, value);
+    }
+
+    Ok(values)
+}
+
+/// The asynchronous version of [load_file].
+///
+/// ```rust
+/// # #[cfg(feature = "with-tokio")]
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// # use newslab_serde_common::load::load_file_async;
+/// # use std::collections::BTreeMap;
+/// let path = std::env::temp_dir().join("newslab-serde-load-async-doc.json");
+/// std::fs::write(&path, r#"{ "lidar": 10 }"#).unwrap();
+/// let rates: BTreeMap<String, u32> = load_file_async(&path).await.unwrap();
+/// assert_eq!(rates["lidar"], 10);
+/// # });
+/// ```
+#[cfg(feature = "with-tokio")]
+pub async fn load_file_async<T, P>(path: P) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let text = read_file_async(path).await?;
+    detect(path, &text)?.parse(path, &text)
+}
+
+/// The asynchronous version of [load_json_file].
+#[cfg(feature = "with-tokio")]
+pub async fn load_json_file_async<T, P>(path: P) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    Format::Json.parse(path, &read_file_async(path).await?)
+}
+
+/// The asynchronous version of [load_yaml_file].
+#[cfg(all(feature = "with-tokio", feature = "with-yaml"))]
+pub async fn load_yaml_file_async<T, P>(path: P) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    Format::Yaml.parse(path, &read_file_async(path).await?)
+}
+
+pub(crate) fn detect(path: &Path, text: &str) -> Result<Format, LoadError> {
+    Format::from_path(path)
+        .or_else(|| Format::from_content(text))
+        .ok_or_else(|| LoadError::new(path, None, LoadErrorKind::UnknownFormat))
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, LoadError> {
+    fs::read_to_string(path).map_err(|err| LoadError::new(path, None, LoadErrorKind::Io(err)))
+}
+
+#[cfg(feature = "with-tokio")]
+async fn read_file_async(path: &Path) -> Result<String, LoadError> {
+    tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| LoadError::new(path, None, LoadErrorKind::Io(err)))
+}
+
+/// The error returned by the loaders.
+#[derive(Debug)]
+pub struct LoadError {
+    path: PathBuf,
+    location: Option<(usize, usize)>,
+    kind: LoadErrorKind,
+}
+
+impl LoadError {
+    pub(crate) fn new(path: &Path, location: Option<(usize, usize)>, kind: LoadErrorKind) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            location,
+            kind,
+        }
+    }
+
+    /// Gets the path to the file failed to load.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the 1-based line number where the error occurs, if known.
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+
+    /// Gets the 1-based column number where the error occurs, if known.
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|(_, column)| column)
+    }
+
+    pub fn kind(&self) -> &LoadErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some((line, column)) = self.location {
+            write!(f, ":{line}:{column}")?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl StdError for LoadError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.kind {
+            LoadErrorKind::Io(err) => Some(err),
+            LoadErrorKind::Json(err) => Some(err),
+            #[cfg(feature = "with-yaml")]
+            LoadErrorKind::Yaml(err) => Some(err),
+            LoadErrorKind::Pattern(err) => Some(err),
+            #[cfg(feature = "with-watch")]
+            LoadErrorKind::Watch(err) => Some(err),
+            LoadErrorKind::UnknownFormat
+            | LoadErrorKind::DuplicateKey { .. }
+            | LoadErrorKind::Invalid(_) => None,
+        }
+    }
+}
+
+/// The cause of a [LoadError].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LoadErrorKind {
+    Io(io::Error),
+    Json(serde_json::Error),
+    #[cfg(feature = "with-yaml")]
+    Yaml(serde_yaml::Error),
+    UnknownFormat,
+    /// The glob pattern passed to [load_dir] is malformed.
+    Pattern(glob::PatternError),
+    /// The key of the file is already taken by the `first` file.
+    DuplicateKey {
+        key: String,
+        first: PathBuf,
+    },
+    /// The value is rejected by the validator.
+    Invalid(String),
+    /// The file cannot be watched for changes.
+    #[cfg(feature = "with-watch")]
+    Watch(notify::Error),
+}
+
+impl Display for LoadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "unable to read the file: {err}"),
+            Self::Json(err) => {
+                let location = (err.line(), err.column());
+                write!(f, "invalid JSON: {}", strip_location(err, location))
+            }
+            #[cfg(feature = "with-yaml")]
+            Self::Yaml(err) => match err.location() {
+                Some(loc) => {
+                    let location = (loc.line(), loc.column());
+                    write!(f, "invalid YAML: {}", strip_location(err, location))
+                }
+                None => write!(f, "invalid YAML: {err}"),
+            },
+            Self::UnknownFormat => write!(f, "unable to determine the file format"),
+            Self::Pattern(err) => write!(f, "invalid glob pattern: {err}"),
+            Self::DuplicateKey { key, first } => {
+                write!(f, "the key '{key}' is already used by {}", first.display())
+            }
+            Self::Invalid(reason) => write!(f, "invalid value: {reason}"),
+            #[cfg(feature = "with-watch")]
+            Self::Watch(err) => write!(f, "unable to watch the file: {err}"),
+        }
+    }
+}
+
+/// Removes the location suffix in the message of a parser error,
+/// which is already shown in the prefix of [LoadError] messages.
+fn strip_location(err: &impl Display, (line, column): (usize, usize)) -> String {
+    let message = err.to_string();
+    let suffix = format!(" at line {line} column {column}");
+    match message.strip_suffix(&suffix) {
+        Some(message) => message.to_string(),
+        None => message,
+    }
+}
@@ -0,0 +1,354 @@
+//! Network addresses and their serialization helpers.
+//!
+//! Malformed addresses are rejected when the configuration is loaded
+//! rather than when a connection is attempted. Host names are checked
+//! to be valid DNS names, but they are not resolved.
+//!
+//! ```rust
+//! # use newslab_serde_common::{ip_addr, net::{Host, HostPort}, socket_addr};
+//! # use serde::{Serialize, Deserialize};
+//! # use std::net::IpAddr;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyLidar {
+//!     #[serde(with = "ip_addr")]
+//!     multicast_group: IpAddr,
+//!     #[serde(with = "socket_addr")]
+//!     endpoint: HostPort,
+//!     #[serde(with = "socket_addr")]
+//!     fallback: HostPort,
+//! }
+//!
+//! let json = r#"{
+//!     "multicast_group": "239.255.0.1",
+//!     "endpoint": "lidar-top.local:2368",
+//!     "fallback": "[fe80::1]:2368"
+//! }"#;
+//! let lidar: MyLidar = serde_json::from_str(json).unwrap();
+//! assert_eq!(lidar.endpoint.host, Host::Domain("lidar-top.local".to_string()));
+//! assert_eq!(lidar.endpoint.port, 2368);
+//! assert!(lidar.fallback.host.is_ip());
+//!
+//! // The port is missing.
+//! let json = r#"{
+//!     "multicast_group": "239.255.0.1",
+//!     "endpoint": "lidar-top.local",
+//!     "fallback": "[fe80::1]:2368"
+//! }"#;
+//! assert!(serde_json::from_str::<MyLidar>(json).is_err());
+//!
+//! // Host names must be valid DNS names.
+//! assert!("lidar_top:2368".parse::<HostPort>().is_err());
+//! assert!("256.0.0.1:2368".parse::<HostPort>().is_err());
+//! ```
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+    io,
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+    str::FromStr,
+    vec,
+};
+
+/// The longest length of a DNS name.
+const MAX_DOMAIN_LEN: usize = 253;
+
+/// The longest length of a label in a DNS name.
+const MAX_LABEL_LEN: usize = 63;
+
+/// A host given by an IP address or a DNS name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Host {
+    Ip(IpAddr),
+    Domain(String),
+}
+
+impl Host {
+    pub fn is_ip(&self) -> bool {
+        matches!(self, Self::Ip(_))
+    }
+}
+
+impl FromStr for Host {
+    type Err = ParseAddrError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseAddrError {
+            text: text.to_string(),
+            reason,
+        };
+
+        if let Ok(ip) = text.parse() {
+            return Ok(Self::Ip(ip));
+        }
+        if text.contains(':') {
+            return Err(error("it is not a valid IPv6 address"));
+        }
+
+        let name = text.strip_suffix('.').unwrap_or(text);
+        if name.is_empty() {
+            return Err(error("the host is empty"));
+        }
+        if name.len() > MAX_DOMAIN_LEN {
+            return Err(error("the host name is longer than 253 characters"));
+        }
+        for label in name.split('.') {
+            if label.is_empty() || label.len() > MAX_LABEL_LEN {
+                return Err(error(
+                    "each dot-separated part must have 1 to 63 characters",
+                ));
+            }
+            if !label
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+            {
+                return Err(error(
+                    "the host name must consist of ASCII letters, digits, hyphens and dots",
+                ));
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err(error(
+                    "a part of the host name starts or ends with a hyphen",
+                ));
+            }
+        }
+        // Rule out malformed IPv4 addresses like "256.0.0.1".
+        if name
+            .rsplit('.')
+            .next()
+            .is_some_and(|label| label.bytes().all(|byte| byte.is_ascii_digit()))
+        {
+            return Err(error("it is not a valid IPv4 address"));
+        }
+
+        Ok(Self::Domain(text.to_string()))
+    }
+}
+
+impl Display for Host {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Host::Ip(ip) => ip.fmt(f),
+            Host::Domain(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A socket address in `host:port` format, where the host is an IP
+/// address or a DNS name. IPv6 addresses are written in brackets,
+/// like `[::1]:8080`.
+///
+/// It implements [ToSocketAddrs], which resolves the host name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HostPort {
+    pub host: Host,
+    pub port: u16,
+}
+
+impl From<SocketAddr> for HostPort {
+    fn from(addr: SocketAddr) -> Self {
+        Self {
+            host: Host::Ip(addr.ip()),
+            port: addr.port(),
+        }
+    }
+}
+
+impl FromStr for HostPort {
+    type Err = ParseAddrError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseAddrError {
+            text: text.to_string(),
+            reason,
+        };
+
+        let (host, port) = if let Some(rest) = text.strip_prefix('[') {
+            let (ip, port) = rest
+                .split_once("]:")
+                .ok_or_else(|| error("it must be in '[ipv6]:port' format"))?;
+            let ip = ip
+                .parse::<IpAddr>()
+                .ok()
+                .filter(IpAddr::is_ipv6)
+                .ok_or_else(|| error("it is not a valid IPv6 address in brackets"))?;
+            (Host::Ip(ip), port)
+        } else {
+            let (host, port) = text
+                .rsplit_once(':')
+                .ok_or_else(|| error("it must be in 'host:port' format"))?;
+            if host.contains(':') {
+                return Err(error("IPv6 addresses must be written in brackets"));
+            }
+            let host = host.parse().map_err(|err: ParseAddrError| ParseAddrError {
+                text: text.to_string(),
+                reason: err.reason,
+            })?;
+            (host, port)
+        };
+        let port = port
+            .parse()
+            .map_err(|_| error("the port must be an integer within 0 and 65535"))?;
+
+        Ok(Self { host, port })
+    }
+}
+
+impl Display for HostPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { host, port } = self;
+        match host {
+            Host::Ip(IpAddr::V6(ip)) => write!(f, "[{ip}]:{port}"),
+            _ => write!(f, "{host}:{port}"),
+        }
+    }
+}
+
+impl ToSocketAddrs for HostPort {
+    type Iter = vec::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        match &self.host {
+            Host::Ip(ip) => Ok(vec![SocketAddr::new(*ip, self.port)].into_iter()),
+            Host::Domain(name) => (name.as_str(), self.port).to_socket_addrs(),
+        }
+    }
+}
+
+impl Serialize for HostPort {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HostPort {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Serialization helper for [IpAddr] in the textual form.
+pub mod ip_addr {
+    use super::ParseAddrError;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+    use std::net::IpAddr;
+
+    pub fn serialize<S>(ip: &IpAddr, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ip.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<IpAddr, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(|_| {
+            D::Error::custom(ParseAddrError {
+                text,
+                reason: "it must be an IPv4 or IPv6 address",
+            })
+        })
+    }
+}
+
+/// Serialization helper for [HostPort] in `host:port` format, where
+/// the host is an IP address or a DNS name.
+pub mod socket_addr {
+    use super::HostPort;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(addr: &HostPort, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        addr.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HostPort, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        HostPort::deserialize(deserializer)
+    }
+}
+
+/// Serialization helper for [Url](::url::Url) values, which must
+/// have a host, like `"rtsp://192.168.1.10:554/stream"`.
+///
+/// It requires the `with-url` feature.
+///
+/// ```rust
+/// # use newslab_serde_common::url;
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct MyCamera {
+///     #[serde(with = "url")]
+///     stream: ::url::Url,
+/// }
+///
+/// let json = r#"{ "stream": "rtsp://192.168.1.10:554/stream" }"#;
+/// let camera: MyCamera = serde_json::from_str(json).unwrap();
+/// assert_eq!(camera.stream.port(), Some(554));
+///
+/// assert!(serde_json::from_str::<MyCamera>(r#"{ "stream": "192.168.1.10:554" }"#).is_err());
+/// assert!(serde_json::from_str::<MyCamera>(r#"{ "stream": "file:///dev/video0" }"#).is_err());
+/// ```
+#[cfg(feature = "with-url")]
+pub mod url {
+    use ::url::Url;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(url: &Url, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        url.as_str().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Url, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        let url = Url::parse(&text)
+            .map_err(|err| D::Error::custom(format!("'{text}' is not a valid URL: {err}")))?;
+        if !url.has_host() {
+            return Err(D::Error::custom(format!("the URL '{text}' has no host")));
+        }
+        Ok(url)
+    }
+}
+
+/// The error returned when an address fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAddrError {
+    text: String,
+    reason: &'static str,
+}
+
+impl ParseAddrError {
+    /// Gets the text that fails to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for ParseAddrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, reason } = self;
+        write!(f, "'{text}' is not a valid address: {reason}")
+    }
+}
+
+impl StdError for ParseAddrError {}
@@ -0,0 +1,409 @@
+//! Serialization helpers for numbers within bounds.
+//!
+//! Out-of-range values, including NaN, are rejected on both
+//! serialization and deserialization, except for the `saturating_*`
+//! helpers, which clamp integers into range. The clamping is logged
+//! as a warning when the `log` feature is enabled.
+
+use serde::de::{self, Visitor};
+use std::fmt::{self, Display};
+
+/// Checks the value and formats the error otherwise.
+fn check(value: f64, is_valid: bool, expect: impl Display) -> Result<f64, String> {
+    if is_valid {
+        Ok(value)
+    } else {
+        Err(format!("{expect}, but got {value}"))
+    }
+}
+
+macro_rules! number_helper {
+    ($(#[$attr:meta])* $name:ident, $expect:literal, |$value:ident| $is_valid:expr) => {
+        $(#[$attr])*
+        pub mod $name {
+            use serde::{
+                de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+            };
+
+            fn check($value: f64) -> Result<f64, String> {
+                super::check($value, $is_valid, $expect)
+            }
+
+            pub fn serialize<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                check(*value)
+                    .map_err(S::Error::custom)?
+                    .serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                check(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+number_helper! {
+    /// Serialize or deserialize a positive number.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::positive_f64;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyLens {
+    ///     #[serde(with = "positive_f64")]
+    ///     focal_length: f64,
+    /// }
+    ///
+    /// let lens: MyLens = serde_json::from_str(r#"{ "focal_length": 4.5 }"#).unwrap();
+    /// assert_eq!(lens.focal_length, 4.5);
+    ///
+    /// assert!(serde_json::from_str::<MyLens>(r#"{ "focal_length": 0.0 }"#).is_err());
+    ///
+    /// let lens = MyLens { focal_length: -1.0 };
+    /// assert!(serde_json::to_string(&lens).is_err());
+    /// ```
+    positive_f64, "number must be positive", |value| value > 0.0
+}
+
+number_helper! {
+    /// Serialize or deserialize a non-negative number.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::non_negative_f64;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyFilter {
+    ///     #[serde(with = "non_negative_f64")]
+    ///     noise: f64,
+    /// }
+    ///
+    /// let filter: MyFilter = serde_json::from_str(r#"{ "noise": 0.0 }"#).unwrap();
+    /// assert_eq!(filter.noise, 0.0);
+    ///
+    /// assert!(serde_json::from_str::<MyFilter>(r#"{ "noise": -0.1 }"#).is_err());
+    /// ```
+    non_negative_f64, "number must be non-negative", |value| value >= 0.0
+}
+
+number_helper! {
+    /// Serialize or deserialize a probability within 0 and 1.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::probability;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyDetector {
+    ///     #[serde(with = "probability")]
+    ///     threshold: f64,
+    /// }
+    ///
+    /// let detector: MyDetector = serde_json::from_str(r#"{ "threshold": 1 }"#).unwrap();
+    /// assert_eq!(detector.threshold, 1.0);
+    ///
+    /// assert!(serde_json::from_str::<MyDetector>(r#"{ "threshold": 1.5 }"#).is_err());
+    /// ```
+    probability, "probability must be within 0 and 1", |value| (0.0..=1.0).contains(&value)
+}
+
+number_helper! {
+    /// Serialize or deserialize a percentage within 0 and 100.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::percentage;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyDisk {
+    ///     #[serde(with = "percentage")]
+    ///     usage_limit: f64,
+    /// }
+    ///
+    /// let disk: MyDisk = serde_json::from_str(r#"{ "usage_limit": 90 }"#).unwrap();
+    /// assert_eq!(disk.usage_limit, 90.0);
+    ///
+    /// assert!(serde_json::from_str::<MyDisk>(r#"{ "usage_limit": 101 }"#).is_err());
+    /// ```
+    percentage, "percentage must be within 0 and 100", |value| (0.0..=100.0).contains(&value)
+}
+
+/// Serialize or deserialize a number within inclusive bounds, which
+/// are given as const parameters.
+///
+/// ```rust
+/// # use serde::{Serialize, Deserialize};
+/// # use newslab_serde_common::bounded;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyCamera {
+///     #[serde(
+///         serialize_with = "bounded::serialize::<-90, 90, _>",
+///         deserialize_with = "bounded::deserialize::<-90, 90, _>"
+///     )]
+///     tilt: f64,
+/// }
+///
+/// let camera: MyCamera = serde_json::from_str(r#"{ "tilt": -30.5 }"#).unwrap();
+/// assert_eq!(camera.tilt, -30.5);
+///
+/// assert!(serde_json::from_str::<MyCamera>(r#"{ "tilt": 91 }"#).is_err());
+///
+/// let camera = MyCamera { tilt: f64::NAN };
+/// assert!(serde_json::to_string(&camera).is_err());
+/// ```
+pub mod bounded {
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    fn check<const MIN: i64, const MAX: i64>(value: f64) -> Result<f64, String> {
+        super::check(
+            value,
+            (MIN as f64..=MAX as f64).contains(&value),
+            format_args!("number must be within {MIN} and {MAX}"),
+        )
+    }
+
+    pub fn serialize<const MIN: i64, const MAX: i64, S>(
+        value: &f64,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        check::<MIN, MAX>(*value)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, const MIN: i64, const MAX: i64, D>(
+        deserializer: D,
+    ) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        check::<MIN, MAX>(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Visits any integer and widens it into `i128`. Integers above
+/// `i128::MAX` are saturated.
+struct IntegerVisitor;
+
+impl Visitor<'_> for IntegerVisitor {
+    type Value = i128;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an integer")
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.into())
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.into())
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value.try_into().unwrap_or(i128::MAX))
+    }
+}
+
+macro_rules! non_zero_helper {
+    ($(#[$attr:meta])* $name:ident, $non_zero:ty, $int:ty, $deserialize_int:ident) => {
+        $(#[$attr])*
+        pub mod $name {
+            use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<S>(value: &$non_zero, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                value.get().serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<$non_zero, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let value = deserializer.$deserialize_int(super::IntegerVisitor)?;
+                if value == 0 {
+                    return Err(D::Error::custom("number must be non-zero, but got 0"));
+                }
+                <$int>::try_from(value)
+                    .ok()
+                    .and_then(<$non_zero>::new)
+                    .ok_or_else(|| {
+                        D::Error::custom(format!(
+                            "number must be within 1 and {}, but got {value}",
+                            <$int>::MAX
+                        ))
+                    })
+            }
+        }
+    };
+}
+
+non_zero_helper! {
+    /// Serialize or deserialize a [NonZeroU32](std::num::NonZeroU32),
+    /// such as a port number or a count, with a friendly error on zero.
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU32;
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::non_zero_u32;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyQueue {
+    ///     #[serde(with = "non_zero_u32")]
+    ///     capacity: NonZeroU32,
+    /// }
+    ///
+    /// let queue: MyQueue = serde_json::from_str(r#"{ "capacity": 16 }"#).unwrap();
+    /// assert_eq!(queue.capacity.get(), 16);
+    /// assert_eq!(serde_json::to_string(&queue).unwrap(), r#"{"capacity":16}"#);
+    ///
+    /// let err = serde_json::from_str::<MyQueue>(r#"{ "capacity": 0 }"#).err().unwrap();
+    /// assert!(err.to_string().starts_with("number must be non-zero, but got 0"));
+    ///
+    /// let err = serde_json::from_str::<MyQueue>(r#"{ "capacity": -1 }"#).err().unwrap();
+    /// assert!(err.to_string().starts_with("number must be within 1 and 4294967295, but got -1"));
+    /// ```
+    non_zero_u32, std::num::NonZeroU32, u32, deserialize_u32
+}
+
+non_zero_helper! {
+    /// Serialize or deserialize a [NonZeroU64](std::num::NonZeroU64)
+    /// like [non_zero_u32](super::non_zero_u32).
+    ///
+    /// ```rust
+    /// # use std::num::NonZeroU64;
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::non_zero_u64;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyLog {
+    ///     #[serde(with = "non_zero_u64")]
+    ///     max_bytes: NonZeroU64,
+    /// }
+    ///
+    /// let log: MyLog = serde_json::from_str(r#"{ "max_bytes": 18446744073709551615 }"#).unwrap();
+    /// assert_eq!(log.max_bytes.get(), u64::MAX);
+    ///
+    /// assert!(serde_json::from_str::<MyLog>(r#"{ "max_bytes": 0 }"#).is_err());
+    /// ```
+    non_zero_u64, std::num::NonZeroU64, u64, deserialize_u64
+}
+
+macro_rules! saturating_helper {
+    ($(#[$attr:meta])* $name:ident, $int:ty, $deserialize_int:ident) => {
+        $(#[$attr])*
+        pub mod $name {
+            use serde::{Deserializer, Serialize, Serializer};
+
+            pub fn serialize<S>(value: &$int, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                value.serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<$int, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let value = deserializer.$deserialize_int(super::IntegerVisitor)?;
+                let clamped = value.clamp(<$int>::MIN.into(), <$int>::MAX.into());
+                #[cfg(feature = "log")]
+                if clamped != value {
+                    log::warn!(
+                        "number {value} is out of the range of {}, and is clamped to {clamped}",
+                        stringify!($int)
+                    );
+                }
+                Ok(clamped as $int)
+            }
+        }
+    };
+}
+
+saturating_helper! {
+    /// Deserialize a `u8`, such as a channel index, which clamps an
+    /// out-of-range integer into range with a warning instead of
+    /// failing. Non-integers are still rejected.
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::saturating_u8;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyLed {
+    ///     #[serde(with = "saturating_u8")]
+    ///     brightness: u8,
+    /// }
+    ///
+    /// let led: MyLed = serde_json::from_str(r#"{ "brightness": 300 }"#).unwrap();
+    /// assert_eq!(led.brightness, 255);
+    ///
+    /// let led: MyLed = serde_json::from_str(r#"{ "brightness": -5 }"#).unwrap();
+    /// assert_eq!(led.brightness, 0);
+    ///
+    /// assert!(serde_json::from_str::<MyLed>(r#"{ "brightness": 1.5 }"#).is_err());
+    /// ```
+    saturating_u8, u8, deserialize_u8
+}
+
+saturating_helper! {
+    /// Deserialize a `u16`, such as a port number, which clamps an
+    /// out-of-range integer like [saturating_u8](super::saturating_u8).
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::saturating_u16;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyServer {
+    ///     #[serde(with = "saturating_u16")]
+    ///     port: u16,
+    /// }
+    ///
+    /// let server: MyServer = serde_json::from_str(r#"{ "port": 70000 }"#).unwrap();
+    /// assert_eq!(server.port, 65535);
+    /// ```
+    saturating_u16, u16, deserialize_u16
+}
+
+saturating_helper! {
+    /// Deserialize a `u32`, such as a count, which clamps an
+    /// out-of-range integer like [saturating_u8](super::saturating_u8).
+    ///
+    /// ```rust
+    /// # use serde::{Serialize, Deserialize};
+    /// # use newslab_serde_common::saturating_u32;
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyRetry {
+    ///     #[serde(with = "saturating_u32")]
+    ///     max_attempts: u32,
+    /// }
+    ///
+    /// let retry: MyRetry = serde_json::from_str(r#"{ "max_attempts": 10000000000 }"#).unwrap();
+    /// assert_eq!(retry.max_attempts, u32::MAX);
+    /// ```
+    saturating_u32, u32, deserialize_u32
+}
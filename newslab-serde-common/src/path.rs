@@ -0,0 +1,207 @@
+//! Serialization helper for file system paths with the expansion of
+//! `~` and environment variables.
+//!
+//! A leading `~` expands to the home directory, and `${NAME}` expands
+//! to the value of the environment variable. Paths are written back
+//! as they are. The [existing_file] and [existing_dir] variants also
+//! check that the expanded path exists, so that a misplaced file is
+//! reported when the configuration is loaded.
+//!
+//! ```rust
+//! # use newslab_serde_common::path;
+//! # use serde::{Serialize, Deserialize};
+//! # use std::path::PathBuf;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyCalibration {
+//!     #[serde(with = "path")]
+//!     output: PathBuf,
+//!     #[serde(with = "path::existing_dir")]
+//!     cache: PathBuf,
+//! }
+//!
+//! std::env::set_var("MY_CALIB_DIR", "/opt/calib");
+//! let json = r#"{ "output": "${MY_CALIB_DIR}/front.json", "cache": "/tmp" }"#;
+//! let calib: MyCalibration = serde_json::from_str(json).unwrap();
+//! assert_eq!(calib.output, PathBuf::from("/opt/calib/front.json"));
+//!
+//! let json = r#"{ "output": "~/front.json", "cache": "/tmp" }"#;
+//! let calib: MyCalibration = serde_json::from_str(json).unwrap();
+//! assert!(calib.output.is_absolute());
+//!
+//! // The variable is not set.
+//! let json = r#"{ "output": "${MY_UNSET_DIR}/front.json", "cache": "/tmp" }"#;
+//! assert!(serde_json::from_str::<MyCalibration>(json).is_err());
+//!
+//! // The directory does not exist.
+//! let json = r#"{ "output": "front.json", "cache": "/no/such/dir" }"#;
+//! assert!(serde_json::from_str::<MyCalibration>(json).is_err());
+//! ```
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    env,
+    error::Error as StdError,
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+};
+
+pub fn serialize<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    path.serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    expand(&text).map_err(D::Error::custom)
+}
+
+/// Serialization helper for paths to existing files, which expands
+/// paths like [the parent module](self).
+pub mod existing_file {
+    use super::*;
+
+    pub fn serialize<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::serialize(path, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = super::deserialize(deserializer)?;
+        if !path.is_file() {
+            return Err(D::Error::custom(format!(
+                "the file '{}' does not exist",
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+}
+
+/// Serialization helper for paths to existing directories, which
+/// expands paths like [the parent module](self).
+pub mod existing_dir {
+    use super::*;
+
+    pub fn serialize<S>(path: &Path, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::serialize(path, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = super::deserialize(deserializer)?;
+        if !path.is_dir() {
+            return Err(D::Error::custom(format!(
+                "the directory '{}' does not exist",
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+}
+
+/// Expands a leading `~` to the home directory and `${NAME}` to the
+/// value of the environment variable.
+pub fn expand(text: &str) -> Result<PathBuf, ExpandPathError> {
+    let error = |kind| ExpandPathError {
+        text: text.to_string(),
+        kind,
+    };
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    if let Some(tail) = rest.strip_prefix('~') {
+        // Only "~" and "~/..." are expanded, but not "~user".
+        if tail.is_empty() || tail.starts_with(['/', std::path::MAIN_SEPARATOR]) {
+            let home = home_dir().ok_or_else(|| error(ExpandPathErrorKind::NoHomeDir))?;
+            output.push_str(&home);
+            rest = tail;
+        }
+    }
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let tail = &rest[start + 2..];
+        let end = tail
+            .find('}')
+            .ok_or_else(|| error(ExpandPathErrorKind::UnclosedVariable))?;
+        let name = &tail[..end];
+        let value = env::var(name)
+            .map_err(|_| error(ExpandPathErrorKind::UnsetVariable(name.to_string())))?;
+        output.push_str(&value);
+        rest = &tail[end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(PathBuf::from(output))
+}
+
+fn home_dir() -> Option<String> {
+    let name = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env::var(name).ok().filter(|home| !home.is_empty())
+}
+
+/// The error returned by [expand].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandPathError {
+    text: String,
+    kind: ExpandPathErrorKind,
+}
+
+impl ExpandPathError {
+    /// Gets the text that fails to expand.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn kind(&self) -> &ExpandPathErrorKind {
+        &self.kind
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandPathErrorKind {
+    NoHomeDir,
+    UnclosedVariable,
+    UnsetVariable(String),
+}
+
+impl Display for ExpandPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, kind } = self;
+        match kind {
+            ExpandPathErrorKind::NoHomeDir => {
+                write!(
+                    f,
+                    "unable to expand '~' in '{text}': the home directory is unknown"
+                )
+            }
+            ExpandPathErrorKind::UnclosedVariable => {
+                write!(f, "a '${{' in the path '{text}' is not closed by '}}'")
+            }
+            ExpandPathErrorKind::UnsetVariable(name) => {
+                write!(
+                    f,
+                    "the environment variable '{name}' in the path '{text}' is not set"
+                )
+            }
+        }
+    }
+}
+
+impl StdError for ExpandPathError {}
@@ -0,0 +1,123 @@
+//! Serialization helpers for strings matching regular expressions.
+//!
+//! The [pattern_string](crate::pattern_string!) macro generates a
+//! helper module for a pattern, which rejects strings not matching
+//! the whole pattern on both serialization and deserialization. The
+//! regular expression is compiled once on the first use.
+//!
+//! It requires the `with-regex` feature.
+//!
+//! ```rust
+//! # use serde::{Serialize, Deserialize};
+//! use newslab_serde_common::pattern_string;
+//!
+//! pattern_string!(frame_id = "[a-z_][a-z0-9_]*");
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct MySensor {
+//!     #[serde(with = "frame_id")]
+//!     frame: String,
+//! }
+//!
+//! let sensor: MySensor = serde_json::from_str(r#"{ "frame": "lidar_top" }"#).unwrap();
+//! assert_eq!(sensor.frame, "lidar_top");
+//!
+//! // The whole string must match.
+//! assert!(serde_json::from_str::<MySensor>(r#"{ "frame": "lidar top" }"#).is_err());
+//!
+//! let sensor = MySensor { frame: "2nd_camera".to_string() };
+//! assert!(serde_json::to_string(&sensor).is_err());
+//! ```
+
+use regex::Regex;
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::sync::OnceLock;
+
+/// Generates a helper module named `$name` for strings matching the
+/// pattern. See the [module](crate::pattern_string) document.
+#[macro_export]
+macro_rules! pattern_string {
+    ($vis:vis $name:ident = $pattern:literal) => {
+        #[doc = concat!("Serialize or deserialize a string matching `", $pattern, "`.")]
+        $vis mod $name {
+            static PATTERN: $crate::pattern_string::Pattern =
+                $crate::pattern_string::Pattern::new($pattern);
+
+            pub fn serialize<S>(text: &str, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                PATTERN.serialize(text, serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                PATTERN.deserialize(deserializer)
+            }
+        }
+    };
+}
+
+/// A pattern compiled on the first use.
+#[derive(Debug)]
+pub struct Pattern {
+    source: &'static str,
+    regex: OnceLock<Regex>,
+}
+
+impl Pattern {
+    pub const fn new(source: &'static str) -> Self {
+        Self {
+            source,
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Gets the pattern as written.
+    pub fn source(&self) -> &'static str {
+        self.source
+    }
+
+    /// Checks if the whole text matches the pattern.
+    ///
+    /// # Panics
+    /// The function panics if the pattern is not a valid regular
+    /// expression.
+    pub fn is_match(&self, text: &str) -> bool {
+        let regex = self.regex.get_or_init(|| {
+            Regex::new(&format!("^(?:{})$", self.source))
+                .unwrap_or_else(|err| panic!("invalid pattern '{}': {err}", self.source))
+        });
+        regex.is_match(text)
+    }
+
+    fn check(&self, text: &str) -> Result<(), String> {
+        if self.is_match(text) {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{text}' does not match the pattern '{}'",
+                self.source
+            ))
+        }
+    }
+
+    pub fn serialize<S>(&self, text: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.check(text).map_err(S::Error::custom)?;
+        text.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(&self, deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        self.check(&text).map_err(D::Error::custom)?;
+        Ok(text)
+    }
+}
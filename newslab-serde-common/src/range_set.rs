@@ -0,0 +1,328 @@
+//! A set of disjoint ranges, such as the valid azimuth sectors of a
+//! LiDAR.
+
+use crate::serde_bound::{AnyRange, Range, SerializedBound};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    error::Error as StdError,
+    fmt::{self, Display},
+    ops::{Bound, Bound::*},
+    str::FromStr,
+};
+
+/// A union of disjoint ranges in ascending order.
+///
+/// It is serialized as a list of ranges in the same format as
+/// [range](crate::range). The ranges must be non-empty, in ascending
+/// order and not overlapping on deserialization. Adjacent ranges such
+/// as `[0, 90)` and `[90, 180)` are allowed.
+///
+/// ```rust
+/// # use newslab_serde_common::RangeSet;
+/// let json = r#"[{ ">=": 0.0, "<": 90.0 }, "[270, 360)"]"#;
+/// let sectors: RangeSet<f64> = serde_json::from_str(json).unwrap();
+/// assert!(sectors.contains(&45.0));
+/// assert!(!sectors.contains(&180.0));
+/// assert!(!sectors.contains(&360.0));
+///
+/// let json = r#"["[60, 120)"]"#;
+/// let fov: RangeSet<f64> = serde_json::from_str(json).unwrap();
+/// assert_eq!(
+///     serde_json::to_string(&sectors.intersection(&fov)).unwrap(),
+///     r#"[{">":null,">=":60.0,"<":90.0,"<=":null}]"#
+/// );
+/// assert_eq!(sectors.union(&fov).ranges().len(), 2);
+///
+/// // The ranges overlap.
+/// let json = r#"["[0, 90]", "[90, 180)"]"#;
+/// assert!(serde_json::from_str::<RangeSet<f64>>(json).is_err());
+///
+/// // The ranges are not in ascending order.
+/// let json = r#"["[270, 360)", "[0, 90)"]"#;
+/// assert!(serde_json::from_str::<RangeSet<f64>>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> RangeSet<T> {
+    /// Creates an empty set.
+    pub fn empty() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// Gets the ranges in ascending order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn into_ranges(self) -> Vec<Range<T>> {
+        self.ranges
+    }
+}
+
+impl<T> RangeSet<T>
+where
+    T: PartialOrd + Clone,
+{
+    /// Creates a set from ranges that are non-empty, in ascending
+    /// order and not overlapping.
+    pub fn new(ranges: Vec<Range<T>>) -> Result<Self, InvalidRangeSetError> {
+        let error = |index, reason| InvalidRangeSetError { index, reason };
+
+        for (index, range) in ranges.iter().enumerate() {
+            if !is_comparable(range) {
+                return Err(error(index, "has a bound that is not comparable"));
+            }
+            if is_empty(range) {
+                return Err(error(index, "is empty"));
+            }
+        }
+        for (index, pair) in ranges.windows(2).enumerate() {
+            let [prev, next] = pair else { unreachable!() };
+            if cmp_lower(&prev.0, &next.0) != Ordering::Less {
+                return Err(error(index + 1, "is not in ascending order"));
+            }
+            if !is_before(&prev.1, &next.0) {
+                return Err(error(index + 1, "overlaps with the previous range"));
+            }
+        }
+
+        Ok(Self { ranges })
+    }
+
+    /// Creates the set covering any of the ranges, which can be in
+    /// any order. Empty ranges are ignored.
+    pub fn from_ranges<I>(ranges: I) -> Self
+    where
+        I: IntoIterator<Item = Range<T>>,
+    {
+        let mut ranges: Vec<_> = ranges
+            .into_iter()
+            .filter(|range| !is_empty(range))
+            .collect();
+        ranges.sort_by(|lhs, rhs| cmp_lower(&lhs.0, &rhs.0));
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if !has_gap(&last.1, &range.0) => {
+                    if cmp_upper(&range.1, &last.1) == Ordering::Greater {
+                        last.1 = range.1;
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    /// Checks if any of the ranges contains the value.
+    pub fn contains(&self, value: &T) -> bool {
+        self.ranges.iter().any(|(lower, upper)| {
+            let above = match lower {
+                Unbounded => true,
+                Included(min) => value >= min,
+                Excluded(min) => value > min,
+            };
+            let below = match upper {
+                Unbounded => true,
+                Included(max) => value <= max,
+                Excluded(max) => value < max,
+            };
+            above && below
+        })
+    }
+
+    /// Computes the set covering values in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).cloned())
+    }
+
+    /// Computes the set covering values in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut lhs = self.ranges.iter().peekable();
+        let mut rhs = other.ranges.iter().peekable();
+
+        while let (Some(&a), Some(&b)) = (lhs.peek(), rhs.peek()) {
+            let lower = match cmp_lower(&a.0, &b.0) {
+                Ordering::Less => &b.0,
+                _ => &a.0,
+            };
+            let (upper, a_ends_first) = match cmp_upper(&a.1, &b.1) {
+                Ordering::Greater => (&b.1, false),
+                _ => (&a.1, true),
+            };
+
+            let range = (lower.clone(), upper.clone());
+            if !is_empty(&range) {
+                ranges.push(range);
+            }
+
+            if a_ends_first {
+                lhs.next();
+            } else {
+                rhs.next();
+            }
+        }
+
+        Self { ranges }
+    }
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> Serialize for RangeSet<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.ranges.iter().map(SerializedBound::from_bound))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RangeSet<T>
+where
+    T: Deserialize<'de> + FromStr + PartialOrd + Clone,
+    T::Err: Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ranges = Vec::<AnyRange<T>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|AnyRange(range)| range)
+            .collect();
+        Self::new(ranges).map_err(D::Error::custom)
+    }
+}
+
+/// The error returned by [RangeSet::new].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRangeSetError {
+    index: usize,
+    reason: &'static str,
+}
+
+impl InvalidRangeSetError {
+    /// Gets the index of the offending range.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl Display for InvalidRangeSetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { index, reason } = self;
+        write!(f, "the range at {index} {reason}")
+    }
+}
+
+impl StdError for InvalidRangeSetError {}
+
+fn value<T>(bound: &Bound<T>) -> Option<&T> {
+    match bound {
+        Included(val) | Excluded(val) => Some(val),
+        Unbounded => None,
+    }
+}
+
+/// Rules out values like NaN, which cannot be ordered.
+fn is_comparable<T>((lower, upper): &Range<T>) -> bool
+where
+    T: PartialOrd,
+{
+    [lower, upper]
+        .into_iter()
+        .filter_map(value)
+        .all(|val| val.partial_cmp(val).is_some())
+}
+
+fn is_empty<T>(range: &Range<T>) -> bool
+where
+    T: PartialOrd,
+{
+    match range {
+        (Included(min), Included(max)) => min > max,
+        (Included(min) | Excluded(min), Included(max) | Excluded(max)) => min >= max,
+        _ => false,
+    }
+}
+
+/// Compares lower bounds, where a lower bound that admits more values
+/// is less.
+fn cmp_lower<T>(lhs: &Bound<T>, rhs: &Bound<T>) -> Ordering
+where
+    T: PartialOrd,
+{
+    match (lhs, rhs) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Less,
+        (_, Unbounded) => Ordering::Greater,
+        (Included(a), Excluded(b)) if a == b => Ordering::Less,
+        (Excluded(a), Included(b)) if a == b => Ordering::Greater,
+        (Included(a) | Excluded(a), Included(b) | Excluded(b)) => {
+            a.partial_cmp(b).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+/// Compares upper bounds, where an upper bound that admits more values
+/// is greater.
+fn cmp_upper<T>(lhs: &Bound<T>, rhs: &Bound<T>) -> Ordering
+where
+    T: PartialOrd,
+{
+    match (lhs, rhs) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Greater,
+        (_, Unbounded) => Ordering::Less,
+        (Included(a), Excluded(b)) if a == b => Ordering::Greater,
+        (Excluded(a), Included(b)) if a == b => Ordering::Less,
+        (Included(a) | Excluded(a), Included(b) | Excluded(b)) => {
+            a.partial_cmp(b).unwrap_or(Ordering::Equal)
+        }
+    }
+}
+
+/// Checks if every value below the upper bound is also below the
+/// lower bound of the next range, so that the ranges do not overlap.
+fn is_before<T>(upper: &Bound<T>, lower: &Bound<T>) -> bool
+where
+    T: PartialOrd,
+{
+    match (upper, lower) {
+        (Included(max), Included(min)) => max < min,
+        (Included(max) | Excluded(max), Included(min) | Excluded(min)) => max <= min,
+        _ => false,
+    }
+}
+
+/// Checks if some values lie between the upper bound and the lower
+/// bound of the next range, so that the ranges cannot be merged.
+fn has_gap<T>(upper: &Bound<T>, lower: &Bound<T>) -> bool
+where
+    T: PartialOrd,
+{
+    match (upper, lower) {
+        (Excluded(max), Excluded(min)) => max <= min,
+        (Included(max) | Excluded(max), Included(min) | Excluded(min)) => max < min,
+        _ => false,
+    }
+}
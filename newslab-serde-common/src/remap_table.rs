@@ -0,0 +1,214 @@
+//! A table of name remappings, such as the topic remappings in a
+//! launch configuration.
+
+use serde::{
+    de::{Error as _, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error as StdError,
+    fmt::{self, Display},
+};
+
+/// The separator between the source and the target of a rule in the
+/// ROS remapping syntax.
+const SEPARATOR: &str = ":=";
+
+/// An ordered table mapping source names to target names.
+///
+/// Human-readable formats write the table as a list of rules in the
+/// ROS remapping syntax `"from:=to"`, and also accept a map from
+/// sources to targets. The names must not be empty, and neither
+/// sources nor targets may repeat. A target can be remapped again by
+/// another rule, but the rules must not form a cycle.
+///
+/// ```rust
+/// # use newslab_serde_common::RemapTable;
+/// let json = r#"["/camera/image_raw:=/front/image", "/front/image:=/image", "scan:=/lidar/points"]"#;
+/// let table: RemapTable = serde_json::from_str(json).unwrap();
+/// assert_eq!(table.get("/camera/image_raw"), Some("/front/image"));
+/// assert_eq!(table.resolve("/camera/image_raw"), "/image");
+/// assert_eq!(table.resolve("/imu"), "/imu");
+///
+/// let json = r#"{ "scan": "/lidar/points" }"#;
+/// let table: RemapTable = serde_json::from_str(json).unwrap();
+/// assert_eq!(serde_json::to_string(&table).unwrap(), r#"["scan:=/lidar/points"]"#);
+///
+/// // The rules form a cycle.
+/// let json = r#"["a:=b", "b:=c", "c:=a"]"#;
+/// assert!(serde_json::from_str::<RemapTable>(json).is_err());
+///
+/// // Two sources are mapped to the same target.
+/// let json = r#"["a:=c", "b:=c"]"#;
+/// assert!(serde_json::from_str::<RemapTable>(json).is_err());
+///
+/// // The separator is missing.
+/// assert!(serde_json::from_str::<RemapTable>(r#"["a=b"]"#).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RemapTable {
+    rules: Vec<(String, String)>,
+    index: HashMap<String, usize>,
+}
+
+impl RemapTable {
+    /// Creates a table from `(source, target)` rules in order. It
+    /// fails if a name is empty, a source or a target repeats, or
+    /// the rules form a cycle.
+    pub fn new(rules: Vec<(String, String)>) -> Result<Self, InvalidRemapTableError> {
+        let error = |message: String| InvalidRemapTableError { message };
+
+        let mut index = HashMap::with_capacity(rules.len());
+        let mut targets = HashSet::with_capacity(rules.len());
+
+        for (position, (source, target)) in rules.iter().enumerate() {
+            if source.is_empty() || target.is_empty() {
+                return Err(error(format!(
+                    "the rule '{source}:={target}' has an empty name"
+                )));
+            }
+            if index.insert(source.clone(), position).is_some() {
+                return Err(error(format!(
+                    "the source '{source}' is remapped more than once"
+                )));
+            }
+            if !targets.insert(target.as_str()) {
+                return Err(error(format!(
+                    "the target '{target}' is mapped from more than one source"
+                )));
+            }
+        }
+
+        // Since targets are unique, every chain of rules is either a
+        // path or a cycle. A chain longer than the number of rules
+        // must run into a cycle.
+        for (source, _) in &rules {
+            let mut name = source;
+            for _ in 0..rules.len() {
+                match index.get(name) {
+                    Some(&position) => name = &rules[position].1,
+                    None => break,
+                }
+                if name == source {
+                    return Err(error(format!("the remapping of '{source}' forms a cycle")));
+                }
+            }
+        }
+
+        Ok(Self { rules, index })
+    }
+
+    /// Gets the `(source, target)` rules in order.
+    pub fn rules(&self) -> &[(String, String)] {
+        &self.rules
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Gets the target of the rule for the source.
+    pub fn get(&self, source: &str) -> Option<&str> {
+        self.index
+            .get(source)
+            .map(|&position| self.rules[position].1.as_str())
+    }
+
+    /// Follows the rules from the name until the name is not
+    /// remapped any further. The name is returned as is if no rule
+    /// applies.
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        let mut name = name;
+        while let Some(target) = self.get(name) {
+            name = target;
+        }
+        name
+    }
+}
+
+impl Serialize for RemapTable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(
+                self.rules
+                    .iter()
+                    .map(|(source, target)| format!("{source}{SEPARATOR}{target}")),
+            )
+        } else {
+            self.rules.serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RemapTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TableVisitor;
+
+        impl<'de> Visitor<'de> for TableVisitor {
+            type Value = Vec<(String, String)>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(
+                    "a list of strings like \"from:=to\", or a map from sources to targets",
+                )
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut rules = vec![];
+                while let Some(text) = seq.next_element::<String>()? {
+                    let (source, target) = text.split_once(SEPARATOR).ok_or_else(|| {
+                        A::Error::custom(format!("'{text}' must be in 'from:=to' format"))
+                    })?;
+                    rules.push((source.to_string(), target.to_string()));
+                }
+                Ok(rules)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut rules = vec![];
+                while let Some(rule) = map.next_entry()? {
+                    rules.push(rule);
+                }
+                Ok(rules)
+            }
+        }
+
+        let rules = if deserializer.is_human_readable() {
+            deserializer.deserialize_any(TableVisitor)?
+        } else {
+            Vec::deserialize(deserializer)?
+        };
+        Self::new(rules).map_err(D::Error::custom)
+    }
+}
+
+/// The error returned by [RemapTable::new].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRemapTableError {
+    message: String,
+}
+
+impl Display for InvalidRemapTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for InvalidRemapTableError {}
@@ -0,0 +1,177 @@
+//! Self-describing documents with a `$schema` header.
+//!
+//! A type declares its schema id by implementing [Schema]. Wrapping
+//! it in [WithSchema] writes the id to the `$schema` field along with
+//! the fields of the document, and rejects documents with a missing
+//! or different id on deserialization.
+//!
+//! ```rust
+//! # use newslab_serde_common::schema::{Schema, WithSchema};
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct MyCalibration {
+//!     focal: f64,
+//! }
+//!
+//! impl Schema for MyCalibration {
+//!     const SCHEMA: &'static str = "newslab/camera_calibration/v2";
+//! }
+//!
+//! let json = serde_json::to_string(&WithSchema(MyCalibration { focal: 640.0 })).unwrap();
+//! assert_eq!(json, r#"{"$schema":"newslab/camera_calibration/v2","focal":640.0}"#);
+//!
+//! let WithSchema(calib) = serde_json::from_str::<WithSchema<MyCalibration>>(&json).unwrap();
+//! assert_eq!(calib.focal, 640.0);
+//!
+//! let json = r#"{ "$schema": "newslab/camera_calibration/v1", "focal": 640.0 }"#;
+//! assert!(serde_json::from_str::<WithSchema<MyCalibration>>(json).is_err());
+//! ```
+//!
+//! The type of a file is found by [sniff_file] before choosing the
+//! target type. Only the `$schema` field is deserialized.
+//!
+//! ```rust
+//! # use newslab_serde_common::schema::sniff_file;
+//! let path = std::env::temp_dir().join("newslab-serde-schema-doc.json");
+//! std::fs::write(&path, r#"{ "$schema": "newslab/lidar/v1", "rate": 10 }"#).unwrap();
+//!
+//! match sniff_file(&path).unwrap().as_deref() {
+//!     Some("newslab/lidar/v1") => { /* load the lidar config */ }
+//!     Some(other) => panic!("unsupported schema '{other}'"),
+//!     None => panic!("the file has no schema"),
+//! }
+//! ```
+
+#[cfg(feature = "with-load")]
+use crate::load::{self, LoadError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "with-load")]
+use std::path::Path;
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+};
+
+/// A document type with a schema id, like
+/// `"newslab/camera_calibration/v2"`.
+pub trait Schema {
+    const SCHEMA: &'static str;
+}
+
+/// A document with the `$schema` header of its type.
+///
+/// The document must be serialized as a map, such as a struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(
+    try_from = "WithSchemaUnchecked<T>",
+    bound(deserialize = "T: Schema + Deserialize<'de>")
+)]
+pub struct WithSchema<T>(pub T);
+
+impl<T> WithSchema<T> {
+    /// Takes the document out of the wrapper.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Serialize for WithSchema<T>
+where
+    T: Schema + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct WithSchemaRef<'a, T> {
+            #[serde(rename = "$schema")]
+            schema: &'static str,
+            #[serde(flatten)]
+            document: &'a T,
+        }
+
+        WithSchemaRef {
+            schema: T::SCHEMA,
+            document: &self.0,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<T> TryFrom<WithSchemaUnchecked<T>> for WithSchema<T>
+where
+    T: Schema,
+{
+    type Error = SchemaMismatch;
+
+    fn try_from(from: WithSchemaUnchecked<T>) -> Result<Self, Self::Error> {
+        let WithSchemaUnchecked { schema, document } = from;
+
+        if schema.as_deref() != Some(T::SCHEMA) {
+            return Err(SchemaMismatch {
+                expected: T::SCHEMA,
+                found: schema,
+            });
+        }
+        Ok(Self(document))
+    }
+}
+
+#[derive(Deserialize)]
+struct WithSchemaUnchecked<T> {
+    #[serde(rename = "$schema")]
+    schema: Option<String>,
+    #[serde(flatten)]
+    document: T,
+}
+
+/// The error returned when the `$schema` header is missing or does
+/// not match the type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    pub expected: &'static str,
+    pub found: Option<String>,
+}
+
+impl Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { expected, found } = self;
+        match found {
+            Some(found) => write!(f, "expect the schema '{expected}', but found '{found}'"),
+            None => write!(
+                f,
+                "expect the schema '{expected}', but the document has no schema"
+            ),
+        }
+    }
+}
+
+impl StdError for SchemaMismatch {}
+
+/// The `$schema` header of a document. The other fields are ignored.
+#[derive(Deserialize)]
+struct Header {
+    #[serde(rename = "$schema")]
+    schema: Option<String>,
+}
+
+/// Reads the `$schema` header of a document without deserializing
+/// the other fields. It returns `None` if the header is absent.
+pub fn sniff<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Header { schema } = Header::deserialize(deserializer)?;
+    Ok(schema)
+}
+
+/// Reads the `$schema` header of a file like [sniff]. The format is
+/// detected as in [load_file](crate::load::load_file).
+#[cfg(feature = "with-load")]
+pub fn sniff_file(path: impl AsRef<Path>) -> Result<Option<String>, LoadError> {
+    let path = path.as_ref();
+    let text = load::read_file(path)?;
+    let Header { schema } = load::detect(path, &text)?.parse(path, &text)?;
+    Ok(schema)
+}
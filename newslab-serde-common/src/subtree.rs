@@ -0,0 +1,227 @@
+//! Deserializing a subtree of a document selected by a dotted path.
+//!
+//! Path segments name the keys of maps, or the indices of sequences
+//! if they are numbers. Only the selected subtree is deserialized
+//! into the target type. The rest of the document is skipped without
+//! building any values.
+//!
+//! ```rust
+//! # use newslab_serde_common::subtree::get_path;
+//! # use serde::Deserialize;
+//! #[derive(Debug, Deserialize)]
+//! struct MyIntrinsics {
+//!     fx: f64,
+//!     fy: f64,
+//! }
+//!
+//! let json = r#"{
+//!     "name": "rig",
+//!     "sensors": {
+//!         "front_left": { "intrinsics": { "fx": 640.0, "fy": 641.0 } },
+//!         "front_right": { "intrinsics": { "fx": 630.0, "fy": 631.0 } }
+//!     },
+//!     "lidars": [{ "rate": 10 }, { "rate": 20 }]
+//! }"#;
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let intrinsics: MyIntrinsics = get_path(&mut de, "sensors.front_left.intrinsics").unwrap();
+//! assert_eq!(intrinsics.fx, 640.0);
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let rate: u32 = get_path(&mut de, "lidars.1.rate").unwrap();
+//! assert_eq!(rate, 20);
+//!
+//! let mut de = serde_json::Deserializer::from_str(json);
+//! let err = get_path::<MyIntrinsics, _>(&mut de, "sensors.rear").unwrap_err();
+//! assert!(err.to_string().starts_with("the path 'sensors.rear' is not found"));
+//! ```
+
+use serde::{
+    de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{fmt, marker::PhantomData};
+
+/// Deserializes the subtree at the dotted `path`. An empty path
+/// selects the whole document.
+pub fn get_path<'de, T, D>(deserializer: D, path: &str) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let segments: Vec<&str> = if path.is_empty() {
+        vec![]
+    } else {
+        path.split('.').collect()
+    };
+
+    PathSeed {
+        path,
+        segments: &segments,
+        _marker: PhantomData,
+    }
+    .deserialize(deserializer)
+}
+
+/// Selects the subtree at the remaining `segments` of the `path`.
+struct PathSeed<'p, T> {
+    path: &'p str,
+    segments: &'p [&'p str],
+    _marker: PhantomData<T>,
+}
+
+impl<'p, T> PathSeed<'p, T> {
+    fn child(&self) -> Self {
+        Self {
+            path: self.path,
+            segments: &self.segments[1..],
+            _marker: PhantomData,
+        }
+    }
+
+    fn not_found<E>(&self) -> E
+    where
+        E: serde::de::Error,
+    {
+        let depth = self.path.split('.').count() - self.segments.len();
+        let parent = self
+            .path
+            .split('.')
+            .take(depth)
+            .collect::<Vec<_>>()
+            .join(".");
+        if parent.is_empty() {
+            E::custom(format!(
+                "the path '{}' is not found in the document",
+                self.path
+            ))
+        } else {
+            E::custom(format!(
+                "the path '{}' is not found, since '{parent}' has no '{}'",
+                self.path, self.segments[0]
+            ))
+        }
+    }
+}
+
+impl<'de, T> DeserializeSeed<'de> for PathSeed<'_, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if self.segments.is_empty() {
+            T::deserialize(deserializer)
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for PathSeed<'_, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a map or a sequence containing '{}'",
+            self.segments[0]
+        )
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<T, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let segment = self.segments[0];
+        let mut value = None;
+
+        while let Some(matched) = map.next_key_seed(KeySeed(segment))? {
+            if matched && value.is_none() {
+                value = Some(map.next_value_seed(self.child())?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        value.ok_or_else(|| self.not_found())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let Ok(index) = self.segments[0].parse::<usize>() else {
+            return Err(self.not_found());
+        };
+
+        for _ in 0..index {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Err(self.not_found());
+            }
+        }
+        let value = seq
+            .next_element_seed(self.child())?
+            .ok_or_else(|| self.not_found())?;
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+
+        Ok(value)
+    }
+}
+
+/// Checks if a map key equals the path segment without allocating
+/// the key.
+struct KeySeed<'p>(&'p str);
+
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = bool;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl Visitor<'_> for KeySeed<'_> {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string or integer key")
+    }
+
+    fn visit_str<E>(self, key: &str) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(key == self.0)
+    }
+
+    fn visit_u64<E>(self, key: u64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(key.to_string() == self.0)
+    }
+
+    fn visit_i64<E>(self, key: i64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(key.to_string() == self.0)
+    }
+
+    fn visit_bool<E>(self, key: bool) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(key.to_string() == self.0)
+    }
+}
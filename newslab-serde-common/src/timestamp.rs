@@ -0,0 +1,354 @@
+//! Serialization helpers for [SystemTime] timestamps.
+//!
+//! - [rfc3339] writes strings like `"2024-05-01T12:34:56.5Z"`.
+//! - [unix_seconds] writes the seconds since the Unix epoch, which
+//!   may be fractional.
+//! - [unix_millis] writes the integer milliseconds since the Unix
+//!   epoch.
+//!
+//! Times before the epoch are supported and written as negative
+//! numbers.
+//!
+//! ```rust
+//! # use newslab_serde_common::timestamp;
+//! # use serde::{Serialize, Deserialize};
+//! # use std::time::{Duration, SystemTime, UNIX_EPOCH};
+//! #[derive(Serialize, Deserialize)]
+//! struct MySession {
+//!     #[serde(with = "timestamp::rfc3339")]
+//!     start: SystemTime,
+//!     #[serde(with = "timestamp::unix_seconds")]
+//!     end: SystemTime,
+//!     #[serde(with = "timestamp::unix_millis")]
+//!     first_frame: SystemTime,
+//! }
+//!
+//! let json = r#"{
+//!     "start": "2024-05-01T20:00:00+08:00",
+//!     "end": 1714565400.25,
+//!     "first_frame": 1714564800100
+//! }"#;
+//! let session: MySession = serde_json::from_str(json).unwrap();
+//! assert_eq!(session.start, UNIX_EPOCH + Duration::from_secs(1714564800));
+//! assert_eq!(session.end, UNIX_EPOCH + Duration::from_millis(1714565400250));
+//!
+//! let json = serde_json::to_string(&session).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"start":"2024-05-01T12:00:00Z","end":1714565400.25,"first_frame":1714564800100}"#
+//! );
+//! ```
+
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Serialization helper for RFC 3339 timestamps.
+///
+/// Timestamps with any UTC offset are accepted, and they are written
+/// in UTC with the `Z` suffix. The fractional seconds are written only
+/// if they are not zero.
+pub mod rfc3339 {
+    use super::{format_rfc3339, parse_rfc3339};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        format_rfc3339(time).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        parse_rfc3339(&text).map_err(D::Error::custom)
+    }
+}
+
+/// Serialization helper for the seconds since the Unix epoch.
+pub mod unix_seconds {
+    use super::{from_unix_nanos, to_unix_nanos, NANOS_PER_SEC};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let nanos = to_unix_nanos(time);
+        if nanos % NANOS_PER_SEC == 0 {
+            let secs = i64::try_from(nanos / NANOS_PER_SEC).map_err(serde::ser::Error::custom)?;
+            secs.serialize(serializer)
+        } else {
+            let secs = nanos.div_euclid(NANOS_PER_SEC) as f64;
+            let subsec = nanos.rem_euclid(NANOS_PER_SEC) as f64 / NANOS_PER_SEC as f64;
+            (secs + subsec).serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let secs = f64::deserialize(deserializer)?;
+        if !secs.is_finite() {
+            return Err(D::Error::custom(format!("{secs} is not a valid timestamp")));
+        }
+        // Split the integral part to avoid losing the precision of
+        // the fraction, which is below a microsecond for current
+        // timestamps.
+        let whole = secs.floor();
+        let nanos =
+            whole as i128 * NANOS_PER_SEC + ((secs - whole) * NANOS_PER_SEC as f64).round() as i128;
+        from_unix_nanos(nanos).ok_or_else(|| D::Error::custom(format!("{secs} is out of range")))
+    }
+}
+
+/// Serialization helper for the milliseconds since the Unix epoch.
+/// Sub-millisecond precision is truncated toward the past.
+pub mod unix_millis {
+    use super::{from_unix_nanos, to_unix_nanos};
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let millis = to_unix_nanos(time).div_euclid(1_000_000);
+        let millis = i64::try_from(millis).map_err(serde::ser::Error::custom)?;
+        millis.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let millis = i64::deserialize(deserializer)?;
+        from_unix_nanos(i128::from(millis) * 1_000_000)
+            .ok_or_else(|| D::Error::custom(format!("{millis} is out of range")))
+    }
+}
+
+/// Formats the time as an RFC 3339 timestamp in UTC.
+pub fn format_rfc3339(time: &SystemTime) -> String {
+    let nanos = to_unix_nanos(time);
+    let secs = nanos.div_euclid(NANOS_PER_SEC) as i64;
+    let subsec = nanos.rem_euclid(NANOS_PER_SEC) as u32;
+
+    let days = secs.div_euclid(SECS_PER_DAY);
+    let secs_of_day = secs.rem_euclid(SECS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (secs_of_day / 3600, secs_of_day / 60 % 60, secs_of_day % 60);
+
+    let mut text = format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}");
+    if subsec != 0 {
+        let fraction = format!("{subsec:09}");
+        text.push('.');
+        text.push_str(fraction.trim_end_matches('0'));
+    }
+    text.push('Z');
+    text
+}
+
+/// Parses an RFC 3339 timestamp like `"2024-05-01T12:34:56.5+08:00"`.
+///
+/// ```rust
+/// # use newslab_serde_common::timestamp::{format_rfc3339, parse_rfc3339};
+/// # use std::time::{Duration, UNIX_EPOCH};
+/// let time = parse_rfc3339("1969-12-31T23:59:59.5Z").unwrap();
+/// assert_eq!(time, UNIX_EPOCH - Duration::from_millis(500));
+/// assert_eq!(format_rfc3339(&time), "1969-12-31T23:59:59.5Z");
+///
+/// assert!(parse_rfc3339("2024-02-29T00:00:00Z").is_ok());
+/// assert!(parse_rfc3339("2023-02-29T00:00:00Z").is_err());
+/// assert!(parse_rfc3339("2024-05-01 12:00:00").is_err());
+/// ```
+pub fn parse_rfc3339(text: &str) -> Result<SystemTime, ParseTimestampError> {
+    let error = |reason| ParseTimestampError {
+        text: text.to_string(),
+        reason,
+    };
+    let bytes = text.trim().as_bytes();
+
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = bytes.get(range)?;
+        digits
+            .iter()
+            .all(u8::is_ascii_digit)
+            .then(|| std::str::from_utf8(digits).ok()?.parse().ok())
+            .flatten()
+    };
+    let expect = |index: usize, chars: &[u8]| bytes.get(index).is_some_and(|c| chars.contains(c));
+
+    let syntax_error =
+        || error("it must be in the form of 'YYYY-MM-DDTHH:MM:SS[.fraction](Z|+HH:MM)'");
+    if !(expect(4, b"-")
+        && expect(7, b"-")
+        && expect(10, b"Tt ")
+        && expect(13, b":")
+        && expect(16, b":"))
+    {
+        return Err(syntax_error());
+    }
+    let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = (
+        number(0..4),
+        number(5..7),
+        number(8..10),
+        number(11..13),
+        number(14..16),
+        number(17..19),
+    ) else {
+        return Err(syntax_error());
+    };
+
+    // Parse the fractional seconds.
+    let mut index = 19;
+    let mut subsec_nanos: i128 = 0;
+    if bytes.get(index) == Some(&b'.') {
+        index += 1;
+        let start = index;
+        while bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            index += 1;
+        }
+        let digits = &bytes[start..index];
+        if digits.is_empty() {
+            return Err(syntax_error());
+        }
+        for (pos, digit) in digits.iter().take(9).enumerate() {
+            subsec_nanos += i128::from(digit - b'0') * 10i128.pow(8 - pos as u32);
+        }
+    }
+
+    // Parse the UTC offset.
+    let offset_secs = match &bytes[index..] {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), ..] if bytes.len() == index + 6 && expect(index + 3, b":") => {
+            let (Some(offset_hour), Some(offset_minute)) =
+                (number(index + 1..index + 3), number(index + 4..index + 6))
+            else {
+                return Err(syntax_error());
+            };
+            if offset_hour > 23 || offset_minute > 59 {
+                return Err(error("the UTC offset is out of range"));
+            }
+            let offset = offset_hour * 3600 + offset_minute * 60;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return Err(syntax_error()),
+    };
+
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(error("the date does not exist"));
+    }
+    if hour > 23 || minute > 59 {
+        return Err(error("the time of day is out of range"));
+    }
+    if second > 59 {
+        return Err(error("leap seconds are not supported"));
+    }
+
+    let secs =
+        days_from_civil(year, month, day) * SECS_PER_DAY + hour * 3600 + minute * 60 + second
+            - offset_secs;
+    from_unix_nanos(i128::from(secs) * NANOS_PER_SEC + subsec_nanos)
+        .ok_or_else(|| error("the time is out of the range of the system time"))
+}
+
+/// The error returned by [parse_rfc3339].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimestampError {
+    text: String,
+    reason: &'static str,
+}
+
+impl ParseTimestampError {
+    /// Gets the text that fails to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for ParseTimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, reason } = self;
+        write!(f, "'{text}' is not a valid RFC 3339 timestamp: {reason}")
+    }
+}
+
+impl StdError for ParseTimestampError {}
+
+/// Gets the signed nanoseconds since the Unix epoch.
+fn to_unix_nanos(time: &SystemTime) -> i128 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_nanos() as i128,
+        Err(err) => -(err.duration().as_nanos() as i128),
+    }
+}
+
+/// Builds the time from the signed nanoseconds since the Unix epoch.
+fn from_unix_nanos(nanos: i128) -> Option<SystemTime> {
+    let duration = |nanos: u128| {
+        let secs = u64::try_from(nanos / NANOS_PER_SEC as u128).ok()?;
+        Some(Duration::new(secs, (nanos % NANOS_PER_SEC as u128) as u32))
+    };
+
+    if nanos >= 0 {
+        UNIX_EPOCH.checked_add(duration(nanos.unsigned_abs())?)
+    } else {
+        UNIX_EPOCH.checked_sub(duration(nanos.unsigned_abs())?)
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Counts the days from 1970-01-01 to the date in the proleptic
+/// Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Finds the date of the days since 1970-01-01, which is the inverse
+/// of [days_from_civil].
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,266 @@
+//! Semantic versions, like `"1.4.0-rc.1"`.
+//!
+//! Versions follow the [Semantic Versioning 2.0.0](https://semver.org)
+//! syntax and are ordered by their precedence, so that schema versions
+//! can be checked when the configuration is loaded.
+//!
+//! ```rust
+//! # use newslab_serde_common::version::Version;
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct MyConfig {
+//!     schema_version: Version,
+//! }
+//!
+//! let config: MyConfig = serde_json::from_str(r#"{ "schema_version": "1.4.0-rc.1" }"#).unwrap();
+//! assert!(config.schema_version.is_prerelease());
+//! assert!(config.schema_version >= Version::new(1, 3, 2));
+//! assert!(config.schema_version < Version::new(1, 4, 0));
+//! assert!(config.schema_version < "1.4.0-rc.2".parse().unwrap());
+//! assert!(config.schema_version > "1.4.0-beta.11".parse().unwrap());
+//!
+//! // Leading zeros are not allowed.
+//! assert!(serde_json::from_str::<MyConfig>(r#"{ "schema_version": "1.04.0" }"#).is_err());
+//! assert!(serde_json::from_str::<MyConfig>(r#"{ "schema_version": "1.4" }"#).is_err());
+//! ```
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    error::Error as StdError,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A semantic version in `major.minor.patch[-pre][+build]` format.
+///
+/// Versions are ordered by the precedence in the specification. The
+/// build metadata does not affect the precedence, but it is compared
+/// last so that the order agrees with the equality.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: vec![],
+            build: vec![],
+        }
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// Compares the precedence of versions, which ignores the build
+    /// metadata.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+/// A dot-separated identifier of a pre-release version. Numeric
+/// identifiers have lower precedence than alphanumeric ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Identifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(num) => num.fmt(f),
+            Identifier::Alphanumeric(text) => f.write_str(text),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseVersionError {
+            text: text.to_string(),
+            reason,
+        };
+
+        let (rest, build) = match text.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (text, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let mut numbers = core.split('.').map(parse_number);
+        let (Some(major), Some(minor), Some(patch), None) = (
+            numbers.next(),
+            numbers.next(),
+            numbers.next(),
+            numbers.next(),
+        ) else {
+            return Err(error("it must be in 'major.minor.patch' format"));
+        };
+        let (Some(major), Some(minor), Some(patch)) = (major, minor, patch) else {
+            return Err(error(
+                "the major, minor and patch versions must be integers without leading zeros",
+            ));
+        };
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(|ident| {
+                    if !is_identifier(ident) {
+                        return Err(error(
+                            "the pre-release must consist of dot-separated ASCII letters, digits and hyphens",
+                        ));
+                    }
+                    if !ident.bytes().all(|byte| byte.is_ascii_digit()) {
+                        return Ok(Identifier::Alphanumeric(ident.to_string()));
+                    }
+                    parse_number(ident).map(Identifier::Numeric).ok_or_else(|| {
+                        error("numeric pre-release identifiers must not have leading zeros")
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+
+        let build = match build {
+            Some(build) => build
+                .split('.')
+                .map(|ident| {
+                    if !is_identifier(ident) {
+                        return Err(error(
+                            "the build metadata must consist of dot-separated ASCII letters, digits and hyphens",
+                        ));
+                    }
+                    Ok(ident.to_string())
+                })
+                .collect::<Result<_, _>>()?,
+            None => vec![],
+        };
+
+        Ok(Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        } = self;
+        write!(f, "{major}.{minor}.{patch}")?;
+
+        for (index, ident) in pre.iter().enumerate() {
+            let sep = if index == 0 { '-' } else { '.' };
+            write!(f, "{sep}{ident}")?;
+        }
+        if !build.is_empty() {
+            write!(f, "+{}", build.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
+
+/// Parses a decimal integer without leading zeros.
+fn parse_number(text: &str) -> Option<u64> {
+    if text.is_empty()
+        || !text.bytes().all(|byte| byte.is_ascii_digit())
+        || (text.len() > 1 && text.starts_with('0'))
+    {
+        return None;
+    }
+    text.parse().ok()
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+}
+
+/// The error returned when a version fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVersionError {
+    text: String,
+    reason: &'static str,
+}
+
+impl ParseVersionError {
+    /// Gets the text that fails to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for ParseVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, reason } = self;
+        write!(f, "'{text}' is not a valid version: {reason}")
+    }
+}
+
+impl StdError for ParseVersionError {}
@@ -0,0 +1,224 @@
+//! Watching configuration files and reloading them on changes.
+//!
+//! A [ConfigWatcher] loads the file once and reloads it whenever the
+//! file is written or replaced. Reloaded values pass through the
+//! deserialization and the optional validator before they replace
+//! the current value, so that an invalid edit is reported but never
+//! replaces the last good value.
+//!
+//! ```rust
+//! # use newslab_serde_common::{load::LoadErrorKind, watch::ConfigWatcher};
+//! # use serde::Deserialize;
+//! # use std::time::Duration;
+//! #[derive(Debug, Deserialize)]
+//! struct MyTuning {
+//!     gain: f64,
+//! }
+//!
+//! let dir = std::env::temp_dir().join("newslab-serde-watch-doc");
+//! std::fs::create_dir_all(&dir).unwrap();
+//! let path = dir.join("tuning.json");
+//! std::fs::write(&path, r#"{ "gain": 0.5 }"#).unwrap();
+//!
+//! let mut watcher = ConfigWatcher::with_validator(&path, |tuning: &MyTuning| {
+//!     if tuning.gain > 0.0 {
+//!         Ok(())
+//!     } else {
+//!         Err("gain must be positive")
+//!     }
+//! })
+//! .unwrap();
+//! assert_eq!(watcher.current().gain, 0.5);
+//!
+//! let timeout = Duration::from_secs(5);
+//!
+//! // An invalid edit is reported and the last good value is kept.
+//! std::fs::write(&path, r#"{ "gain": -1.0 }"#).unwrap();
+//! let err = watcher.recv_timeout(timeout).unwrap().unwrap_err();
+//! assert!(matches!(err.kind(), LoadErrorKind::Invalid(_)));
+//! assert_eq!(watcher.current().gain, 0.5);
+//!
+//! // A valid edit replaces the value.
+//! std::fs::write(&path, r#"{ "gain": 0.8 }"#).unwrap();
+//! let tuning = watcher.recv_timeout(timeout).unwrap().unwrap();
+//! assert_eq!(tuning.gain, 0.8);
+//! assert_eq!(watcher.current().gain, 0.8);
+//! ```
+
+use crate::load::{self, LoadError, LoadErrorKind};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use serde::de::DeserializeOwned;
+use std::{
+    ffi::OsString,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// The time to wait for further events after a change, so that a
+/// file is reloaded once its writer finishes.
+const SETTLE_TIME: Duration = Duration::from_millis(100);
+
+/// Keeps the last good value of a configuration file and receives
+/// the updates when the file changes.
+///
+/// The file is watched until the watcher is dropped.
+pub struct ConfigWatcher<T> {
+    path: PathBuf,
+    current: T,
+    updates: Receiver<Result<T, LoadError>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl<T> ConfigWatcher<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    /// Loads the file and starts watching it. The format is detected
+    /// as in [load_file](crate::load::load_file).
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        Self::with_validator(path, |_: &T| Ok::<_, &str>(()))
+    }
+
+    /// Loads the file and starts watching it. Loaded values are
+    /// additionally checked by `validate`, and the values it rejects
+    /// are reported as [LoadErrorKind::Invalid].
+    ///
+    /// It fails if the initial value cannot be loaded or is rejected.
+    pub fn with_validator<F, E>(path: impl AsRef<Path>, validate: F) -> Result<Self, LoadError>
+    where
+        F: Fn(&T) -> Result<(), E> + Send + 'static,
+        E: Display,
+    {
+        let path = path.as_ref().to_path_buf();
+        let watch_error = |err| LoadError::new(&path, None, LoadErrorKind::Watch(err));
+
+        let mut last_text = load::read_file(&path)?;
+        let current = parse(&path, &last_text, &validate)?;
+
+        // Watch the parent directory rather than the file, so that
+        // the file is still followed after it is replaced by a rename.
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let file_name: Option<OsString> = path.file_name().map(ToOwned::to_owned);
+
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = event_tx.send(event);
+        })
+        .map_err(watch_error)?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(watch_error)?;
+
+        let (update_tx, updates) = mpsc::channel();
+        let worker_path = path.clone();
+
+        thread::spawn(move || {
+            let path = worker_path;
+
+            while let Ok(event) = event_rx.recv() {
+                let event: Event = match event {
+                    Ok(event) => event,
+                    Err(err) => {
+                        let err = LoadError::new(&path, None, LoadErrorKind::Watch(err));
+                        if update_tx.send(Err(err)).is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let is_written = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+                let is_target = event
+                    .paths
+                    .iter()
+                    .any(|changed| changed.file_name() == file_name.as_deref());
+                if !(is_written && is_target) {
+                    continue;
+                }
+
+                // Skip the burst of events from the same write.
+                while event_rx.recv_timeout(SETTLE_TIME).is_ok() {}
+
+                let result = match load::read_file(&path) {
+                    // The file may be missing in the middle of being
+                    // replaced. It is reloaded once it is created.
+                    Err(_) if !path.exists() => continue,
+                    Err(err) => Err(err),
+                    Ok(text) if text == last_text => continue,
+                    Ok(text) => {
+                        let result = parse(&path, &text, &validate);
+                        last_text = text;
+                        result
+                    }
+                };
+                if update_tx.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            path,
+            current,
+            updates,
+            _watcher: watcher,
+        })
+    }
+}
+
+impl<T> ConfigWatcher<T> {
+    /// Gets the path to the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the last good value.
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// Blocks until the file changes. It returns the new value if it
+    /// replaces the current value, or the error if the change is
+    /// rejected. It returns `None` if the file is no longer watched.
+    pub fn recv(&mut self) -> Option<Result<&T, LoadError>> {
+        let update = self.updates.recv().ok()?;
+        Some(self.apply(update))
+    }
+
+    /// Blocks until the file changes like [recv](Self::recv), or
+    /// returns `None` after the `timeout`.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Option<Result<&T, LoadError>> {
+        let update = self.updates.recv_timeout(timeout).ok()?;
+        Some(self.apply(update))
+    }
+
+    /// Returns the pending update like [recv](Self::recv) without
+    /// blocking, or `None` if there is none.
+    pub fn try_recv(&mut self) -> Option<Result<&T, LoadError>> {
+        let update = self.updates.try_recv().ok()?;
+        Some(self.apply(update))
+    }
+
+    fn apply(&mut self, update: Result<T, LoadError>) -> Result<&T, LoadError> {
+        self.current = update?;
+        Ok(&self.current)
+    }
+}
+
+fn parse<T, F, E>(path: &Path, text: &str, validate: &F) -> Result<T, LoadError>
+where
+    T: DeserializeOwned,
+    F: Fn(&T) -> Result<(), E>,
+    E: Display,
+{
+    let value: T = load::detect(path, text)?.parse(path, text)?;
+    validate(&value)
+        .map_err(|err| LoadError::new(path, None, LoadErrorKind::Invalid(err.to_string())))?;
+    Ok(value)
+}
@@ -0,0 +1,279 @@
+//! Deserialization into a caller-provided bump arena.
+//!
+//! Large tables, such as the nuScenes `calibrated_sensor.json` with
+//! thousands of records, allocate a string for every token when they
+//! are loaded into the owned types. The arena variants of the types
+//! borrow their strings and slices from a [Bump] arena instead, which
+//! is freed at once when it is dropped.
+//!
+//! The arena variants are `CalibratedSensorRef` and `SensorRef` of
+//! the nuScenes tables, and `CameraCalibrationRef` for camera rigs
+//! when the `with-nalgebra` feature is enabled. The intrinsic and
+//! extrinsic parameters own no heap memory, so they are nested as
+//! they are.
+//!
+//! ```rust
+//! # use newslab_serde_cv::{arena, nuscenes::SensorRef};
+//! # use bumpalo::Bump;
+//! let json = r#"[
+//!     { "token": "725903f5b62f56118f4094b46a4470d8", "channel": "CAM_FRONT", "modality": "camera" },
+//!     { "token": "dc8b396651c05aedbb9cdaae573bb567", "channel": "LIDAR_TOP", "modality": "lidar" }
+//! ]"#;
+//!
+//! let bump = Bump::new();
+//! let sensors: &[SensorRef] = arena::from_str_in(json, &bump).unwrap();
+//! assert_eq!(sensors[1].channel, "LIDAR_TOP");
+//!
+//! // Escaped strings are unescaped into the arena.
+//! let channel: &str = arena::from_str_in(r#""CAM\u005FBACK""#, &bump).unwrap();
+//! assert_eq!(channel, "CAM_BACK");
+//! ```
+
+#[cfg(feature = "with-nalgebra")]
+use crate::CameraExtrinsicParams;
+use crate::{CameraIntrinsicParams, ImageSize};
+use bumpalo::{collections::Vec as BumpVec, Bump};
+use serde::de::{DeserializeOwned, DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+use std::{fmt, marker::PhantomData};
+
+/// A type that can be deserialized with its strings and slices
+/// allocated in a bump arena.
+pub trait DeserializeIn<'a>: Sized {
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// The [DeserializeSeed] that deserializes a `T` into the arena. It
+/// is used to nest arena types in hand-written visitors and to use
+/// deserializers other than serde_json.
+pub struct InArena<'a, T> {
+    bump: &'a Bump,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, T> InArena<'a, T> {
+    pub fn new(bump: &'a Bump) -> Self {
+        Self {
+            bump,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, 'a, T> DeserializeSeed<'de> for InArena<'a, T>
+where
+    T: DeserializeIn<'a>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize_in(deserializer, self.bump)
+    }
+}
+
+/// Deserializes a JSON string into the arena.
+pub fn from_str_in<'a, T>(json: &str, bump: &'a Bump) -> serde_json::Result<T>
+where
+    T: DeserializeIn<'a>,
+{
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let value = T::deserialize_in(&mut deserializer, bump)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+impl<'a> DeserializeIn<'a> for &'a str {
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StrVisitor<'a>(&'a Bump);
+
+        impl<'a> Visitor<'_> for StrVisitor<'a> {
+            type Value = &'a str;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(self.0.alloc_str(text))
+            }
+        }
+
+        deserializer.deserialize_str(StrVisitor(bump))
+    }
+}
+
+impl<'a, T> DeserializeIn<'a> for &'a [T]
+where
+    T: DeserializeIn<'a>,
+{
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SliceVisitor<'a, T>(&'a Bump, PhantomData<T>);
+
+        impl<'de, 'a, T> Visitor<'de> for SliceVisitor<'a, T>
+        where
+            T: DeserializeIn<'a> + 'a,
+        {
+            type Value = &'a [T];
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut items = BumpVec::new_in(self.0);
+                while let Some(item) = seq.next_element_seed(InArena::new(self.0))? {
+                    items.push(item);
+                }
+                Ok(items.into_bump_slice())
+            }
+        }
+
+        deserializer.deserialize_seq(SliceVisitor(bump, PhantomData))
+    }
+}
+
+impl<'a, T> DeserializeIn<'a> for Option<T>
+where
+    T: DeserializeIn<'a>,
+{
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct OptionVisitor<'a, T>(&'a Bump, PhantomData<T>);
+
+        impl<'de, 'a, T> Visitor<'de> for OptionVisitor<'a, T>
+        where
+            T: DeserializeIn<'a>,
+        {
+            type Value = Option<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an optional value")
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                T::deserialize_in(deserializer, self.0).map(Some)
+            }
+        }
+
+        deserializer.deserialize_option(OptionVisitor(bump, PhantomData))
+    }
+}
+
+/// Implements [DeserializeIn] for types that own no heap memory. They
+/// are deserialized as they are, so that they can be nested in arena
+/// types.
+macro_rules! owned_deserialize_in {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'a> DeserializeIn<'a> for $ty {
+                fn deserialize_in<'de, D>(
+                    deserializer: D,
+                    _bump: &'a Bump,
+                ) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    <$ty as serde::Deserialize>::deserialize(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+owned_deserialize_in!(ImageSize, CameraIntrinsicParams);
+
+#[cfg(feature = "with-nalgebra")]
+owned_deserialize_in!(CameraExtrinsicParams);
+
+/// The seed that deserializes a sequence of owned values, such as
+/// numbers, into a slice in the arena. The values are restricted to
+/// [Copy] types, since the arena never runs their destructors.
+pub(crate) struct OwnedSliceIn<'a, T>(&'a Bump, PhantomData<T>);
+
+impl<'a, T> OwnedSliceIn<'a, T> {
+    pub fn new(bump: &'a Bump) -> Self {
+        Self(bump, PhantomData)
+    }
+}
+
+impl<'de, 'a, T> DeserializeSeed<'de> for OwnedSliceIn<'a, T>
+where
+    T: DeserializeOwned + Copy + 'a,
+{
+    type Value = &'a [T];
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a, T> Visitor<'de> for OwnedSliceIn<'a, T>
+where
+    T: DeserializeOwned + Copy + 'a,
+{
+    type Value = &'a [T];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = BumpVec::new_in(self.0);
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(items.into_bump_slice())
+    }
+}
+
+/// Stores a field value, or fails if the field was already seen.
+pub(crate) fn set_field<T, E>(slot: &mut Option<T>, value: T, name: &'static str) -> Result<(), E>
+where
+    E: Error,
+{
+    if slot.replace(value).is_some() {
+        return Err(E::duplicate_field(name));
+    }
+    Ok(())
+}
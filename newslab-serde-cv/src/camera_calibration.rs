@@ -0,0 +1,288 @@
+use crate::{CameraExtrinsicParams, CameraIntrinsicParams, ImageSize, Issue, Provenance};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "with-arena")]
+use {
+    crate::{
+        arena::{set_field, DeserializeIn, InArena},
+        ProvenanceRef,
+    },
+    bumpalo::Bump,
+    serde::de::{Deserializer, Error as _, IgnoredAny, MapAccess, Visitor},
+    std::fmt,
+};
+
+/// A complete calibration of a camera, which combines the intrinsic
+/// and extrinsic parameters with the size of captured images. The
+/// optional `provenance` block records the calibration run.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraCalibration;
+/// let json = r#"{
+///     "name": "front",
+///     "frame_id": "camera_front",
+///     "image_size": "1920x1080",
+///     "intrinsics": {
+///         "camera_matrix": [[1000.0, 0.0, 960.0],
+///                           [0.0, 1000.0, 540.0],
+///                           [0.0, 0.0, 1.0]],
+///         "distortion_coefs": [0.1, -0.2, 0.0, 0.0, 0.0]
+///     },
+///     "extrinsics": {
+///         "rotation": { "roll": "-90deg", "pitch": "0deg", "yaw": "-90deg" },
+///         "translation": "[1.5, 0, 1.2] m"
+///     },
+///     "provenance": {
+///         "tool": "kalibr",
+///         "tool_version": "1.0.0",
+///         "date": "2024-03-01"
+///     }
+/// }"#;
+/// let calib: CameraCalibration = serde_json::from_str(json).unwrap();
+/// assert_eq!(calib.name.as_deref(), Some("front"));
+/// assert_eq!(calib.provenance.as_ref().unwrap().tool, "kalibr");
+/// assert_eq!(calib.image_size.width, 1920);
+/// assert!(calib.validate().is_ok());
+///
+/// // The name, the frame ID and the provenance are optional.
+/// let json = r#"{
+///     "image_size": "640x480",
+///     "intrinsics": {
+///         "camera_matrix": [[500.0, 0.0, 960.0],
+///                           [0.0, 500.0, 240.0],
+///                           [0.0, 0.0, 1.0]],
+///         "distortion_coefs": [0.0, 0.0, 0.0, 0.0, 0.0]
+///     },
+///     "extrinsics": {
+///         "rotation": { "roll": "0deg", "pitch": "0deg", "yaw": "0deg" },
+///         "translation": "[0, 0, 0] m"
+///     }
+/// }"#;
+/// let calib: CameraCalibration = serde_json::from_str(json).unwrap();
+/// assert_eq!(calib.frame_id, None);
+/// assert_eq!(calib.provenance, None);
+///
+/// // The principal point is out of the image.
+/// let issues = calib.validate().unwrap_err();
+/// assert_eq!(issues[0].field, "intrinsics.camera_matrix.cx");
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraCalibration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<String>,
+    pub image_size: ImageSize,
+    pub intrinsics: CameraIntrinsicParams,
+    pub extrinsics: CameraExtrinsicParams,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl CameraCalibration {
+    /// Checks the intrinsic parameters against the image size like
+    /// [CameraIntrinsicParams::validate] and the extrinsic parameters
+    /// like [CameraExtrinsicParams::validate]. The fields of the
+    /// issues are prefixed with `"intrinsics."` and `"extrinsics."`.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{CameraCalibration, CameraExtrinsicParams, CameraIntrinsicParams, ImageSize};
+    /// # use nalgebra::{Quaternion, UnitQuaternion};
+    /// let mut calib = CameraCalibration {
+    ///     name: None,
+    ///     frame_id: None,
+    ///     image_size: ImageSize { width: 2, height: 2 },
+    ///     intrinsics: CameraIntrinsicParams::identity(),
+    ///     extrinsics: CameraExtrinsicParams::identity(),
+    ///     provenance: None,
+    /// };
+    /// assert!(calib.validate().is_ok());
+    ///
+    /// // The rotation is not orthonormal.
+    /// calib.extrinsics.rotation = UnitQuaternion::new_unchecked(Quaternion::new(2.0, 0.0, 0.0, 0.0));
+    /// let issues = calib.validate().unwrap_err();
+    /// assert_eq!(issues[0].field, "extrinsics.rotation");
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<Issue>> {
+        let prefix = |prefix: &'static str, issues: Vec<Issue>| {
+            issues.into_iter().map(move |issue| Issue {
+                field: format!("{prefix}.{}", issue.field),
+                ..issue
+            })
+        };
+        let mut issues = vec![];
+        if let Err(found) = self.intrinsics.validate(&self.image_size) {
+            issues.extend(prefix("intrinsics", found));
+        }
+        if let Err(found) = self.extrinsics.validate() {
+            issues.extend(prefix("extrinsics", found));
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+/// A [CameraCalibration] whose strings are allocated in a bump arena,
+/// for rigs with hundreds of cameras. The parameters own no heap
+/// memory and are stored as they are.
+///
+/// ```rust
+/// # use newslab_serde_cv::{arena, CameraCalibration, CameraCalibrationRef};
+/// # use bumpalo::Bump;
+/// let json = r#"[
+///     {
+///         "name": "front",
+///         "frame_id": "camera_front",
+///         "image_size": "1920x1080",
+///         "intrinsics": {
+///             "camera_matrix": [[1000.0, 0.0, 960.0],
+///                               [0.0, 1000.0, 540.0],
+///                               [0.0, 0.0, 1.0]],
+///             "distortion_coefs": [0.1, -0.2, 0.0, 0.0, 0.0]
+///         },
+///         "extrinsics": {
+///             "rotation": { "roll": "-90deg", "pitch": "0deg", "yaw": "-90deg" },
+///             "translation": "[1.5, 0, 1.2] m"
+///         },
+///         "provenance": { "tool": "kalibr", "tool_version": "1.0.0", "date": "2024-03-01" }
+///     },
+///     {
+///         "image_size": "640x480",
+///         "intrinsics": {
+///             "camera_matrix": [[500.0, 0.0, 320.0],
+///                               [0.0, 500.0, 240.0],
+///                               [0.0, 0.0, 1.0]],
+///             "distortion_coefs": [0.0, 0.0, 0.0, 0.0, 0.0]
+///         },
+///         "extrinsics": {
+///             "rotation": { "roll": "0deg", "pitch": "0deg", "yaw": "0deg" },
+///             "translation": "[0, 0, 0] m"
+///         }
+///     }
+/// ]"#;
+///
+/// let bump = Bump::new();
+/// let cameras: &[CameraCalibrationRef] = arena::from_str_in(json, &bump).unwrap();
+/// assert_eq!(cameras[0].name, Some("front"));
+/// assert_eq!(cameras[0].provenance.unwrap().tool, "kalibr");
+/// assert_eq!(cameras[1].frame_id, None);
+///
+/// let owned: Vec<CameraCalibration> = serde_json::from_str(json).unwrap();
+/// let copied: Vec<_> = cameras.iter().map(|camera| camera.clone().into_owned()).collect();
+/// assert_eq!(copied, owned);
+///
+/// // Missing fields are rejected as in the owned type.
+/// let json = r#"{ "name": "front", "image_size": "640x480" }"#;
+/// assert!(arena::from_str_in::<CameraCalibrationRef>(json, &bump).is_err());
+/// ```
+#[cfg(feature = "with-arena")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraCalibrationRef<'a> {
+    pub name: Option<&'a str>,
+    pub frame_id: Option<&'a str>,
+    pub image_size: ImageSize,
+    pub intrinsics: CameraIntrinsicParams,
+    pub extrinsics: CameraExtrinsicParams,
+    pub provenance: Option<ProvenanceRef<'a>>,
+}
+
+#[cfg(feature = "with-arena")]
+impl CameraCalibrationRef<'_> {
+    /// Copies the calibration out of the arena.
+    pub fn into_owned(self) -> CameraCalibration {
+        CameraCalibration {
+            name: self.name.map(str::to_string),
+            frame_id: self.frame_id.map(str::to_string),
+            image_size: self.image_size,
+            intrinsics: self.intrinsics,
+            extrinsics: self.extrinsics,
+            provenance: self.provenance.map(ProvenanceRef::into_owned),
+        }
+    }
+}
+
+#[cfg(feature = "with-arena")]
+impl<'a> DeserializeIn<'a> for CameraCalibrationRef<'a> {
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            Name,
+            FrameId,
+            ImageSize,
+            Intrinsics,
+            Extrinsics,
+            Provenance,
+            #[serde(other)]
+            Other,
+        }
+
+        struct CalibrationVisitor<'a>(&'a Bump);
+
+        impl<'de, 'a> Visitor<'de> for CalibrationVisitor<'a> {
+            type Value = CameraCalibrationRef<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a camera calibration")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut name: Option<Option<&str>> = None;
+                let mut frame_id: Option<Option<&str>> = None;
+                let mut image_size = None;
+                let mut intrinsics = None;
+                let mut extrinsics = None;
+                let mut provenance: Option<Option<ProvenanceRef>> = None;
+
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Name => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut name, value, "name")?;
+                        }
+                        Field::FrameId => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut frame_id, value, "frame_id")?;
+                        }
+                        Field::ImageSize => {
+                            set_field(&mut image_size, map.next_value()?, "image_size")?;
+                        }
+                        Field::Intrinsics => {
+                            set_field(&mut intrinsics, map.next_value()?, "intrinsics")?;
+                        }
+                        Field::Extrinsics => {
+                            set_field(&mut extrinsics, map.next_value()?, "extrinsics")?;
+                        }
+                        Field::Provenance => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut provenance, value, "provenance")?;
+                        }
+                        Field::Other => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(CameraCalibrationRef {
+                    name: name.flatten(),
+                    frame_id: frame_id.flatten(),
+                    image_size: image_size.ok_or_else(|| A::Error::missing_field("image_size"))?,
+                    intrinsics: intrinsics.ok_or_else(|| A::Error::missing_field("intrinsics"))?,
+                    extrinsics: extrinsics.ok_or_else(|| A::Error::missing_field("extrinsics"))?,
+                    provenance: provenance.flatten(),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(CalibrationVisitor(bump))
+    }
+}
@@ -0,0 +1,134 @@
+use crate::Issue;
+use nalgebra::{Isometry3, Matrix3, Translation3, UnitQuaternion, Vector3};
+use newslab_serde_measurements::length;
+use newslab_serde_nalgebra::unit_quaternion_as_euler_angles;
+use serde::{Deserialize, Serialize};
+
+/// Represent extrinsic parameters for a camera, which is the pose of
+/// the camera in the reference frame, such as the vehicle body.
+///
+/// The rotation is written as (roll, pitch, yaw) angles and the
+/// translation as lengths with units.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraExtrinsicParams;
+/// # use nalgebra::{Isometry3, Vector3};
+/// let json = r#"{
+///     "rotation": { "roll": "-90deg", "pitch": "0deg", "yaw": "-90deg" },
+///     "translation": "[1.5, 0, 120] cm"
+/// }"#;
+/// let params: CameraExtrinsicParams = serde_json::from_str(json).unwrap();
+/// assert!((params.translation - Vector3::new(0.015, 0.0, 1.2)).norm() < 1e-12);
+///
+/// // The optical axis points forward.
+/// let forward = params.rotation * Vector3::z();
+/// assert!((forward - Vector3::x()).norm() < 1e-12);
+///
+/// let pose = Isometry3::from(&params);
+/// assert_eq!(pose.translation.vector, params.translation);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CameraExtrinsicParams {
+    #[serde(with = "unit_quaternion_as_euler_angles")]
+    pub rotation: UnitQuaternion<f64>,
+    #[serde(with = "length::vector3")]
+    pub translation: Vector3<f64>,
+}
+
+impl CameraExtrinsicParams {
+    /// The tolerance of the deviation of `RᵀR` from the identity in
+    /// Frobenius norm, where `R` is the rotation matrix.
+    pub const ROTATION_TOLERANCE: f64 = 1e-6;
+
+    pub fn identity() -> Self {
+        Self {
+            rotation: UnitQuaternion::identity(),
+            translation: Vector3::zeros(),
+        }
+    }
+
+    /// Checks that the rotation matrix is orthonormal with a positive
+    /// determinant and that the translation is finite. All found
+    /// issues are returned at once.
+    ///
+    /// Deserialized rotations are always normalized, but the rotation
+    /// field can be built from a quaternion that is not a unit one
+    /// with [UnitQuaternion::new_unchecked].
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::CameraExtrinsicParams;
+    /// # use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+    /// assert!(CameraExtrinsicParams::identity().validate().is_ok());
+    ///
+    /// let params = CameraExtrinsicParams {
+    ///     rotation: UnitQuaternion::new_unchecked(Quaternion::new(1.0, 0.5, 0.0, 0.0)),
+    ///     translation: Vector3::new(f64::NAN, 0.0, 0.0),
+    /// };
+    /// let issues = params.validate().unwrap_err();
+    /// let fields: Vec<_> = issues.iter().map(|issue| issue.field.as_str()).collect();
+    /// assert_eq!(fields, ["rotation", "translation"]);
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<Issue>> {
+        let mut issues = vec![];
+
+        let rot = self.rotation.to_rotation_matrix().into_inner();
+        let error = (rot.transpose() * rot - Matrix3::identity()).norm();
+        if error.is_nan() || error > Self::ROTATION_TOLERANCE {
+            issues.push(Issue::error(
+                "rotation",
+                format!("the rotation matrix is not orthonormal, where |RᵀR - I| = {error}"),
+            ));
+        } else if rot.determinant() <= 0.0 {
+            issues.push(Issue::error(
+                "rotation",
+                "the rotation matrix has a non-positive determinant",
+            ));
+        }
+
+        if !self.translation.iter().all(|value| value.is_finite()) {
+            issues.push(Issue::error(
+                "translation",
+                format!("{:?} is not finite", self.translation.as_slice()),
+            ));
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+impl Default for CameraExtrinsicParams {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl From<&CameraExtrinsicParams> for Isometry3<f64> {
+    fn from(from: &CameraExtrinsicParams) -> Self {
+        Isometry3::from_parts(Translation3::from(from.translation), from.rotation)
+    }
+}
+
+impl From<CameraExtrinsicParams> for Isometry3<f64> {
+    fn from(from: CameraExtrinsicParams) -> Self {
+        (&from).into()
+    }
+}
+
+impl From<&Isometry3<f64>> for CameraExtrinsicParams {
+    fn from(from: &Isometry3<f64>) -> Self {
+        Self {
+            rotation: from.rotation,
+            translation: from.translation.vector,
+        }
+    }
+}
+
+impl From<Isometry3<f64>> for CameraExtrinsicParams {
+    fn from(from: Isometry3<f64>) -> Self {
+        (&from).into()
+    }
+}
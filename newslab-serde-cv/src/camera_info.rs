@@ -0,0 +1,345 @@
+//! ROS `sensor_msgs/CameraInfo` messages in the YAML layout printed
+//! by `rostopic echo` and `ros2 topic echo`.
+//!
+//! The matrices K, R and P are flattened in row-major order. ROS1
+//! writes the upper-case field names `D`, `K`, `R` and `P`, and ROS2
+//! the lower-case ones. Both are accepted, and the upper-case ones
+//! are written.
+//!
+//! ```rust
+//! # use newslab_serde_cv::{camera_info::RosCameraInfo, CameraIntrinsicParams};
+//! let json = r#"{
+//!     "header": { "stamp": { "secs": 0, "nsecs": 0 }, "frame_id": "camera_front" },
+//!     "height": 1080,
+//!     "width": 1920,
+//!     "distortion_model": "plumb_bob",
+//!     "D": [0.1, -0.2, 0.0, 0.0, 0.05],
+//!     "K": [1000.0, 0.0, 960.0, 0.0, 1000.0, 540.0, 0.0, 0.0, 1.0],
+//!     "R": [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+//!     "P": [1000.0, 0.0, 960.0, 0.0, 0.0, 1000.0, 540.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+//!     "binning_x": 0,
+//!     "binning_y": 0,
+//!     "roi": { "x_offset": 0, "y_offset": 0, "height": 0, "width": 0, "do_rectify": false }
+//! }"#;
+//! let info: RosCameraInfo = serde_json::from_str(json).unwrap();
+//! assert_eq!(info.header.frame_id, "camera_front");
+//!
+//! let params = CameraIntrinsicParams::from_ros_camera_info(&info).unwrap();
+//! assert_eq!(params.camera_matrix.cx(), 960.0);
+//! assert_eq!(params.distortion_coefs.coefs()[4], 0.05);
+//!
+//! // The conversion back is lossless.
+//! let size = info.image_size();
+//! let back = params.to_ros_camera_info(&size).unwrap();
+//! assert_eq!(CameraIntrinsicParams::from_ros_camera_info(&back).unwrap(), params);
+//! assert_eq!(back.p, info.p);
+//! ```
+
+#[cfg(feature = "with-nalgebra")]
+use crate::{CameraCalibration, CameraExtrinsicParams};
+use crate::{
+    CameraIntrinsicParams, CameraMatrix, DistortionCoefs, DistortionModel, ImageSize, Roi,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use noisy_float::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A `sensor_msgs/CameraInfo` message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RosCameraInfo {
+    /// The message header, of which only the frame ID is kept.
+    #[serde(default)]
+    pub header: RosHeader,
+    pub height: usize,
+    pub width: usize,
+    pub distortion_model: RosDistortionModel,
+    /// The distortion coefficients, whose number depends on the
+    /// distortion model. An empty vector means no distortion.
+    #[serde(rename = "D", alias = "d")]
+    pub d: Vec<R64>,
+    /// The 3x3 camera matrix of the full-resolution image.
+    #[serde(rename = "K", alias = "k")]
+    pub k: [R64; 9],
+    /// The 3x3 rectification rotation of stereo cameras.
+    #[serde(rename = "R", alias = "r")]
+    pub r: [R64; 9],
+    /// The 3x4 projection matrix of the rectified image.
+    #[serde(rename = "P", alias = "p")]
+    pub p: [R64; 12],
+    /// The horizontal binning factor, where 0 and 1 mean no binning.
+    #[serde(default)]
+    pub binning_x: usize,
+    /// The vertical binning factor, where 0 and 1 mean no binning.
+    #[serde(default)]
+    pub binning_y: usize,
+    #[serde(default)]
+    pub roi: RosRegionOfInterest,
+}
+
+impl RosCameraInfo {
+    /// Gets the size of the published images, which is the size of
+    /// the region of interest divided by the binning factors.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::camera_info::RosCameraInfo;
+    /// let json = r#"{
+    ///     "height": 1080,
+    ///     "width": 1920,
+    ///     "distortion_model": "plumb_bob",
+    ///     "D": [],
+    ///     "K": [1000.0, 0.0, 960.0, 0.0, 1000.0, 540.0, 0.0, 0.0, 1.0],
+    ///     "R": [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+    ///     "P": [1000.0, 0.0, 960.0, 0.0, 0.0, 1000.0, 540.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+    ///     "binning_x": 2,
+    ///     "binning_y": 2,
+    ///     "roi": { "x_offset": 320, "y_offset": 180, "height": 720, "width": 1280 }
+    /// }"#;
+    /// let info: RosCameraInfo = serde_json::from_str(json).unwrap();
+    /// assert_eq!(info.image_size().to_string(), "640x360");
+    /// ```
+    pub fn image_size(&self) -> ImageSize {
+        let (width, height) = if self.roi.is_full() {
+            (self.width, self.height)
+        } else {
+            (self.roi.width, self.roi.height)
+        };
+        ImageSize {
+            width: width / self.binning_x.max(1),
+            height: height / self.binning_y.max(1),
+        }
+    }
+}
+
+/// The header of a message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct RosHeader {
+    #[serde(default)]
+    pub frame_id: String,
+}
+
+/// The region of interest in the full-resolution image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RosRegionOfInterest {
+    pub x_offset: usize,
+    pub y_offset: usize,
+    pub height: usize,
+    pub width: usize,
+    pub do_rectify: bool,
+}
+
+impl RosRegionOfInterest {
+    /// Checks if the region covers the full image, which is written
+    /// as a region of zero size.
+    pub fn is_full(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// The distortion models defined in `sensor_msgs/distortion_models.h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RosDistortionModel {
+    PlumbBob,
+    RationalPolynomial,
+    /// The Kannala-Brandt fisheye model.
+    #[serde(alias = "fisheye")]
+    Equidistant,
+}
+
+impl CameraIntrinsicParams {
+    /// Converts the camera info to the intrinsics of the published
+    /// images. The camera matrix K is cropped to the region of
+    /// interest and then binned. The `equidistant` model becomes the
+    /// Kannala-Brandt model.
+    ///
+    /// It fails if the camera has a rectification rotation, or if the
+    /// number of distortion coefficients does not match the model.
+    pub fn from_ros_camera_info(info: &RosCameraInfo) -> Result<Self> {
+        use slice_of_array::prelude::*;
+
+        let RosCameraInfo {
+            distortion_model,
+            d,
+            k,
+            r,
+            binning_x,
+            binning_y,
+            roi,
+            ..
+        } = info;
+
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        ensure!(
+            r.iter().zip(identity).all(|(&lhs, rhs)| lhs == rhs),
+            "the camera with stereo rectification is not supported"
+        );
+
+        let expected = match distortion_model {
+            RosDistortionModel::PlumbBob => 5,
+            RosDistortionModel::RationalPolynomial => 8,
+            RosDistortionModel::Equidistant => 4,
+        };
+        ensure!(
+            d.len() == expected
+                || (d.is_empty() && *distortion_model == RosDistortionModel::PlumbBob),
+            "expect {expected} {distortion_model:?} distortion coefficients, but get {}",
+            d.len()
+        );
+        let distortion_coefs = match distortion_model {
+            _ if d.is_empty() => DistortionModel::zeros(),
+            RosDistortionModel::PlumbBob => DistortionCoefs(d[..].try_into().unwrap()).into(),
+            RosDistortionModel::RationalPolynomial => {
+                DistortionModel::RationalPolynomial(d[..].try_into().unwrap())
+            }
+            RosDistortionModel::Equidistant => {
+                DistortionModel::KannalaBrandt(d[..].try_into().unwrap())
+            }
+        };
+
+        let rows: &[[R64; 3]] = k.nest();
+        let mut camera_matrix = CameraMatrix::try_from(<[[R64; 3]; 3]>::try_from(rows).unwrap())
+            .map_err(|_| anyhow!("K is not a pinhole camera matrix"))?;
+        if !roi.is_full() {
+            camera_matrix = camera_matrix.cropped(&Roi {
+                x: roi.x_offset,
+                y: roi.y_offset,
+                width: roi.width,
+                height: roi.height,
+            });
+        }
+        let (bx, by) = ((*binning_x).max(1), (*binning_y).max(1));
+        if bx > 1 || by > 1 {
+            camera_matrix = camera_matrix.scaled(1.0 / bx as f64, 1.0 / by as f64)?;
+        }
+
+        Ok(Self {
+            camera_matrix,
+            distortion_coefs,
+        })
+    }
+
+    /// Converts the intrinsics to the camera info of images of the
+    /// given size. The rectification rotation R is the identity, the
+    /// projection matrix P is `K [I | 0]`, and neither binning nor
+    /// region of interest is applied.
+    ///
+    /// It fails if the distortion model has no ROS counterpart.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{CameraIntrinsicParams, ImageSize};
+    /// let json = r#"{
+    ///     "camera_matrix": [[1000.0, 0.0, 960.0],
+    ///                       [0.0, 1000.0, 540.0],
+    ///                       [0.0, 0.0, 1.0]],
+    ///     "distortion_coefs": { "model": "kannala_brandt", "coefs": [0.1, 0.0, 0.0, 0.0] }
+    /// }"#;
+    /// let params: CameraIntrinsicParams = serde_json::from_str(json).unwrap();
+    /// let size = ImageSize { width: 1920, height: 1080 };
+    ///
+    /// let info = params.to_ros_camera_info(&size).unwrap();
+    /// let json = serde_json::to_value(&info).unwrap();
+    /// assert_eq!(json["distortion_model"], "equidistant");
+    /// assert_eq!(json["K"][2], 960.0);
+    ///
+    /// let json = r#"{
+    ///     "camera_matrix": [[1000.0, 0.0, 960.0],
+    ///                       [0.0, 1000.0, 540.0],
+    ///                       [0.0, 0.0, 1.0]],
+    ///     "distortion_coefs": {
+    ///         "model": "thin_prism",
+    ///         "coefs": [0.1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.01, 0.0, 0.0, 0.0]
+    ///     }
+    /// }"#;
+    /// let params: CameraIntrinsicParams = serde_json::from_str(json).unwrap();
+    /// assert!(params.to_ros_camera_info(&size).is_err());
+    /// ```
+    pub fn to_ros_camera_info(&self, image_size: &ImageSize) -> Result<RosCameraInfo> {
+        let Self {
+            camera_matrix,
+            distortion_coefs,
+        } = self;
+
+        let distortion_model = match distortion_coefs {
+            DistortionModel::PlumbBob(_) => RosDistortionModel::PlumbBob,
+            DistortionModel::RationalPolynomial(_) => RosDistortionModel::RationalPolynomial,
+            DistortionModel::KannalaBrandt(_) => RosDistortionModel::Equidistant,
+            model => bail!(
+                "the {} distortion model cannot be represented in ROS camera info",
+                model.name()
+            ),
+        };
+
+        let [[k0, k1, k2], [k3, k4, k5], [k6, k7, k8]] = camera_matrix.0;
+        let zero = r64(0.0);
+        let one = r64(1.0);
+
+        Ok(RosCameraInfo {
+            header: RosHeader::default(),
+            height: image_size.height,
+            width: image_size.width,
+            distortion_model,
+            d: distortion_coefs.coefs().to_vec(),
+            k: [k0, k1, k2, k3, k4, k5, k6, k7, k8],
+            r: [one, zero, zero, zero, one, zero, zero, zero, one],
+            p: [k0, k1, k2, zero, k3, k4, k5, zero, k6, k7, k8, zero],
+            binning_x: 0,
+            binning_y: 0,
+            roi: RosRegionOfInterest::default(),
+        })
+    }
+}
+
+#[cfg(feature = "with-nalgebra")]
+impl CameraCalibration {
+    /// Converts the camera info to a calibration along with the
+    /// extrinsics, which the camera info does not carry. The frame
+    /// ID is taken from the header if it is not empty.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{camera_info::RosCameraInfo, CameraCalibration, CameraExtrinsicParams};
+    /// let json = r#"{
+    ///     "header": { "frame_id": "camera_front" },
+    ///     "height": 480,
+    ///     "width": 640,
+    ///     "distortion_model": "rational_polynomial",
+    ///     "d": [0.1, -0.2, 0.0, 0.0, 0.05, 0.1, -0.2, 0.05],
+    ///     "k": [500.0, 0.0, 320.0, 0.0, 500.0, 240.0, 0.0, 0.0, 1.0],
+    ///     "r": [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+    ///     "p": [500.0, 0.0, 320.0, 0.0, 0.0, 500.0, 240.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+    /// }"#;
+    /// let info: RosCameraInfo = serde_json::from_str(json).unwrap();
+    ///
+    /// let calib =
+    ///     CameraCalibration::from_ros_camera_info(&info, CameraExtrinsicParams::identity()).unwrap();
+    /// assert_eq!(calib.frame_id.as_deref(), Some("camera_front"));
+    /// assert_eq!(calib.intrinsics.distortion_coefs.name(), "rational_polynomial");
+    ///
+    /// assert_eq!(calib.to_ros_camera_info().unwrap(), info);
+    /// ```
+    pub fn from_ros_camera_info(
+        info: &RosCameraInfo,
+        extrinsics: CameraExtrinsicParams,
+    ) -> Result<Self> {
+        let frame_id = &info.header.frame_id;
+
+        Ok(Self {
+            name: None,
+            frame_id: (!frame_id.is_empty()).then(|| frame_id.clone()),
+            image_size: info.image_size(),
+            intrinsics: CameraIntrinsicParams::from_ros_camera_info(info)?,
+            extrinsics,
+            provenance: None,
+        })
+    }
+
+    /// Converts the intrinsics and the image size to the camera info
+    /// like [CameraIntrinsicParams::to_ros_camera_info]. The frame ID
+    /// goes to the header, while the name and the extrinsics are
+    /// left out.
+    pub fn to_ros_camera_info(&self) -> Result<RosCameraInfo> {
+        let mut info = self.intrinsics.to_ros_camera_info(&self.image_size)?;
+        info.header.frame_id = self.frame_id.clone().unwrap_or_default();
+        Ok(info)
+    }
+}
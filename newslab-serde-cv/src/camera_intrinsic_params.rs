@@ -1,8 +1,15 @@
-use crate::{CameraMatrix, DistortionCoefs};
+use crate::{CameraMatrix, DistortionLimits, DistortionModel, ImageSize, Issue};
 use serde::{Deserialize, Serialize};
 
+/// The largest tolerable ratio between fx and fy before a warning is
+/// reported.
+const MAX_FOCAL_LENGTH_RATIO: f64 = 1.5;
+
 /// Represent intrinsic parameters for a camera.
 ///
+/// The distortion coefficients are a bare array of plumb bob
+/// coefficients or any [DistortionModel].
+///
 /// ```rust
 /// # use newslab_serde_cv::CameraIntrinsicParams;
 /// let json = r#"{
@@ -12,18 +19,134 @@ use serde::{Deserialize, Serialize};
 ///     "distortion_coefs": [1.0, 0.0, 0.0, 0.5, 0.0]
 /// }"#;
 /// let params: CameraIntrinsicParams = serde_json::from_str(json).unwrap();
+///
+/// let json = r#"{
+///     "camera_matrix": [[1.0, 0.0, 4.0],
+///                       [0.0, 1.5, 7.0],
+///                       [0.0, 0.0, 1.0]],
+///     "distortion_coefs": { "model": "kannala_brandt", "coefs": [0.1, 0.0, 0.0, 0.0] }
+/// }"#;
+/// let params: CameraIntrinsicParams = serde_json::from_str(json).unwrap();
+/// assert!(params.distortion_coefs.is_fisheye());
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "schemars",
+    derive(schemars::JsonSchema),
+    schemars(description = "The camera matrix and the distortion coefficients of a camera.")
+)]
 pub struct CameraIntrinsicParams {
     pub camera_matrix: CameraMatrix,
-    pub distortion_coefs: DistortionCoefs,
+    pub distortion_coefs: DistortionModel,
 }
 
 impl CameraIntrinsicParams {
     pub fn identity() -> Self {
         Self {
             camera_matrix: CameraMatrix::identity(),
-            distortion_coefs: DistortionCoefs::zeros(),
+            distortion_coefs: DistortionModel::zeros(),
+        }
+    }
+
+    /// Checks the consistency of the parameters against the size of
+    /// images captured by the camera. All found issues are returned
+    /// at once.
+    ///
+    /// - The principal point (cx, cy) must lie within the image.
+    /// - The focal lengths fx and fy must be positive, and they are
+    ///   expected to be similar.
+    /// - The skew is expected to be within
+    ///   [CameraMatrix::DEFAULT_SKEW_TOLERANCE], since most tools
+    ///   ignore it.
+    /// - The distortion coefficients are expected to have sane
+    ///   magnitudes within the lenient [DistortionLimits].
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{CameraIntrinsicParams, ImageSize};
+    /// let json = r#"{
+    ///     "camera_matrix": [[1000.0, 0.0, 2000.0],
+    ///                       [0.0, 1000.0, 540.0],
+    ///                       [0.0, 0.0, 1.0]],
+    ///     "distortion_coefs": [4000.0, 0.0, 0.0, 0.0, 0.0]
+    /// }"#;
+    /// let params: CameraIntrinsicParams = serde_json::from_str(json).unwrap();
+    /// let size = ImageSize {
+    ///     width: 1920,
+    ///     height: 1080,
+    /// };
+    ///
+    /// let issues = params.validate(&size).unwrap_err();
+    /// let fields: Vec<_> = issues.iter().map(|issue| issue.field.as_str()).collect();
+    /// assert_eq!(fields, ["camera_matrix.cx", "distortion_coefs.k1"]);
+    /// ```
+    pub fn validate(&self, image_size: &ImageSize) -> Result<(), Vec<Issue>> {
+        self.validate_with(image_size, &DistortionLimits::lenient())
+    }
+
+    /// Checks the consistency like [validate](Self::validate) with
+    /// the limits of distortion coefficients.
+    pub fn validate_with(
+        &self,
+        image_size: &ImageSize,
+        limits: &DistortionLimits,
+    ) -> Result<(), Vec<Issue>> {
+        let Self {
+            camera_matrix,
+            distortion_coefs,
+        } = self;
+        let mut issues = vec![];
+
+        let fx = camera_matrix.fx().raw();
+        let fy = camera_matrix.fy().raw();
+        let cx = camera_matrix.cx().raw();
+        let cy = camera_matrix.cy().raw();
+
+        if !(0.0..image_size.width as f64).contains(&cx) {
+            issues.push(Issue::error(
+                "camera_matrix.cx",
+                format!("{cx} is out of image width {}", image_size.width),
+            ));
+        }
+        if !(0.0..image_size.height as f64).contains(&cy) {
+            issues.push(Issue::error(
+                "camera_matrix.cy",
+                format!("{cy} is out of image height {}", image_size.height),
+            ));
+        }
+
+        if fx <= 0.0 {
+            issues.push(Issue::error(
+                "camera_matrix.fx",
+                format!("{fx} is not positive"),
+            ));
+        }
+        if fy <= 0.0 {
+            issues.push(Issue::error(
+                "camera_matrix.fy",
+                format!("{fy} is not positive"),
+            ));
+        }
+        if fx > 0.0 && fy > 0.0 && fx.max(fy) / fx.min(fy) > MAX_FOCAL_LENGTH_RATIO {
+            issues.push(Issue::warning(
+                "camera_matrix.fy",
+                format!("fx ({fx}) and fy ({fy}) differ too much"),
+            ));
+        }
+        if camera_matrix.has_skew(CameraMatrix::DEFAULT_SKEW_TOLERANCE) {
+            issues.push(Issue::warning(
+                "camera_matrix.skew",
+                format!("{} is not zero", camera_matrix.skew()),
+            ));
+        }
+
+        if let Err(coef_issues) = distortion_coefs.validate(limits) {
+            issues.extend(coef_issues);
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
         }
     }
 }
@@ -34,28 +157,118 @@ impl Default for CameraIntrinsicParams {
     }
 }
 
-// #[cfg(feature = "with-nalgebra")]
-// impl From<&CameraIntrinsic> for opencv_ros_camera::RosOpenCvIntrinsics<f64> {
-//     fn from(from: &CameraIntrinsic) -> Self {
-//         let CameraIntrinsic {
-//             camera_matrix,
-//             distortion_coefs,
-//         } = from;
-
-//         opencv_ros_camera::RosOpenCvIntrinsics::from_params_with_distortion(
-//             camera_matrix.fx().raw(),
-//             0.0, // skew
-//             camera_matrix.fy().raw(),
-//             camera_matrix.cx().raw(),
-//             camera_matrix.cy().raw(),
-//             distortion_coefs.into(),
-//         )
-//     }
-// }
-
-// #[cfg(feature = "with-nalgebra")]
-// impl From<CameraIntrinsic> for opencv_ros_camera::RosOpenCvIntrinsics<f64> {
-//     fn from(from: CameraIntrinsic) -> Self {
-//         (&from).into()
-//     }
-// }
+/// Builds the 3x4 projection matrix `K [I | 0]` of the camera at the
+/// origin. The distortion coefficients are not involved.
+///
+/// ```rust
+/// # use newslab_serde_cv::{CameraIntrinsicParams, CameraMatrix};
+/// # use noisy_float::prelude::*;
+/// let params = CameraIntrinsicParams {
+///     camera_matrix: CameraMatrix::from_params(r64(1000.0), r64(1000.0), r64(960.0), r64(540.0)),
+///     ..CameraIntrinsicParams::identity()
+/// };
+/// let proj = nalgebra::Matrix3x4::from(&params);
+/// let point = proj * nalgebra::Vector4::new(1.0, 2.0, 10.0, 1.0);
+/// assert_eq!(point.xy() / point.z, nalgebra::Vector2::new(1060.0, 740.0));
+/// ```
+#[cfg(feature = "with-nalgebra")]
+impl From<&CameraIntrinsicParams> for nalgebra::Matrix3x4<f64> {
+    fn from(from: &CameraIntrinsicParams) -> Self {
+        let camera_matrix: nalgebra::Matrix3<f64> = (&from.camera_matrix).into();
+        let mut proj = Self::zeros();
+        proj.fixed_view_mut::<3, 3>(0, 0).copy_from(&camera_matrix);
+        proj
+    }
+}
+
+#[cfg(feature = "with-nalgebra")]
+impl From<CameraIntrinsicParams> for nalgebra::Matrix3x4<f64> {
+    fn from(from: CameraIntrinsicParams) -> Self {
+        (&from).into()
+    }
+}
+
+/// Converts the intrinsics to the plumb bob camera, which fails if
+/// the distortion model is not representable in plumb bob
+/// coefficients.
+#[cfg(feature = "with-opencv-ros-camera")]
+impl TryFrom<&CameraIntrinsicParams> for opencv_ros_camera::RosOpenCvIntrinsics<f64> {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &CameraIntrinsicParams) -> Result<Self, Self::Error> {
+        let CameraIntrinsicParams {
+            camera_matrix,
+            distortion_coefs,
+        } = from;
+
+        Ok(
+            opencv_ros_camera::RosOpenCvIntrinsics::from_params_with_distortion(
+                camera_matrix.fx().raw(),
+                camera_matrix.skew().raw(),
+                camera_matrix.fy().raw(),
+                camera_matrix.cx().raw(),
+                camera_matrix.cy().raw(),
+                distortion_coefs.try_into()?,
+            ),
+        )
+    }
+}
+
+#[cfg(feature = "with-opencv-ros-camera")]
+impl TryFrom<CameraIntrinsicParams> for opencv_ros_camera::RosOpenCvIntrinsics<f64> {
+    type Error = anyhow::Error;
+
+    fn try_from(from: CameraIntrinsicParams) -> Result<Self, Self::Error> {
+        (&from).try_into()
+    }
+}
+
+/// Converts the intrinsics back from the `K` matrix and the
+/// distortion. It fails if the camera has stereo rectification,
+/// which cannot be represented.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraIntrinsicParams;
+/// # use opencv_ros_camera::{Distortion, RosOpenCvIntrinsics};
+/// let json = r#"{
+///     "camera_matrix": [[1000.0, 0.0, 960.0],
+///                       [0.0, 1000.0, 540.0],
+///                       [0.0, 0.0, 1.0]],
+///     "distortion_coefs": [0.1, -0.2, 0.01, 0.0, 0.05]
+/// }"#;
+/// let params: CameraIntrinsicParams = serde_json::from_str(json).unwrap();
+///
+/// let intrinsics = RosOpenCvIntrinsics::try_from(&params).unwrap();
+/// assert!(intrinsics.is_opencv_compatible);
+/// assert_eq!(intrinsics.k[(0, 2)], 960.0);
+/// assert_eq!(CameraIntrinsicParams::try_from(&intrinsics).unwrap(), params);
+///
+/// let skewed = RosOpenCvIntrinsics::from_params(1000.0, 0.5, 1000.0, 960.0, 540.0);
+/// let params = CameraIntrinsicParams::try_from(skewed).unwrap();
+/// assert_eq!(params.camera_matrix.skew(), 0.5);
+/// ```
+#[cfg(feature = "with-opencv-ros-camera")]
+impl TryFrom<&opencv_ros_camera::RosOpenCvIntrinsics<f64>> for CameraIntrinsicParams {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &opencv_ros_camera::RosOpenCvIntrinsics<f64>) -> Result<Self, Self::Error> {
+        anyhow::ensure!(
+            from.rect == nalgebra::Matrix3::identity(),
+            "the camera with stereo rectification is not supported"
+        );
+
+        Ok(Self {
+            camera_matrix: (&from.k).try_into()?,
+            distortion_coefs: (&from.distortion).try_into()?,
+        })
+    }
+}
+
+#[cfg(feature = "with-opencv-ros-camera")]
+impl TryFrom<opencv_ros_camera::RosOpenCvIntrinsics<f64>> for CameraIntrinsicParams {
+    type Error = anyhow::Error;
+
+    fn try_from(from: opencv_ros_camera::RosOpenCvIntrinsics<f64>) -> Result<Self, Self::Error> {
+        (&from).try_into()
+    }
+}
@@ -1,10 +1,21 @@
-use anyhow::ensure;
+use crate::{ImageSize, Roi};
+use anyhow::{anyhow, bail, ensure, Result};
 use noisy_float::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{
+        value::MapAccessDeserializer, value::SeqAccessDeserializer, MapAccess, SeqAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize,
+};
+use std::fmt;
 
 /// The camera matrix describes the mapping from 3D world points to 2D
 /// image points. The format is audited during ser/deserialization.
 ///
+/// The matrix may have a skew at (0, 1). Use
+/// [without_skew](Self::without_skew) where the skew is not
+/// supported.
+///
 /// ```rust
 /// # use newslab_serde_cv::CameraMatrix;
 /// let json = "[
@@ -14,6 +25,57 @@ use serde::{Deserialize, Serialize};
 /// ]";
 /// let coefs: CameraMatrix = serde_json::from_str(json).unwrap();
 /// ```
+///
+/// Human-readable formats also accept the named parameters. The
+/// skew defaults to zero.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraMatrix;
+/// let json = r#"{ "fx": 1000.0, "fy": 1000.0, "cx": 959.5, "cy": 539.5, "skew": 1e-9 }"#;
+/// let mat: CameraMatrix = serde_json::from_str(json).unwrap();
+/// assert_eq!(mat.skew(), 1e-9);
+/// assert!(!mat.has_skew(CameraMatrix::DEFAULT_SKEW_TOLERANCE));
+///
+/// let json = r#"{ "fx": 1000.0, "fy": 1000.0, "image_size": "1920x1080" }"#;
+/// let mat: CameraMatrix = serde_json::from_str(json).unwrap();
+/// assert_eq!(mat.cx(), 959.5);
+///
+/// // The focal lengths are given twice.
+/// let json = r#"{ "fx": 1000.0, "fy": 1000.0, "hfov": "90deg", "image_size": "1920x1080" }"#;
+/// assert!(serde_json::from_str::<CameraMatrix>(json).is_err());
+///
+/// // The principal point is missing.
+/// let json = r#"{ "fx": 1000.0, "fy": 1000.0 }"#;
+/// assert!(serde_json::from_str::<CameraMatrix>(json).is_err());
+/// ```
+///
+/// Human-readable formats also accept the fields of view along with
+/// the image size, and the focal lengths are computed from them. If
+/// only one field of view is given, the pixels are assumed to be
+/// square. The principal point defaults to the image center.
+///
+/// The fields of view are angles like `"90deg"`, `"90°"` or
+/// `"1.5rad"`. With the `with-nalgebra` feature, they are parsed like
+/// the `angle` module of newslab-serde-measurements. Without it, only
+/// a number followed by `deg`, `°` or `rad` is accepted.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraMatrix;
+/// let json = r#"{ "hfov": "90deg", "vfov": "60deg", "image_size": "1920x1080" }"#;
+/// let mat: CameraMatrix = serde_json::from_str(json).unwrap();
+/// assert!((mat.fx().raw() - 960.0).abs() < 1e-9);
+/// assert!((mat.fy().raw() - 540.0 * 3f64.sqrt()).abs() < 1e-9);
+/// assert_eq!(mat.cx(), 959.5);
+/// assert_eq!(mat.cy(), 539.5);
+///
+/// let json = r#"{ "hfov": "1.5rad", "image_size": "640x480", "cx": 320.0, "cy": 240.0 }"#;
+/// let mat: CameraMatrix = serde_json::from_str(json).unwrap();
+/// assert_eq!(mat.fx(), mat.fy());
+/// assert_eq!(mat.cx(), 320.0);
+///
+/// // The matrix is always serialized in full.
+/// assert!(serde_json::to_string(&mat).unwrap().starts_with("[["));
+/// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(try_from = "CameraMatrixUnchecked", into = "CameraMatrixUnchecked")]
 pub struct CameraMatrix(pub [[R64; 3]; 3]);
@@ -24,7 +86,79 @@ impl Default for CameraMatrix {
     }
 }
 
+/// Generates pinhole camera matrices with finite parameters and
+/// positive focal lengths.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraMatrix;
+/// # use arbitrary::{Arbitrary, Unstructured};
+/// for byte in [0x00, 0x80, 0xff] {
+///     let data = [byte; 32];
+///     let mut u = Unstructured::new(&data);
+///     let mat = CameraMatrix::arbitrary(&mut u).unwrap();
+///     assert!(mat.fx() > 0.0 && mat.fy() > 0.0);
+/// }
+/// ```
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CameraMatrix {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut param = || -> arbitrary::Result<f64> {
+            let value: f64 = u.arbitrary()?;
+            Ok(if value.is_finite() { value } else { 0.0 })
+        };
+        let mut focal = || -> arbitrary::Result<R64> {
+            let value = param()?.abs();
+            Ok(r64(if value.is_normal() { value } else { 1.0 }))
+        };
+        let fx = focal()?;
+        let fy = focal()?;
+        Ok(Self::from_params(fx, fy, r64(param()?), r64(param()?)))
+    }
+}
+
+/// Accepts the 3x3 matrix, or the map of the named parameters or
+/// the fields of view.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraMatrix;
+/// # use schemars::schema_for;
+/// let schema = serde_json::to_value(schema_for!(CameraMatrix)).unwrap();
+/// assert_eq!(schema["anyOf"][0]["maxItems"], 3);
+/// assert_eq!(schema["anyOf"][0]["items"]["maxItems"], 3);
+///
+/// let fields = &schema["anyOf"][1];
+/// assert_eq!(fields["additionalProperties"], false);
+/// assert!(fields["properties"]["hfov"]["pattern"].as_str().unwrap().ends_with(r"\s*(deg|°|rad)\s*$"));
+/// ```
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for CameraMatrix {
+    fn schema_name() -> String {
+        "CameraMatrix".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{SchemaObject, SubschemaValidation};
+
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    <[[f64; 3]; 3]>::json_schema(gen),
+                    FieldParams::json_schema(gen),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl CameraMatrix {
+    /// The largest skew in pixels that is treated as zero by
+    /// default, which covers the rounding noise emitted by some
+    /// calibration tools.
+    pub const DEFAULT_SKEW_TOLERANCE: f64 = 1e-6;
+
     pub fn identity() -> Self {
         CameraMatrix([
             [r64(1.0), r64(0.0), r64(0.0)],
@@ -33,6 +167,44 @@ impl CameraMatrix {
         ])
     }
 
+    /// Creates a pinhole camera matrix from focal lengths and the
+    /// principal point.
+    pub fn from_params(fx: R64, fy: R64, cx: R64, cy: R64) -> Self {
+        let zero = r64(0.0);
+        CameraMatrix([[fx, zero, cx], [zero, fy, cy], [zero, zero, r64(1.0)]])
+    }
+
+    /// Creates a pinhole camera matrix from the focal lengths and the
+    /// principal point. It fails if a parameter is not finite.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::CameraMatrix;
+    /// let mut mat = CameraMatrix::from_fx_fy_cx_cy(1000.0, 1000.0, 959.5, 539.5).unwrap();
+    /// assert_eq!(mat.skew(), 0.0);
+    ///
+    /// mat.set_skew(0.5).unwrap();
+    /// assert!(mat.has_skew(CameraMatrix::DEFAULT_SKEW_TOLERANCE));
+    /// assert!(mat.without_skew(CameraMatrix::DEFAULT_SKEW_TOLERANCE).is_err());
+    /// assert_eq!(mat.without_skew(1.0).unwrap().skew(), 0.0);
+    ///
+    /// assert!(CameraMatrix::from_fx_fy_cx_cy(f64::NAN, 1000.0, 959.5, 539.5).is_err());
+    /// ```
+    pub fn from_fx_fy_cx_cy(fx: f64, fy: f64, cx: f64, cy: f64) -> Result<Self> {
+        Ok(Self::from_params(
+            finite(fx, "fx")?,
+            finite(fy, "fy")?,
+            finite(cx, "cx")?,
+            finite(cy, "cy")?,
+        ))
+    }
+
+    /// Creates a camera matrix with the skew at (0, 1).
+    pub fn from_params_with_skew(fx: R64, fy: R64, cx: R64, cy: R64, skew: R64) -> Self {
+        let mut mat = Self::from_params(fx, fy, cx, cy);
+        mat.0[0][1] = skew;
+        mat
+    }
+
     pub fn fx(&self) -> R64 {
         self.0[0][0]
     }
@@ -48,6 +220,228 @@ impl CameraMatrix {
     pub fn cy(&self) -> R64 {
         self.0[1][2]
     }
+
+    pub fn skew(&self) -> R64 {
+        self.0[0][1]
+    }
+
+    pub fn set_fx(&mut self, fx: f64) -> Result<()> {
+        self.0[0][0] = finite(fx, "fx")?;
+        Ok(())
+    }
+
+    pub fn set_fy(&mut self, fy: f64) -> Result<()> {
+        self.0[1][1] = finite(fy, "fy")?;
+        Ok(())
+    }
+
+    pub fn set_cx(&mut self, cx: f64) -> Result<()> {
+        self.0[0][2] = finite(cx, "cx")?;
+        Ok(())
+    }
+
+    pub fn set_cy(&mut self, cy: f64) -> Result<()> {
+        self.0[1][2] = finite(cy, "cy")?;
+        Ok(())
+    }
+
+    pub fn set_skew(&mut self, skew: f64) -> Result<()> {
+        self.0[0][1] = finite(skew, "skew")?;
+        Ok(())
+    }
+
+    /// Checks if the magnitude of the skew exceeds the tolerance.
+    pub fn has_skew(&self, tolerance: f64) -> bool {
+        self.skew().abs() > tolerance
+    }
+
+    /// Returns the matrix with the skew set to zero. It fails if the
+    /// skew exceeds the tolerance, so that a real skew is not
+    /// dropped silently.
+    pub fn without_skew(&self, tolerance: f64) -> Result<Self> {
+        ensure!(
+            !self.has_skew(tolerance),
+            "the camera matrix has skew {}, which exceeds the tolerance {tolerance}",
+            self.skew()
+        );
+        let mut mat = self.clone();
+        mat.0[0][1] = r64(0.0);
+        Ok(mat)
+    }
+
+    /// Returns the camera matrix for the image resized by `sx` and
+    /// `sy` factors along x and y axes. It fails if the factors are
+    /// not positive finite numbers, or if the scaled parameters
+    /// overflow.
+    ///
+    /// It follows the OpenCV `resize()` convention that pixel centers
+    /// locate at integer coordinates, that is, the principal point
+    /// is mapped by `c' = (c + 0.5) * s - 0.5`.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::CameraMatrix;
+    /// # use noisy_float::prelude::*;
+    /// let mat = CameraMatrix([
+    ///     [r64(1000.0), r64(0.0), r64(959.5)],
+    ///     [r64(0.0), r64(1000.0), r64(539.5)],
+    ///     [r64(0.0), r64(0.0), r64(1.0)],
+    /// ]);
+    ///
+    /// // Resize 1920x1080 to 960x540.
+    /// let scaled = mat.scaled(0.5, 0.5).unwrap();
+    /// assert_eq!(scaled.fx(), 500.0);
+    /// assert_eq!(scaled.fy(), 500.0);
+    /// assert_eq!(scaled.cx(), 479.5);
+    /// assert_eq!(scaled.cy(), 269.5);
+    ///
+    /// assert!(mat.scaled(0.0, 0.5).is_err());
+    /// assert!(mat.scaled(-1.0, 0.5).is_err());
+    /// assert!(mat.scaled(f64::NAN, 0.5).is_err());
+    /// assert!(mat.scaled(f64::INFINITY, 0.5).is_err());
+    ///
+    /// // The scaled parameters overflow.
+    /// assert!(mat.scaled(1e307, 1.0).is_err());
+    /// ```
+    ///
+    /// `resize()` samples the destination pixel `x'` from the source
+    /// at `(x' + 0.5) / s - 0.5`. A point projected by the scaled
+    /// matrix therefore lands where `resize()` moves the pixel of the
+    /// point projected by the original matrix.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::CameraMatrix;
+    /// # use noisy_float::prelude::*;
+    /// let mat = CameraMatrix([
+    ///     [r64(1200.0), r64(0.0), r64(955.25)],
+    ///     [r64(0.0), r64(1180.0), r64(541.75)],
+    ///     [r64(0.0), r64(0.0), r64(1.0)],
+    /// ]);
+    /// let project = |mat: &CameraMatrix, [x, y, z]: [f64; 3]| {
+    ///     (mat.fx() * x / z + mat.cx(), mat.fy() * y / z + mat.cy())
+    /// };
+    ///
+    /// // Resize 1920x1080 to 1280x720 and 2880x1620.
+    /// for scale in [2.0 / 3.0, 1.5] {
+    ///     let scaled = mat.scaled(scale, scale).unwrap();
+    ///     for point in [[0.3, -0.2, 2.0], [-1.5, 0.8, 4.0]] {
+    ///         let (u, v) = project(&mat, point);
+    ///         let (su, sv) = project(&scaled, point);
+    ///         assert!(((su + 0.5) / scale - 0.5 - u).abs() < 1e-9);
+    ///         assert!(((sv + 0.5) / scale - 0.5 - v).abs() < 1e-9);
+    ///     }
+    /// }
+    /// ```
+    pub fn scaled(&self, sx: f64, sy: f64) -> Result<Self> {
+        ensure!(
+            sx.is_finite() && sy.is_finite() && sx > 0.0 && sy > 0.0,
+            "scaling factors must be positive, but get ({sx}, {sy})"
+        );
+        let [[fx, skew, cx], [_, fy, cy], _] = self.0.map(|row| row.map(|val| val.raw()));
+        let scaled = [
+            fx * sx,
+            skew * sx,
+            (cx + 0.5) * sx - 0.5,
+            fy * sy,
+            (cy + 0.5) * sy - 0.5,
+        ];
+        ensure!(
+            scaled.iter().all(|val| val.is_finite()),
+            "the camera matrix scaled by ({sx}, {sy}) is not finite"
+        );
+        let [fx, skew, cx, fy, cy] = scaled.map(r64);
+
+        Ok(CameraMatrix([
+            [fx, skew, cx],
+            [r64(0.0), fy, cy],
+            [r64(0.0), r64(0.0), r64(1.0)],
+        ]))
+    }
+
+    /// Returns the camera matrix for the image cropped to the region
+    /// of interest.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{CameraMatrix, Roi};
+    /// # use noisy_float::prelude::*;
+    /// let mat = CameraMatrix([
+    ///     [r64(1000.0), r64(0.0), r64(959.5)],
+    ///     [r64(0.0), r64(1000.0), r64(539.5)],
+    ///     [r64(0.0), r64(0.0), r64(1.0)],
+    /// ]);
+    /// let roi = Roi {
+    ///     x: 320,
+    ///     y: 180,
+    ///     width: 1280,
+    ///     height: 720,
+    /// };
+    ///
+    /// let cropped = mat.cropped(&roi);
+    /// assert_eq!(cropped.fx(), 1000.0);
+    /// assert_eq!(cropped.cx(), 639.5);
+    /// assert_eq!(cropped.cy(), 359.5);
+    /// ```
+    ///
+    /// Like `Mat::roi()`, the pixel `(x, y)` of the cropped image is
+    /// the pixel `(x + roi.x, y + roi.y)` of the original image, so
+    /// cropping commutes with integer binning of aligned regions.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{CameraMatrix, Roi};
+    /// # use noisy_float::prelude::*;
+    /// let mat = CameraMatrix([
+    ///     [r64(1000.0), r64(0.0), r64(959.5)],
+    ///     [r64(0.0), r64(1000.0), r64(539.5)],
+    ///     [r64(0.0), r64(0.0), r64(1.0)],
+    /// ]);
+    /// let roi = Roi { x: 320, y: 180, width: 1280, height: 720 };
+    /// let binned_roi = Roi { x: 160, y: 90, width: 640, height: 360 };
+    ///
+    /// let lhs = mat.cropped(&roi).binned(2).unwrap();
+    /// let rhs = mat.binned(2).unwrap().cropped(&binned_roi);
+    /// assert_eq!(lhs, rhs);
+    /// ```
+    pub fn cropped(&self, roi: &Roi) -> Self {
+        let mut mat = self.0;
+        mat[0][2] -= roi.x as f64;
+        mat[1][2] -= roi.y as f64;
+        CameraMatrix(mat)
+    }
+
+    /// Returns the camera matrix for the image binned by `n` pixels
+    /// in both axes. It is equivalent to `self.scaled(1/n, 1/n)`, and
+    /// fails if `n` is zero.
+    ///
+    /// Each binned pixel averages `n`x`n` source pixels like
+    /// `resize()` with `INTER_AREA`, so the binned pixel `x'` centers
+    /// at `n * x' + (n - 1) / 2` in the source image.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::CameraMatrix;
+    /// # use noisy_float::prelude::*;
+    /// let mat = CameraMatrix([
+    ///     [r64(1000.0), r64(0.0), r64(959.5)],
+    ///     [r64(0.0), r64(1000.0), r64(539.5)],
+    ///     [r64(0.0), r64(0.0), r64(1.0)],
+    /// ]);
+    ///
+    /// let binned = mat.binned(2).unwrap();
+    /// assert_eq!(binned, mat.scaled(0.5, 0.5).unwrap());
+    ///
+    /// // The principal point maps back to the same source position.
+    /// for n in [2, 3, 4] {
+    ///     let binned = mat.binned(n).unwrap();
+    ///     let n = n as f64;
+    ///     assert_eq!(binned.cx() * n + (n - 1.0) / 2.0, 959.5);
+    ///     assert_eq!(binned.cy() * n + (n - 1.0) / 2.0, 539.5);
+    /// }
+    ///
+    /// assert!(mat.binned(0).is_err());
+    /// ```
+    pub fn binned(&self, n: usize) -> Result<Self> {
+        ensure!(n > 0, "binning factor must be positive");
+        let scale = 1.0 / n as f64;
+        self.scaled(scale, scale)
+    }
 }
 
 #[cfg(feature = "with-nalgebra")]
@@ -59,6 +453,53 @@ impl From<&CameraMatrix> for nalgebra::Matrix3<f64> {
     }
 }
 
+/// Converts an nalgebra matrix to a camera matrix. The matrix is
+/// audited like deserialization.
+///
+/// ```rust
+/// # use newslab_serde_cv::CameraMatrix;
+/// let mat = nalgebra::Matrix3::new(
+///     1000.0, 0.0, 960.0,
+///     0.0, 1000.0, 540.0,
+///     0.0, 0.0, 1.0,
+/// );
+/// let camera_matrix = CameraMatrix::try_from(&mat).unwrap();
+/// assert_eq!(camera_matrix.cx(), 960.0);
+/// assert_eq!(nalgebra::Matrix3::from(&camera_matrix), mat);
+///
+/// // The bottom row is not [0, 0, 1].
+/// let mat = nalgebra::Matrix3::new(
+///     1000.0, 0.0, 960.0,
+///     0.0, 1000.0, 540.0,
+///     0.0, 0.0, 2.0,
+/// );
+/// assert!(CameraMatrix::try_from(mat).is_err());
+/// ```
+#[cfg(feature = "with-nalgebra")]
+impl TryFrom<&nalgebra::Matrix3<f64>> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &nalgebra::Matrix3<f64>) -> Result<Self, Self::Error> {
+        let mut mat = [[r64(0.0); 3]; 3];
+        for (row, elems) in mat.iter_mut().enumerate() {
+            for (col, elem) in elems.iter_mut().enumerate() {
+                *elem = R64::try_new(from[(row, col)])
+                    .ok_or_else(|| anyhow!("the element at ({row}, {col}) is not finite"))?;
+            }
+        }
+        CameraMatrixUnchecked::Matrix(mat).try_into()
+    }
+}
+
+#[cfg(feature = "with-nalgebra")]
+impl TryFrom<nalgebra::Matrix3<f64>> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: nalgebra::Matrix3<f64>) -> Result<Self, Self::Error> {
+        (&from).try_into()
+    }
+}
+
 #[cfg(feature = "with-opencv")]
 impl From<&CameraMatrix> for opencv::core::Mat {
     fn from(from: &CameraMatrix) -> Self {
@@ -79,9 +520,52 @@ impl From<CameraMatrix> for opencv::core::Mat {
     }
 }
 
+/// Reads a 3x3 `Mat` of `f64` or `f32`, such as the camera matrix
+/// computed by `calibrateCamera()`. The matrix is audited like
+/// deserialization.
+#[cfg(feature = "with-opencv")]
+impl TryFrom<&opencv::core::Mat> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &opencv::core::Mat) -> Result<Self, Self::Error> {
+        let (elements, rows, cols) = crate::mat::read_elements(from)?;
+        ensure!(
+            (rows, cols) == (3, 3),
+            "expect a 3x3 camera matrix, but get {rows}x{cols}"
+        );
+
+        let mut mat = [[r64(0.0); 3]; 3];
+        for (row, chunk) in mat.iter_mut().zip(elements.chunks(3)) {
+            for (elem, &value) in row.iter_mut().zip(chunk) {
+                *elem = r64(value);
+            }
+        }
+        CameraMatrixUnchecked::Matrix(mat).try_into()
+    }
+}
+
+#[cfg(feature = "with-opencv")]
+impl TryFrom<opencv::core::Mat> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: opencv::core::Mat) -> Result<Self, Self::Error> {
+        (&from).try_into()
+    }
+}
+
+/// Converts the rows of a matrix to a camera matrix. The matrix is
+/// audited like deserialization.
+impl TryFrom<[[R64; 3]; 3]> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: [[R64; 3]; 3]) -> Result<Self, Self::Error> {
+        CameraMatrixUnchecked::Matrix(from).try_into()
+    }
+}
+
 impl From<CameraMatrix> for CameraMatrixUnchecked {
     fn from(from: CameraMatrix) -> Self {
-        Self(from.0)
+        Self::Matrix(from.0)
     }
 }
 
@@ -89,18 +573,215 @@ impl TryFrom<CameraMatrixUnchecked> for CameraMatrix {
     type Error = anyhow::Error;
 
     fn try_from(from: CameraMatrixUnchecked) -> Result<Self, Self::Error> {
-        let mat = from.0;
+        let mat = match from {
+            CameraMatrixUnchecked::Matrix(mat) => mat,
+            CameraMatrixUnchecked::Fields(fields) => return fields.try_into(),
+        };
         ensure!(
-            mat[0][1] == 0.0
-                && mat[1][0] == 0.0
-                && mat[2][0] == 0.0
-                && mat[2][1] == 0.0
-                && mat[2][2] == 1.0
+            mat[1][0] == 0.0 && mat[2][0] == 0.0 && mat[2][1] == 0.0 && mat[2][2] == 1.0,
+            "the camera matrix must be in [[fx, skew, cx], [0, fy, cy], [0, 0, 1]] form"
         );
         Ok(Self(mat))
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(untagged)]
+enum CameraMatrixUnchecked {
+    Matrix([[R64; 3]; 3]),
+    Fields(FieldParams),
+}
+
+impl<'de> Deserialize<'de> for CameraMatrixUnchecked {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Accepts the matrix, the named parameters or the fields of
+        /// view.
+        struct UncheckedVisitor;
+
+        impl<'de> Visitor<'de> for UncheckedVisitor {
+            type Value = CameraMatrixUnchecked;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(
+                    "a 3x3 matrix, or a map of the focal lengths or the fields of view \
+                     along with the principal point or the image size",
+                )
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                Deserialize::deserialize(SeqAccessDeserializer::new(seq))
+                    .map(CameraMatrixUnchecked::Matrix)
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                Deserialize::deserialize(MapAccessDeserializer::new(map))
+                    .map(CameraMatrixUnchecked::Fields)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(UncheckedVisitor)
+        } else {
+            Deserialize::deserialize(deserializer).map(Self::Matrix)
+        }
+    }
+}
+
+/// The focal lengths or the fields of view, along with the principal
+/// point or the image size.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(transparent)]
-struct CameraMatrixUnchecked(pub [[R64; 3]; 3]);
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+struct FieldParams {
+    #[serde(default)]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    fx: Option<R64>,
+    #[serde(default)]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    fy: Option<R64>,
+    #[serde(default)]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(regex(
+            pattern = r"^\s*[-+]?(\d+(\.\d*)?|\.\d+)([eE][-+]?\d+)?\s*(deg|°|rad)\s*$"
+        ))
+    )]
+    hfov: Option<String>,
+    #[serde(default)]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(regex(
+            pattern = r"^\s*[-+]?(\d+(\.\d*)?|\.\d+)([eE][-+]?\d+)?\s*(deg|°|rad)\s*$"
+        ))
+    )]
+    vfov: Option<String>,
+    #[serde(default)]
+    image_size: Option<ImageSize>,
+    #[serde(default)]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    cx: Option<R64>,
+    #[serde(default)]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    cy: Option<R64>,
+    #[serde(default)]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<f64>"))]
+    skew: Option<R64>,
+}
+
+impl TryFrom<FieldParams> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: FieldParams) -> Result<Self, Self::Error> {
+        let FieldParams {
+            fx,
+            fy,
+            hfov,
+            vfov,
+            image_size,
+            cx,
+            cy,
+            skew,
+        } = from;
+        if let Some(ImageSize { width, height }) = image_size {
+            ensure!(width > 0 && height > 0, "the image size must not be zero");
+        }
+
+        let (fx, fy) = if fx.is_some() || fy.is_some() {
+            ensure!(
+                hfov.is_none() && vfov.is_none(),
+                "the focal lengths and the fields of view must not be both specified"
+            );
+            let (Some(fx), Some(fy)) = (fx, fy) else {
+                bail!("both fx and fy must be specified");
+            };
+            (fx, fy)
+        } else {
+            let Some(ImageSize { width, height }) = image_size else {
+                bail!("the image size must be specified along with the fields of view");
+            };
+            let (width, height) = (width as f64, height as f64);
+
+            // The focal length that spans half of the image within half of
+            // the field of view.
+            let focal = |fov: &str, len: f64| -> Result<f64> {
+                let fov = parse_fov(fov)?;
+                Ok(len / 2.0 / (fov / 2.0).tan())
+            };
+            let fx = hfov.as_deref().map(|fov| focal(fov, width)).transpose()?;
+            let fy = vfov.as_deref().map(|fov| focal(fov, height)).transpose()?;
+            let (fx, fy) = match (fx, fy) {
+                (Some(fx), Some(fy)) => (fx, fy),
+                (Some(fx), None) => (fx, fx),
+                (None, Some(fy)) => (fy, fy),
+                (None, None) => bail!("at least one of fx, fy, hfov and vfov must be specified"),
+            };
+            (r64(fx), r64(fy))
+        };
+
+        let (cx, cy) = match (cx, cy, image_size) {
+            (Some(cx), Some(cy), _) => (cx, cy),
+            (cx, cy, Some(ImageSize { width, height })) => (
+                cx.unwrap_or_else(|| r64((width as f64 - 1.0) / 2.0)),
+                cy.unwrap_or_else(|| r64((height as f64 - 1.0) / 2.0)),
+            ),
+            _ => bail!("both cx and cy must be specified unless the image size is given"),
+        };
+        let skew = skew.unwrap_or(r64(0.0));
+        Ok(Self::from_params_with_skew(fx, fy, cx, cy, skew))
+    }
+}
+
+/// Converts a parameter to a finite number.
+fn finite(value: f64, name: &str) -> Result<R64> {
+    R64::try_new(value).ok_or_else(|| anyhow!("{name} must be finite, but get {value}"))
+}
+
+/// Parses a field of view to radians with the angle units of the
+/// [angle](newslab_serde_measurements::angle) module.
+#[cfg(feature = "with-nalgebra")]
+fn parse_fov(text: &str) -> Result<f64> {
+    use newslab_serde_measurements::field::Quantity;
+
+    let fov = measurements::Angle::parse(text)
+        .map_err(|_| fov_error(text))?
+        .as_radians();
+    ensure!(fov > 0.0 && fov < std::f64::consts::PI, fov_error(text));
+    Ok(fov)
+}
+
+/// Parses a field of view to radians without the measurements
+/// dependency. It accepts the subset of the angle syntax that is a
+/// number followed by `deg`, `°` or `rad`, with optional whitespace
+/// around them.
+#[cfg(not(feature = "with-nalgebra"))]
+fn parse_fov(text: &str) -> Result<f64> {
+    let trimmed = text.trim();
+    let (value, scale) = if let Some(value) = trimmed.strip_suffix("deg") {
+        (value, std::f64::consts::PI / 180.0)
+    } else if let Some(value) = trimmed.strip_suffix('°') {
+        (value, std::f64::consts::PI / 180.0)
+    } else if let Some(value) = trimmed.strip_suffix("rad") {
+        (value, 1.0)
+    } else {
+        return Err(fov_error(text));
+    };
+    let fov = value.trim().parse::<f64>().map_err(|_| fov_error(text))? * scale;
+    ensure!(fov > 0.0 && fov < std::f64::consts::PI, fov_error(text));
+    Ok(fov)
+}
+
+fn fov_error(text: &str) -> anyhow::Error {
+    anyhow!(
+        "Invalid field of view '{text}'. \
+         It must be an angle within 0 and 180 degrees, for example, '90deg'."
+    )
+}
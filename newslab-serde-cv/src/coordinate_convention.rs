@@ -0,0 +1,384 @@
+use anyhow::{anyhow, bail, ensure, Error, Result};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// The directions of the x, y and z axes of a coordinate frame,
+/// written like `"x-forward,y-left,z-up"` or abbreviated like
+/// `"FLU"`.
+///
+/// The directions are either relative to a body, such as forward
+/// and left, or relative to the earth, such as east and north. Up
+/// and down are shared by both. The axes must be perpendicular, and
+/// the body and earth directions cannot be mixed.
+///
+/// ```rust
+/// # use newslab_serde_cv::{CoordinateConvention, Direction, Handedness};
+/// let convention: CoordinateConvention = serde_json::from_str(r#""x-right,y-down,z-forward""#).unwrap();
+/// assert_eq!(convention, CoordinateConvention::RDF);
+/// assert_eq!(convention.z, Direction::Forward);
+/// assert_eq!(convention.handedness(), Handedness::Right);
+///
+/// let convention: CoordinateConvention = serde_json::from_str(r#""NED""#).unwrap();
+/// assert_eq!(convention, CoordinateConvention::NED);
+/// assert_eq!(serde_json::to_string(&convention).unwrap(), r#""x-north,y-east,z-down""#);
+///
+/// let convention: CoordinateConvention = "x-forward, y-right, z-up".parse().unwrap();
+/// assert_eq!(convention.handedness(), Handedness::Left);
+///
+/// // The x and y axes are parallel.
+/// assert!("x-forward,y-backward,z-up".parse::<CoordinateConvention>().is_err());
+///
+/// // The body and earth directions are mixed.
+/// assert!("x-forward,y-north,z-up".parse::<CoordinateConvention>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoordinateConvention {
+    pub x: Direction,
+    pub y: Direction,
+    pub z: Direction,
+}
+
+/// The direction of an axis in a [CoordinateConvention].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Forward,
+    Backward,
+    Left,
+    Right,
+    Up,
+    Down,
+    North,
+    South,
+    East,
+    West,
+}
+
+/// The handedness of a [CoordinateConvention].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Handedness {
+    Right,
+    Left,
+}
+
+impl CoordinateConvention {
+    /// The vehicle and robot body frame used by ROS.
+    pub const FLU: Self = Self::new_unchecked(Direction::Forward, Direction::Left, Direction::Up);
+    /// The aircraft body frame.
+    pub const FRD: Self =
+        Self::new_unchecked(Direction::Forward, Direction::Right, Direction::Down);
+    /// The camera optical frame used by OpenCV.
+    pub const RDF: Self =
+        Self::new_unchecked(Direction::Right, Direction::Down, Direction::Forward);
+    /// The local tangent plane used by ROS.
+    pub const ENU: Self = Self::new_unchecked(Direction::East, Direction::North, Direction::Up);
+    /// The local tangent plane used in aviation.
+    pub const NED: Self = Self::new_unchecked(Direction::North, Direction::East, Direction::Down);
+
+    /// Creates a convention from the directions of the x, y and z
+    /// axes. It fails if two axes are parallel, or if the body and
+    /// earth directions are mixed.
+    pub fn new(x: Direction, y: Direction, z: Direction) -> Result<Self> {
+        let axes = [("x", x), ("y", y), ("z", z)];
+
+        for (index, &(name, dir)) in axes.iter().enumerate() {
+            for &(other_name, other) in &axes[(index + 1)..] {
+                ensure!(
+                    dir.line() != other.line(),
+                    "the {name} axis ({dir}) and the {other_name} axis ({other}) are parallel"
+                );
+                if let (Some(frame), Some(other_frame)) = (dir.frame(), other.frame()) {
+                    ensure!(
+                        frame == other_frame,
+                        "the {name} axis ({dir}) is relative to the {frame}, \
+                         but the {other_name} axis ({other}) is relative to the {other_frame}"
+                    );
+                }
+            }
+        }
+
+        Ok(Self { x, y, z })
+    }
+
+    const fn new_unchecked(x: Direction, y: Direction, z: Direction) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn handedness(&self) -> Handedness {
+        let [x, y, z] = self.basis();
+        let cross = [
+            x[1] * y[2] - x[2] * y[1],
+            x[2] * y[0] - x[0] * y[2],
+            x[0] * y[1] - x[1] * y[0],
+        ];
+        let det: i8 = cross.iter().zip(z).map(|(lhs, rhs)| lhs * rhs).sum();
+
+        if det > 0 {
+            Handedness::Right
+        } else {
+            Handedness::Left
+        }
+    }
+
+    /// Checks if the axes are relative to the earth rather than a
+    /// body.
+    pub fn is_earth_fixed(&self) -> bool {
+        [self.x, self.y, self.z]
+            .iter()
+            .any(|dir| dir.frame() == Some("earth"))
+    }
+
+    /// Gets the three-letter abbreviation, such as `"FLU"`.
+    pub fn abbreviation(&self) -> String {
+        [self.x, self.y, self.z]
+            .iter()
+            .map(Direction::letter)
+            .collect()
+    }
+
+    /// Gets the unit vectors of the x, y and z axes in the
+    /// forward-left-up frame, or in the east-north-up frame if the
+    /// convention is earth-fixed.
+    fn basis(&self) -> [[i8; 3]; 3] {
+        [self.x.vector(), self.y.vector(), self.z.vector()]
+    }
+}
+
+impl Direction {
+    pub const ALL: [Self; 10] = [
+        Self::Forward,
+        Self::Backward,
+        Self::Left,
+        Self::Right,
+        Self::Up,
+        Self::Down,
+        Self::North,
+        Self::South,
+        Self::East,
+        Self::West,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Backward => "backward",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::North => "north",
+            Self::South => "south",
+            Self::East => "east",
+            Self::West => "west",
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Forward => Self::Backward,
+            Self::Backward => Self::Forward,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::East => Self::West,
+            Self::West => Self::East,
+        }
+    }
+
+    /// Checks if the direction is up or down, which is relative to
+    /// both a body and the earth.
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Self::Up | Self::Down)
+    }
+
+    fn letter(&self) -> char {
+        self.as_str().chars().next().unwrap().to_ascii_uppercase()
+    }
+
+    /// Gets the frame that the direction is relative to, or `None`
+    /// for vertical directions.
+    fn frame(&self) -> Option<&'static str> {
+        match self {
+            Self::Up | Self::Down => None,
+            Self::North | Self::South | Self::East | Self::West => Some("earth"),
+            _ => Some("body"),
+        }
+    }
+
+    /// Gets the unit vector in the forward-left-up frame for body
+    /// directions, or in the east-north-up frame for earth
+    /// directions.
+    fn vector(&self) -> [i8; 3] {
+        match self {
+            Self::Forward | Self::East => [1, 0, 0],
+            Self::Backward | Self::West => [-1, 0, 0],
+            Self::Left | Self::North => [0, 1, 0],
+            Self::Right | Self::South => [0, -1, 0],
+            Self::Up => [0, 0, 1],
+            Self::Down => [0, 0, -1],
+        }
+    }
+
+    /// Identifies the line of the axis regardless of the sign.
+    fn line(&self) -> usize {
+        self.vector().iter().position(|&val| val != 0).unwrap()
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        Self::ALL
+            .into_iter()
+            .find(|dir| dir.as_str().eq_ignore_ascii_case(text))
+            .ok_or_else(|| {
+                anyhow!(
+                    "'{text}' is not a direction. It must be one of {}.",
+                    Self::ALL.map(|dir| dir.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+impl FromStr for CoordinateConvention {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let trimmed = text.trim();
+
+        // The abbreviated form like "FLU".
+        if trimmed.len() == 3 && trimmed.chars().all(|ch| ch.is_ascii_alphabetic()) {
+            let mut dirs = trimmed.chars().map(|ch| {
+                Direction::ALL
+                    .into_iter()
+                    .find(|dir| dir.letter() == ch.to_ascii_uppercase())
+                    .ok_or_else(|| anyhow!("'{ch}' in '{text}' is not a direction letter"))
+            });
+            let x = dirs.next().unwrap()?;
+            let y = dirs.next().unwrap()?;
+            let z = dirs.next().unwrap()?;
+            return Self::new(x, y, z);
+        }
+
+        let mut axes = [None; 3];
+        for item in trimmed.split(',') {
+            let (axis, dir) = item.split_once('-').ok_or_else(|| {
+                anyhow!(
+                    "Invalid coordinate convention '{text}'. \
+                     It must be in 'x-forward,y-left,z-up' format or abbreviated like 'FLU'."
+                )
+            })?;
+            let index = match axis.trim() {
+                "x" | "X" => 0,
+                "y" | "Y" => 1,
+                "z" | "Z" => 2,
+                axis => bail!("'{axis}' in '{text}' is not an axis"),
+            };
+            ensure!(
+                axes[index].is_none(),
+                "the {} axis is given more than once in '{text}'",
+                axis.trim()
+            );
+            axes[index] = Some(dir.parse::<Direction>()?);
+        }
+
+        let [Some(x), Some(y), Some(z)] = axes else {
+            bail!("'{text}' must give the directions of all x, y and z axes");
+        };
+        Self::new(x, y, z)
+    }
+}
+
+impl Display for CoordinateConvention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { x, y, z } = self;
+        write!(f, "x-{x},y-{y},z-{z}")
+    }
+}
+
+impl Serialize for CoordinateConvention {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CoordinateConvention {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(feature = "with-nalgebra")]
+impl CoordinateConvention {
+    /// Gets the matrix converting the coordinates in this
+    /// convention to the coordinates in the `other` convention.
+    ///
+    /// The matrix is a rotation if both conventions have the
+    /// same handedness, or a reflection otherwise. It fails if
+    /// one convention is relative to a body and the other is
+    /// relative to the earth, since their relation depends on
+    /// the heading of the body.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::CoordinateConvention;
+    /// # use nalgebra::Vector3;
+    /// let matrix = CoordinateConvention::RDF
+    ///     .conversion_to(&CoordinateConvention::FLU)
+    ///     .unwrap();
+    /// // A point in front of the camera.
+    /// let point = matrix * Vector3::new(0.0, 0.0, 2.0);
+    /// assert_eq!(point, Vector3::new(2.0, 0.0, 0.0));
+    /// assert_eq!(matrix.determinant(), 1.0);
+    ///
+    /// let matrix = CoordinateConvention::ENU
+    ///     .conversion_to(&CoordinateConvention::NED)
+    ///     .unwrap();
+    /// assert_eq!(matrix * Vector3::new(1.0, 2.0, 3.0), Vector3::new(2.0, 1.0, -3.0));
+    ///
+    /// assert!(CoordinateConvention::FLU
+    ///     .conversion_to(&CoordinateConvention::ENU)
+    ///     .is_err());
+    /// ```
+    pub fn conversion_to(&self, other: &CoordinateConvention) -> Result<nalgebra::Matrix3<f64>> {
+        ensure!(
+            self.is_earth_fixed() == other.is_earth_fixed(),
+            "unable to convert between the body-fixed and earth-fixed conventions {self} and {other}"
+        );
+        Ok(other.to_reference().transpose() * self.to_reference())
+    }
+
+    /// Gets the matrix converting the coordinates in this
+    /// convention to the forward-left-up frame, or to the
+    /// east-north-up frame if the convention is earth-fixed. The
+    /// columns are the unit vectors of the axes.
+    pub fn to_reference(&self) -> nalgebra::Matrix3<f64> {
+        let [x, y, z] = self.basis().map(|axis| axis.map(f64::from));
+        nalgebra::Matrix3::new(
+            x[0], y[0], z[0], //
+            x[1], y[1], z[1], //
+            x[2], y[2], z[2],
+        )
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Issue;
 use noisy_float::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -9,8 +10,11 @@ use serde::{Deserialize, Serialize};
 /// let coefs: DistortionCoefs = serde_json::from_str(json).unwrap();
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(transparent)]
-pub struct DistortionCoefs(pub [R64; 5]);
+pub struct DistortionCoefs(
+    #[cfg_attr(feature = "schemars", schemars(with = "[f64; 5]"))] pub [R64; 5],
+);
 
 impl DistortionCoefs {
     pub fn zeros() -> Self {
@@ -36,6 +40,148 @@ impl DistortionCoefs {
     pub fn k3(&self) -> R64 {
         self.0[4]
     }
+
+    /// Checks that the coefficients have plausible magnitudes within
+    /// the limits. All found issues are returned at once. They are
+    /// errors unless the limits are lenient.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{DistortionCoefs, DistortionLimits, Severity};
+    /// let coefs: DistortionCoefs = serde_json::from_str("[4000.0, 0.0, 1.5, 0.0, 0.0]").unwrap();
+    ///
+    /// let issues = coefs.validate(&DistortionLimits::default()).unwrap_err();
+    /// let fields: Vec<_> = issues.iter().map(|issue| issue.field.as_str()).collect();
+    /// assert_eq!(fields, ["distortion_coefs.k1", "distortion_coefs.p1"]);
+    /// assert!(issues.iter().all(|issue| issue.is_error()));
+    ///
+    /// let limits = DistortionLimits {
+    ///     max_tangential: 2.0,
+    ///     ..DistortionLimits::lenient()
+    /// };
+    /// let issues = coefs.validate(&limits).unwrap_err();
+    /// assert_eq!(issues.len(), 1);
+    /// assert_eq!(issues[0].severity, Severity::Warning);
+    /// ```
+    pub fn validate(&self, limits: &DistortionLimits) -> Result<(), Vec<Issue>> {
+        let DistortionLimits {
+            max_radial,
+            max_tangential,
+            lenient,
+        } = *limits;
+
+        let coefs = [
+            ("k1", self.k1().raw(), max_radial),
+            ("k2", self.k2().raw(), max_radial),
+            ("p1", self.p1().raw(), max_tangential),
+            ("p2", self.p2().raw(), max_tangential),
+            ("k3", self.k3().raw(), max_radial),
+        ];
+        let issues: Vec<_> = coefs
+            .into_iter()
+            .filter(|(_, value, max)| value.abs() > *max)
+            .map(|(name, value, max)| {
+                let field = format!("distortion_coefs.{name}");
+                let message = format!("the magnitude of {value} exceeds {max}");
+                if lenient {
+                    Issue::warning(field, message)
+                } else {
+                    Issue::error(field, message)
+                }
+            })
+            .collect();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+/// The largest plausible magnitudes of distortion coefficients.
+///
+/// The default limits are 10 for radial coefficients and 1 for
+/// tangential ones, and the violations are errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistortionLimits {
+    /// The largest magnitude of k1, k2 and k3.
+    pub max_radial: f64,
+    /// The largest magnitude of p1 and p2.
+    pub max_tangential: f64,
+    /// Reports violations as warnings instead of errors.
+    pub lenient: bool,
+}
+
+impl DistortionLimits {
+    /// The default limits reporting warnings.
+    pub fn lenient() -> Self {
+        Self {
+            lenient: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for DistortionLimits {
+    fn default() -> Self {
+        Self {
+            max_radial: 10.0,
+            max_tangential: 1.0,
+            lenient: false,
+        }
+    }
+}
+
+/// Serialize or deserialize [DistortionCoefs], rejecting implausible
+/// magnitudes beyond the default [DistortionLimits].
+///
+/// ```rust
+/// # use newslab_serde_cv::{plausible_distortion_coefs, DistortionCoefs};
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct MyCamera {
+///     #[serde(with = "plausible_distortion_coefs")]
+///     distortion_coefs: DistortionCoefs,
+/// }
+///
+/// let json = r#"{ "distortion_coefs": [-0.1, 0.01, 0.0, 0.0, 0.0] }"#;
+/// assert!(serde_json::from_str::<MyCamera>(json).is_ok());
+///
+/// let json = r#"{ "distortion_coefs": [4000.0, 0.0, 0.0, 0.0, 0.0] }"#;
+/// assert!(serde_json::from_str::<MyCamera>(json).is_err());
+/// ```
+pub mod plausible_distortion_coefs {
+    use super::{DistortionCoefs, DistortionLimits};
+    use crate::Issue;
+    use serde::{
+        de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    fn error_message(issues: Vec<Issue>) -> String {
+        let issues: Vec<_> = issues.iter().map(Issue::to_string).collect();
+        format!("implausible distortion coefficients: {}", issues.join("; "))
+    }
+
+    pub fn serialize<S>(coefs: &DistortionCoefs, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        coefs
+            .validate(&DistortionLimits::default())
+            .map_err(|issues| S::Error::custom(error_message(issues)))?;
+        coefs.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DistortionCoefs, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let coefs = DistortionCoefs::deserialize(deserializer)?;
+        coefs
+            .validate(&DistortionLimits::default())
+            .map_err(|issues| D::Error::custom(error_message(issues)))?;
+        Ok(coefs)
+    }
 }
 
 impl Default for DistortionCoefs {
@@ -44,29 +190,10 @@ impl Default for DistortionCoefs {
     }
 }
 
-#[cfg(feature = "with-nalgebra")]
-pub use with_nalgebra::*;
-
 #[cfg(feature = "with-nalgebra")]
 mod with_nalgebra {
     use super::*;
 
-    // impl From<&DistortionCoefs> for opencv_ros_camera::Distortion<f64> {
-    //     fn from(from: &DistortionCoefs) -> Self {
-    //         let coefs: [f64; 5] = unsafe { mem::transmute(from.0) };
-    //         let slice: &[f64] = coefs.as_ref();
-    //         opencv_ros_camera::Distortion::from_opencv_vec(na::Vector5::from_column_slice(
-    //             slice,
-    //         ))
-    //     }
-    // }
-
-    // impl From<DistortionCoefs> for opencv_ros_camera::Distortion<f64> {
-    //     fn from(from: DistortionCoefs) -> Self {
-    //         (&from).into()
-    //     }
-    // }
-
     impl From<&DistortionCoefs> for nalgebra::Vector5<f64> {
         fn from(from: &DistortionCoefs) -> Self {
             nalgebra::Vector5::from_iterator(from.0.iter().map(|val| val.raw()))
@@ -74,6 +201,61 @@ mod with_nalgebra {
     }
 }
 
+#[cfg(feature = "with-opencv-ros-camera")]
+mod with_opencv_ros_camera {
+    use super::*;
+    use anyhow::anyhow;
+
+    impl From<&DistortionCoefs> for opencv_ros_camera::Distortion<f64> {
+        fn from(from: &DistortionCoefs) -> Self {
+            Self::from_opencv_vec(from.into())
+        }
+    }
+
+    impl From<DistortionCoefs> for opencv_ros_camera::Distortion<f64> {
+        fn from(from: DistortionCoefs) -> Self {
+            (&from).into()
+        }
+    }
+
+    /// Converts the distortion of the plumb bob model back, which
+    /// fails if any coefficient is not finite.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::DistortionCoefs;
+    /// # use opencv_ros_camera::Distortion;
+    /// let coefs: DistortionCoefs = serde_json::from_str("[0.1, -0.2, 0.01, 0.0, 0.05]").unwrap();
+    /// let distortion = Distortion::from(&coefs);
+    /// assert_eq!(distortion.radial2(), -0.2);
+    /// assert_eq!(DistortionCoefs::try_from(&distortion).unwrap(), coefs);
+    ///
+    /// let distortion = Distortion::from_opencv_vec(nalgebra::Vector5::new(f64::NAN, 0.0, 0.0, 0.0, 0.0));
+    /// assert!(DistortionCoefs::try_from(distortion).is_err());
+    /// ```
+    impl TryFrom<&opencv_ros_camera::Distortion<f64>> for DistortionCoefs {
+        type Error = anyhow::Error;
+
+        fn try_from(from: &opencv_ros_camera::Distortion<f64>) -> Result<Self, Self::Error> {
+            let mut coefs = [r64(0.0); 5];
+            for (index, (coef, &value)) in
+                coefs.iter_mut().zip(from.opencv_vec().iter()).enumerate()
+            {
+                *coef = R64::try_new(value)
+                    .ok_or_else(|| anyhow!("the coefficient at {index} is not finite"))?;
+            }
+            Ok(Self(coefs))
+        }
+    }
+
+    impl TryFrom<opencv_ros_camera::Distortion<f64>> for DistortionCoefs {
+        type Error = anyhow::Error;
+
+        fn try_from(from: opencv_ros_camera::Distortion<f64>) -> Result<Self, Self::Error> {
+            (&from).try_into()
+        }
+    }
+}
+
 #[cfg(feature = "with-opencv")]
 impl From<&DistortionCoefs> for opencv::core::Mat {
     fn from(from: &DistortionCoefs) -> Self {
@@ -87,3 +269,51 @@ impl From<DistortionCoefs> for opencv::core::Mat {
         (&from).into()
     }
 }
+
+/// Reads a row or column vector of `f64` or `f32` in the OpenCV
+/// order, such as the coefficients computed by `calibrateCamera()`.
+///
+/// A vector of 4 elements leaves k3 zero. Longer vectors of the
+/// rational or thin prism models are accepted only if the extra
+/// coefficients are zero.
+#[cfg(feature = "with-opencv")]
+impl TryFrom<&opencv::core::Mat> for DistortionCoefs {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &opencv::core::Mat) -> Result<Self, Self::Error> {
+        use anyhow::ensure;
+
+        let (elements, rows, cols) = crate::mat::read_elements(from)?;
+        ensure!(
+            rows == 1 || cols == 1,
+            "expect a row or column vector, but get {rows}x{cols}"
+        );
+        ensure!(
+            elements.len() >= 4,
+            "expect at least 4 distortion coefficients, but get {}",
+            elements.len()
+        );
+        if let Some(extra) = elements.get(5..) {
+            ensure!(
+                extra.iter().all(|&value| value == 0.0),
+                "only 5 distortion coefficients are supported, \
+                 but the extra coefficients {extra:?} are not zero"
+            );
+        }
+
+        let mut coefs = [r64(0.0); 5];
+        for (coef, &value) in coefs.iter_mut().zip(&elements) {
+            *coef = r64(value);
+        }
+        Ok(Self(coefs))
+    }
+}
+
+#[cfg(feature = "with-opencv")]
+impl TryFrom<opencv::core::Mat> for DistortionCoefs {
+    type Error = anyhow::Error;
+
+    fn try_from(from: opencv::core::Mat) -> Result<Self, Self::Error> {
+        (&from).try_into()
+    }
+}
@@ -0,0 +1,452 @@
+use crate::{DistortionCoefs, DistortionLimits, Issue};
+use anyhow::{bail, Result};
+use noisy_float::prelude::*;
+use serde::{
+    de::{
+        value::{MapAccessDeserializer, SeqAccessDeserializer},
+        MapAccess, SeqAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt;
+
+/// The names of the coefficients in the OpenCV order.
+const OPENCV_COEF_NAMES: [&str; 14] = [
+    "k1", "k2", "p1", "p2", "k3", "k4", "k5", "k6", "s1", "s2", "s3", "s4", "tau_x", "tau_y",
+];
+
+/// The names of the coefficients of the Kannala-Brandt model.
+const FISHEYE_COEF_NAMES: [&str; 4] = ["k1", "k2", "k3", "k4"];
+
+/// The largest plausible magnitude of the tilt angles in radians.
+const MAX_TILT: f64 = std::f64::consts::FRAC_PI_2;
+
+/// The camera distortion model along with its coefficients.
+///
+/// The OpenCV models list their coefficients in the OpenCV order
+/// `[k1, k2, p1, p2, k3, k4, k5, k6, s1, s2, s3, s4, tau_x, tau_y]`,
+/// truncated to the number of coefficients of the model. The
+/// Kannala-Brandt fisheye model has the coefficients `[k1, k2, k3,
+/// k4]`, as in the OpenCV fisheye module.
+///
+/// The model is written as `{ "model": .., "coefs": [..] }` in
+/// human-readable formats. A bare array of 5 coefficients is the
+/// plumb bob model, which is also the form the plumb bob model is
+/// written in for compatibility. Binary formats store the model as a
+/// plain enum.
+///
+/// ```rust
+/// # use newslab_serde_cv::DistortionModel;
+/// let json = r#"{ "model": "kannala_brandt", "coefs": [0.1, -0.02, 0.003, 0.0] }"#;
+/// let model: DistortionModel = serde_json::from_str(json).unwrap();
+/// assert!(model.is_fisheye());
+/// assert_eq!(model.coefs()[1], -0.02);
+///
+/// let json = r#"{
+///     "model": "rational_polynomial",
+///     "coefs": [0.1, -0.2, 0.0, 0.0, 0.05, 0.1, -0.2, 0.05]
+/// }"#;
+/// let model: DistortionModel = serde_json::from_str(json).unwrap();
+/// assert_eq!(model.name(), "rational_polynomial");
+/// assert!(serde_json::to_string(&model).unwrap().starts_with(r#"{"model":"rational_polynomial","#));
+///
+/// let model: DistortionModel = serde_json::from_str("[0.1, -0.2, 0.0, 0.0, 0.05]").unwrap();
+/// assert_eq!(model.name(), "plumb_bob");
+/// assert_eq!(serde_json::to_string(&model).unwrap(), "[0.1,-0.2,0.0,0.0,0.05]");
+///
+/// // The number of coefficients is wrong.
+/// let json = r#"{ "model": "thin_prism", "coefs": [0.1, -0.2, 0.0, 0.0, 0.05] }"#;
+/// assert!(serde_json::from_str::<DistortionModel>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(
+    remote = "Self",
+    tag = "model",
+    content = "coefs",
+    rename_all = "snake_case"
+)]
+pub enum DistortionModel {
+    /// The 5 coefficients `[k1, k2, p1, p2, k3]`.
+    PlumbBob(DistortionCoefs),
+    /// The 8 coefficients up to k6.
+    RationalPolynomial([R64; 8]),
+    /// The 12 coefficients up to s4 of the thin prism model.
+    ThinPrism([R64; 12]),
+    /// The 14 coefficients up to tau_y of the tilted model.
+    Tilted([R64; 14]),
+    /// The 4 coefficients `[k1, k2, k3, k4]` of the fisheye model.
+    KannalaBrandt([R64; 4]),
+}
+
+impl DistortionModel {
+    /// The plumb bob model without distortion.
+    pub fn zeros() -> Self {
+        Self::PlumbBob(DistortionCoefs::zeros())
+    }
+
+    /// Creates an OpenCV model from the coefficients in the OpenCV
+    /// order. The model is chosen by the number of coefficients,
+    /// which is 4, 5, 8, 12 or 14. 4 coefficients leave k3 zero.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::DistortionModel;
+    /// let model = DistortionModel::from_opencv_coefs(&[0.1; 12]).unwrap();
+    /// assert_eq!(model.name(), "thin_prism");
+    ///
+    /// assert!(DistortionModel::from_opencv_coefs(&[0.1; 6]).is_err());
+    /// assert!(DistortionModel::from_opencv_coefs(&[f64::NAN; 5]).is_err());
+    /// ```
+    pub fn from_opencv_coefs(values: &[f64]) -> Result<Self> {
+        let mut coefs = [r64(0.0); 14];
+        for (index, (coef, &value)) in coefs.iter_mut().zip(values).enumerate() {
+            *coef = match R64::try_new(value) {
+                Some(value) => value,
+                None => bail!("the coefficient {} is not finite", OPENCV_COEF_NAMES[index]),
+            };
+        }
+
+        let model = match values.len() {
+            4 | 5 => Self::PlumbBob(DistortionCoefs(coefs[..5].try_into().unwrap())),
+            8 => Self::RationalPolynomial(coefs[..8].try_into().unwrap()),
+            12 => Self::ThinPrism(coefs[..12].try_into().unwrap()),
+            14 => Self::Tilted(coefs),
+            len => bail!("expect 4, 5, 8, 12 or 14 distortion coefficients, but get {len}"),
+        };
+        Ok(model)
+    }
+
+    /// Gets the name of the model, which is the `model` field in the
+    /// serialized form.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PlumbBob(_) => "plumb_bob",
+            Self::RationalPolynomial(_) => "rational_polynomial",
+            Self::ThinPrism(_) => "thin_prism",
+            Self::Tilted(_) => "tilted",
+            Self::KannalaBrandt(_) => "kannala_brandt",
+        }
+    }
+
+    pub fn coefs(&self) -> &[R64] {
+        match self {
+            Self::PlumbBob(coefs) => &coefs.0,
+            Self::RationalPolynomial(coefs) => coefs,
+            Self::ThinPrism(coefs) => coefs,
+            Self::Tilted(coefs) => coefs,
+            Self::KannalaBrandt(coefs) => coefs,
+        }
+    }
+
+    fn coef_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::KannalaBrandt(_) => &FISHEYE_COEF_NAMES,
+            _ => &OPENCV_COEF_NAMES[..self.coefs().len()],
+        }
+    }
+
+    pub fn is_fisheye(&self) -> bool {
+        matches!(self, Self::KannalaBrandt(_))
+    }
+
+    /// Gets the OpenCV model as the plumb bob coefficients, which
+    /// returns `None` if the model is fisheye or has non-zero
+    /// coefficients beyond k3.
+    pub fn to_plumb_bob(&self) -> Option<DistortionCoefs> {
+        if self.is_fisheye() {
+            return None;
+        }
+        let coefs = self.coefs();
+        if coefs[5..].iter().any(|&coef| coef != 0.0) {
+            return None;
+        }
+        Some(DistortionCoefs(coefs[..5].try_into().unwrap()))
+    }
+
+    /// Checks that the coefficients have plausible magnitudes within
+    /// the limits like [DistortionCoefs::validate]. The radial
+    /// coefficients k1 to k6 are checked against the radial limit,
+    /// and p1, p2 and s1 to s4 against the tangential one. The tilt
+    /// angles must be within ±π/2 radians.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::{DistortionLimits, DistortionModel};
+    /// let json = r#"{
+    ///     "model": "tilted",
+    ///     "coefs": [0.1, 0.0, 0.0, 0.0, 0.0, 20.0, 0.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 2.0]
+    /// }"#;
+    /// let model: DistortionModel = serde_json::from_str(json).unwrap();
+    ///
+    /// let issues = model.validate(&DistortionLimits::default()).unwrap_err();
+    /// let fields: Vec<_> = issues.iter().map(|issue| issue.field.as_str()).collect();
+    /// assert_eq!(fields, ["distortion_coefs.k4", "distortion_coefs.s3", "distortion_coefs.tau_y"]);
+    /// ```
+    pub fn validate(&self, limits: &DistortionLimits) -> Result<(), Vec<Issue>> {
+        if let Self::PlumbBob(coefs) = self {
+            return coefs.validate(limits);
+        }
+
+        let DistortionLimits {
+            max_radial,
+            max_tangential,
+            lenient,
+        } = *limits;
+
+        let issues: Vec<_> = self
+            .coef_names()
+            .iter()
+            .zip(self.coefs())
+            .filter_map(|(&name, value)| {
+                let max = match name.as_bytes()[0] {
+                    b'k' => max_radial,
+                    b't' => MAX_TILT,
+                    _ => max_tangential,
+                };
+                let value = value.raw();
+                (value.abs() > max).then(|| {
+                    let field = format!("distortion_coefs.{name}");
+                    let message = format!("the magnitude of {value} exceeds {max}");
+                    if lenient {
+                        Issue::warning(field, message)
+                    } else {
+                        Issue::error(field, message)
+                    }
+                })
+            })
+            .collect();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+impl Default for DistortionModel {
+    fn default() -> Self {
+        Self::zeros()
+    }
+}
+
+impl From<DistortionCoefs> for DistortionModel {
+    fn from(from: DistortionCoefs) -> Self {
+        Self::PlumbBob(from)
+    }
+}
+
+/// The plain enum form of [DistortionModel] in binary formats, which
+/// cannot read the tag of adjacently tagged enums.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "DistortionModel")]
+enum BareModel {
+    PlumbBob(DistortionCoefs),
+    RationalPolynomial([R64; 8]),
+    ThinPrism([R64; 12]),
+    Tilted([R64; 14]),
+    KannalaBrandt([R64; 4]),
+}
+
+// The tagged form of [DistortionModel] in human-readable formats,
+// which describes the schema of the object form. It is not a doc
+// comment, which would become the description in the schema.
+#[cfg(feature = "schemars")]
+#[derive(schemars::JsonSchema)]
+#[serde(tag = "model", content = "coefs", rename_all = "snake_case")]
+#[allow(dead_code)]
+enum TaggedModel {
+    PlumbBob([f64; 5]),
+    RationalPolynomial([f64; 8]),
+    ThinPrism([f64; 12]),
+    Tilted([f64; 14]),
+    KannalaBrandt([f64; 4]),
+}
+
+/// Accepts the bare array of plumb bob coefficients, or the object
+/// of the model name and the coefficients.
+///
+/// ```rust
+/// # use newslab_serde_cv::DistortionModel;
+/// # use schemars::schema_for;
+/// let schema = serde_json::to_value(schema_for!(DistortionModel)).unwrap();
+/// assert_eq!(schema["anyOf"][0]["maxItems"], 5);
+/// let fisheye = &schema["anyOf"][1]["oneOf"][4];
+/// assert_eq!(fisheye["properties"]["model"]["enum"][0], "kannala_brandt");
+/// assert_eq!(fisheye["properties"]["coefs"]["maxItems"], 4);
+/// ```
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for DistortionModel {
+    fn schema_name() -> String {
+        "DistortionModel".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{SchemaObject, SubschemaValidation};
+
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    gen.subschema_for::<DistortionCoefs>(),
+                    TaggedModel::json_schema(gen),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl Serialize for DistortionModel {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return BareModel::serialize(self, serializer);
+        }
+        match self {
+            Self::PlumbBob(coefs) => coefs.serialize(serializer),
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DistortionModel {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ModelVisitor;
+
+        impl<'de> Visitor<'de> for ModelVisitor {
+            type Value = DistortionModel;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(
+                    "an array of 5 plumb bob coefficients, or an object of \"model\" and \"coefs\"",
+                )
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let coefs = DistortionCoefs::deserialize(SeqAccessDeserializer::new(seq))?;
+                Ok(DistortionModel::PlumbBob(coefs))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                DistortionModel::deserialize(MapAccessDeserializer::new(map))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ModelVisitor)
+        } else {
+            BareModel::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(feature = "with-nalgebra")]
+impl From<&DistortionModel> for nalgebra::DVector<f64> {
+    fn from(from: &DistortionModel) -> Self {
+        nalgebra::DVector::from_iterator(
+            from.coefs().len(),
+            from.coefs().iter().map(|val| val.raw()),
+        )
+    }
+}
+
+#[cfg(feature = "with-opencv-ros-camera")]
+mod with_opencv_ros_camera {
+    use super::*;
+    use anyhow::anyhow;
+
+    /// Converts the model to the plumb bob distortion, which fails
+    /// if the model is not representable as
+    /// [to_plumb_bob](DistortionModel::to_plumb_bob) describes.
+    impl TryFrom<&DistortionModel> for opencv_ros_camera::Distortion<f64> {
+        type Error = anyhow::Error;
+
+        fn try_from(from: &DistortionModel) -> Result<Self, Self::Error> {
+            let coefs = from.to_plumb_bob().ok_or_else(|| {
+                anyhow!(
+                    "the {} distortion model cannot be represented in plumb bob coefficients",
+                    from.name()
+                )
+            })?;
+            Ok((&coefs).into())
+        }
+    }
+
+    impl TryFrom<DistortionModel> for opencv_ros_camera::Distortion<f64> {
+        type Error = anyhow::Error;
+
+        fn try_from(from: DistortionModel) -> Result<Self, Self::Error> {
+            (&from).try_into()
+        }
+    }
+
+    /// Converts the plumb bob distortion back, which fails if any
+    /// coefficient is not finite.
+    impl TryFrom<&opencv_ros_camera::Distortion<f64>> for DistortionModel {
+        type Error = anyhow::Error;
+
+        fn try_from(from: &opencv_ros_camera::Distortion<f64>) -> Result<Self, Self::Error> {
+            Ok(DistortionCoefs::try_from(from)?.into())
+        }
+    }
+
+    impl TryFrom<opencv_ros_camera::Distortion<f64>> for DistortionModel {
+        type Error = anyhow::Error;
+
+        fn try_from(from: opencv_ros_camera::Distortion<f64>) -> Result<Self, Self::Error> {
+            (&from).try_into()
+        }
+    }
+}
+
+#[cfg(feature = "with-opencv")]
+impl From<&DistortionModel> for opencv::core::Mat {
+    fn from(from: &DistortionModel) -> Self {
+        opencv::core::Mat::from_exact_iter(from.coefs().iter().map(|val| val.raw())).unwrap()
+    }
+}
+
+#[cfg(feature = "with-opencv")]
+impl From<DistortionModel> for opencv::core::Mat {
+    fn from(from: DistortionModel) -> Self {
+        (&from).into()
+    }
+}
+
+/// Reads a row or column vector of `f64` or `f32` in the OpenCV
+/// order as [DistortionModel::from_opencv_coefs] does. A vector of 4
+/// elements is read as the plumb bob model rather than the fisheye
+/// model.
+#[cfg(feature = "with-opencv")]
+impl TryFrom<&opencv::core::Mat> for DistortionModel {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &opencv::core::Mat) -> Result<Self, Self::Error> {
+        let (elements, rows, cols) = crate::mat::read_elements(from)?;
+        anyhow::ensure!(
+            rows == 1 || cols == 1,
+            "expect a row or column vector, but get {rows}x{cols}"
+        );
+        Self::from_opencv_coefs(&elements)
+    }
+}
+
+#[cfg(feature = "with-opencv")]
+impl TryFrom<opencv::core::Mat> for DistortionModel {
+    type Error = anyhow::Error;
+
+    fn try_from(from: opencv::core::Mat) -> Result<Self, Self::Error> {
+        (&from).try_into()
+    }
+}
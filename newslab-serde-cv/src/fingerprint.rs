@@ -0,0 +1,119 @@
+use anyhow::{anyhow, ensure, Error, Result};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A SHA-256 digest identifying a configuration, written as
+/// `"sha256:{hex}"`.
+///
+/// The digest is computed over the canonical JSON form of the value,
+/// so it does not depend on field order or formatting of the source
+/// file.
+///
+/// ```rust
+/// # use newslab_serde_cv::{CameraIntrinsicParams, Fingerprint};
+/// let params = CameraIntrinsicParams::identity();
+/// let fingerprint = Fingerprint::of(&params).unwrap();
+///
+/// let text = fingerprint.to_string();
+/// assert!(text.starts_with("sha256:"));
+/// assert_eq!(text.parse::<Fingerprint>().unwrap(), fingerprint);
+///
+/// // The canonical form sorts keys and strips whitespace.
+/// let json = Fingerprint::canonical_json(&params).unwrap();
+/// assert!(json.starts_with(r#"{"camera_matrix":[[1.0,0.0,0.0],"#));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(pub [u8; 32]);
+
+impl Fingerprint {
+    /// Computes the fingerprint of a value.
+    pub fn of<T>(value: &T) -> Result<Self>
+    where
+        T: Serialize + ?Sized,
+    {
+        let json = Self::canonical_json(value)?;
+        Ok(Self(Sha256::digest(json.as_bytes()).into()))
+    }
+
+    /// Renders the value in canonical JSON, in which map keys are
+    /// sorted and no whitespace is inserted.
+    pub fn canonical_json<T>(value: &T) -> Result<String>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = sort_keys(serde_json::to_value(value)?);
+        Ok(serde_json::to_string(&value)?)
+    }
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_keys(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(array) => Value::Array(array.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let hex = text
+            .strip_prefix("sha256:")
+            .ok_or_else(|| anyhow!("fingerprint '{text}' must start with 'sha256:'"))?;
+        ensure!(
+            hex.len() == 64 && hex.is_ascii(),
+            "fingerprint '{text}' must have 64 hex digits"
+        );
+
+        let mut bytes = [0; 32];
+        for (byte, index) in bytes.iter_mut().zip((0..64).step_by(2)) {
+            *byte = u8::from_str_radix(&hex[index..index + 2], 16)
+                .map_err(|_| anyhow!("fingerprint '{text}' must have 64 hex digits"))?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sha256:")?;
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Fingerprint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fingerprint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
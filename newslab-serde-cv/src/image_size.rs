@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Error};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// The image size in pixels, written in `"{width}x{height}"` format.
+///
+/// ```rust
+/// # use newslab_serde_cv::ImageSize;
+/// let size: ImageSize = serde_json::from_str(r#" "1920x1080" "#).unwrap();
+/// assert_eq!(size.width, 1920);
+/// assert_eq!(size.height, 1080);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl FromStr for ImageSize {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            anyhow!(
+                "Invalid image size '{}'. It must be in 'widthxheight' format.",
+                text
+            )
+        };
+
+        let (width, height) = text.split_once('x').ok_or_else(err)?;
+        let width = width.trim().parse().map_err(|_| err())?;
+        let height = height.trim().parse().map_err(|_| err())?;
+
+        Ok(Self { width, height })
+    }
+}
+
+impl Display for ImageSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { width, height } = *self;
+        write!(f, "{width}x{height}")
+    }
+}
+
+impl Serialize for ImageSize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.to_string().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for ImageSize {
+    fn schema_name() -> String {
+        "ImageSize".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, SchemaObject, StringValidation};
+
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(r"^\s*\+?\d+\s*x\s*\+?\d+\s*$".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageSize {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+/// A problem found by a consistency check.
+///
+/// The checks report all findings at once rather than stopping at
+/// the first one, so that the caller can decide whether to reject
+/// the data or just emit warnings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The name of the offending field, such as `"camera_matrix.cx"`.
+    pub field: String,
+    pub message: String,
+}
+
+impl Issue {
+    pub fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            severity,
+            field,
+            message,
+        } = self;
+        write!(f, "{severity}: {field}: {message}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(text)
+    }
+}
@@ -0,0 +1,423 @@
+//! Kalibr camera chains written in `camchain.yaml` files.
+//!
+//! A camera chain is keyed by camera names `cam0`, `cam1` and so on.
+//! Each camera except the first one has a 4x4 row-major `T_cn_cnm1`
+//! transform, which maps points in the frame of the previous camera
+//! to the frame of this camera.
+//!
+//! ```rust
+//! # use newslab_serde_cv::kalibr::KalibrCamchain;
+//! let json = r#"{
+//!     "cam0": {
+//!         "cam_overlaps": [1],
+//!         "camera_model": "pinhole",
+//!         "distortion_coeffs": [-0.28, 0.07, 0.0002, 0.00002],
+//!         "distortion_model": "radtan",
+//!         "intrinsics": [458.6, 457.3, 367.2, 248.4],
+//!         "resolution": [752, 480],
+//!         "rostopic": "/cam0/image_raw"
+//!     },
+//!     "cam1": {
+//!         "T_cn_cnm1": [[1.0, 0.0, 0.0, -0.11],
+//!                       [0.0, 1.0, 0.0,  0.00],
+//!                       [0.0, 0.0, 1.0,  0.00],
+//!                       [0.0, 0.0, 0.0,  1.00]],
+//!         "cam_overlaps": [0],
+//!         "camera_model": "pinhole",
+//!         "distortion_coeffs": [0.01, -0.002, 0.0003, -0.0004],
+//!         "distortion_model": "equidistant",
+//!         "intrinsics": [457.6, 456.1, 379.9, 255.2],
+//!         "resolution": [752, 480],
+//!         "rostopic": "/cam1/image_raw"
+//!     }
+//! }"#;
+//! let chain: KalibrCamchain = serde_json::from_str(json).unwrap();
+//!
+//! let params = chain["cam1"].intrinsic_params().unwrap();
+//! assert!(params.distortion_coefs.is_fisheye());
+//! assert_eq!(chain["cam1"].image_size().height, 480);
+//! assert_eq!(chain["cam1"].extrinsic().unwrap().unwrap().trans[0], -0.11);
+//! assert!(chain["cam0"].extrinsic().is_none());
+//! ```
+
+use crate::{
+    mrpt::ExtrinsicMatrix, CameraIntrinsicParams, CameraMatrix, DistortionCoefs, DistortionModel,
+    ImageSize,
+};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use noisy_float::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A Kalibr camera chain keyed by camera names like `cam0`.
+pub type KalibrCamchain = BTreeMap<String, KalibrCamera>;
+
+/// A camera block in a Kalibr camera chain.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct KalibrCamera {
+    /// The transform from the previous camera frame to this camera
+    /// frame in row-major order. It is absent in the first camera.
+    #[serde(rename = "T_cn_cnm1", default, skip_serializing_if = "Option::is_none")]
+    pub t_cn_cnm1: Option<[[R64; 4]; 4]>,
+    /// The indices of cameras sharing the field of view.
+    #[serde(default)]
+    pub cam_overlaps: Vec<usize>,
+    pub camera_model: KalibrCameraModel,
+    pub distortion_coeffs: Vec<R64>,
+    pub distortion_model: KalibrDistortionModel,
+    /// The projection parameters, which are `[fu, fv, pu, pv]` for
+    /// the pinhole model.
+    pub intrinsics: Vec<R64>,
+    /// The `[width, height]` of images.
+    pub resolution: [usize; 2],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rostopic: Option<String>,
+}
+
+impl KalibrCamera {
+    /// Creates a pinhole camera block without the transform and the
+    /// ROS topic. The plumb bob model becomes the `radtan` model and
+    /// the Kannala-Brandt model becomes the `equidistant` model.
+    ///
+    /// It fails if the distortion model has no Kalibr counterpart,
+    /// including the plumb bob model with a non-zero k3, or if the
+    /// camera matrix has skew beyond
+    /// [CameraMatrix::DEFAULT_SKEW_TOLERANCE].
+    pub fn from_intrinsic_params(
+        params: &CameraIntrinsicParams,
+        image_size: &ImageSize,
+    ) -> Result<Self> {
+        let CameraIntrinsicParams {
+            camera_matrix,
+            distortion_coefs,
+        } = params;
+
+        let (distortion_model, distortion_coeffs) = match distortion_coefs {
+            DistortionModel::PlumbBob(DistortionCoefs([k1, k2, p1, p2, k3])) => {
+                ensure!(
+                    *k3 == 0.0,
+                    "the radtan distortion model has no k3, but get {k3}"
+                );
+                (KalibrDistortionModel::Radtan, vec![*k1, *k2, *p1, *p2])
+            }
+            DistortionModel::KannalaBrandt(coefs) => {
+                (KalibrDistortionModel::Equidistant, coefs.to_vec())
+            }
+            model => bail!(
+                "the {} distortion model cannot be represented in Kalibr",
+                model.name()
+            ),
+        };
+        let camera_matrix = camera_matrix.without_skew(CameraMatrix::DEFAULT_SKEW_TOLERANCE)?;
+
+        Ok(Self {
+            t_cn_cnm1: None,
+            cam_overlaps: vec![],
+            camera_model: KalibrCameraModel::Pinhole,
+            distortion_coeffs,
+            distortion_model,
+            intrinsics: vec![
+                camera_matrix.fx(),
+                camera_matrix.fy(),
+                camera_matrix.cx(),
+                camera_matrix.cy(),
+            ],
+            resolution: [image_size.width, image_size.height],
+            rostopic: None,
+        })
+    }
+
+    /// Converts the intrinsics to camera parameters. The `radtan`
+    /// model becomes the plumb bob model, the `equidistant` model
+    /// becomes the Kannala-Brandt model, and the `none` model has
+    /// zero coefficients.
+    ///
+    /// It fails if the camera model is not `pinhole` or the
+    /// distortion model is `fov`.
+    pub fn intrinsic_params(&self) -> Result<CameraIntrinsicParams> {
+        let Self {
+            camera_model,
+            distortion_coeffs,
+            distortion_model,
+            intrinsics,
+            ..
+        } = self;
+
+        ensure!(
+            *camera_model == KalibrCameraModel::Pinhole,
+            "the {camera_model:?} camera model is not supported"
+        );
+        let [fx, fy, cx, cy] = intrinsics[..] else {
+            bail!("expect 4 pinhole intrinsics, but get {}", intrinsics.len());
+        };
+
+        let err = |expected: usize| {
+            anyhow!(
+                "expect {expected} {distortion_model:?} distortion coefficients, but get {}",
+                distortion_coeffs.len()
+            )
+        };
+        let distortion_coefs = match distortion_model {
+            KalibrDistortionModel::Radtan => {
+                let [k1, k2, p1, p2] = distortion_coeffs[..] else {
+                    return Err(err(4));
+                };
+                DistortionCoefs([k1, k2, p1, p2, r64(0.0)]).into()
+            }
+            KalibrDistortionModel::Equidistant => DistortionModel::KannalaBrandt(
+                distortion_coeffs[..].try_into().map_err(|_| err(4))?,
+            ),
+            KalibrDistortionModel::None => {
+                if !distortion_coeffs.is_empty() {
+                    return Err(err(0));
+                }
+                DistortionModel::zeros()
+            }
+            KalibrDistortionModel::Fov => {
+                bail!("the fov distortion model is not supported")
+            }
+        };
+
+        Ok(CameraIntrinsicParams {
+            camera_matrix: CameraMatrix::from_params(fx, fy, cx, cy),
+            distortion_coefs,
+        })
+    }
+
+    pub fn image_size(&self) -> ImageSize {
+        let [width, height] = self.resolution;
+        ImageSize { width, height }
+    }
+
+    /// Gets the transform from the previous camera frame to this
+    /// camera frame. It returns `None` if the transform is absent,
+    /// and fails if the matrix is not a rigid transform.
+    pub fn extrinsic(&self) -> Option<Result<ExtrinsicMatrix>> {
+        self.t_cn_cnm1
+            .as_ref()
+            .map(|mat| ExtrinsicMatrix::from_homogeneous(mat).context("invalid T_cn_cnm1"))
+    }
+}
+
+/// The projection models in Kalibr camera chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KalibrCameraModel {
+    Pinhole,
+    Omni,
+    Ds,
+    Eucm,
+}
+
+/// The distortion models in Kalibr camera chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KalibrDistortionModel {
+    Radtan,
+    Equidistant,
+    Fov,
+    None,
+}
+
+#[cfg(feature = "with-nalgebra")]
+pub use with_nalgebra::{KalibrRig, KalibrRigCamera};
+
+#[cfg(feature = "with-nalgebra")]
+mod with_nalgebra {
+    use super::*;
+    use crate::{CameraCalibration, CameraExtrinsicParams};
+    use nalgebra::{Isometry3, Matrix4};
+    use serde::{ser::Error as _, Serializer};
+
+    /// A multi-camera rig read from a Kalibr camera chain.
+    ///
+    /// The cameras are ordered by their indices in the chain and
+    /// named after their keys like `cam0`. The extrinsics of each
+    /// camera are its pose in the `cam0` frame, which are chained
+    /// from the `T_cn_cnm1` transforms. The rig is written back as a
+    /// camera chain.
+    ///
+    /// ```rust
+    /// # use newslab_serde_cv::kalibr::KalibrRig;
+    /// let json = r#"{
+    ///     "cam0": {
+    ///         "camera_model": "pinhole",
+    ///         "distortion_coeffs": [-0.28, 0.07, 0.0002, 0.00002],
+    ///         "distortion_model": "radtan",
+    ///         "intrinsics": [458.6, 457.3, 367.2, 248.4],
+    ///         "resolution": [752, 480],
+    ///         "rostopic": "/cam0/image_raw"
+    ///     },
+    ///     "cam1": {
+    ///         "T_cn_cnm1": [[0.0, -1.0, 0.0, 0.0],
+    ///                       [1.0,  0.0, 0.0, 0.0],
+    ///                       [0.0,  0.0, 1.0, 0.0],
+    ///                       [0.0,  0.0, 0.0, 1.0]],
+    ///         "camera_model": "pinhole",
+    ///         "distortion_coeffs": [0.0, 0.0, 0.0, 0.0],
+    ///         "distortion_model": "radtan",
+    ///         "intrinsics": [457.6, 456.1, 379.9, 255.2],
+    ///         "resolution": [752, 480]
+    ///     },
+    ///     "cam2": {
+    ///         "T_cn_cnm1": [[1.0, 0.0, 0.0, -0.1],
+    ///                       [0.0, 1.0, 0.0,  0.0],
+    ///                       [0.0, 0.0, 1.0,  0.0],
+    ///                       [0.0, 0.0, 0.0,  1.0]],
+    ///         "camera_model": "pinhole",
+    ///         "distortion_coeffs": [0.01, -0.002, 0.0003, -0.0004],
+    ///         "distortion_model": "equidistant",
+    ///         "intrinsics": [457.6, 456.1, 379.9, 255.2],
+    ///         "resolution": [752, 480]
+    ///     }
+    /// }"#;
+    /// let rig: KalibrRig = serde_json::from_str(json).unwrap();
+    /// assert_eq!(rig.cameras.len(), 3);
+    /// assert_eq!(rig.cameras[2].calibration.name.as_deref(), Some("cam2"));
+    /// assert_eq!(rig.cameras[0].rostopic.as_deref(), Some("/cam0/image_raw"));
+    ///
+    /// // The origin of cam2 is at x = 0.1 in cam1, which is y = -0.1 in cam0.
+    /// let translation = rig.cameras[2].calibration.extrinsics.translation;
+    /// assert!((translation - nalgebra::Vector3::new(0.0, -0.1, 0.0)).norm() < 1e-12);
+    ///
+    /// // The rig is written back as a camera chain.
+    /// let json = serde_json::to_value(&rig).unwrap();
+    /// assert!(json["cam0"].get("T_cn_cnm1").is_none());
+    /// let elem = json["cam1"]["T_cn_cnm1"][1][0].as_f64().unwrap();
+    /// assert!((elem - 1.0).abs() < 1e-12);
+    /// assert_eq!(json["cam2"]["distortion_model"], "equidistant");
+    ///
+    /// // The camera indices must be contiguous.
+    /// let json = r#"{
+    ///     "cam1": {
+    ///         "camera_model": "pinhole",
+    ///         "distortion_coeffs": [],
+    ///         "distortion_model": "none",
+    ///         "intrinsics": [457.6, 456.1, 379.9, 255.2],
+    ///         "resolution": [752, 480]
+    ///     }
+    /// }"#;
+    /// assert!(serde_json::from_str::<KalibrRig>(json).is_err());
+    /// ```
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(try_from = "KalibrCamchain")]
+    pub struct KalibrRig {
+        pub cameras: Vec<KalibrRigCamera>,
+    }
+
+    /// A camera in a [KalibrRig].
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct KalibrRigCamera {
+        pub calibration: CameraCalibration,
+        pub cam_overlaps: Vec<usize>,
+        pub rostopic: Option<String>,
+    }
+
+    impl TryFrom<KalibrCamchain> for KalibrRig {
+        type Error = anyhow::Error;
+
+        fn try_from(from: KalibrCamchain) -> Result<Self, Self::Error> {
+            let mut indexed = vec![];
+            for (name, camera) in from {
+                let index: usize = name
+                    .strip_prefix("cam")
+                    .and_then(|index| index.parse().ok())
+                    .ok_or_else(|| anyhow!("invalid camera name '{name}'"))?;
+                indexed.push((index, name, camera));
+            }
+            indexed.sort_unstable_by_key(|(index, _, _)| *index);
+
+            let mut cameras = Vec::with_capacity(indexed.len());
+            let mut pose = Isometry3::identity();
+
+            for (expected, (index, name, camera)) in indexed.into_iter().enumerate() {
+                ensure!(
+                    index == expected,
+                    "expect camera cam{expected}, but get {name}"
+                );
+
+                if index > 0 {
+                    let t_cn_cnm1 = camera
+                        .extrinsic()
+                        .ok_or_else(|| anyhow!("T_cn_cnm1 is missing in {name}"))??;
+                    pose *= t_cn_cnm1.to_na().inverse();
+                }
+
+                let calibration = CameraCalibration {
+                    intrinsics: camera
+                        .intrinsic_params()
+                        .with_context(|| format!("invalid intrinsics in {name}"))?,
+                    image_size: camera.image_size(),
+                    extrinsics: CameraExtrinsicParams::from(&pose),
+                    name: Some(name),
+                    frame_id: None,
+                    provenance: None,
+                };
+                cameras.push(KalibrRigCamera {
+                    calibration,
+                    cam_overlaps: camera.cam_overlaps,
+                    rostopic: camera.rostopic,
+                });
+            }
+
+            Ok(Self { cameras })
+        }
+    }
+
+    /// Converts the rig to a camera chain keyed by the indices of the
+    /// cameras, regardless of the camera names. It fails if any
+    /// camera is not representable as
+    /// [KalibrCamera::from_intrinsic_params] describes.
+    impl TryFrom<&KalibrRig> for KalibrCamchain {
+        type Error = anyhow::Error;
+
+        fn try_from(from: &KalibrRig) -> Result<Self, Self::Error> {
+            let mut chain = KalibrCamchain::new();
+            let mut prev_pose: Option<Isometry3<f64>> = None;
+
+            for (index, camera) in from.cameras.iter().enumerate() {
+                let KalibrRigCamera {
+                    calibration,
+                    cam_overlaps,
+                    rostopic,
+                } = camera;
+                let pose = Isometry3::from(&calibration.extrinsics);
+
+                let mut block = KalibrCamera::from_intrinsic_params(
+                    &calibration.intrinsics,
+                    &calibration.image_size,
+                )
+                .with_context(|| format!("invalid intrinsics in cam{index}"))?;
+                block.t_cn_cnm1 = prev_pose.map(|prev_pose| {
+                    let mat: Matrix4<f64> = (pose.inverse() * prev_pose).to_homogeneous();
+                    [0, 1, 2, 3].map(|row| [0, 1, 2, 3].map(|col| r64(mat[(row, col)])))
+                });
+                block.cam_overlaps = cam_overlaps.clone();
+                block.rostopic = rostopic.clone();
+
+                chain.insert(format!("cam{index}"), block);
+                prev_pose = Some(pose);
+            }
+
+            Ok(chain)
+        }
+    }
+
+    impl TryFrom<KalibrRig> for KalibrCamchain {
+        type Error = anyhow::Error;
+
+        fn try_from(from: KalibrRig) -> Result<Self, Self::Error> {
+            (&from).try_into()
+        }
+    }
+
+    impl Serialize for KalibrRig {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let chain = KalibrCamchain::try_from(self).map_err(S::Error::custom)?;
+            chain.serialize(serializer)
+        }
+    }
+}
@@ -3,10 +3,77 @@
 pub use camera_matrix::CameraMatrix;
 mod camera_matrix;
 
+#[cfg(feature = "with-arena")]
+pub mod arena;
+#[cfg(feature = "with-camera-info")]
+pub mod camera_info;
+#[cfg(feature = "with-kalibr")]
+pub mod kalibr;
 pub mod mrpt;
+#[cfg(feature = "with-nuscenes")]
+pub mod nuscenes;
+#[cfg(feature = "with-opencv-storage")]
+pub mod opencv_storage;
+#[cfg(feature = "with-ros2")]
+pub mod ros2;
+#[cfg(feature = "with-vins")]
+pub mod vins;
+#[cfg(feature = "with-waymo")]
+pub mod waymo;
 
 pub use camera_intrinsic_params::CameraIntrinsicParams;
 mod camera_intrinsic_params;
 
-pub use distortion_coefs::DistortionCoefs;
+#[cfg(feature = "with-nalgebra")]
+pub use camera_extrinsic_params::CameraExtrinsicParams;
+#[cfg(feature = "with-nalgebra")]
+mod camera_extrinsic_params;
+
+#[cfg(feature = "with-nalgebra")]
+pub use camera_calibration::CameraCalibration;
+#[cfg(all(feature = "with-nalgebra", feature = "with-arena"))]
+pub use camera_calibration::CameraCalibrationRef;
+#[cfg(feature = "with-nalgebra")]
+mod camera_calibration;
+
+pub use coordinate_convention::{CoordinateConvention, Direction, Handedness};
+mod coordinate_convention;
+
+pub use distortion_coefs::{plausible_distortion_coefs, DistortionCoefs, DistortionLimits};
 mod distortion_coefs;
+
+pub use distortion_model::DistortionModel;
+mod distortion_model;
+
+pub use stereo_calibration::{StereoCalibration, StereoRectification};
+mod stereo_calibration;
+
+pub use roi::Roi;
+mod roi;
+
+pub use image_size::ImageSize;
+mod image_size;
+
+pub use issue::{Issue, Severity};
+mod issue;
+
+#[cfg(feature = "with-fingerprint")]
+pub use fingerprint::Fingerprint;
+#[cfg(feature = "with-fingerprint")]
+mod fingerprint;
+
+#[cfg(feature = "with-fingerprint")]
+pub use recording_metadata::RecordingMetadata;
+#[cfg(feature = "with-fingerprint")]
+mod recording_metadata;
+
+#[cfg(feature = "with-opencv")]
+mod mat;
+
+#[cfg(any(feature = "with-ros2", feature = "with-opencv-storage"))]
+mod ordered_value;
+
+#[cfg(feature = "with-arena")]
+pub use provenance::ProvenanceRef;
+pub use provenance::{Attested, Provenance, WithProvenance};
+mod provenance;
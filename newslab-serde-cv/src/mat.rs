@@ -0,0 +1,68 @@
+use anyhow::{bail, ensure, Result};
+use noisy_float::prelude::*;
+use opencv::{core::Mat, prelude::*};
+
+/// Reads the elements of a single-channel 2D `Mat` of `f64` or `f32`
+/// in row-major order, along with the numbers of rows and columns.
+pub(crate) fn read_elements(mat: &Mat) -> Result<(Vec<f64>, usize, usize)> {
+    ensure!(
+        mat.dims() == 2,
+        "expect a 2D Mat, but get {} dimensions",
+        mat.dims()
+    );
+    ensure!(
+        mat.channels() == 1,
+        "expect a single-channel Mat, but get {} channels",
+        mat.channels()
+    );
+
+    let rows = mat.rows();
+    let cols = mat.cols();
+    let mut elements = Vec::with_capacity((rows * cols) as usize);
+    for row in 0..rows {
+        for col in 0..cols {
+            let value = match mat.depth() {
+                opencv::core::CV_64F => *mat.at_2d::<f64>(row, col)?,
+                opencv::core::CV_32F => *mat.at_2d::<f32>(row, col)? as f64,
+                depth => bail!("expect a Mat of CV_64F or CV_32F, but get depth {depth}"),
+            };
+            ensure!(
+                value.is_finite(),
+                "the element at ({row}, {col}) is not finite"
+            );
+            elements.push(value);
+        }
+    }
+
+    Ok((elements, rows as usize, cols as usize))
+}
+
+/// Reads a `Mat` of `f64` or `f32` with exactly `R` rows and `C`
+/// columns. The `name` is shown in error messages.
+pub(crate) fn read_rows<const C: usize, const R: usize>(
+    mat: &Mat,
+    name: &str,
+) -> Result<[[R64; C]; R]> {
+    let (elements, rows, cols) = read_elements(mat)?;
+    ensure!(
+        (rows, cols) == (R, C),
+        "expect {name} to be a {R}x{C} matrix, but get {rows}x{cols}"
+    );
+
+    let mut output = [[r64(0.0); C]; R];
+    for (row, chunk) in output.iter_mut().zip(elements.chunks(C)) {
+        for (elem, &value) in row.iter_mut().zip(chunk) {
+            *elem = r64(value);
+        }
+    }
+    Ok(output)
+}
+
+/// Creates a `Mat` of `f64` from the rows of a matrix.
+pub(crate) fn from_rows<const C: usize>(rows: &[[R64; C]]) -> Mat {
+    let elements: Vec<f64> = rows.iter().flatten().map(|val| val.raw()).collect();
+    Mat::from_exact_iter(elements.into_iter())
+        .unwrap()
+        .reshape(1, rows.len() as i32)
+        .unwrap()
+}
@@ -10,6 +10,9 @@ use opencv::prelude::*;
 use serde::{de::Error as _, Deserialize, Deserializer};
 use std::mem;
 
+/// The tolerance used to check that a rotation matrix is orthonormal.
+pub(crate) const ROTATION_TOLERANCE: f64 = 1e-6;
+
 /// The type defines the calibration parameter file generated by MRPT
 /// camera-calib.
 #[derive(Debug, Clone, Deserialize)]
@@ -56,7 +59,7 @@ impl MrptCalibration {
 
         Ok(CameraIntrinsicParams {
             camera_matrix,
-            distortion_coefs,
+            distortion_coefs: distortion_coefs.into(),
         })
     }
 }
@@ -189,6 +192,41 @@ pub struct ExtrinsicMatrix {
 }
 
 impl ExtrinsicMatrix {
+    /// Creates a transform after checking that the rotation matrix is
+    /// orthonormal with a positive determinant.
+    pub fn new(rot: [[R64; 3]; 3], trans: [R64; 3]) -> Result<Self> {
+        let m = rot.map(|row| row.map(|val| val.raw()));
+
+        for i in 0..3 {
+            for j in 0..3 {
+                let dot: f64 = (0..3).map(|k| m[i][k] * m[j][k]).sum();
+                let expect = if i == j { 1.0 } else { 0.0 };
+                ensure!(
+                    (dot - expect).abs() <= ROTATION_TOLERANCE,
+                    "the rotation matrix is not orthonormal"
+                );
+            }
+        }
+
+        let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+        ensure!(det > 0.0, "the rotation matrix is a reflection");
+
+        Ok(Self { rot, trans })
+    }
+
+    /// Creates a transform from a 4x4 row-major homogeneous matrix.
+    pub fn from_homogeneous(mat: &[[R64; 4]; 4]) -> Result<Self> {
+        ensure!(
+            mat[3] == [r64(0.0), r64(0.0), r64(0.0), r64(1.0)],
+            "the last row of a homogeneous transform must be [0, 0, 0, 1]"
+        );
+        let rot = [0, 1, 2].map(|row| [mat[row][0], mat[row][1], mat[row][2]]);
+        let trans = [mat[0][3], mat[1][3], mat[2][3]];
+        Self::new(rot, trans)
+    }
+
     #[cfg(feature = "with-nalgebra")]
     pub fn to_na(&self) -> na::Isometry3<f64> {
         use slice_of_array::prelude::*;
@@ -0,0 +1,369 @@
+//! Records in the nuScenes dataset tables related to calibration.
+//!
+//! ```rust
+//! # use newslab_serde_cv::nuscenes::CalibratedSensor;
+//! let json = r#"[
+//!     {
+//!         "token": "f4d2a6c281f34a7eb8bb033d82321f79",
+//!         "sensor_token": "47fcd48f71d75e0da5c8c1704a9bfe0a",
+//!         "translation": [3.412, 0.0, 0.5],
+//!         "rotation": [0.9999984769132877, 0.0, 0.0, 0.0017453283658983088],
+//!         "camera_intrinsic": []
+//!     },
+//!     {
+//!         "token": "1d31c729b073425e8e0202c5c6e66ee1",
+//!         "sensor_token": "725903f5b62f56118f4094b46a4470d8",
+//!         "translation": [1.70079118954, 0.0159456324149, 1.51095763913],
+//!         "rotation": [0.4998015430569128, -0.5030316162024876, 0.4997798114386805, -0.49737083824542755],
+//!         "camera_intrinsic": [[1266.417203046554, 0.0, 816.2670197447984],
+//!                              [0.0, 1266.417203046554, 491.50706579294757],
+//!                              [0.0, 0.0, 1.0]]
+//!     }
+//! ]"#;
+//! let sensors: Vec<CalibratedSensor> = serde_json::from_str(json).unwrap();
+//!
+//! // The radar has no intrinsics.
+//! assert!(sensors[0].intrinsic_params().unwrap().is_none());
+//!
+//! let params = sensors[1].intrinsic_params().unwrap().unwrap();
+//! assert_eq!(params.camera_matrix.cx(), 816.2670197447984);
+//! assert_eq!(sensors[1].extrinsic().unwrap().trans_xyz[2], 1.51095763913);
+//!
+//! // The rotation is not a unit quaternion.
+//! let mut sensor = sensors[0].clone();
+//! sensor.rotation[0] = noisy_float::prelude::r64(0.9);
+//! assert!(sensor.extrinsic().is_err());
+//! ```
+
+use crate::{
+    mrpt::{ExtrinsicTransform, ROTATION_TOLERANCE},
+    CameraIntrinsicParams, CameraMatrix, DistortionModel,
+};
+use anyhow::{anyhow, ensure, Result};
+use noisy_float::prelude::*;
+use serde::Deserialize;
+#[cfg(feature = "with-arena")]
+use {
+    crate::arena::{set_field, DeserializeIn, InArena, OwnedSliceIn},
+    bumpalo::Bump,
+    serde::de::{Deserializer, Error as _, IgnoredAny, MapAccess, Visitor},
+    std::fmt,
+};
+
+/// A record in `calibrated_sensor.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CalibratedSensor {
+    pub token: String,
+    pub sensor_token: String,
+    /// The sensor position in the ego vehicle frame in meters.
+    pub translation: [R64; 3],
+    /// The sensor orientation in the ego vehicle frame as a `[w, x,
+    /// y, z]` quaternion.
+    pub rotation: [R64; 4],
+    /// The camera matrix, which is empty for non-camera sensors.
+    #[serde(default)]
+    pub camera_intrinsic: Vec<[R64; 3]>,
+}
+
+impl CalibratedSensor {
+    /// Gets the intrinsic parameters, or `None` if the sensor is not
+    /// a camera. nuScenes images are undistorted, so the distortion
+    /// coefficients are zeros.
+    pub fn intrinsic_params(&self) -> Result<Option<CameraIntrinsicParams>> {
+        intrinsic_params(&self.camera_intrinsic)
+    }
+
+    /// Gets the sensor-to-ego transform. It fails if the rotation is
+    /// not a unit quaternion.
+    pub fn extrinsic(&self) -> Result<ExtrinsicTransform> {
+        extrinsic(self.rotation, self.translation)
+    }
+}
+
+fn intrinsic_params(camera_intrinsic: &[[R64; 3]]) -> Result<Option<CameraIntrinsicParams>> {
+    if camera_intrinsic.is_empty() {
+        return Ok(None);
+    }
+
+    let rows: [[R64; 3]; 3] = camera_intrinsic
+        .try_into()
+        .map_err(|_| anyhow!("camera_intrinsic must be a 3x3 matrix"))?;
+    ensure!(
+        rows[1][0] == 0.0 && rows[2] == [r64(0.0), r64(0.0), r64(1.0)],
+        "camera_intrinsic is not a camera matrix"
+    );
+
+    Ok(Some(CameraIntrinsicParams {
+        camera_matrix: CameraMatrix(rows),
+        distortion_coefs: DistortionModel::zeros(),
+    }))
+}
+
+fn extrinsic(rotation: [R64; 4], translation: [R64; 3]) -> Result<ExtrinsicTransform> {
+    let norm = rotation
+        .iter()
+        .map(|val| val.raw().powi(2))
+        .sum::<f64>()
+        .sqrt();
+    ensure!(
+        (norm - 1.0).abs() <= ROTATION_TOLERANCE,
+        "the rotation is not a unit quaternion, whose norm is {norm}"
+    );
+
+    Ok(ExtrinsicTransform {
+        rot_wijk: rotation,
+        trans_xyz: translation,
+    })
+}
+
+/// A record in `sensor.json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Sensor {
+    pub token: String,
+    /// The sensor name like `CAM_FRONT` or `LIDAR_TOP`.
+    pub channel: String,
+    pub modality: Modality,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modality {
+    Camera,
+    Lidar,
+    Radar,
+}
+
+/// A record in `calibrated_sensor.json` whose strings and matrix are
+/// allocated in a bump arena. It is deserialized by
+/// [arena::from_str_in](crate::arena::from_str_in).
+///
+/// ```rust
+/// # use newslab_serde_cv::{arena, nuscenes::CalibratedSensorRef};
+/// # use bumpalo::Bump;
+/// let json = r#"[{
+///     "token": "1d31c729b073425e8e0202c5c6e66ee1",
+///     "sensor_token": "725903f5b62f56118f4094b46a4470d8",
+///     "translation": [1.70079118954, 0.0159456324149, 1.51095763913],
+///     "rotation": [0.4998015430569128, -0.5030316162024876, 0.4997798114386805, -0.49737083824542755],
+///     "camera_intrinsic": [[1266.417203046554, 0.0, 816.2670197447984],
+///                          [0.0, 1266.417203046554, 491.50706579294757],
+///                          [0.0, 0.0, 1.0]]
+/// }]"#;
+///
+/// let bump = Bump::new();
+/// let sensors: &[CalibratedSensorRef] = arena::from_str_in(json, &bump).unwrap();
+/// assert_eq!(sensors[0].sensor_token, "725903f5b62f56118f4094b46a4470d8");
+///
+/// let params = sensors[0].intrinsic_params().unwrap().unwrap();
+/// assert_eq!(params.camera_matrix.cx(), 816.2670197447984);
+/// assert_eq!(sensors[0].into_owned().token, sensors[0].token);
+///
+/// // Missing fields are rejected as in the owned record.
+/// let json = r#"{ "token": "1d31c729b073425e8e0202c5c6e66ee1" }"#;
+/// assert!(arena::from_str_in::<CalibratedSensorRef>(json, &bump).is_err());
+/// ```
+#[cfg(feature = "with-arena")]
+#[derive(Debug, Clone, Copy)]
+pub struct CalibratedSensorRef<'a> {
+    pub token: &'a str,
+    pub sensor_token: &'a str,
+    /// The sensor position in the ego vehicle frame in meters.
+    pub translation: [R64; 3],
+    /// The sensor orientation in the ego vehicle frame as a `[w, x,
+    /// y, z]` quaternion.
+    pub rotation: [R64; 4],
+    /// The camera matrix, which is empty for non-camera sensors.
+    pub camera_intrinsic: &'a [[R64; 3]],
+}
+
+#[cfg(feature = "with-arena")]
+impl CalibratedSensorRef<'_> {
+    /// Gets the intrinsic parameters in the way of
+    /// [CalibratedSensor::intrinsic_params].
+    pub fn intrinsic_params(&self) -> Result<Option<CameraIntrinsicParams>> {
+        intrinsic_params(self.camera_intrinsic)
+    }
+
+    /// Gets the sensor-to-ego transform in the way of
+    /// [CalibratedSensor::extrinsic].
+    pub fn extrinsic(&self) -> Result<ExtrinsicTransform> {
+        extrinsic(self.rotation, self.translation)
+    }
+
+    /// Copies the record out of the arena.
+    pub fn into_owned(self) -> CalibratedSensor {
+        CalibratedSensor {
+            token: self.token.to_string(),
+            sensor_token: self.sensor_token.to_string(),
+            translation: self.translation,
+            rotation: self.rotation,
+            camera_intrinsic: self.camera_intrinsic.to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "with-arena")]
+impl<'a> DeserializeIn<'a> for CalibratedSensorRef<'a> {
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            Token,
+            SensorToken,
+            Translation,
+            Rotation,
+            CameraIntrinsic,
+            #[serde(other)]
+            Other,
+        }
+
+        struct RecordVisitor<'a>(&'a Bump);
+
+        impl<'de, 'a> Visitor<'de> for RecordVisitor<'a> {
+            type Value = CalibratedSensorRef<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a calibrated sensor record")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut token = None;
+                let mut sensor_token = None;
+                let mut translation = None;
+                let mut rotation = None;
+                let mut camera_intrinsic = None;
+
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Token => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut token, value, "token")?;
+                        }
+                        Field::SensorToken => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut sensor_token, value, "sensor_token")?;
+                        }
+                        Field::Translation => {
+                            set_field(&mut translation, map.next_value()?, "translation")?;
+                        }
+                        Field::Rotation => {
+                            set_field(&mut rotation, map.next_value()?, "rotation")?;
+                        }
+                        Field::CameraIntrinsic => {
+                            let value = map.next_value_seed(OwnedSliceIn::new(self.0))?;
+                            set_field(&mut camera_intrinsic, value, "camera_intrinsic")?;
+                        }
+                        Field::Other => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(CalibratedSensorRef {
+                    token: token.ok_or_else(|| A::Error::missing_field("token"))?,
+                    sensor_token: sensor_token
+                        .ok_or_else(|| A::Error::missing_field("sensor_token"))?,
+                    translation: translation
+                        .ok_or_else(|| A::Error::missing_field("translation"))?,
+                    rotation: rotation.ok_or_else(|| A::Error::missing_field("rotation"))?,
+                    camera_intrinsic: camera_intrinsic.unwrap_or_default(),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(RecordVisitor(bump))
+    }
+}
+
+/// A record in `sensor.json` whose strings are allocated in a bump
+/// arena.
+#[cfg(feature = "with-arena")]
+#[derive(Debug, Clone, Copy)]
+pub struct SensorRef<'a> {
+    pub token: &'a str,
+    /// The sensor name like `CAM_FRONT` or `LIDAR_TOP`.
+    pub channel: &'a str,
+    pub modality: Modality,
+}
+
+#[cfg(feature = "with-arena")]
+impl SensorRef<'_> {
+    /// Copies the record out of the arena.
+    pub fn into_owned(self) -> Sensor {
+        Sensor {
+            token: self.token.to_string(),
+            channel: self.channel.to_string(),
+            modality: self.modality,
+        }
+    }
+}
+
+#[cfg(feature = "with-arena")]
+impl<'a> DeserializeIn<'a> for SensorRef<'a> {
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            Token,
+            Channel,
+            Modality,
+            #[serde(other)]
+            Other,
+        }
+
+        struct RecordVisitor<'a>(&'a Bump);
+
+        impl<'de, 'a> Visitor<'de> for RecordVisitor<'a> {
+            type Value = SensorRef<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sensor record")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut token = None;
+                let mut channel = None;
+                let mut modality = None;
+
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Token => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut token, value, "token")?;
+                        }
+                        Field::Channel => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut channel, value, "channel")?;
+                        }
+                        Field::Modality => {
+                            set_field(&mut modality, map.next_value()?, "modality")?;
+                        }
+                        Field::Other => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(SensorRef {
+                    token: token.ok_or_else(|| A::Error::missing_field("token"))?,
+                    channel: channel.ok_or_else(|| A::Error::missing_field("channel"))?,
+                    modality: modality.ok_or_else(|| A::Error::missing_field("modality"))?,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(RecordVisitor(bump))
+    }
+}
@@ -0,0 +1,761 @@
+//! OpenCV `FileStorage` documents in YAML and XML, such as the
+//! calibration files saved by the `calibrateCamera()` samples.
+//!
+//! The parser is written in pure Rust and covers the subset of the
+//! formats that `FileStorage` writes for flat documents: top-level
+//! scalars, nested maps, flat sequences and `!!opencv-matrix` nodes.
+//! A matrix node is read as a map of `rows`, `cols`, `dt` and `data`
+//! fields, so it can be deserialized into [OpenCvMatrix].
+//!
+//! ```rust
+//! # use newslab_serde_cv::opencv_storage::{self, OpenCvCalibration};
+//! let yaml = r#"%YAML:1.0
+//! ---
+//! calibration_time: "Mon Oct 12 10:00:00 2026"
+//! image_width: 640
+//! image_height: 480
+//! camera_matrix: !!opencv-matrix
+//!    rows: 3
+//!    cols: 3
+//!    dt: d
+//!    data: [ 5.3e+02, 0., 3.2e+02, 0., 5.3e+02, 2.4e+02, 0., 0.,
+//!        1. ]
+//! distortion_coefficients: !!opencv-matrix
+//!    rows: 5
+//!    cols: 1
+//!    dt: d
+//!    data: [ -2.8e-01, 7.0e-02, 1.0e-03, -2.0e-04, 0. ]
+//! avg_reprojection_error: 2.5e-01
+//! "#;
+//! let calib: OpenCvCalibration = opencv_storage::from_str(yaml).unwrap();
+//! let params = calib.intrinsic_params().unwrap();
+//! assert_eq!(params.camera_matrix.cx(), 320.0);
+//! assert_eq!(params.distortion_coefs.coefs()[0], -0.28);
+//! assert_eq!(calib.image_size().unwrap().width, 640);
+//!
+//! let xml = r#"<?xml version="1.0"?>
+//! <opencv_storage>
+//! <cameraMatrix type_id="opencv-matrix">
+//!   <rows>3</rows>
+//!   <cols>3</cols>
+//!   <dt>d</dt>
+//!   <data>
+//!     5.3e+02 0. 3.2e+02 0. 5.3e+02 2.4e+02 0. 0. 1.</data></cameraMatrix>
+//! <distCoeffs type_id="opencv-matrix">
+//!   <rows>1</rows>
+//!   <cols>8</cols>
+//!   <dt>d</dt>
+//!   <data>
+//!     -2.8e-01 7.0e-02 1.0e-03 -2.0e-04 0. 1.0e-02 0. 0.</data></distCoeffs>
+//! </opencv_storage>
+//! "#;
+//! let calib: OpenCvCalibration = opencv_storage::from_str(xml).unwrap();
+//! let params = calib.intrinsic_params().unwrap();
+//! assert_eq!(params.distortion_coefs.name(), "rational_polynomial");
+//! assert!(calib.image_size().is_none());
+//! ```
+
+use crate::{
+    ordered_value::OrderedValue, CameraIntrinsicParams, CameraMatrix, DistortionModel, ImageSize,
+};
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use indexmap::IndexMap;
+use noisy_float::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt::Write as _;
+
+/// The indentation of nested nodes in YAML, which is what OpenCV
+/// writes.
+const YAML_INDENT: usize = 3;
+
+/// Parses a YAML or XML document, which is told apart by whether it
+/// starts with `<`.
+pub fn from_str<T>(text: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let value = if text.trim_start().starts_with('<') {
+        parse_xml(text)?
+    } else {
+        parse_yaml(text)?
+    };
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Writes the value as a YAML document. Maps with the `rows`, `cols`,
+/// `dt` and `data` fields are written as `!!opencv-matrix` nodes.
+///
+/// ```rust
+/// # use newslab_serde_cv::{opencv_storage::{self, OpenCvCalibration}, CameraIntrinsicParams, ImageSize};
+/// let params = CameraIntrinsicParams::identity();
+/// let size = ImageSize { width: 640, height: 480 };
+/// let calib = OpenCvCalibration::from_intrinsic_params(&params, &size).unwrap();
+///
+/// let yaml = opencv_storage::to_yaml_string(&calib).unwrap();
+/// assert!(yaml.starts_with("%YAML:1.0\n---\nimage_width: 640\n"));
+/// assert!(yaml.contains("camera_matrix: !!opencv-matrix\n   rows: 3\n"));
+///
+/// let back: OpenCvCalibration = opencv_storage::from_str(&yaml).unwrap();
+/// assert_eq!(back.intrinsic_params().unwrap(), params);
+/// ```
+pub fn to_yaml_string<T>(value: &T) -> Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    let OrderedValue::Object(map) = OrderedValue::from_serialize(value)? else {
+        bail!("the value must be serialized to a map");
+    };
+    let mut out = "%YAML:1.0\n---\n".to_string();
+    write_yaml_map(&mut out, &map, 0)?;
+    Ok(out)
+}
+
+/// Writes the value as an XML document like [to_yaml_string].
+///
+/// ```rust
+/// # use newslab_serde_cv::{opencv_storage::{self, OpenCvCalibration}, CameraIntrinsicParams, ImageSize};
+/// let params = CameraIntrinsicParams::identity();
+/// let size = ImageSize { width: 640, height: 480 };
+/// let calib = OpenCvCalibration::from_intrinsic_params(&params, &size).unwrap();
+///
+/// let xml = opencv_storage::to_xml_string(&calib).unwrap();
+/// assert!(xml.contains("<camera_matrix type_id=\"opencv-matrix\">\n  <rows>3</rows>\n"));
+///
+/// let back: OpenCvCalibration = opencv_storage::from_str(&xml).unwrap();
+/// assert_eq!(back.intrinsic_params().unwrap(), params);
+/// ```
+pub fn to_xml_string<T>(value: &T) -> Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    let OrderedValue::Object(map) = OrderedValue::from_serialize(value)? else {
+        bail!("the value must be serialized to a map");
+    };
+    let mut out = "<?xml version=\"1.0\"?>\n<opencv_storage>\n".to_string();
+    for (key, value) in &map {
+        write_xml_node(&mut out, key, value, 0)?;
+    }
+    out.push_str("</opencv_storage>\n");
+    Ok(out)
+}
+
+/// An `!!opencv-matrix` node. The elements are kept in row-major
+/// order, with the channels of an element next to each other.
+///
+/// ```rust
+/// # use newslab_serde_cv::opencv_storage::OpenCvMatrix;
+/// let json = r#"{ "rows": 2, "cols": 1, "dt": "2f", "data": [1.0, 2.0, 3.0, 4.0] }"#;
+/// let mat: OpenCvMatrix = serde_json::from_str(json).unwrap();
+/// assert_eq!(mat.channels(), 2);
+///
+/// // The data size does not match the shape.
+/// let json = r#"{ "rows": 2, "cols": 2, "dt": "d", "data": [1.0, 2.0, 3.0] }"#;
+/// assert!(serde_json::from_str::<OpenCvMatrix>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "OpenCvMatrixUnchecked", into = "OpenCvMatrixUnchecked")]
+pub struct OpenCvMatrix {
+    rows: usize,
+    cols: usize,
+    dt: String,
+    data: Vec<R64>,
+}
+
+impl OpenCvMatrix {
+    /// Creates a single-channel matrix of `f64` from the elements in
+    /// row-major order.
+    pub fn new(rows: usize, cols: usize, data: Vec<R64>) -> Result<Self> {
+        OpenCvMatrixUnchecked {
+            rows,
+            cols,
+            dt: "d".to_string(),
+            data,
+        }
+        .try_into()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Gets the element type like `d` or `2f`.
+    pub fn dt(&self) -> &str {
+        &self.dt
+    }
+
+    pub fn data(&self) -> &[R64] {
+        &self.data
+    }
+
+    /// Gets the number of channels, which is the number leading the
+    /// element type.
+    pub fn channels(&self) -> usize {
+        channels_of(&self.dt).unwrap()
+    }
+}
+
+/// Reads a 3x3 single-channel matrix as audited in deserialization.
+impl TryFrom<&OpenCvMatrix> for CameraMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &OpenCvMatrix) -> Result<Self, Self::Error> {
+        use slice_of_array::prelude::*;
+
+        ensure!(
+            from.rows == 3 && from.cols == 3 && from.channels() == 1,
+            "expect a 3x3 single-channel camera matrix, but get {}x{} of type {}",
+            from.rows,
+            from.cols,
+            from.dt
+        );
+        let rows: &[[R64; 3]] = from.data.nest();
+        CameraMatrix::try_from(<[[R64; 3]; 3]>::try_from(rows).unwrap())
+            .map_err(|_| anyhow!("the camera matrix is not a pinhole camera matrix"))
+    }
+}
+
+/// Reads a row or column vector in the OpenCV order as
+/// [DistortionModel::from_opencv_coefs] does.
+impl TryFrom<&OpenCvMatrix> for DistortionModel {
+    type Error = anyhow::Error;
+
+    fn try_from(from: &OpenCvMatrix) -> Result<Self, Self::Error> {
+        ensure!(
+            (from.rows == 1 || from.cols == 1) && from.channels() == 1,
+            "expect a single-channel row or column vector, but get {}x{} of type {}",
+            from.rows,
+            from.cols,
+            from.dt
+        );
+        let coefs: Vec<f64> = from.data.iter().map(|val| val.raw()).collect();
+        Self::from_opencv_coefs(&coefs)
+    }
+}
+
+impl From<&CameraMatrix> for OpenCvMatrix {
+    fn from(from: &CameraMatrix) -> Self {
+        Self::new(3, 3, from.0.concat()).unwrap()
+    }
+}
+
+/// Writes the coefficients as a column vector like
+/// `calibrateCamera()` does.
+impl From<&DistortionModel> for OpenCvMatrix {
+    fn from(from: &DistortionModel) -> Self {
+        let coefs = from.coefs();
+        Self::new(coefs.len(), 1, coefs.to_vec()).unwrap()
+    }
+}
+
+impl TryFrom<OpenCvMatrixUnchecked> for OpenCvMatrix {
+    type Error = anyhow::Error;
+
+    fn try_from(from: OpenCvMatrixUnchecked) -> Result<Self, Self::Error> {
+        let OpenCvMatrixUnchecked {
+            rows,
+            cols,
+            dt,
+            data,
+        } = from;
+
+        let channels =
+            channels_of(&dt).ok_or_else(|| anyhow!("invalid matrix element type '{dt}'"))?;
+        ensure!(
+            rows * cols * channels == data.len(),
+            "data size ({}) does not match rows ({rows}), cols ({cols}) and channels ({channels})",
+            data.len()
+        );
+        Ok(Self {
+            rows,
+            cols,
+            dt,
+            data,
+        })
+    }
+}
+
+impl From<OpenCvMatrix> for OpenCvMatrixUnchecked {
+    fn from(from: OpenCvMatrix) -> Self {
+        let OpenCvMatrix {
+            rows,
+            cols,
+            dt,
+            data,
+        } = from;
+        Self {
+            rows,
+            cols,
+            dt,
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct OpenCvMatrixUnchecked {
+    rows: usize,
+    cols: usize,
+    dt: String,
+    data: Vec<R64>,
+}
+
+/// Parses the number of channels from an element type like `2f`,
+/// where the depth is one of `u`, `c`, `w`, `s`, `i`, `f` and `d`.
+fn channels_of(dt: &str) -> Option<usize> {
+    let depth = dt.chars().last()?;
+    if !"ucwsifd".contains(depth) {
+        return None;
+    }
+    match &dt[..dt.len() - 1] {
+        "" => Some(1),
+        count => count.parse().ok().filter(|&count| count > 0),
+    }
+}
+
+/// The camera calibration saved by the OpenCV calibration samples.
+///
+/// The camera matrix and the distortion coefficients are also read
+/// from the `cameraMatrix`/`distCoeffs` and `K`/`D` names used by
+/// other tools. Other fields in the file are ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OpenCvCalibration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_width: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_height: Option<usize>,
+    #[serde(alias = "cameraMatrix", alias = "K")]
+    pub camera_matrix: OpenCvMatrix,
+    #[serde(alias = "distCoeffs", alias = "D")]
+    pub distortion_coefficients: OpenCvMatrix,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_reprojection_error: Option<R64>,
+}
+
+impl OpenCvCalibration {
+    /// Creates a calibration from the intrinsics and the image size.
+    ///
+    /// It fails for the Kannala-Brandt model, which cannot be told
+    /// apart from the plumb bob model in the file.
+    pub fn from_intrinsic_params(
+        params: &CameraIntrinsicParams,
+        image_size: &ImageSize,
+    ) -> Result<Self> {
+        let CameraIntrinsicParams {
+            camera_matrix,
+            distortion_coefs,
+        } = params;
+        ensure!(
+            !distortion_coefs.is_fisheye(),
+            "the kannala_brandt distortion model cannot be represented in OpenCV calibration files"
+        );
+
+        Ok(Self {
+            image_width: Some(image_size.width),
+            image_height: Some(image_size.height),
+            camera_matrix: camera_matrix.into(),
+            distortion_coefficients: distortion_coefs.into(),
+            avg_reprojection_error: None,
+        })
+    }
+
+    /// Converts the camera matrix and the distortion coefficients to
+    /// camera parameters. The distortion model is chosen by the
+    /// number of coefficients.
+    pub fn intrinsic_params(&self) -> Result<CameraIntrinsicParams> {
+        Ok(CameraIntrinsicParams {
+            camera_matrix: (&self.camera_matrix)
+                .try_into()
+                .context("invalid camera_matrix")?,
+            distortion_coefs: (&self.distortion_coefficients)
+                .try_into()
+                .context("invalid distortion_coefficients")?,
+        })
+    }
+
+    /// Gets the image size if both the width and the height are
+    /// given.
+    pub fn image_size(&self) -> Option<ImageSize> {
+        Some(ImageSize {
+            width: self.image_width?,
+            height: self.image_height?,
+        })
+    }
+}
+
+/// A non-empty line of a YAML document with its indentation.
+struct YamlLine<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+fn parse_yaml(text: &str) -> Result<Value> {
+    let lines: Vec<_> = text
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.trim_end();
+            let text = line.trim_start();
+            let is_skipped =
+                text.is_empty() || text.starts_with(['#', '%']) || text == "---" || text == "...";
+            (!is_skipped).then_some(YamlLine {
+                number: index + 1,
+                indent: line.len() - text.len(),
+                text,
+            })
+        })
+        .collect();
+
+    let mut pos = 0;
+    let map = parse_yaml_map(&lines, &mut pos, 0)?;
+    if let Some(line) = lines.get(pos) {
+        bail!("unexpected indentation at line {}", line.number);
+    }
+    Ok(Value::Object(map))
+}
+
+fn parse_yaml_map(
+    lines: &[YamlLine],
+    pos: &mut usize,
+    indent: usize,
+) -> Result<Map<String, Value>> {
+    let mut map = Map::new();
+
+    while let Some(line) = lines.get(*pos) {
+        if line.indent < indent {
+            break;
+        }
+        ensure!(
+            line.indent == indent,
+            "unexpected indentation at line {}",
+            line.number
+        );
+        let (key, rest) = line
+            .text
+            .split_once(':')
+            .ok_or_else(|| anyhow!("expect 'key: value' at line {}", line.number))?;
+        *pos += 1;
+
+        // Tags like `!!opencv-matrix` carry no information beyond the
+        // fields of the node.
+        let mut rest = rest.trim();
+        if rest.starts_with("!!") {
+            rest = rest
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, rest)| rest.trim());
+        }
+
+        let value = if rest.is_empty() {
+            match lines.get(*pos) {
+                Some(next) if next.indent > indent => {
+                    Value::Object(parse_yaml_map(lines, pos, next.indent)?)
+                }
+                _ => Value::Null,
+            }
+        } else if rest.starts_with('[') {
+            let mut text = rest.to_string();
+            while !text.ends_with(']') {
+                let next = lines
+                    .get(*pos)
+                    .ok_or_else(|| anyhow!("the sequence at line {} is not closed", line.number))?;
+                text.push(' ');
+                text.push_str(next.text);
+                *pos += 1;
+            }
+            let items = text[1..text.len() - 1]
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(parse_scalar)
+                .collect();
+            Value::Array(items)
+        } else if rest.starts_with(['{', '-']) {
+            bail!("unsupported YAML syntax at line {}", line.number);
+        } else {
+            parse_scalar(rest)
+        };
+
+        map.insert(unquote(key.trim()).to_string(), value);
+    }
+
+    Ok(map)
+}
+
+/// Parses a scalar, which is an integer, a finite real number, or a
+/// string that is optionally quoted.
+fn parse_scalar(text: &str) -> Value {
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        return Value::String(unquote(text).to_string());
+    }
+    if let Ok(value) = text.parse::<i64>() {
+        return value.into();
+    }
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => value.into(),
+        _ => Value::String(text.to_string()),
+    }
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+fn parse_xml(text: &str) -> Result<Value> {
+    let mut parser = XmlParser { text, pos: 0 };
+    parser.skip_misc();
+    let (name, _, value) = parser.element()?;
+    ensure!(
+        name == "opencv_storage",
+        "expect the root element <opencv_storage>, but get <{name}>"
+    );
+    parser.skip_misc();
+    ensure!(
+        parser.rest().is_empty(),
+        "unexpected content after </opencv_storage>"
+    );
+    Ok(value)
+}
+
+struct XmlParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    /// Skips whitespace, declarations and comments.
+    fn skip_misc(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            let end = if trimmed.starts_with("<?") {
+                trimmed.find("?>").map(|end| end + 2)
+            } else if trimmed.starts_with("<!--") {
+                trimmed.find("-->").map(|end| end + 3)
+            } else {
+                return;
+            };
+            self.pos += end.unwrap_or(trimmed.len());
+        }
+    }
+
+    /// Parses an element into its name, its `type_id` attribute and
+    /// its value.
+    fn element(&mut self) -> Result<(String, Option<String>, Value)> {
+        let rest = self.rest();
+        ensure!(
+            rest.starts_with('<'),
+            "expect an element at byte {}",
+            self.pos
+        );
+        let end = rest
+            .find('>')
+            .ok_or_else(|| anyhow!("the tag at byte {} is not closed", self.pos))?;
+        let tag = &rest[1..end];
+        self.pos += end + 1;
+
+        let (tag, is_empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let name = name.to_string();
+        let type_id = attrs.split_once("type_id=").map(|(_, value)| {
+            let value = value.trim_start().trim_start_matches(['"', '\'']);
+            value[..value.find(['"', '\'']).unwrap_or(value.len())].to_string()
+        });
+        if is_empty {
+            return Ok((name, type_id, Value::Null));
+        }
+
+        let mut children = vec![];
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_misc();
+            } else if let Some(closing) = rest.strip_prefix("</") {
+                let end = closing
+                    .find('>')
+                    .ok_or_else(|| anyhow!("the tag at byte {} is not closed", self.pos))?;
+                ensure!(
+                    closing[..end].trim() == name,
+                    "expect </{name}>, but get </{}>",
+                    &closing[..end]
+                );
+                self.pos += end + 3;
+                break;
+            } else if rest.starts_with('<') {
+                children.push(self.element()?);
+            } else if rest.is_empty() {
+                bail!("the element <{name}> is not closed");
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                text.push_str(&unescape_xml(&rest[..end]));
+                self.pos += end;
+            }
+        }
+
+        let value = if children.is_empty() {
+            let text = text.trim();
+            if text.starts_with('"') {
+                Value::String(unquote(text).to_string())
+            } else {
+                let mut items: Vec<_> = text.split_whitespace().map(parse_scalar).collect();
+                match items.len() {
+                    0 => Value::Null,
+                    1 => items.pop().unwrap(),
+                    _ => Value::Array(items),
+                }
+            }
+        } else if children.iter().all(|(name, _, _)| name == "_") {
+            Value::Array(children.into_iter().map(|(_, _, value)| value).collect())
+        } else {
+            let mut map: Map<_, _> = children
+                .into_iter()
+                .map(|(name, _, value)| (name, value))
+                .collect();
+            // The data of a matrix is a sequence even if it has only
+            // one element.
+            if type_id.as_deref() == Some("opencv-matrix") {
+                if let Some(data) = map.get_mut("data") {
+                    if !data.is_array() {
+                        *data = Value::Array(vec![data.take()]);
+                    }
+                }
+            }
+            Value::Object(map)
+        };
+
+        Ok((name, type_id, value))
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Checks if the map is a matrix node.
+fn is_matrix(map: &IndexMap<String, OrderedValue>) -> bool {
+    map.len() == 4
+        && ["rows", "cols", "dt", "data"]
+            .iter()
+            .all(|key| map.contains_key(*key))
+        && map["data"].is_array()
+}
+
+fn format_scalar(value: &OrderedValue) -> Result<String> {
+    let text = match value {
+        OrderedValue::Bool(value) => (*value as u8).to_string(),
+        OrderedValue::Number(value) => value.to_string(),
+        OrderedValue::String(value) => format!("\"{value}\""),
+        _ => bail!("expect a scalar, but get {value}"),
+    };
+    Ok(text)
+}
+
+fn write_yaml_map(
+    out: &mut String,
+    map: &IndexMap<String, OrderedValue>,
+    indent: usize,
+) -> Result<()> {
+    let pad = " ".repeat(indent);
+
+    for (key, value) in map {
+        match value {
+            OrderedValue::Null => {}
+            OrderedValue::Object(map) if is_matrix(map) => {
+                writeln!(out, "{pad}{key}: !!opencv-matrix").unwrap();
+                let pad = " ".repeat(indent + YAML_INDENT);
+                writeln!(out, "{pad}rows: {}", map["rows"]).unwrap();
+                writeln!(out, "{pad}cols: {}", map["cols"]).unwrap();
+                writeln!(out, "{pad}dt: {}", map["dt"].as_str().unwrap_or("d")).unwrap();
+                writeln!(out, "{pad}data: [ {} ]", format_items(&map["data"], ", ")?).unwrap();
+            }
+            OrderedValue::Object(map) => {
+                writeln!(out, "{pad}{key}:").unwrap();
+                write_yaml_map(out, map, indent + YAML_INDENT)?;
+            }
+            OrderedValue::Array(_) => {
+                writeln!(out, "{pad}{key}: [ {} ]", format_items(value, ", ")?).unwrap();
+            }
+            _ => {
+                writeln!(out, "{pad}{key}: {}", format_scalar(value)?).unwrap();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_xml_node(out: &mut String, key: &str, value: &OrderedValue, indent: usize) -> Result<()> {
+    let pad = " ".repeat(indent);
+
+    match value {
+        OrderedValue::Null => {}
+        OrderedValue::Object(map) if is_matrix(map) => {
+            writeln!(out, "{pad}<{key} type_id=\"opencv-matrix\">").unwrap();
+            for key in ["rows", "cols", "dt"] {
+                let text = match &map[key] {
+                    OrderedValue::String(text) => escape_xml(text),
+                    value => value.to_string(),
+                };
+                writeln!(out, "{pad}  <{key}>{text}</{key}>").unwrap();
+            }
+            writeln!(out, "{pad}  <data>").unwrap();
+            writeln!(
+                out,
+                "{pad}    {}</data></{key}>",
+                format_items(&map["data"], " ")?
+            )
+            .unwrap();
+        }
+        OrderedValue::Object(map) => {
+            writeln!(out, "{pad}<{key}>").unwrap();
+            for (key, value) in map {
+                write_xml_node(out, key, value, indent + 2)?;
+            }
+            writeln!(out, "{pad}</{key}>").unwrap();
+        }
+        OrderedValue::Array(_) => {
+            writeln!(out, "{pad}<{key}>").unwrap();
+            writeln!(out, "{pad}  {}</{key}>", format_items(value, " ")?).unwrap();
+        }
+        _ => {
+            let text = escape_xml(&format_scalar(value)?);
+            writeln!(out, "{pad}<{key}>{text}</{key}>").unwrap();
+        }
+    }
+
+    Ok(())
+}
+
+fn format_items(value: &OrderedValue, sep: &str) -> Result<String> {
+    let OrderedValue::Array(items) = value else {
+        bail!("expect a sequence, but get {value}");
+    };
+    let items: Vec<_> = items.iter().map(format_scalar).collect::<Result<_>>()?;
+    Ok(items.join(sep))
+}
@@ -0,0 +1,234 @@
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "with-arena")]
+use {
+    crate::arena::{set_field, DeserializeIn, InArena},
+    bumpalo::Bump,
+    serde::de::{Deserializer, Error as _, IgnoredAny, MapAccess, Visitor},
+    std::fmt,
+};
+
+/// The record of the calibration run that produced a document.
+///
+/// The `date` is expected in ISO 8601 form, such as `"2024-03-01"` or
+/// `"2024-03-01T10:00:00Z"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Provenance {
+    pub tool: String,
+    pub tool_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    pub date: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_dataset: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+}
+
+/// A [Provenance] whose strings are allocated in a bump arena. It is
+/// nested in [CameraCalibrationRef](crate::CameraCalibrationRef).
+#[cfg(feature = "with-arena")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProvenanceRef<'a> {
+    pub tool: &'a str,
+    pub tool_version: &'a str,
+    pub operator: Option<&'a str>,
+    pub date: &'a str,
+    pub input_dataset: Option<&'a str>,
+    pub git_commit: Option<&'a str>,
+}
+
+#[cfg(feature = "with-arena")]
+impl ProvenanceRef<'_> {
+    /// Copies the record out of the arena.
+    pub fn into_owned(self) -> Provenance {
+        Provenance {
+            tool: self.tool.to_string(),
+            tool_version: self.tool_version.to_string(),
+            operator: self.operator.map(str::to_string),
+            date: self.date.to_string(),
+            input_dataset: self.input_dataset.map(str::to_string),
+            git_commit: self.git_commit.map(str::to_string),
+        }
+    }
+}
+
+#[cfg(feature = "with-arena")]
+impl<'a> DeserializeIn<'a> for ProvenanceRef<'a> {
+    fn deserialize_in<'de, D>(deserializer: D, bump: &'a Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "snake_case")]
+        enum Field {
+            Tool,
+            ToolVersion,
+            Operator,
+            Date,
+            InputDataset,
+            GitCommit,
+            #[serde(other)]
+            Other,
+        }
+
+        struct ProvenanceVisitor<'a>(&'a Bump);
+
+        impl<'de, 'a> Visitor<'de> for ProvenanceVisitor<'a> {
+            type Value = ProvenanceRef<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a provenance block")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut tool = None;
+                let mut tool_version = None;
+                let mut operator: Option<Option<&str>> = None;
+                let mut date = None;
+                let mut input_dataset: Option<Option<&str>> = None;
+                let mut git_commit: Option<Option<&str>> = None;
+
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Tool => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut tool, value, "tool")?;
+                        }
+                        Field::ToolVersion => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut tool_version, value, "tool_version")?;
+                        }
+                        Field::Operator => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut operator, value, "operator")?;
+                        }
+                        Field::Date => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut date, value, "date")?;
+                        }
+                        Field::InputDataset => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut input_dataset, value, "input_dataset")?;
+                        }
+                        Field::GitCommit => {
+                            let value = map.next_value_seed(InArena::new(self.0))?;
+                            set_field(&mut git_commit, value, "git_commit")?;
+                        }
+                        Field::Other => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(ProvenanceRef {
+                    tool: tool.ok_or_else(|| A::Error::missing_field("tool"))?,
+                    tool_version: tool_version
+                        .ok_or_else(|| A::Error::missing_field("tool_version"))?,
+                    operator: operator.flatten(),
+                    date: date.ok_or_else(|| A::Error::missing_field("date"))?,
+                    input_dataset: input_dataset.flatten(),
+                    git_commit: git_commit.flatten(),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(ProvenanceVisitor(bump))
+    }
+}
+
+/// A document with an optional `provenance` block.
+///
+/// The fields of the document are flattened, so the block is added
+/// next to them. Documents that deny unknown fields are not
+/// supported.
+///
+/// ```rust
+/// # use newslab_serde_cv::{Attested, CameraIntrinsicParams, WithProvenance};
+/// let json = r#"{
+///     "camera_matrix": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+///     "distortion_coefs": [0.0, 0.0, 0.0, 0.0, 0.0],
+///     "provenance": {
+///         "tool": "kalibr",
+///         "tool_version": "1.0.0",
+///         "operator": "field-team",
+///         "date": "2024-03-01",
+///         "git_commit": "3f2a9c1"
+///     }
+/// }"#;
+/// let doc: WithProvenance<CameraIntrinsicParams> = serde_json::from_str(json).unwrap();
+/// assert_eq!(doc.provenance.as_ref().unwrap().tool, "kalibr");
+///
+/// // The strict form requires the block.
+/// let doc: Attested<CameraIntrinsicParams> = serde_json::from_str(json).unwrap();
+/// assert_eq!(doc.provenance.date, "2024-03-01");
+///
+/// let json = r#"{
+///     "camera_matrix": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+///     "distortion_coefs": [0.0, 0.0, 0.0, 0.0, 0.0]
+/// }"#;
+/// let doc: WithProvenance<CameraIntrinsicParams> = serde_json::from_str(json).unwrap();
+/// assert!(doc.provenance.is_none());
+/// assert!(Attested::try_from(doc).is_err());
+/// assert!(serde_json::from_str::<Attested<CameraIntrinsicParams>>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithProvenance<T> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    #[serde(flatten)]
+    pub document: T,
+}
+
+impl<T> WithProvenance<T> {
+    pub fn new(document: T, provenance: Provenance) -> Self {
+        Self {
+            provenance: Some(provenance),
+            document,
+        }
+    }
+}
+
+impl<T> From<Attested<T>> for WithProvenance<T> {
+    fn from(from: Attested<T>) -> Self {
+        let Attested {
+            provenance,
+            document,
+        } = from;
+        Self::new(document, provenance)
+    }
+}
+
+/// A document with a mandatory `provenance` block. It is the strict
+/// form of [WithProvenance].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attested<T> {
+    pub provenance: Provenance,
+    #[serde(flatten)]
+    pub document: T,
+}
+
+impl<T> Attested<T> {
+    pub fn new(document: T, provenance: Provenance) -> Self {
+        Self {
+            provenance,
+            document,
+        }
+    }
+}
+
+impl<T> TryFrom<WithProvenance<T>> for Attested<T> {
+    type Error = Error;
+
+    fn try_from(from: WithProvenance<T>) -> Result<Self, Self::Error> {
+        let WithProvenance {
+            provenance,
+            document,
+        } = from;
+        let provenance = provenance.ok_or_else(|| anyhow!("the provenance block is missing"))?;
+        Ok(Self::new(document, provenance))
+    }
+}
@@ -0,0 +1,202 @@
+use crate::Fingerprint;
+use anyhow::{anyhow, ensure, Context, Error, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The metadata embedded in a recording, such as an MCAP file or a
+/// rosbag, to make it self-describing.
+///
+/// It keeps a snapshot of the calibration used during recording, the
+/// fingerprint of the snapshot and the versions of the recording
+/// software. The fingerprint is audited against the snapshot during
+/// ser/deserialization. In deserialization, it is computed over the
+/// snapshot as stored, before it is converted to `T`, so fields that
+/// `T` ignores or normalizes cannot be edited unnoticed.
+///
+/// ```rust
+/// # use newslab_serde_cv::{CameraIntrinsicParams, Fingerprint, RecordingMetadata};
+/// # use std::collections::BTreeMap;
+/// let mut calibration = BTreeMap::new();
+/// calibration.insert("camera_front".to_string(), CameraIntrinsicParams::identity());
+///
+/// let mut metadata = RecordingMetadata::new(calibration).unwrap();
+/// metadata.add_software("recorder", "1.4.2");
+/// assert_eq!(
+///     metadata.rig_fingerprint(),
+///     Fingerprint::of(metadata.calibration()).unwrap()
+/// );
+///
+/// // Convert to MCAP metadata key-value pairs and back.
+/// let records = metadata.to_mcap_metadata().unwrap();
+/// assert_eq!(records["software.recorder"], "1.4.2");
+/// let parsed = RecordingMetadata::from_mcap_metadata(&records).unwrap();
+/// assert_eq!(parsed, metadata);
+///
+/// // Unknown fields are dropped by the calibration type, but they
+/// // are still covered by the fingerprint.
+/// let mut value = serde_json::to_value(&metadata).unwrap();
+/// value["calibration"]["camera_front"]["note"] = "edited".into();
+/// let result: Result<RecordingMetadata<BTreeMap<String, CameraIntrinsicParams>>, _> =
+///     serde_json::from_value(value);
+/// assert!(result.is_err());
+///
+/// // The fingerprint does not match the calibration.
+/// let json = r#"{
+///     "rig_fingerprint": "sha256:0000000000000000000000000000000000000000000000000000000000000000",
+///     "calibration": {},
+///     "software": {}
+/// }"#;
+/// let result: Result<RecordingMetadata<BTreeMap<String, CameraIntrinsicParams>>, _> =
+///     serde_json::from_str(json);
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    try_from = "RecordingMetadataUnchecked<Value>",
+    into = "RecordingMetadataUnchecked<T>",
+    bound(
+        serialize = "T: Serialize + Clone",
+        deserialize = "T: DeserializeOwned"
+    )
+)]
+pub struct RecordingMetadata<T> {
+    rig_fingerprint: Fingerprint,
+    calibration: T,
+    software: BTreeMap<String, String>,
+}
+
+impl<T> RecordingMetadata<T>
+where
+    T: Serialize,
+{
+    /// The name of the MCAP metadata record holding the metadata.
+    pub const MCAP_METADATA_NAME: &'static str = "newslab.recording";
+
+    /// Creates metadata with the fingerprint of the calibration.
+    pub fn new(calibration: T) -> Result<Self> {
+        Ok(Self {
+            rig_fingerprint: Fingerprint::of(&calibration)?,
+            calibration,
+            software: BTreeMap::new(),
+        })
+    }
+
+    pub fn rig_fingerprint(&self) -> Fingerprint {
+        self.rig_fingerprint
+    }
+
+    pub fn calibration(&self) -> &T {
+        &self.calibration
+    }
+
+    /// Gets the software names and versions.
+    pub fn software(&self) -> &BTreeMap<String, String> {
+        &self.software
+    }
+
+    /// Records the version of a software component.
+    pub fn add_software(&mut self, name: impl Into<String>, version: impl Into<String>) {
+        self.software.insert(name.into(), version.into());
+    }
+
+    /// Converts to the key-value pairs of an MCAP metadata record.
+    /// The calibration is stored in canonical JSON and software
+    /// versions are stored in `software.{name}` keys.
+    pub fn to_mcap_metadata(&self) -> Result<BTreeMap<String, String>> {
+        let mut records = BTreeMap::new();
+        records.insert(
+            "rig_fingerprint".to_string(),
+            self.rig_fingerprint.to_string(),
+        );
+        records.insert(
+            "calibration".to_string(),
+            Fingerprint::canonical_json(&self.calibration)?,
+        );
+        for (name, version) in &self.software {
+            records.insert(format!("software.{name}"), version.clone());
+        }
+        Ok(records)
+    }
+
+    /// Parses the key-value pairs of an MCAP metadata record.
+    /// Unknown keys are ignored.
+    pub fn from_mcap_metadata(records: &BTreeMap<String, String>) -> Result<Self>
+    where
+        T: DeserializeOwned,
+    {
+        let get = |key: &str| {
+            records
+                .get(key)
+                .ok_or_else(|| anyhow!("the '{key}' entry is missing"))
+        };
+
+        let rig_fingerprint = get("rig_fingerprint")?.parse()?;
+        let calibration: Value =
+            serde_json::from_str(get("calibration")?).context("invalid calibration entry")?;
+        let software = records
+            .iter()
+            .filter_map(|(key, version)| {
+                let name = key.strip_prefix("software.")?;
+                Some((name.to_string(), version.clone()))
+            })
+            .collect();
+
+        RecordingMetadataUnchecked {
+            rig_fingerprint,
+            calibration,
+            software,
+        }
+        .try_into()
+    }
+}
+
+impl<T> TryFrom<RecordingMetadataUnchecked<Value>> for RecordingMetadata<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = Error;
+
+    fn try_from(from: RecordingMetadataUnchecked<Value>) -> Result<Self, Self::Error> {
+        let RecordingMetadataUnchecked {
+            rig_fingerprint,
+            calibration,
+            software,
+        } = from;
+
+        let expect = Fingerprint::of(&calibration)?;
+        ensure!(
+            rig_fingerprint == expect,
+            "the rig fingerprint {rig_fingerprint} does not match the calibration, which has {expect}"
+        );
+
+        Ok(Self {
+            rig_fingerprint,
+            calibration: serde_json::from_value(calibration).context("invalid calibration")?,
+            software,
+        })
+    }
+}
+
+impl<T> From<RecordingMetadata<T>> for RecordingMetadataUnchecked<T> {
+    fn from(from: RecordingMetadata<T>) -> Self {
+        let RecordingMetadata {
+            rig_fingerprint,
+            calibration,
+            software,
+        } = from;
+        Self {
+            rig_fingerprint,
+            calibration,
+            software,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordingMetadataUnchecked<T> {
+    rig_fingerprint: Fingerprint,
+    calibration: T,
+    #[serde(default)]
+    software: BTreeMap<String, String>,
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// A rectangular region of interest on an image in pixels.
+///
+/// ```rust
+/// # use newslab_serde_cv::Roi;
+/// let json = r#"{ "x": 10, "y": 20, "width": 640, "height": 480 }"#;
+/// let roi: Roi = serde_json::from_str(json).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Roi {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
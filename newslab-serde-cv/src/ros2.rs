@@ -0,0 +1,318 @@
+//! Rendering of configurations into ROS2 parameter files.
+
+use crate::ordered_value::OrderedValue;
+use anyhow::{bail, ensure, Context, Result};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Renders serializable values into ROS2 parameter YAML in the
+/// `node: ros__parameters:` layout.
+///
+/// ROS2 parameters are scalars or flat arrays of one type, so nested
+/// structs become nested parameter groups and nested arrays such as
+/// matrices are flattened in row-major order. Null values and empty
+/// arrays carry no type and are left out. Fields are renamed by
+/// dotted paths, which also allows moving a field into another
+/// group.
+///
+/// ```rust
+/// # use newslab_serde_cv::{ros2::Ros2ParamsWriter, CameraIntrinsicParams};
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// struct MyCamera {
+///     frame_id: String,
+///     rate: u32,
+///     intrinsics: CameraIntrinsicParams,
+/// }
+///
+/// let camera = MyCamera {
+///     frame_id: "camera_front".to_string(),
+///     rate: 30,
+///     intrinsics: CameraIntrinsicParams::identity(),
+/// };
+///
+/// let text = Ros2ParamsWriter::new("/sensors/camera_front")
+///     .rename("intrinsics.camera_matrix", "camera.k")
+///     .rename("intrinsics.distortion_coefs", "camera.d")
+///     .to_string(&camera)
+///     .unwrap();
+///
+/// assert_eq!(
+///     text,
+///     r#"/sensors/camera_front:
+///   ros__parameters:
+///     frame_id: "camera_front"
+///     rate: 30
+///     camera:
+///       k: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+///       d: [0.0, 0.0, 0.0, 0.0, 0.0]
+/// "#
+/// );
+///
+/// // Keys that YAML would read as aliases are quoted.
+/// let params = serde_json::json!({ "*rate": 30 });
+/// let text = Ros2ParamsWriter::new("/**").to_string(&params).unwrap();
+/// assert_eq!(text, "/**:\n  ros__parameters:\n    \"*rate\": 30\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ros2ParamsWriter {
+    node: String,
+    renames: Vec<(String, String)>,
+}
+
+impl Ros2ParamsWriter {
+    /// Creates a writer for the node, which can be a fully qualified
+    /// name like `/ns/node` or a wildcard like `/**`.
+    pub fn new(node: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            renames: vec![],
+        }
+    }
+
+    /// Renames the field at the dotted path `from`, including all
+    /// fields under it, to the dotted path `to`. The longest matching
+    /// path takes effect if several renames apply.
+    pub fn rename(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.renames.push((from.into(), to.into()));
+        self
+    }
+
+    /// Renders the value, which must serialize to a map.
+    pub fn to_string<T>(&self, value: &T) -> Result<String>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = OrderedValue::from_serialize(value)?;
+        let OrderedValue::Object(map) = value else {
+            bail!("ROS2 parameters must be rendered from a map");
+        };
+
+        let mut params = vec![];
+        for (key, value) in &map {
+            flatten(key.clone(), value, &mut params)?;
+        }
+
+        let mut root = Group::default();
+        for (path, param) in params {
+            let path = self.renamed(&path);
+            root.insert(&path, param)
+                .with_context(|| format!("unable to place parameter '{path}'"))?;
+        }
+
+        let mut text = String::new();
+        writeln!(text, "{}:", yaml_key(&self.node)).unwrap();
+        writeln!(text, "  ros__parameters:").unwrap();
+        root.write(&mut text, 4);
+        Ok(text)
+    }
+
+    fn renamed(&self, path: &str) -> String {
+        let rename = self
+            .renames
+            .iter()
+            .filter(|(from, _)| {
+                path == from
+                    || path
+                        .strip_prefix(from.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .max_by_key(|(from, _)| from.len());
+
+        match rename {
+            Some((from, to)) => format!("{to}{}", &path[from.len()..]),
+            None => path.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Param {
+    Bool(bool),
+    Integer(i64),
+    Double(f64),
+    String(String),
+    Array(Vec<Param>),
+}
+
+#[derive(Debug, Default)]
+struct Group {
+    entries: Vec<(String, Entry)>,
+}
+
+#[derive(Debug)]
+enum Entry {
+    Param(Param),
+    Group(Group),
+}
+
+impl Group {
+    fn insert(&mut self, path: &str, param: Param) -> Result<()> {
+        let (name, rest) = match path.split_once('.') {
+            Some((name, rest)) => (name, Some(rest)),
+            None => (path, None),
+        };
+        ensure!(!name.is_empty(), "empty parameter name");
+
+        let index = self.entries.iter().position(|(key, _)| key == name);
+
+        match (rest, index) {
+            (None, None) => {
+                self.entries.push((name.to_string(), Entry::Param(param)));
+            }
+            (Some(rest), None) => {
+                let mut group = Group::default();
+                group.insert(rest, param)?;
+                self.entries.push((name.to_string(), Entry::Group(group)));
+            }
+            (Some(rest), Some(index)) => match &mut self.entries[index].1 {
+                Entry::Group(group) => group.insert(rest, param)?,
+                Entry::Param(_) => bail!("'{name}' is both a parameter and a group"),
+            },
+            (None, Some(_)) => bail!("'{name}' is defined more than once"),
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, text: &mut String, indent: usize) {
+        for (key, entry) in &self.entries {
+            let key = yaml_key(key);
+            match entry {
+                Entry::Param(param) => {
+                    writeln!(text, "{:indent$}{key}: {}", "", param_to_yaml(param)).unwrap();
+                }
+                Entry::Group(group) => {
+                    writeln!(text, "{:indent$}{key}:", "").unwrap();
+                    group.write(text, indent + 2);
+                }
+            }
+        }
+    }
+}
+
+/// Collects the parameters under the path with their dotted paths.
+fn flatten(path: String, value: &OrderedValue, params: &mut Vec<(String, Param)>) -> Result<()> {
+    match value {
+        OrderedValue::Object(map) => {
+            for (key, value) in map {
+                flatten(format!("{path}.{key}"), value, params)?;
+            }
+        }
+        OrderedValue::Null => {}
+        OrderedValue::Array(_) => {
+            let mut elements = vec![];
+            flatten_array(value, &mut elements)
+                .with_context(|| format!("invalid array '{path}'"))?;
+            if !elements.is_empty() {
+                let elements =
+                    unify_array(elements).with_context(|| format!("invalid array '{path}'"))?;
+                params.push((path, Param::Array(elements)));
+            }
+        }
+        value => {
+            let param = scalar(value).with_context(|| format!("invalid parameter '{path}'"))?;
+            params.push((path, param));
+        }
+    }
+    Ok(())
+}
+
+fn flatten_array(value: &OrderedValue, elements: &mut Vec<Param>) -> Result<()> {
+    match value {
+        OrderedValue::Array(array) => {
+            for value in array {
+                flatten_array(value, elements)?;
+            }
+        }
+        OrderedValue::Object(_) => bail!("arrays of maps are not supported"),
+        OrderedValue::Null => bail!("arrays with null elements are not supported"),
+        value => elements.push(scalar(value)?),
+    }
+    Ok(())
+}
+
+/// Promotes a mix of integers and doubles to doubles, and rejects
+/// arrays of other mixed types.
+fn unify_array(elements: Vec<Param>) -> Result<Vec<Param>> {
+    let has_double = elements.iter().any(|elem| matches!(elem, Param::Double(_)));
+    let elements: Vec<_> = elements
+        .into_iter()
+        .map(|elem| match elem {
+            Param::Integer(val) if has_double => Param::Double(val as f64),
+            elem => elem,
+        })
+        .collect();
+
+    let first = std::mem::discriminant(&elements[0]);
+    ensure!(
+        elements
+            .iter()
+            .all(|elem| std::mem::discriminant(elem) == first),
+        "array elements must have the same type"
+    );
+    Ok(elements)
+}
+
+fn scalar(value: &OrderedValue) -> Result<Param> {
+    let param = match value {
+        OrderedValue::Bool(val) => Param::Bool(*val),
+        OrderedValue::Number(num) => {
+            if let Some(val) = num.as_i64() {
+                Param::Integer(val)
+            } else if num.is_u64() {
+                bail!("the integer {num} exceeds the range of ROS2 parameters");
+            } else {
+                Param::Double(num.as_f64().unwrap())
+            }
+        }
+        OrderedValue::String(val) => Param::String(val.clone()),
+        _ => unreachable!(),
+    };
+    Ok(param)
+}
+
+fn param_to_yaml(param: &Param) -> String {
+    match param {
+        Param::Bool(val) => val.to_string(),
+        Param::Integer(val) => val.to_string(),
+        Param::Double(val) => double_to_yaml(*val),
+        Param::String(val) => serde_json::to_string(val).unwrap(),
+        Param::Array(elements) => {
+            let elements: Vec<_> = elements.iter().map(param_to_yaml).collect();
+            format!("[{}]", elements.join(", "))
+        }
+    }
+}
+
+/// Formats a double such that it is not mistaken as an integer.
+fn double_to_yaml(val: f64) -> String {
+    if val.is_nan() {
+        ".nan".to_string()
+    } else if val.is_infinite() {
+        if val > 0.0 { ".inf" } else { "-.inf" }.to_string()
+    } else {
+        let text = val.to_string();
+        if text.contains(['.', 'e']) {
+            text
+        } else {
+            format!("{text}.0")
+        }
+    }
+}
+
+/// Quotes the key unless it is made of plain name characters. Keys
+/// starting with `*` or `-` are quoted as well, since they would be
+/// read as an alias or a sequence entry.
+fn yaml_key(key: &str) -> String {
+    let is_plain = !key.is_empty()
+        && !key.starts_with(['*', '-'])
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '/' | '*' | '-'));
+    if is_plain {
+        key.to_string()
+    } else {
+        serde_json::to_string(key).unwrap()
+    }
+}
@@ -0,0 +1,457 @@
+use crate::{CameraIntrinsicParams, ImageSize, Issue};
+use anyhow::{ensure, Error, Result};
+use noisy_float::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The relative tolerance in the consistency checks between
+/// matrices, which allows values written with 6 significant digits.
+const RELATIVE_TOLERANCE: f64 = 1e-5;
+
+/// The calibration of a stereo camera pair, following the outputs
+/// of OpenCV `stereoCalibrate()` and `stereoRectify()`.
+///
+/// The `rotation` and `translation` (R and T in OpenCV) transform
+/// points from the left camera frame to the right camera frame. The
+/// translation is in the unit of the calibration pattern, usually
+/// meters. The rotation must be a proper rotation matrix.
+///
+/// ```rust
+/// # use newslab_serde_cv::StereoCalibration;
+/// let json = r#"{
+///     "image_size": "1280x720",
+///     "left": {
+///         "camera_matrix": [[1000.0, 0.0, 640.0],
+///                           [0.0, 1000.0, 360.0],
+///                           [0.0, 0.0, 1.0]],
+///         "distortion_coefs": [0.1, -0.2, 0.0, 0.0, 0.0]
+///     },
+///     "right": {
+///         "camera_matrix": [[1000.0, 0.0, 650.0],
+///                           [0.0, 1000.0, 350.0],
+///                           [0.0, 0.0, 1.0]],
+///         "distortion_coefs": [0.1, -0.2, 0.0, 0.0, 0.0]
+///     },
+///     "R": [[1.0, 0.0, 0.0],
+///           [0.0, 1.0, 0.0],
+///           [0.0, 0.0, 1.0]],
+///     "T": [-0.12, 0.0, 0.0]
+/// }"#;
+/// let calib: StereoCalibration = serde_json::from_str(json).unwrap();
+/// assert!((calib.baseline() - 0.12).abs() < 1e-12);
+/// assert!(calib.rectification.is_none());
+/// assert!(calib.validate().is_ok());
+///
+/// // The rotation is not orthogonal.
+/// let bad = json.replace("[0.0, 1.0, 0.0]", "[0.0, 2.0, 0.0]");
+/// assert!(serde_json::from_str::<StereoCalibration>(&bad).is_err());
+///
+/// // The cameras are at the same position.
+/// let bad = json.replace("[-0.12, 0.0, 0.0]", "[0.0, 0.0, 0.0]");
+/// assert!(serde_json::from_str::<StereoCalibration>(&bad).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(
+    try_from = "StereoCalibrationUnchecked",
+    into = "StereoCalibrationUnchecked"
+)]
+pub struct StereoCalibration {
+    pub image_size: ImageSize,
+    pub left: CameraIntrinsicParams,
+    pub right: CameraIntrinsicParams,
+    pub rotation: [[R64; 3]; 3],
+    pub translation: [R64; 3],
+    pub rectification: Option<StereoRectification>,
+}
+
+/// The rectification of a stereo camera pair computed by OpenCV
+/// `stereoRectify()`.
+///
+/// R1 and R2 rotate the left and right camera frames to the
+/// rectified frames. P1 and P2 are the 3x4 projection matrices in
+/// the rectified frames, and Q is the 4x4 disparity-to-depth
+/// mapping matrix. Both horizontal and vertical stereo are
+/// supported.
+///
+/// ```rust
+/// # use newslab_serde_cv::StereoRectification;
+/// let json = r#"{
+///     "R1": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+///     "R2": [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+///     "P1": [[1000.0, 0.0, 640.0, 0.0],
+///            [0.0, 1000.0, 360.0, 0.0],
+///            [0.0, 0.0, 1.0, 0.0]],
+///     "P2": [[1000.0, 0.0, 640.0, -120.0],
+///            [0.0, 1000.0, 360.0, 0.0],
+///            [0.0, 0.0, 1.0, 0.0]],
+///     "Q": [[1.0, 0.0, 0.0, -640.0],
+///           [0.0, 1.0, 0.0, -360.0],
+///           [0.0, 0.0, 0.0, 1000.0],
+///           [0.0, 0.0, 8.333333, 0.0]]
+/// }"#;
+/// let rect: StereoRectification = serde_json::from_str(json).unwrap();
+/// assert!(rect.is_horizontal());
+/// assert!((rect.baseline() - 0.12).abs() < 1e-12);
+///
+/// // Q does not match the baseline.
+/// let bad = json.replace("8.333333", "4.0");
+/// assert!(serde_json::from_str::<StereoRectification>(&bad).is_err());
+///
+/// // The left projection matrix has a translation.
+/// let bad = json.replacen("640.0, 0.0]", "640.0, 5.0]", 1);
+/// assert!(serde_json::from_str::<StereoRectification>(&bad).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(
+    try_from = "StereoRectificationUnchecked",
+    into = "StereoRectificationUnchecked"
+)]
+pub struct StereoRectification {
+    pub r1: [[R64; 3]; 3],
+    pub r2: [[R64; 3]; 3],
+    pub p1: [[R64; 4]; 3],
+    pub p2: [[R64; 4]; 3],
+    pub q: [[R64; 4]; 4],
+}
+
+impl StereoCalibration {
+    /// Gets the distance between the camera centers.
+    pub fn baseline(&self) -> f64 {
+        norm(&self.translation.map(|val| val.raw()))
+    }
+
+    /// Checks the intrinsic parameters of both cameras against the
+    /// image size like [CameraIntrinsicParams::validate]. The fields
+    /// of the issues are prefixed with `"left."` or `"right."`.
+    pub fn validate(&self) -> Result<(), Vec<Issue>> {
+        let issues: Vec<_> = [("left", &self.left), ("right", &self.right)]
+            .into_iter()
+            .flat_map(|(side, intrinsics)| {
+                intrinsics
+                    .validate(&self.image_size)
+                    .err()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |issue| Issue {
+                        field: format!("{side}.{}", issue.field),
+                        ..issue
+                    })
+            })
+            .collect();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+}
+
+impl StereoRectification {
+    /// Checks if the cameras are placed side by side rather than one
+    /// above the other.
+    pub fn is_horizontal(&self) -> bool {
+        self.p2[0][3] != 0.0
+    }
+
+    /// Gets the distance between the camera centers, which is
+    /// recovered from P2.
+    pub fn baseline(&self) -> f64 {
+        let (shift, focal) = self.shift_and_focal();
+        (shift / focal).abs()
+    }
+
+    /// Gets the translation term in P2 and the focal length along
+    /// the baseline.
+    fn shift_and_focal(&self) -> (f64, f64) {
+        if self.is_horizontal() {
+            (self.p2[0][3].raw(), self.p2[0][0].raw())
+        } else {
+            (self.p2[1][3].raw(), self.p2[1][1].raw())
+        }
+    }
+}
+
+impl TryFrom<StereoCalibrationUnchecked> for StereoCalibration {
+    type Error = Error;
+
+    fn try_from(from: StereoCalibrationUnchecked) -> Result<Self, Self::Error> {
+        let StereoCalibrationUnchecked {
+            image_size,
+            left,
+            right,
+            rotation,
+            translation,
+            rectification,
+        } = from;
+
+        check_rotation(&rotation, "R")?;
+        ensure!(
+            translation.iter().any(|&val| val != 0.0),
+            "T must not be zero, or the cameras are at the same position"
+        );
+
+        Ok(Self {
+            image_size,
+            left,
+            right,
+            rotation,
+            translation,
+            rectification,
+        })
+    }
+}
+
+impl From<StereoCalibration> for StereoCalibrationUnchecked {
+    fn from(from: StereoCalibration) -> Self {
+        let StereoCalibration {
+            image_size,
+            left,
+            right,
+            rotation,
+            translation,
+            rectification,
+        } = from;
+
+        Self {
+            image_size,
+            left,
+            right,
+            rotation,
+            translation,
+            rectification,
+        }
+    }
+}
+
+impl TryFrom<StereoRectificationUnchecked> for StereoRectification {
+    type Error = Error;
+
+    fn try_from(from: StereoRectificationUnchecked) -> Result<Self, Self::Error> {
+        let StereoRectificationUnchecked { r1, r2, p1, p2, q } = from;
+
+        check_rotation(&r1, "R1")?;
+        check_rotation(&r2, "R2")?;
+        check_projection(&p1, "P1")?;
+        check_projection(&p2, "P2")?;
+
+        let [tx1, ty1] = [p1[0][3], p1[1][3]];
+        ensure!(
+            tx1 == 0.0 && ty1 == 0.0,
+            "P1 must have no translation, but get [{tx1}, {ty1}]"
+        );
+        let [tx2, ty2] = [p2[0][3], p2[1][3]];
+        ensure!(
+            (tx2 == 0.0) != (ty2 == 0.0),
+            "P2 must translate along exactly one of x and y axes, but get [{tx2}, {ty2}]"
+        );
+        ensure!(
+            approx_eq(p1[0][0].raw(), p2[0][0].raw()) && approx_eq(p1[1][1].raw(), p2[1][1].raw()),
+            "P1 and P2 must have the same focal lengths"
+        );
+
+        // Q = [1, 0, 0, -cx1; 0, 1, 0, -cy1; 0, 0, 0, f; 0, 0, -1/T, (c1 - c2)/T]
+        // where T is the baseline along the x or y axis.
+        let rect = Self { r1, r2, p1, p2, q };
+        let (shift, focal) = rect.shift_and_focal();
+        let baseline = shift / focal;
+        let (c1, c2) = if rect.is_horizontal() {
+            (p1[0][2], p2[0][2])
+        } else {
+            (p1[1][2], p2[1][2])
+        };
+        let expect = [
+            [1.0, 0.0, 0.0, -p1[0][2].raw()],
+            [0.0, 1.0, 0.0, -p1[1][2].raw()],
+            [0.0, 0.0, 0.0, p1[0][0].raw()],
+            [0.0, 0.0, -1.0 / baseline, (c1 - c2).raw() / baseline],
+        ];
+        for (row, (actual, expect)) in q.iter().zip(&expect).enumerate() {
+            for (col, (&actual, &expect)) in actual.iter().zip(expect).enumerate() {
+                ensure!(
+                    approx_eq(actual.raw(), expect),
+                    "Q[{row}][{col}] is expected to be {expect} from P1 and P2, but get {actual}"
+                );
+            }
+        }
+
+        Ok(rect)
+    }
+}
+
+impl From<StereoRectification> for StereoRectificationUnchecked {
+    fn from(from: StereoRectification) -> Self {
+        let StereoRectification { r1, r2, p1, p2, q } = from;
+        Self { r1, r2, p1, p2, q }
+    }
+}
+
+#[cfg(feature = "with-opencv")]
+impl StereoCalibration {
+    /// Gets the camera matrices, the distortion coefficients, the
+    /// image size, R and T in the order of the arguments of OpenCV
+    /// `stereoRectify()`.
+    #[allow(clippy::type_complexity)]
+    pub fn to_opencv_stereo_rectify_args(
+        &self,
+    ) -> Result<(
+        opencv::core::Mat,
+        opencv::core::Mat,
+        opencv::core::Mat,
+        opencv::core::Mat,
+        opencv::core::Size,
+        opencv::core::Mat,
+        opencv::core::Mat,
+    )> {
+        let size = opencv::core::Size::new(
+            self.image_size.width.try_into()?,
+            self.image_size.height.try_into()?,
+        );
+        Ok((
+            (&self.left.camera_matrix).into(),
+            (&self.left.distortion_coefs).into(),
+            (&self.right.camera_matrix).into(),
+            (&self.right.distortion_coefs).into(),
+            size,
+            crate::mat::from_rows(&self.rotation),
+            crate::mat::from_rows(&self.translation.map(|val| [val])),
+        ))
+    }
+}
+
+/// Converts the rectification to R1, R2, P1, P2 and Q in the order
+/// of the outputs of OpenCV `stereoRectify()`.
+#[cfg(feature = "with-opencv")]
+impl From<&StereoRectification>
+    for (
+        opencv::core::Mat,
+        opencv::core::Mat,
+        opencv::core::Mat,
+        opencv::core::Mat,
+        opencv::core::Mat,
+    )
+{
+    fn from(from: &StereoRectification) -> Self {
+        use crate::mat::from_rows;
+        (
+            from_rows(&from.r1),
+            from_rows(&from.r2),
+            from_rows(&from.p1),
+            from_rows(&from.p2),
+            from_rows(&from.q),
+        )
+    }
+}
+
+/// Reads R1, R2, P1, P2 and Q computed by OpenCV `stereoRectify()`.
+/// The matrices are audited like deserialization.
+#[cfg(feature = "with-opencv")]
+impl
+    TryFrom<(
+        &opencv::core::Mat,
+        &opencv::core::Mat,
+        &opencv::core::Mat,
+        &opencv::core::Mat,
+        &opencv::core::Mat,
+    )> for StereoRectification
+{
+    type Error = Error;
+
+    fn try_from(
+        (r1, r2, p1, p2, q): (
+            &opencv::core::Mat,
+            &opencv::core::Mat,
+            &opencv::core::Mat,
+            &opencv::core::Mat,
+            &opencv::core::Mat,
+        ),
+    ) -> Result<Self, Self::Error> {
+        use crate::mat::read_rows;
+        StereoRectificationUnchecked {
+            r1: read_rows(r1, "R1")?,
+            r2: read_rows(r2, "R2")?,
+            p1: read_rows(p1, "P1")?,
+            p2: read_rows(p2, "P2")?,
+            q: read_rows(q, "Q")?,
+        }
+        .try_into()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct StereoCalibrationUnchecked {
+    image_size: ImageSize,
+    left: CameraIntrinsicParams,
+    right: CameraIntrinsicParams,
+    #[serde(rename = "R", alias = "rotation")]
+    rotation: [[R64; 3]; 3],
+    #[serde(rename = "T", alias = "translation")]
+    translation: [R64; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rectification: Option<StereoRectification>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct StereoRectificationUnchecked {
+    #[serde(rename = "R1", alias = "r1")]
+    r1: [[R64; 3]; 3],
+    #[serde(rename = "R2", alias = "r2")]
+    r2: [[R64; 3]; 3],
+    #[serde(rename = "P1", alias = "p1")]
+    p1: [[R64; 4]; 3],
+    #[serde(rename = "P2", alias = "p2")]
+    p2: [[R64; 4]; 3],
+    #[serde(rename = "Q", alias = "q")]
+    q: [[R64; 4]; 4],
+}
+
+/// Checks that the matrix is orthogonal with determinant 1.
+fn check_rotation(mat: &[[R64; 3]; 3], name: &str) -> Result<()> {
+    let mat = mat.map(|row| row.map(|val| val.raw()));
+
+    for i in 0..3 {
+        for j in 0..3 {
+            let dot: f64 = (0..3).map(|k| mat[k][i] * mat[k][j]).sum();
+            let expect = if i == j { 1.0 } else { 0.0 };
+            ensure!(
+                (dot - expect).abs() <= 1e-6,
+                "{name} must be a rotation matrix, but its columns are not orthonormal"
+            );
+        }
+    }
+
+    let [a, b, c] = mat;
+    let det = a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+        + a[2] * (b[0] * c[1] - b[1] * c[0]);
+    ensure!(
+        det > 0.0,
+        "{name} must be a rotation matrix, but it is a reflection"
+    );
+    Ok(())
+}
+
+/// Checks that the matrix is in `[fx, 0, cx, tx; 0, fy, cy, ty; 0, 0, 1, 0]`
+/// form with positive focal lengths.
+fn check_projection(mat: &[[R64; 4]; 3], name: &str) -> Result<()> {
+    let [[fx, s, _, _], [zero, fy, _, _], bottom] = *mat;
+    ensure!(
+        fx > 0.0 && fy > 0.0,
+        "{name} must have positive focal lengths, but get fx={fx} and fy={fy}"
+    );
+    ensure!(
+        s == 0.0 && zero == 0.0,
+        "{name} must have zero skew and zero element at (1, 0)"
+    );
+    ensure!(
+        bottom == [0.0, 0.0, 1.0, 0.0],
+        "the bottom row of {name} must be [0, 0, 1, 0], but get {bottom:?}"
+    );
+    Ok(())
+}
+
+fn approx_eq(lhs: f64, rhs: f64) -> bool {
+    (lhs - rhs).abs() <= RELATIVE_TOLERANCE * lhs.abs().max(rhs.abs()).max(1.0)
+}
+
+fn norm(vec: &[f64; 3]) -> f64 {
+    vec.iter().map(|val| val * val).sum::<f64>().sqrt()
+}
@@ -0,0 +1,234 @@
+//! Camera-IMU calibration blocks used by VINS-Mono and OpenVINS.
+//!
+//! OpenVINS reads Kalibr-style camera chains, in which each camera
+//! has a 4x4 row-major `T_imu_cam` transform, an `intrinsics` vector
+//! and a `distortion_coeffs` vector.
+//!
+//! ```rust
+//! # use newslab_serde_cv::vins::OpenVinsCamchain;
+//! let json = r#"{
+//!     "cam0": {
+//!         "T_imu_cam": [[0.0, -1.0, 0.0, 0.05],
+//!                       [1.0,  0.0, 0.0, 0.00],
+//!                       [0.0,  0.0, 1.0, 0.01],
+//!                       [0.0,  0.0, 0.0, 1.00]],
+//!         "cam_overlaps": [],
+//!         "camera_model": "pinhole",
+//!         "distortion_coeffs": [-0.28, 0.07, 0.0002, 0.00002],
+//!         "distortion_model": "radtan",
+//!         "intrinsics": [458.6, 457.3, 367.2, 248.4],
+//!         "resolution": [752, 480],
+//!         "rostopic": "/cam0/image_raw"
+//!     }
+//! }"#;
+//! let chain: OpenVinsCamchain = serde_json::from_str(json).unwrap();
+//! let camera = &chain["cam0"];
+//!
+//! let params = camera.intrinsic_params().unwrap();
+//! assert_eq!(params.camera_matrix.cx(), 367.2);
+//! assert_eq!(params.distortion_coefs.coefs()[4], 0.0);
+//! assert_eq!(camera.image_size().width, 752);
+//!
+//! let extrinsic = camera.extrinsic().unwrap();
+//! assert_eq!(extrinsic.trans[0], 0.05);
+//! ```
+//!
+//! VINS-Mono keeps the intrinsics and the camera-to-IMU extrinsics in
+//! named fields. The `!!opencv-matrix` entries are read as mappings
+//! with `rows`, `cols` and `data` fields.
+//!
+//! ```rust
+//! # use newslab_serde_cv::vins::VinsMonoCalibration;
+//! let json = r#"{
+//!     "model_type": "PINHOLE",
+//!     "camera_name": "camera",
+//!     "image_width": 752,
+//!     "image_height": 480,
+//!     "distortion_parameters": { "k1": -0.28, "k2": 0.07, "p1": 0.0002, "p2": 0.00002 },
+//!     "projection_parameters": { "fx": 458.6, "fy": 457.3, "cx": 367.2, "cy": 248.4 },
+//!     "extrinsicRotation": {
+//!         "rows": 3, "cols": 3, "dt": "d",
+//!         "data": [0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+//!     },
+//!     "extrinsicTranslation": {
+//!         "rows": 3, "cols": 1, "dt": "d",
+//!         "data": [0.05, 0.0, 0.01]
+//!     }
+//! }"#;
+//! let calib: VinsMonoCalibration = serde_json::from_str(json).unwrap();
+//! assert_eq!(calib.intrinsic_params().unwrap().camera_matrix.fy(), 457.3);
+//! assert_eq!(calib.extrinsic().unwrap().rot[0][1], -1.0);
+//! ```
+
+use crate::{
+    mrpt::{ExtrinsicMatrix, Matrix},
+    CameraIntrinsicParams, CameraMatrix, DistortionCoefs, DistortionModel, ImageSize,
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use noisy_float::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// An OpenVINS or Kalibr camera chain keyed by camera names like
+/// `cam0`.
+pub type OpenVinsCamchain = BTreeMap<String, OpenVinsCamera>;
+
+/// A camera block in an OpenVINS or Kalibr camera chain.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenVinsCamera {
+    /// The transform from the camera frame to the IMU frame in
+    /// row-major order.
+    #[serde(rename = "T_imu_cam")]
+    pub t_imu_cam: [[R64; 4]; 4],
+    #[serde(default)]
+    pub cam_overlaps: Vec<usize>,
+    #[serde(default)]
+    pub camera_model: Option<String>,
+    pub distortion_coeffs: Vec<R64>,
+    pub distortion_model: OpenVinsDistortionModel,
+    /// The `[fu, fv, cu, cv]` parameters.
+    pub intrinsics: [R64; 4],
+    /// The `[width, height]` of images.
+    pub resolution: [usize; 2],
+    #[serde(default)]
+    pub rostopic: Option<String>,
+}
+
+impl OpenVinsCamera {
+    /// Converts the intrinsics to camera parameters. The `radtan`
+    /// model becomes the plumb bob model and the `equidistant` model
+    /// becomes the Kannala-Brandt model.
+    pub fn intrinsic_params(&self) -> Result<CameraIntrinsicParams> {
+        let Self {
+            distortion_coeffs,
+            distortion_model,
+            intrinsics: [fx, fy, cx, cy],
+            ..
+        } = self;
+
+        let coefs: [R64; 4] = distortion_coeffs[..].try_into().map_err(|_| {
+            anyhow!(
+                "expect 4 {distortion_model:?} distortion coefficients, but get {}",
+                distortion_coeffs.len()
+            )
+        })?;
+        let distortion_coefs = match distortion_model {
+            OpenVinsDistortionModel::Radtan => {
+                let [k1, k2, p1, p2] = coefs;
+                DistortionCoefs([k1, k2, p1, p2, r64(0.0)]).into()
+            }
+            OpenVinsDistortionModel::Equidistant => DistortionModel::KannalaBrandt(coefs),
+        };
+
+        Ok(CameraIntrinsicParams {
+            camera_matrix: CameraMatrix::from_params(*fx, *fy, *cx, *cy),
+            distortion_coefs,
+        })
+    }
+
+    pub fn image_size(&self) -> ImageSize {
+        let [width, height] = self.resolution;
+        ImageSize { width, height }
+    }
+
+    /// Gets the camera-to-IMU transform. It fails if the matrix is
+    /// not a rigid transform.
+    pub fn extrinsic(&self) -> Result<ExtrinsicMatrix> {
+        ExtrinsicMatrix::from_homogeneous(&self.t_imu_cam).context("invalid T_imu_cam")
+    }
+}
+
+/// The distortion models in OpenVINS camera chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenVinsDistortionModel {
+    Radtan,
+    Equidistant,
+}
+
+/// The camera calibration of a VINS-Mono config file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct VinsMonoCalibration {
+    pub model_type: String,
+    pub camera_name: String,
+    pub image_width: usize,
+    pub image_height: usize,
+    pub distortion_parameters: VinsMonoDistortion,
+    pub projection_parameters: VinsMonoProjection,
+    /// The rotation from the camera frame to the IMU frame.
+    #[serde(rename = "extrinsicRotation")]
+    pub extrinsic_rotation: Matrix,
+    /// The translation from the camera frame to the IMU frame.
+    #[serde(rename = "extrinsicTranslation")]
+    pub extrinsic_translation: Matrix,
+}
+
+impl VinsMonoCalibration {
+    /// Converts the intrinsics to plumb bob parameters. It fails if
+    /// the camera model is not `PINHOLE`.
+    pub fn intrinsic_params(&self) -> Result<CameraIntrinsicParams> {
+        let Self {
+            model_type,
+            distortion_parameters: VinsMonoDistortion { k1, k2, p1, p2 },
+            projection_parameters: VinsMonoProjection { fx, fy, cx, cy },
+            ..
+        } = self;
+
+        ensure!(
+            model_type == "PINHOLE",
+            "the {model_type} camera model cannot be represented in plumb bob coefficients"
+        );
+
+        Ok(CameraIntrinsicParams {
+            camera_matrix: CameraMatrix::from_params(*fx, *fy, *cx, *cy),
+            distortion_coefs: DistortionCoefs([*k1, *k2, *p1, *p2, r64(0.0)]).into(),
+        })
+    }
+
+    pub fn image_size(&self) -> ImageSize {
+        ImageSize {
+            width: self.image_width,
+            height: self.image_height,
+        }
+    }
+
+    /// Gets the camera-to-IMU transform. It fails if the rotation is
+    /// not orthonormal.
+    pub fn extrinsic(&self) -> Result<ExtrinsicMatrix> {
+        let Self {
+            extrinsic_rotation: rot,
+            extrinsic_translation: trans,
+            ..
+        } = self;
+
+        ensure!(
+            rot.rows() == 3 && rot.cols() == 3,
+            "extrinsicRotation must be a 3x3 matrix"
+        );
+        ensure!(
+            trans.rows() * trans.cols() == 3 && (trans.rows() == 1 || trans.cols() == 1),
+            "extrinsicTranslation must be a 3-vector"
+        );
+
+        let data = rot.data();
+        let rot = [0, 1, 2].map(|row| [data[row * 3], data[row * 3 + 1], data[row * 3 + 2]]);
+        let trans = trans.data().try_into().unwrap();
+        ExtrinsicMatrix::new(rot, trans).context("invalid extrinsicRotation")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VinsMonoDistortion {
+    pub k1: R64,
+    pub k2: R64,
+    pub p1: R64,
+    pub p2: R64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VinsMonoProjection {
+    pub fx: R64,
+    pub fy: R64,
+    pub cx: R64,
+    pub cy: R64,
+}
@@ -0,0 +1,149 @@
+//! Calibration messages of the Waymo Open Dataset in protobuf JSON
+//! form.
+//!
+//! Both the camelCase field names written by `MessageToJson` and the
+//! original snake_case proto field names are accepted.
+//!
+//! ```rust
+//! # use newslab_serde_cv::waymo::WaymoContext;
+//! let json = r#"{
+//!     "name": "10017090168044687777_6380_000_6400_000",
+//!     "cameraCalibrations": [
+//!         {
+//!             "name": "FRONT",
+//!             "intrinsic": [2055.5, 2055.5, 939.6, 641.0, 0.032, -0.321, 0.0001, -0.0001, 0.0],
+//!             "extrinsic": {
+//!                 "transform": [1.0, 0.0, 0.0, 1.54,
+//!                               0.0, 1.0, 0.0, -0.02,
+//!                               0.0, 0.0, 1.0, 2.11,
+//!                               0.0, 0.0, 0.0, 1.0]
+//!             },
+//!             "width": 1920,
+//!             "height": 1280,
+//!             "rollingShutterDirection": "LEFT_TO_RIGHT"
+//!         }
+//!     ],
+//!     "laserCalibrations": [
+//!         {
+//!             "name": "TOP",
+//!             "beamInclinationMin": -0.31,
+//!             "beamInclinationMax": 0.04,
+//!             "extrinsic": {
+//!                 "transform": [1.0, 0.0, 0.0, 1.43,
+//!                               0.0, 1.0, 0.0, 0.0,
+//!                               0.0, 0.0, 1.0, 2.18,
+//!                               0.0, 0.0, 0.0, 1.0]
+//!             }
+//!         }
+//!     ]
+//! }"#;
+//! let context: WaymoContext = serde_json::from_str(json).unwrap();
+//! let camera = &context.camera_calibrations[0];
+//!
+//! let params = camera.intrinsic_params().unwrap();
+//! assert_eq!(params.camera_matrix.fx(), 2055.5);
+//! assert_eq!(params.distortion_coefs.coefs()[1], -0.321);
+//! assert_eq!(camera.image_size().height, 1280);
+//! assert_eq!(camera.extrinsic.to_matrix().unwrap().trans[2], 2.11);
+//!
+//! let laser = &context.laser_calibrations[0];
+//! assert_eq!(laser.extrinsic.to_matrix().unwrap().trans[0], 1.43);
+//! ```
+
+use crate::{
+    mrpt::ExtrinsicMatrix, CameraIntrinsicParams, CameraMatrix, DistortionCoefs, ImageSize,
+};
+use anyhow::{anyhow, Result};
+use noisy_float::prelude::*;
+use serde::Deserialize;
+
+/// The calibration part of a `Context` message.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaymoContext {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, alias = "camera_calibrations")]
+    pub camera_calibrations: Vec<WaymoCameraCalibration>,
+    #[serde(default, alias = "laser_calibrations")]
+    pub laser_calibrations: Vec<WaymoLaserCalibration>,
+}
+
+/// A `CameraCalibration` message.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaymoCameraCalibration {
+    /// The camera name like `FRONT` or `SIDE_LEFT`.
+    pub name: String,
+    /// The `[f_u, f_v, c_u, c_v, k1, k2, p1, p2, k3]` parameters.
+    pub intrinsic: Vec<R64>,
+    /// The camera-to-vehicle transform. The camera frame is x-forward,
+    /// y-left and z-up rather than the optical convention.
+    pub extrinsic: WaymoTransform,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default, alias = "rolling_shutter_direction")]
+    pub rolling_shutter_direction: Option<String>,
+}
+
+impl WaymoCameraCalibration {
+    pub fn intrinsic_params(&self) -> Result<CameraIntrinsicParams> {
+        let [fx, fy, cx, cy, k1, k2, p1, p2, k3] = self.intrinsic[..] else {
+            return Err(anyhow!(
+                "expect 9 intrinsic values, but get {}",
+                self.intrinsic.len()
+            ));
+        };
+
+        Ok(CameraIntrinsicParams {
+            camera_matrix: CameraMatrix::from_params(fx, fy, cx, cy),
+            distortion_coefs: DistortionCoefs([k1, k2, p1, p2, k3]).into(),
+        })
+    }
+
+    pub fn image_size(&self) -> ImageSize {
+        ImageSize {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// A `LaserCalibration` message.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaymoLaserCalibration {
+    /// The LiDAR name like `TOP` or `FRONT`.
+    pub name: String,
+    /// The beam inclinations in radians. It is empty if the beams are
+    /// uniformly distributed between the min and max inclinations.
+    #[serde(default, alias = "beam_inclinations")]
+    pub beam_inclinations: Vec<R64>,
+    #[serde(default, alias = "beam_inclination_min")]
+    pub beam_inclination_min: Option<R64>,
+    #[serde(default, alias = "beam_inclination_max")]
+    pub beam_inclination_max: Option<R64>,
+    /// The LiDAR-to-vehicle transform.
+    pub extrinsic: WaymoTransform,
+}
+
+/// A `Transform` message with a 4x4 row-major matrix.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaymoTransform {
+    pub transform: Vec<R64>,
+}
+
+impl WaymoTransform {
+    /// Converts to a rigid transform. It fails if the matrix is not a
+    /// rigid transform.
+    pub fn to_matrix(&self) -> Result<ExtrinsicMatrix> {
+        let Ok::<[R64; 16], _>(values) = self.transform.as_slice().try_into() else {
+            return Err(anyhow!(
+                "expect 16 transform values, but get {}",
+                self.transform.len()
+            ));
+        };
+        let rows = [0, 1, 2, 3].map(|row| [0, 1, 2, 3].map(|col| values[row * 4 + col]));
+        ExtrinsicMatrix::from_homogeneous(&rows)
+    }
+}
@@ -0,0 +1,478 @@
+//! Attributes on quantity fields, which expand to the unit helper
+//! modules of `newslab-serde-measurements` along with range checks.
+//!
+//! Put [macro@unit_fields] on a struct above its `#[derive(Serialize,
+//! Deserialize)]`. Then mark the quantity fields with either
+//!
+//! - `#[unit(...)]`, which takes the quantity from the type of the
+//!   field, or
+//! - `#[measurement(<quantity>, ...)]`, which names the quantity, like
+//!   `angle` or `length`, and checks the type of the field against it.
+//!
+//! Both accept the options below. All values are strings.
+//!
+//! - `serialize = "mm"`: writes the value in the unit.
+//! - `default_unit = "deg"`: writes the value in the unit, and also
+//!   accepts bare numbers in the unit.
+//! - `range = "0deg..360deg"`: rejects values out of the range, in
+//!   the syntax of Rust ranges.
+//! - `default = "1.5m"`: the value used when the field is missing.
+//!
+//! `Option` fields are written as `null` when `None` and default to
+//! `None` when missing. The units, ranges and default values of known
+//! quantity types are checked at compile time.
+//!
+//! ```rust
+//! use measurements::{Angle, Length};
+//! use newslab_serde_derive::unit_fields;
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[unit_fields]
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct LidarConfig {
+//!     #[unit(serialize = "cm")]
+//!     mount_height: Length,
+//!     #[measurement(angle, default_unit = "deg", range = "0deg..=360deg")]
+//!     fov: Angle,
+//!     #[unit(range = "0m..", default = "120m")]
+//!     max_range: Length,
+//!     #[unit(serialize = "ms")]
+//!     timeout: Option<Duration>,
+//! }
+//!
+//! let config: LidarConfig = serde_json::from_str(
+//!     r#"{ "mount_height": "1.8m", "fov": 270 }"#,
+//! )
+//! .unwrap();
+//! assert_eq!(config.fov.as_degrees(), 270.0);
+//! assert_eq!(config.max_range.as_meters(), 120.0);
+//! assert_eq!(config.timeout, None);
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"mount_height":"180cm","fov":"270deg","max_range":"120m","timeout":null}"#,
+//! );
+//!
+//! let err = serde_json::from_str::<LidarConfig>(
+//!     r#"{ "mount_height": "1.8m", "fov": "400deg" }"#,
+//! )
+//! .unwrap_err();
+//! assert!(err.to_string().contains("out of the range 0deg..=360deg"));
+//! ```
+
+use newslab_serde_measurements::field::{self, FieldSpec};
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Fields, GenericArgument, Ident,
+    ItemStruct, LitStr, Meta, Path, PathArguments, Token, Type, TypePath,
+};
+
+/// Expands the `#[unit]` and `#[measurement]` attributes on the fields
+/// of the struct. See the [crate] documents for the options.
+///
+/// The generated code refers to `::newslab_serde_measurements` by
+/// default. Set the path with `#[unit_fields(crate = "...")]` if the
+/// crate is used through another one.
+///
+/// ```rust
+/// use measurements::Pressure;
+/// use newslab_serde_derive::unit_fields;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[unit_fields(crate = "newslab_serde_measurements")]
+/// #[derive(Serialize, Deserialize)]
+/// struct Tire {
+///     #[measurement(pressure, default_unit = "kPa", range = "150kPa..300kPa")]
+///     pressure: Pressure,
+/// }
+///
+/// let tire: Tire = serde_json::from_str(r#"{ "pressure": 220 }"#).unwrap();
+/// assert_eq!(tire.pressure.as_kilopascals(), 220.0);
+/// ```
+///
+/// The options are checked at compile time.
+///
+/// ```rust,compile_fail
+/// # use measurements::Length;
+/// # use newslab_serde_derive::unit_fields;
+/// # use serde::{Deserialize, Serialize};
+/// #[unit_fields]
+/// #[derive(Serialize, Deserialize)]
+/// struct Mount {
+///     // The default value is out of the range.
+///     #[unit(range = "0m..2m", default = "3m")]
+///     height: Length,
+/// }
+/// ```
+///
+/// ```rust,compile_fail
+/// # use measurements::Length;
+/// # use newslab_serde_derive::unit_fields;
+/// # use serde::{Deserialize, Serialize};
+/// #[unit_fields]
+/// #[derive(Serialize, Deserialize)]
+/// struct Mount {
+///     // The field is not an angle.
+///     #[measurement(angle, default_unit = "deg")]
+///     height: Length,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn unit_fields(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut crate_path: Option<Path> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("crate") {
+            let path: LitStr = meta.value()?.parse()?;
+            crate_path = Some(path.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported unit_fields option"))
+        }
+    });
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(input as ItemStruct);
+
+    expand(crate_path, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(crate_path: Option<Path>, mut item: ItemStruct) -> syn::Result<TokenStream2> {
+    // The path is used in the generated modules, where relative paths
+    // no longer resolve.
+    let krate = match crate_path {
+        Some(mut path) => {
+            if path.leading_colon.is_none() && !path.segments[0].ident.eq("crate") {
+                path.leading_colon = Some(Default::default());
+            }
+            path
+        }
+        None => parse_quote!(::newslab_serde_measurements),
+    };
+    let is_generic = !item.generics.params.is_empty();
+    let struct_name = item.ident.clone();
+
+    let mut modules = vec![];
+    let mut errors: Option<syn::Error> = None;
+    let fields = match &mut item.fields {
+        Fields::Named(fields) => &mut fields.named,
+        Fields::Unnamed(fields) => &mut fields.unnamed,
+        Fields::Unit => return Ok(item.into_token_stream()),
+    };
+
+    for (index, field) in fields.iter_mut().enumerate() {
+        let options = match FieldOptions::take(&mut field.attrs) {
+            Ok(Some(options)) => options,
+            Ok(None) => continue,
+            Err(err) => {
+                combine(&mut errors, err);
+                continue;
+            }
+        };
+        let module = match &field.ident {
+            Some(ident) => format_ident!("__unit_fields_{}_{}", struct_name, ident),
+            None => format_ident!("__unit_fields_{}_{}", struct_name, index),
+        };
+        let has_serde_default = field.attrs.iter().any(is_serde_default);
+
+        match options.expand(&krate, &module, &field.ty, is_generic) {
+            Ok(tokens) => modules.push(tokens),
+            Err(err) => {
+                combine(&mut errors, err);
+                continue;
+            }
+        }
+
+        let with = module.to_string();
+        field.attrs.push(parse_quote!(#[serde(with = #with)]));
+        if options.default.is_some() {
+            let default = format!("{module}::default");
+            field.attrs.push(parse_quote!(#[serde(default = #default)]));
+        } else if option_inner(&field.ty).is_some() && !has_serde_default {
+            field.attrs.push(parse_quote!(#[serde(default)]));
+        }
+    }
+
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    Ok(quote! {
+        #item
+        #(#modules)*
+    })
+}
+
+/// The options in a `#[unit]` or `#[measurement]` attribute.
+#[derive(Default)]
+struct FieldOptions {
+    quantity: Option<Ident>,
+    unit: Option<LitStr>,
+    lenient: bool,
+    range: Option<LitStr>,
+    default: Option<LitStr>,
+}
+
+impl FieldOptions {
+    /// Removes the attribute from the field and parses it.
+    fn take(attrs: &mut Vec<Attribute>) -> syn::Result<Option<Self>> {
+        let is_ours =
+            |attr: &Attribute| attr.path().is_ident("unit") || attr.path().is_ident("measurement");
+        let mut ours = attrs.iter().filter(|attr| is_ours(attr));
+        let Some(attr) = ours.next() else {
+            return Ok(None);
+        };
+        if let Some(other) = ours.next() {
+            return Err(syn::Error::new_spanned(
+                other,
+                "a field can only have one #[unit] or #[measurement] attribute",
+            ));
+        }
+        let options = Self::parse(attr)?;
+        attrs.retain(|attr| !is_ours(attr));
+        Ok(Some(options))
+    }
+
+    fn parse(attr: &Attribute) -> syn::Result<Self> {
+        let is_measurement = attr.path().is_ident("measurement");
+        if let Meta::Path(_) = &attr.meta {
+            let message = if is_measurement {
+                "expected a quantity, like #[measurement(angle)]"
+            } else {
+                "expected options, like #[unit(serialize = \"mm\")]"
+            };
+            return Err(syn::Error::new_spanned(attr, message));
+        }
+
+        let mut options = Self::default();
+        let mut is_first = true;
+        attr.parse_nested_meta(|meta| {
+            let is_quantity = is_measurement && is_first && !meta.input.peek(Token![=]);
+            is_first = false;
+            let key = meta
+                .path
+                .get_ident()
+                .ok_or_else(|| meta.error("expected an identifier"))?;
+            if is_quantity {
+                options.quantity = Some(key.clone());
+                return Ok(());
+            }
+
+            let slot = match key.to_string().as_str() {
+                "serialize" | "default_unit" => {
+                    if options.unit.is_some() {
+                        return Err(meta.error(
+                            "only one of `serialize` and `default_unit` can be given",
+                        ));
+                    }
+                    options.lenient = key == "default_unit";
+                    &mut options.unit
+                }
+                "range" => &mut options.range,
+                "default" => &mut options.default,
+                _ => {
+                    return Err(meta.error(
+                        "unsupported option. It must be one of serialize, default_unit, range and default",
+                    ))
+                }
+            };
+            if slot.is_some() {
+                return Err(meta.error(format!("duplicated option `{key}`")));
+            }
+            *slot = Some(meta.value()?.parse()?);
+            Ok(())
+        })?;
+
+        if is_measurement && options.quantity.is_none() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected a quantity, like #[measurement(angle)]",
+            ));
+        }
+        Ok(options)
+    }
+
+    /// Generates the helper module of the field.
+    fn expand(
+        &self,
+        krate: &Path,
+        module: &Ident,
+        ty: &Type,
+        is_generic: bool,
+    ) -> syn::Result<TokenStream2> {
+        let inner = option_inner(ty);
+        let quantity_ty = inner.unwrap_or(ty);
+
+        let quantity = match &self.quantity {
+            Some(ident) => {
+                let name = ident.to_string();
+                if !field::is_quantity(&name) {
+                    let err = field::validate_named(&name, &FieldSpec::default(), None);
+                    return Err(syn::Error::new_spanned(ident, err.unwrap_err()));
+                }
+                Some(name)
+            }
+            // Types of the quantities are named after them.
+            None => type_name(quantity_ty).filter(|name| field::is_quantity(name)),
+        };
+        if let Some(quantity) = &quantity {
+            self.validate(quantity)?;
+        }
+
+        let field = quote!(#krate::field);
+        let unit = option_tokens(&self.unit);
+        let lenient = self.lenient;
+        let range = option_tokens(&self.range);
+        let (value_ty, serialize, deserialize) = match inner {
+            Some(_) => (
+                quote!(::core::option::Option<Q>),
+                quote!(serialize_opt),
+                quote!(deserialize_opt),
+            ),
+            None => (quote!(Q), quote!(serialize), quote!(deserialize)),
+        };
+
+        let default = self.default.as_ref().map(|text| {
+            let value = quote!(SPEC.default_value(#text));
+            let value = match inner {
+                Some(_) => quote!(::core::option::Option::Some(#value)),
+                None => value,
+            };
+            quote! {
+                pub fn default<Q>() -> #value_ty
+                where
+                    Q: #field::Quantity,
+                {
+                    #value
+                }
+            }
+        });
+
+        // Checks the type of the field against the named quantity,
+        // which is left to the type checker for generic structs.
+        let assertion = match (&self.quantity, &quantity, is_generic) {
+            (Some(_), Some(name), false) => Some(quote_spanned! {quantity_ty.span()=>
+                const _: () = #field::assert_quantity::<#quantity_ty>(#name);
+            }),
+            _ => None,
+        };
+
+        Ok(quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            mod #module {
+                const SPEC: #field::FieldSpec<'static> = #field::FieldSpec {
+                    unit: #unit,
+                    lenient: #lenient,
+                    range: #range,
+                };
+
+                pub fn serialize<Q, S>(
+                    value: &#value_ty,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    Q: #field::Quantity,
+                    S: ::serde::Serializer,
+                {
+                    SPEC.#serialize(value, serializer)
+                }
+
+                pub fn deserialize<'de, Q, D>(
+                    deserializer: D,
+                ) -> ::core::result::Result<#value_ty, D::Error>
+                where
+                    Q: #field::Quantity,
+                    D: ::serde::Deserializer<'de>,
+                {
+                    SPEC.#deserialize(deserializer)
+                }
+
+                #default
+            }
+
+            #assertion
+        })
+    }
+
+    /// Validates the options against the quantity, and reports the
+    /// error at the first invalid option.
+    fn validate(&self, quantity: &str) -> syn::Result<()> {
+        let unit = self.unit.as_ref().map(LitStr::value);
+        let range = self.range.as_ref().map(LitStr::value);
+        let default = self.default.as_ref().map(LitStr::value);
+
+        let mut spec = FieldSpec {
+            unit: unit.as_deref(),
+            lenient: self.lenient,
+            range: None,
+        };
+        let error = |lit: &Option<LitStr>, message: String| match lit {
+            Some(lit) => syn::Error::new_spanned(lit, message),
+            None => syn::Error::new(proc_macro2::Span::call_site(), message),
+        };
+        field::validate_named(quantity, &spec, None).map_err(|err| error(&self.unit, err))?;
+        spec.range = range.as_deref();
+        field::validate_named(quantity, &spec, None).map_err(|err| error(&self.range, err))?;
+        field::validate_named(quantity, &spec, default.as_deref())
+            .map_err(|err| error(&self.default, err))?;
+        Ok(())
+    }
+}
+
+/// Gets `T` in `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+/// Gets the lowercased name of a path type, like `"length"` for
+/// `measurements::Length`.
+fn type_name(ty: &Type) -> Option<String> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    Some(segment.ident.to_string().to_lowercase())
+}
+
+/// Checks if the attribute is like `#[serde(default)]` or
+/// `#[serde(default = "...")]`.
+fn is_serde_default(attr: &Attribute) -> bool {
+    let Meta::List(list) = &attr.meta else {
+        return false;
+    };
+    list.path.is_ident("serde")
+        && list.tokens.clone().into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == "default",
+            _ => false,
+        })
+}
+
+fn option_tokens(lit: &Option<LitStr>) -> TokenStream2 {
+    match lit {
+        Some(lit) => quote!(::core::option::Option::Some(#lit)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+fn combine(errors: &mut Option<syn::Error>, err: syn::Error) {
+    match errors {
+        Some(errors) => errors.combine(err),
+        None => *errors = Some(err),
+    }
+}
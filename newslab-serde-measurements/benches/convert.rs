@@ -0,0 +1,29 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use measurements::{Angle, Length};
+use newslab_serde_measurements::{angle, length};
+
+/// The size of a beam table of a 128-beam lidar with 2048 azimuths.
+const TABLE_SIZE: usize = 128 * 2048;
+
+fn convert_lengths(c: &mut Criterion) {
+    let lengths: Vec<_> = (0..TABLE_SIZE)
+        .map(|ix| Length::from_meters(ix as f64 * 1e-3))
+        .collect();
+
+    c.bench_function("length::to_f32_slice", |b| {
+        b.iter(|| length::to_f32_slice(black_box(&lengths), "cm").unwrap())
+    });
+}
+
+fn convert_angles(c: &mut Criterion) {
+    let angles: Vec<_> = (0..TABLE_SIZE)
+        .map(|ix| Angle::from_radians(ix as f64 * 1e-5))
+        .collect();
+
+    c.bench_function("angle::to_f64_slice", |b| {
+        b.iter(|| angle::to_f64_slice(black_box(&angles), "deg").unwrap())
+    });
+}
+
+criterion_group!(benches, convert_lengths, convert_angles);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+//! Serialization helper to en/decode an acceleration value with units.
+//!
+//! Accepted units are `m/s^2` (or `m/s²`), `ft/s^2` (or `ft/s²`), and
+//! `g` and `mg` in multiples of standard gravity. Accelerations are
+//! serialized in meters per second squared.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in meters per second squared instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::acceleration;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Acceleration;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyImu {
+//!     #[serde(with = "acceleration")]
+//!     gravity: Acceleration,
+//!     #[serde(with = "acceleration")]
+//!     range: Acceleration,
+//!     #[serde(with = "acceleration")]
+//!     bias: Acceleration,
+//! }
+//!
+//! let json = r#"{ "gravity": "9.81m/s^2", "range": "2g", "bias": "-50mg" }"#;
+//! let imu: MyImu = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(imu.gravity.as_meters_per_second_per_second(), 9.81);
+//! assert_eq!(imu.range.as_meters_per_second_per_second(), 19.6133);
+//!
+//! let json = serde_json::to_string(&imu).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"gravity":"9.81m/s^2","range":"19.6133m/s^2","bias":"-0.4903325m/s^2"}"#
+//! );
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Acceleration;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+/// The standard gravity in meters per second squared.
+const STANDARD_GRAVITY: f64 = 9.80665;
+
+pub(crate) static PARSER: UnitParser<Acceleration> = UnitParser::new(
+    "acceleration",
+    "9.81m/s^2",
+    &[
+        Unit::new(
+            &["m/s^2", "m/s²"],
+            Acceleration::from_meters_per_second_per_second,
+            Acceleration::as_meters_per_second_per_second,
+        ),
+        Unit::new(
+            &["ft/s^2", "ft/s²"],
+            Acceleration::from_feet_per_second_per_second,
+            Acceleration::as_feet_per_second_per_second,
+        ),
+        Unit::new(
+            &["g"],
+            |val| Acceleration::from_meters_per_second_per_second(val * STANDARD_GRAVITY),
+            |accel| accel.as_meters_per_second_per_second() / STANDARD_GRAVITY,
+        ),
+        Unit::new(
+            &["mg"],
+            |val| Acceleration::from_meters_per_second_per_second(val * STANDARD_GRAVITY / 1e3),
+            |accel| accel.as_meters_per_second_per_second() * 1e3 / STANDARD_GRAVITY,
+        ),
+    ],
+);
+
+pub fn serialize<S>(accel: &Acceleration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return accel
+            .as_meters_per_second_per_second()
+            .serialize(serializer);
+    }
+    PARSER
+        .format(accel, &PARSER.units()[0])
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Acceleration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer)
+            .map(Acceleration::from_meters_per_second_per_second);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "acceleration::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Acceleration);
+
+pinned_units! {
+    Acceleration;
+    in_meters_per_second_squared => "m/s^2",
+    in_standard_gravity => "g",
+}
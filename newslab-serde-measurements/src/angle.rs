@@ -0,0 +1,434 @@
+//! Serialization helper to en/decode an angle value with units.
+//!
+//! Accepted units are `deg` (or `°`) and `rad`. Angles are
+//! serialized in degrees.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in radians instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::angle;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyAngle {
+//!     #[serde(with = "angle")]
+//!     angle1: Angle,
+//!     #[serde(with = "angle")]
+//!     angle2: Angle,
+//! }
+//!
+//! let json = r#"{ "angle1": "3.0deg", "angle2": "-1.0 rad" }"#;
+//! let MyAngle { angle1, angle2 } = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(angle1.as_degrees(), 3.0);
+//! assert_eq!(angle2.as_radians(), -1.0);
+//! ```
+//!
+//! The submodules also store bare radians in binary formats.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::angle;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyAngle {
+//!     #[serde(with = "angle::in_degrees")]
+//!     yaw: Angle,
+//! }
+//!
+//! let bytes = bincode::serialize(&MyAngle { yaw: Angle::from_radians(0.5) }).unwrap();
+//! assert_eq!(bytes, 0.5f64.to_le_bytes());
+//! let MyAngle { yaw } = bincode::deserialize(&bytes).unwrap();
+//! assert_eq!(yaw.as_radians(), 0.5);
+//! ```
+//!
+//! The [in_radians] submodule writes angles in radians instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::angle;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyJoint {
+//!     #[serde(with = "angle::in_radians")]
+//!     limit: Angle,
+//! }
+//!
+//! let joint: MyJoint = serde_json::from_str(r#"{ "limit": "0.5rad" }"#).unwrap();
+//! assert_eq!(serde_json::to_string(&joint).unwrap(), r#"{"limit":"0.5rad"}"#);
+//! ```
+//!
+//! Angles that overflow in degrees, such as `1e308` radians, fail to
+//! serialize rather than being written as `"infdeg"`, which cannot be
+//! read back.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::angle;
+//! # use serde::Serialize;
+//! # use measurements::Angle;
+//! #[derive(Serialize)]
+//! struct MyAngle {
+//!     #[serde(with = "angle")]
+//!     angle: Angle,
+//! }
+//!
+//! let huge = MyAngle { angle: Angle::from_radians(1e308) };
+//! assert!(serde_json::to_string(&huge).is_err());
+//! let nan = MyAngle { angle: Angle::from_radians(f64::NAN) };
+//! assert!(serde_json::to_string(&nan).is_err());
+//!
+//! // It is still stored in radians in binary formats.
+//! assert!(bincode::serialize(&huge).is_ok());
+//! ```
+//!
+//! Legacy files with bare numbers are read by the `lenient`
+//! submodule of a pinned unit, which interprets numbers in that unit.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::angle;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyMount {
+//!     #[serde(with = "angle::in_degrees::lenient")]
+//!     yaw: Angle,
+//!     #[serde(with = "angle::in_degrees::lenient")]
+//!     pitch: Angle,
+//! }
+//!
+//! let json = r#"{ "yaw": 3, "pitch": "0.5rad" }"#;
+//! let mount: MyMount = serde_json::from_str(json).unwrap();
+//! assert_eq!(mount.yaw.as_degrees(), 3.0);
+//! assert_eq!(mount.pitch.as_radians(), 0.5);
+//! ```
+//!
+//! Collections of angles are handled by the [`vec`](mod@vec) and [map]
+//! submodules, which every unit helper module provides.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::angle;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! # use std::collections::BTreeMap;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyLidar {
+//!     #[serde(with = "angle::vec")]
+//!     elevations: Vec<Angle>,
+//!     #[serde(with = "angle::map")]
+//!     mount_yaw: BTreeMap<String, Angle>,
+//! }
+//!
+//! let json = r#"{
+//!     "elevations": ["-3deg", "0deg", "3deg"],
+//!     "mount_yaw": { "front": "0deg", "rear": "180deg" }
+//! }"#;
+//! let lidar: MyLidar = serde_json::from_str(json).unwrap();
+//! assert_eq!(lidar.elevations.len(), 3);
+//! assert_eq!(lidar.mount_yaw["front"].as_degrees(), 0.0);
+//!
+//! let json = serde_json::to_string(&lidar).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"elevations":["-3deg","0deg","3deg"],"mount_yaw":{"front":"0deg","rear":"180deg"}}"#
+//! );
+//! ```
+
+use crate::unit::{ParseUnitError, Unit, UnitParser};
+use measurements::Angle;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+use std::f64::consts::TAU;
+
+pub(crate) static PARSER: UnitParser<Angle> = UnitParser::new(
+    "angle",
+    "10.0deg",
+    &[
+        Unit::new(&["deg", "°"], Angle::from_degrees, Angle::as_degrees),
+        Unit::new(&["rad"], Angle::from_radians, Angle::as_radians),
+    ],
+);
+
+pub fn serialize<S>(angle: &Angle, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return angle.as_radians().serialize(serializer);
+    }
+    PARSER
+        .format(angle, &PARSER.units()[0])
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Angle, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Angle::from_radians);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Converts angles to `f64` numbers in the unit, such as `"deg"`,
+/// which fails if the unit is unknown. It is meant for large tables
+/// like the beam tables of lidars.
+///
+/// ```rust
+/// # use newslab_serde_measurements::angle;
+/// # use measurements::Angle;
+/// let elevations = [Angle::from_radians(0.5), Angle::from_radians(-1.0)];
+/// assert_eq!(angle::to_f64_slice(&elevations, "rad").unwrap(), [0.5, -1.0]);
+///
+/// assert!(angle::to_f64_slice(&elevations, "m").is_err());
+/// ```
+pub fn to_f64_slice(angles: &[Angle], unit: &str) -> Result<Vec<f64>, ParseUnitError> {
+    let factor = PARSER.factor(unit, &Angle::from_radians(1.0))?;
+    Ok(angles
+        .iter()
+        .map(|value| value.as_radians() * factor)
+        .collect())
+}
+
+/// Converts angles to `f32` numbers in the unit like
+/// [to_f64_slice].
+pub fn to_f32_slice(angles: &[Angle], unit: &str) -> Result<Vec<f32>, ParseUnitError> {
+    let factor = PARSER.factor(unit, &Angle::from_radians(1.0))?;
+    Ok(angles
+        .iter()
+        .map(|value| (value.as_radians() * factor) as f32)
+        .collect())
+}
+
+/// Returns a zero angle. It is intended to be used in
+/// `#[serde(default = "angle::default_zero")]`.
+pub fn default_zero() -> Angle {
+    Angle::from_degrees(0.0)
+}
+
+/// Returns an angle of `DEG` degrees. It is intended to be used in
+/// `#[serde(default = "angle::default_degrees::<90>")]`.
+pub fn default_degrees<const DEG: i64>() -> Angle {
+    Angle::from_degrees(DEG as f64)
+}
+
+/// Returns an angle of `RAD` radians. It is intended to be used in
+/// `#[serde(default = "angle::default_radians::<1>")]`.
+pub fn default_radians<const RAD: i64>() -> Angle {
+    Angle::from_radians(RAD as f64)
+}
+
+/// Wraps the angle to the range [-180°, 180°). Angles already in
+/// the range are returned unchanged.
+pub fn normalize_signed(angle: Angle) -> Angle {
+    Angle::from_radians(wrap(angle.as_radians(), TAU, true))
+}
+
+/// Wraps the angle to the range [0°, 360°). Angles already in the
+/// range are returned unchanged.
+pub fn normalize_positive(angle: Angle) -> Angle {
+    Angle::from_radians(wrap(angle.as_radians(), TAU, false))
+}
+
+/// Wraps the value to [-period/2, period/2) if `signed`, or to
+/// [0, period) otherwise. Values in the range are kept as is, so that
+/// they are not perturbed by rounding.
+fn wrap(value: f64, period: f64, signed: bool) -> f64 {
+    let half = period / 2.0;
+    let in_range = if signed {
+        (-half..half).contains(&value)
+    } else {
+        (0.0..period).contains(&value)
+    };
+    if in_range || !value.is_finite() {
+        return value;
+    }
+
+    let value = value.rem_euclid(period);
+
+    // The remainder can be rounded up to the divisor.
+    let value = if value >= period { 0.0 } else { value };
+    if signed && value >= half {
+        value - period
+    } else {
+        value
+    }
+}
+
+/// Returns the period of a full turn in the unit.
+fn turn_in(unit: &Unit<Angle>) -> f64 {
+    match unit.symbol() {
+        "deg" => 360.0,
+        _ => TAU,
+    }
+}
+
+/// Serializes the angle wrapped in degrees, the unit it is written in.
+fn serialize_wrapped<S>(angle: &Angle, signed: bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return wrap(angle.as_radians(), TAU, signed).serialize(serializer);
+    }
+    let unit = &PARSER.units()[0];
+    let value = wrap((unit.to_value)(angle), turn_in(unit), signed);
+    PARSER
+        .format_value(value, unit)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+/// Deserializes the angle and wraps it in the unit it is written in.
+fn deserialize_wrapped<'de, D>(deserializer: D, signed: bool) -> Result<Angle, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        let radians = crate::compat::deserialize_bare_number(deserializer)?;
+        return Ok(Angle::from_radians(wrap(radians, TAU, signed)));
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    let (value, unit) = PARSER.parse_with_unit(&text).map_err(D::Error::custom)?;
+    Ok((unit.from_value)(wrap(value, turn_in(unit), signed)))
+}
+
+/// Serialization helper that wraps angles to [-180°, 180°) on
+/// ser/deserialization. It is useful for yaw angles.
+///
+/// ```rust
+/// # use newslab_serde_measurements::angle;
+/// # use serde::{Serialize, Deserialize};
+/// # use measurements::Angle;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyPose {
+///     #[serde(with = "angle::normalized_signed")]
+///     yaw: Angle,
+/// }
+///
+/// let pose: MyPose = serde_json::from_str(r#"{ "yaw": "270deg" }"#).unwrap();
+/// assert!((pose.yaw.as_degrees() + 90.0).abs() < 1e-9);
+///
+/// let pose: MyPose = serde_json::from_str(r#"{ "yaw": "180deg" }"#).unwrap();
+/// assert_eq!(pose.yaw.as_degrees(), -180.0);
+///
+/// // Angles in the range are kept as written.
+/// for yaw in ["10deg", "-90deg"] {
+///     let json = format!(r#"{{"yaw":"{yaw}"}}"#);
+///     let pose: MyPose = serde_json::from_str(&json).unwrap();
+///     assert_eq!(serde_json::to_string(&pose).unwrap(), json);
+/// }
+/// ```
+pub mod normalized_signed {
+    use super::*;
+
+    pub fn serialize<S>(angle: &Angle, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_wrapped(angle, true, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Angle, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_wrapped(deserializer, true)
+    }
+
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        super::json_schema(gen)
+    }
+
+    unit_combinators!(Angle);
+}
+
+/// Serialization helper that wraps angles to [0°, 360°) on
+/// ser/deserialization.
+///
+/// ```rust
+/// # use newslab_serde_measurements::angle;
+/// # use serde::{Serialize, Deserialize};
+/// # use measurements::Angle;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyLidar {
+///     #[serde(with = "angle::normalized_positive::vec")]
+///     azimuths: Vec<Angle>,
+/// }
+///
+/// let json = r#"{ "azimuths": ["-90deg", "0deg", "360deg"] }"#;
+/// let lidar: MyLidar = serde_json::from_str(json).unwrap();
+/// assert_eq!(lidar.azimuths[0].as_degrees(), 270.0);
+/// assert_eq!(lidar.azimuths[2].as_degrees(), 0.0);
+///
+/// let json = r#"{"azimuths":["-450deg","10deg","0.5rad"]}"#;
+/// let lidar: MyLidar = serde_json::from_str(json).unwrap();
+/// assert_eq!(
+///     serde_json::to_string(&lidar).unwrap(),
+///     r#"{"azimuths":["270deg","10deg","28.64788975654116deg"]}"#
+/// );
+/// ```
+pub mod normalized_positive {
+    use super::*;
+
+    pub fn serialize<S>(angle: &Angle, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_wrapped(angle, false, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Angle, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_wrapped(deserializer, false)
+    }
+
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        super::json_schema(gen)
+    }
+
+    unit_combinators!(Angle);
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "angle::json_schema")]`.
+///
+/// ```rust
+/// # use newslab_serde_measurements::angle;
+/// # use measurements::Angle;
+/// # use schemars::{schema_for, JsonSchema};
+/// #[derive(JsonSchema)]
+/// struct MyMount {
+///     #[schemars(schema_with = "angle::json_schema")]
+///     yaw: Angle,
+///     #[schemars(schema_with = "angle::vec::json_schema")]
+///     elevations: Vec<Angle>,
+/// }
+///
+/// let schema = serde_json::to_value(schema_for!(MyMount)).unwrap();
+/// let yaw = &schema["properties"]["yaw"];
+/// assert_eq!(
+///     yaw["anyOf"][0]["pattern"],
+///     r"^\s*[-+]?(\d+(\.\d*)?|\.\d+)([eE][-+]?\d+)?\s*(deg|°|rad)\s*$"
+/// );
+/// assert_eq!(schema["properties"]["elevations"]["items"], *yaw);
+/// ```
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Angle);
+
+pinned_units! {
+    Angle;
+    in_degrees => "deg",
+    in_radians => "rad",
+}
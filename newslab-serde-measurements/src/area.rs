@@ -0,0 +1,124 @@
+//! Serialization helper to en/decode an area value with units.
+//!
+//! Accepted units are `mm^2`, `cm^2`, `m^2`, `km^2`, `ha`, `in^2`,
+//! `ft^2` and `acre`. The square units are also written as `m2` or
+//! `m²`. Areas are serialized in the square meter unit that fits the
+//! magnitude best.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in square meters instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::area;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Area;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyField {
+//!     #[serde(with = "area")]
+//!     patch: Area,
+//!     #[serde(with = "area")]
+//!     sensor: Area,
+//!     #[serde(with = "area")]
+//!     site: Area,
+//! }
+//!
+//! let json = r#"{ "patch": "2.5m^2", "sensor": "10cm2", "site": "1ha" }"#;
+//! let field: MyField = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(field.patch.as_square_meters(), 2.5);
+//! assert!((field.sensor.as_square_centimeters() - 10.0).abs() < 1e-9);
+//! assert_eq!(field.site.as_square_meters(), 10_000.0);
+//!
+//! let json = serde_json::to_string(&field).unwrap();
+//! assert!(json.contains(r#""patch":"2.5m^2""#));
+//! assert!(json.contains(r#""site":"1e4m^2""#));
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Area;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Area> = UnitParser::new(
+    "area",
+    "2.5m^2",
+    &[
+        Unit::new(
+            &["mm^2", "mm2", "mm²"],
+            Area::from_square_millimeters,
+            Area::as_square_millimeters,
+        ),
+        Unit::new(
+            &["cm^2", "cm2", "cm²"],
+            Area::from_square_centimeters,
+            Area::as_square_centimeters,
+        ),
+        Unit::new(
+            &["m^2", "m2", "m²"],
+            Area::from_square_meters,
+            Area::as_square_meters,
+        ),
+        Unit::new(
+            &["km^2", "km2", "km²"],
+            Area::from_square_kilometers,
+            Area::as_square_kilometers,
+        ),
+        Unit::new(&["ha"], Area::from_hectares, Area::as_hectares),
+        Unit::new(
+            &["in^2", "in2", "in²"],
+            Area::from_square_inches,
+            Area::as_square_inches,
+        ),
+        Unit::new(
+            &["ft^2", "ft2", "ft²"],
+            Area::from_square_feet,
+            Area::as_square_feet,
+        ),
+        Unit::new(&["acre"], Area::from_acres, Area::as_acres),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["mm^2", "cm^2", "m^2", "km^2"];
+
+pub fn serialize<S>(area: &Area, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return area.as_square_meters().serialize(serializer);
+    }
+    PARSER
+        .format_prefixed(area, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Area, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Area::from_square_meters);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "area::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Area);
+
+pinned_units! {
+    Area;
+    in_square_millimeters => "mm^2",
+    in_square_centimeters => "cm^2",
+    in_square_meters => "m^2",
+    in_square_kilometers => "km^2",
+    in_hectares => "ha",
+}
@@ -0,0 +1,376 @@
+use crate::{
+    tolerance::Quantity,
+    unit::{Unit, UnitParser},
+};
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, SeqAccess, Visitor},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, marker::PhantomData};
+
+/// The bins of a histogram over a quantity, such as the range or the
+/// angle bins of an evaluation.
+///
+/// The bins are given either by explicit edges, like
+/// `"[0, 10, 30, 50] m"` or `["0m", "10m", "30m", "50m"]`, or by
+/// `count` bins of equal width from `min` to `max`. The edges must be
+/// strictly increasing. Each bin contains its lower edge but not the
+/// upper one, except for the last bin, which contains both.
+///
+/// ```rust
+/// # use newslab_serde_measurements::BinningSpec;
+/// # use serde::{Serialize, Deserialize};
+/// # use measurements::{Angle, Length};
+/// #[derive(Serialize, Deserialize)]
+/// struct MyEvaluation {
+///     range_bins: BinningSpec<Length>,
+///     yaw_bins: BinningSpec<Angle>,
+/// }
+///
+/// let json = r#"{
+///     "range_bins": "[0, 10, 30, 50] m",
+///     "yaw_bins": { "min": "-180deg", "max": "180deg", "count": 4 }
+/// }"#;
+/// let eval: MyEvaluation = serde_json::from_str(json).unwrap();
+///
+/// let bins = &eval.range_bins;
+/// assert_eq!(bins.bin_count(), 3);
+/// assert_eq!(bins.bin_index(&Length::from_meters(0.0)), Some(0));
+/// assert_eq!(bins.bin_index(&Length::from_meters(10.0)), Some(1));
+/// assert_eq!(bins.bin_index(&Length::from_meters(50.0)), Some(2));
+/// assert_eq!(bins.bin_index(&Length::from_meters(50.1)), None);
+/// assert_eq!(bins.bin_index(&Length::from_meters(-0.1)), None);
+///
+/// let bins = &eval.yaw_bins;
+/// assert_eq!(bins.bin_index(&Angle::from_degrees(-90.0)), Some(1));
+/// assert_eq!(bins.bin_index(&Angle::from_degrees(180.0)), Some(3));
+///
+/// let json = serde_json::to_value(&eval).unwrap();
+/// assert_eq!(json["range_bins"], "[0, 10, 30, 50] m");
+/// assert_eq!(json["yaw_bins"]["min"], "-180deg");
+///
+/// let bytes = bincode::serialize(&eval).unwrap();
+/// let decoded: MyEvaluation = bincode::deserialize(&bytes).unwrap();
+/// assert_eq!(decoded.yaw_bins, eval.yaw_bins);
+///
+/// // The edges are not sorted.
+/// assert!(serde_json::from_str::<BinningSpec<Length>>(r#"["0m", "30m", "10m"]"#).is_err());
+/// // There is no bin.
+/// assert!(serde_json::from_str::<BinningSpec<Length>>(r#"["0m"]"#).is_err());
+/// assert!(serde_json::from_str::<BinningSpec<Length>>(
+///     r#"{ "min": "0m", "max": "10m", "count": 0 }"#
+/// )
+/// .is_err());
+/// ```
+#[derive(Clone)]
+pub struct BinningSpec<Q: Quantity> {
+    kind: Kind<Q>,
+    unit: &'static Unit<Q>,
+}
+
+#[derive(Clone)]
+enum Kind<Q> {
+    Edges(Vec<Q>),
+    Uniform { min: Q, max: Q, count: usize },
+}
+
+impl<Q: Quantity> BinningSpec<Q> {
+    /// Creates bins from explicit edges written in the unit with the
+    /// symbol. It fails if there are less than two edges, or the
+    /// edges are not strictly increasing.
+    pub fn from_edges(edges: Vec<Q>, symbol: &str) -> Result<Self, String> {
+        let unit = find_unit::<Q>(symbol)?;
+        let parser = Q::parser();
+
+        if edges.len() < 2 {
+            return Err(format!(
+                "at least two edges are required, but got {}",
+                edges.len()
+            ));
+        }
+        if let Some(edge) = edges.iter().find(|edge| !edge.as_base_units().is_finite()) {
+            return Err(format!(
+                "the edge {} must be finite",
+                parser.format_lossy(edge, unit)
+            ));
+        }
+        if let Some(pair) = edges
+            .windows(2)
+            .find(|pair| pair[0].as_base_units() >= pair[1].as_base_units())
+        {
+            return Err(format!(
+                "the edges must be strictly increasing, but {} is followed by {}",
+                parser.format_lossy(&pair[0], unit),
+                parser.format_lossy(&pair[1], unit)
+            ));
+        }
+
+        Ok(Self {
+            kind: Kind::Edges(edges),
+            unit,
+        })
+    }
+
+    /// Creates `count` bins of equal width from `min` to `max`
+    /// written in the unit with the symbol. It fails if `min` is not
+    /// below `max` or the count is zero.
+    pub fn uniform(min: Q, max: Q, count: usize, symbol: &str) -> Result<Self, String> {
+        let unit = find_unit::<Q>(symbol)?;
+        let parser = Q::parser();
+
+        let (lower, upper) = (min.as_base_units(), max.as_base_units());
+        if !(lower.is_finite() && upper.is_finite() && lower < upper) {
+            return Err(format!(
+                "the min {} must be less than the max {}",
+                parser.format_lossy(&min, unit),
+                parser.format_lossy(&max, unit)
+            ));
+        }
+        if count == 0 {
+            return Err("the bin count must be positive".to_string());
+        }
+
+        Ok(Self {
+            kind: Kind::Uniform { min, max, count },
+            unit,
+        })
+    }
+
+    pub fn bin_count(&self) -> usize {
+        match &self.kind {
+            Kind::Edges(edges) => edges.len() - 1,
+            Kind::Uniform { count, .. } => *count,
+        }
+    }
+
+    /// Gets the edges of all bins, which has one more element than
+    /// the bins.
+    pub fn edges(&self) -> Vec<Q> {
+        match &self.kind {
+            Kind::Edges(edges) => edges.clone(),
+            Kind::Uniform { min, max, count } => {
+                let (lower, upper) = (min.as_base_units(), max.as_base_units());
+                let mut edges: Vec<_> = (0..*count)
+                    .map(|index| {
+                        let ratio = index as f64 / *count as f64;
+                        Q::from_base_units(lower + (upper - lower) * ratio)
+                    })
+                    .collect();
+                edges.push(*max);
+                edges
+            }
+        }
+    }
+
+    /// Finds the bin containing the value, which returns `None` if
+    /// the value is outside all bins or NaN.
+    pub fn bin_index(&self, value: &Q) -> Option<usize> {
+        let value = value.as_base_units();
+        let last = self.bin_count() - 1;
+
+        match &self.kind {
+            Kind::Edges(edges) => {
+                let lower = edges[0].as_base_units();
+                let upper = edges[last + 1].as_base_units();
+                if !(lower..=upper).contains(&value) {
+                    return None;
+                }
+                let index = edges.partition_point(|edge| edge.as_base_units() <= value);
+                Some((index - 1).min(last))
+            }
+            Kind::Uniform { min, max, count } => {
+                let (lower, upper) = (min.as_base_units(), max.as_base_units());
+                if !(lower..=upper).contains(&value) {
+                    return None;
+                }
+                let index = ((value - lower) / (upper - lower) * *count as f64) as usize;
+                Some(index.min(last))
+            }
+        }
+    }
+}
+
+fn find_unit<Q: Quantity>(symbol: &str) -> Result<&'static Unit<Q>, String> {
+    let parser = Q::parser();
+    parser
+        .find_unit(symbol)
+        .ok_or_else(|| format!("unknown {} unit '{symbol}'", parser.quantity()))
+}
+
+/// Finds the base unit of the quantity, which is used when the bins
+/// are stored as bare numbers.
+fn base_unit<Q: Quantity>(parser: &UnitParser<Q>) -> &'static Unit<Q> {
+    let name = Q::from_base_units(0.0).get_base_units_name();
+    parser.find_unit(name).unwrap_or(&parser.units()[0])
+}
+
+impl<Q: Quantity> fmt::Debug for BinningSpec<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parser = Q::parser();
+        match &self.kind {
+            Kind::Edges(edges) => f
+                .debug_tuple("Edges")
+                .field(&parser.format_vector_lossy(edges, self.unit))
+                .finish(),
+            Kind::Uniform { min, max, count } => f
+                .debug_struct("Uniform")
+                .field("min", &parser.format_lossy(min, self.unit))
+                .field("max", &parser.format_lossy(max, self.unit))
+                .field("count", count)
+                .finish(),
+        }
+    }
+}
+
+impl<Q: Quantity> PartialEq for BinningSpec<Q> {
+    fn eq(&self, other: &Self) -> bool {
+        let base = |values: &[Q]| -> Vec<f64> { values.iter().map(Q::as_base_units).collect() };
+        match (&self.kind, &other.kind) {
+            (Kind::Edges(lhs), Kind::Edges(rhs)) => base(lhs) == base(rhs),
+            (
+                Kind::Uniform {
+                    min: lmin,
+                    max: lmax,
+                    count: lcount,
+                },
+                Kind::Uniform {
+                    min: rmin,
+                    max: rmax,
+                    count: rcount,
+                },
+            ) => base(&[*lmin, *lmax]) == base(&[*rmin, *rmax]) && lcount == rcount,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UniformText {
+    min: String,
+    max: String,
+    count: usize,
+}
+
+/// The bins stored as bare numbers in the base unit.
+#[derive(Serialize, Deserialize)]
+enum BareBins {
+    Edges(Vec<f64>),
+    Uniform { min: f64, max: f64, count: usize },
+}
+
+/// Edges are written as a vector sharing the unit, like
+/// `"[0, 10, 30] m"`, and uniform bins as an object, in
+/// human-readable formats. They are stored as bare numbers in the
+/// base unit otherwise.
+impl<Q: Quantity> Serialize for BinningSpec<Q> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let parser = Q::parser();
+
+        if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+            let bins = match &self.kind {
+                Kind::Edges(edges) => BareBins::Edges(edges.iter().map(Q::as_base_units).collect()),
+                Kind::Uniform { min, max, count } => BareBins::Uniform {
+                    min: min.as_base_units(),
+                    max: max.as_base_units(),
+                    count: *count,
+                },
+            };
+            return bins.serialize(serializer);
+        }
+
+        match &self.kind {
+            Kind::Edges(edges) => parser
+                .format_vector(edges, self.unit)
+                .map_err(S::Error::custom)?
+                .serialize(serializer),
+            Kind::Uniform { min, max, count } => UniformText {
+                min: parser.format(min, self.unit).map_err(S::Error::custom)?,
+                max: parser.format(max, self.unit).map_err(S::Error::custom)?,
+                count: *count,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de, Q: Quantity> Deserialize<'de> for BinningSpec<Q> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+            let parser = Q::parser();
+            let symbol = base_unit(parser).symbol();
+            let bins = match BareBins::deserialize(deserializer)? {
+                BareBins::Edges(edges) => {
+                    let edges = edges.into_iter().map(Q::from_base_units).collect();
+                    Self::from_edges(edges, symbol)
+                }
+                BareBins::Uniform { min, max, count } => Self::uniform(
+                    Q::from_base_units(min),
+                    Q::from_base_units(max),
+                    count,
+                    symbol,
+                ),
+            };
+            return bins.map_err(de::Error::custom);
+        }
+        deserializer.deserialize_any(BinsVisitor(PhantomData))
+    }
+}
+
+struct BinsVisitor<Q>(PhantomData<Q>);
+
+impl<'de, Q: Quantity> Visitor<'de> for BinsVisitor<Q> {
+    type Value = BinningSpec<Q>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(
+            "bin edges like \"[0, 10, 30] m\", or an object of \"min\", \"max\" and \"count\"",
+        )
+    }
+
+    fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let parser = Q::parser();
+        let edges = parser.parse_vector(text).map_err(E::custom)?;
+        let (_, symbol) = text.trim().rsplit_once(']').unwrap();
+        BinningSpec::from_edges(edges, symbol.trim()).map_err(E::custom)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let parser = Q::parser();
+        let mut edges = vec![];
+        let mut unit = None;
+        while let Some(text) = seq.next_element::<String>()? {
+            let (value, edge_unit) = parser.parse_with_unit(&text).map_err(de::Error::custom)?;
+            edges.push((edge_unit.from_value)(value));
+            unit.get_or_insert(edge_unit);
+        }
+        let symbol = unit.unwrap_or_else(|| base_unit(parser)).symbol();
+        BinningSpec::from_edges(edges, symbol).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let parser = Q::parser();
+        let UniformText { min, max, count } =
+            UniformText::deserialize(MapAccessDeserializer::new(map))?;
+        let (value, unit) = parser.parse_with_unit(&min).map_err(de::Error::custom)?;
+        let min = (unit.from_value)(value);
+        let max = parser.parse(&max).map_err(de::Error::custom)?;
+        BinningSpec::uniform(min, max, count, unit.symbol()).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,710 @@
+#[cfg(feature = "schemars")]
+use schemars::schema::{
+    ArrayValidation, InstanceType, ObjectValidation, Schema, SchemaObject, SubschemaValidation,
+};
+use serde::{
+    de::{value::MapAccessDeserializer, Error, MapAccess, Visitor},
+    ser::{self, Impossible},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::fmt::{self, Display};
+
+/// Generates the combinator submodules of a unit helper module from
+/// its `serialize` and `deserialize` functions.
+///
+/// The macro is invoked at the end of the helper module with the
+/// quantity type. With the `schemars` feature, the helper module must
+/// also provide a `json_schema` function.
+macro_rules! unit_combinators {
+    ($ty:ty) => {
+        /// Serializes a borrowed value with the helper of the
+        /// enclosing module.
+        struct SerializeWith<'a>(&'a $ty);
+
+        impl ::serde::Serialize for SerializeWith<'_> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                serialize(self.0, serializer)
+            }
+        }
+
+        /// Deserializes a value with the helper of the enclosing
+        /// module.
+        struct DeserializeWith($ty);
+
+        impl<'de> ::serde::Deserialize<'de> for DeserializeWith {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                deserialize(deserializer).map(Self)
+            }
+        }
+
+        /// Serialization helper that writes values as objects like
+        /// `{ "value": 3.5, "unit": "m" }` in the unit of the enclosing
+        /// module, for tools that cannot handle values embedded in
+        /// strings. Both the objects and the strings are deserialized.
+        pub mod object {
+            use super::*;
+            use serde::{ser::Error as _, Deserializer, Serialize, Serializer};
+            use $crate::combinators::{Captured, ValueUnit};
+
+            pub fn serialize<S>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                if !serializer.is_human_readable() {
+                    return serialize(value, serializer);
+                }
+                match $crate::combinators::capture(&SerializeWith(value))
+                    .map_err(S::Error::custom)?
+                {
+                    Captured::Text(text) => match ValueUnit::from_text(&text) {
+                        Some(object) => object.serialize(serializer),
+                        None => text.serialize(serializer),
+                    },
+                    Captured::Number(value) => value.serialize(serializer),
+                }
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                super::deserialize(deserializer)
+            }
+
+            #[cfg(feature = "schemars")]
+            pub fn json_schema(
+                gen: &mut ::schemars::SchemaGenerator,
+            ) -> ::schemars::schema::Schema {
+                super::json_schema(gen)
+            }
+        }
+
+        /// Serialization helper for `Option` values in the format of
+        /// the enclosing module. A `null` value becomes `None`. Add
+        /// `#[serde(default)]` to also accept missing fields.
+        pub mod opt {
+            use super::*;
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<S>(value: &Option<$ty>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                value.as_ref().map(SerializeWith).serialize(serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<$ty>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let value = Option::<DeserializeWith>::deserialize(deserializer)?;
+                Ok(value.map(|DeserializeWith(value)| value))
+            }
+
+            #[cfg(feature = "schemars")]
+            pub fn json_schema(
+                gen: &mut ::schemars::SchemaGenerator,
+            ) -> ::schemars::schema::Schema {
+                $crate::combinators::nullable_schema(super::json_schema(gen))
+            }
+        }
+
+        /// Serialization helper for sequences of values in the format
+        /// of the enclosing module. Values are deserialized into a
+        /// `Vec`.
+        pub mod vec {
+            use super::*;
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            pub fn serialize<S>(values: &[$ty], serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.collect_seq(values.iter().map(SerializeWith))
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<$ty>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let values = Vec::<DeserializeWith>::deserialize(deserializer)?;
+                Ok(values
+                    .into_iter()
+                    .map(|DeserializeWith(value)| value)
+                    .collect())
+            }
+
+            #[cfg(feature = "schemars")]
+            pub fn json_schema(
+                gen: &mut ::schemars::SchemaGenerator,
+            ) -> ::schemars::schema::Schema {
+                $crate::combinators::array_schema(super::json_schema(gen))
+            }
+        }
+
+        /// Serialization helper for maps with values in the format of
+        /// the enclosing module. It works with any map type, such as
+        /// `HashMap` and `BTreeMap`.
+        pub mod map {
+            use super::*;
+            use serde::{
+                de::{MapAccess, Visitor},
+                Deserialize, Deserializer, Serialize, Serializer,
+            };
+            use std::{fmt, marker::PhantomData};
+
+            pub fn serialize<S, K, M>(map: &M, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+                K: Serialize,
+                for<'a> &'a M: IntoIterator<Item = (&'a K, &'a $ty)>,
+            {
+                serializer.collect_map(
+                    map.into_iter()
+                        .map(|(key, value)| (key, SerializeWith(value))),
+                )
+            }
+
+            pub fn deserialize<'de, D, K, M>(deserializer: D) -> Result<M, D::Error>
+            where
+                D: Deserializer<'de>,
+                K: Deserialize<'de>,
+                M: FromIterator<(K, $ty)>,
+            {
+                struct MapVisitor<K, M>(PhantomData<(K, M)>);
+
+                impl<'de, K, M> Visitor<'de> for MapVisitor<K, M>
+                where
+                    K: Deserialize<'de>,
+                    M: FromIterator<(K, $ty)>,
+                {
+                    type Value = M;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("a map")
+                    }
+
+                    fn visit_map<A>(self, mut access: A) -> Result<M, A::Error>
+                    where
+                        A: MapAccess<'de>,
+                    {
+                        let mut entries = vec![];
+                        while let Some((key, DeserializeWith(value))) = access.next_entry()? {
+                            entries.push((key, value));
+                        }
+                        Ok(entries.into_iter().collect())
+                    }
+                }
+
+                deserializer.deserialize_map(MapVisitor(PhantomData))
+            }
+
+            #[cfg(feature = "schemars")]
+            pub fn json_schema(
+                gen: &mut ::schemars::SchemaGenerator,
+            ) -> ::schemars::schema::Schema {
+                $crate::combinators::map_schema(super::json_schema(gen))
+            }
+        }
+    };
+}
+
+/// Generates submodules of a unit helper module that serialize values
+/// in a pinned unit, so that written files keep stable units. Values
+/// in any accepted unit are still deserialized. Each submodule also
+/// provides the combinator submodules and a `lenient` submodule that
+/// accepts bare numbers in the pinned unit.
+///
+/// Each entry names the submodule and the unit symbol. The quantity
+/// type can be followed by a function formatting the quantity in the
+/// unit with the symbol, in case the parser does not work on the
+/// quantity type.
+macro_rules! pinned_units {
+    ($ty:ty => $format:path; $($name:ident => $symbol:literal),* $(,)?) => {
+        $(
+            #[doc = concat!(
+                "Serialization helper that writes values in `", $symbol, "`. ",
+                "Values in any accepted unit are deserialized."
+            )]
+            pub mod $name {
+                use super::*;
+                use serde::{Deserializer, Serialize, Serializer};
+
+                pub fn serialize<S>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    if $crate::compat::stores_bare_number(serializer.is_human_readable()) {
+                        return super::serialize(value, serializer);
+                    }
+                    $format(value, $symbol)
+                        .map_err(serde::ser::Error::custom)?
+                        .serialize(serializer)
+                }
+
+                pub fn deserialize<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    super::deserialize(deserializer)
+                }
+
+                #[cfg(feature = "schemars")]
+                pub fn json_schema(
+                    gen: &mut ::schemars::SchemaGenerator,
+                ) -> ::schemars::schema::Schema {
+                    super::json_schema(gen)
+                }
+
+                unit_combinators!($ty);
+
+                #[doc = concat!(
+                    "Serialization helper that writes values in `", $symbol, "` and ",
+                    "also accepts bare numbers in `", $symbol, "`, which eases the ",
+                    "migration of configurations without units."
+                )]
+                pub mod lenient {
+                    use super::*;
+                    use serde::{de::IntoDeserializer as _, Deserialize, Deserializer, Serializer};
+                    use $crate::combinators::Lenient;
+
+                    pub fn serialize<S>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error>
+                    where
+                        S: Serializer,
+                    {
+                        super::serialize(value, serializer)
+                    }
+
+                    pub fn deserialize<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+                    where
+                        D: Deserializer<'de>,
+                    {
+                        if !deserializer.is_human_readable() {
+                            return super::deserialize(deserializer);
+                        }
+                        let text = match Lenient::deserialize(deserializer)? {
+                            Lenient::Text(text) => text,
+                            Lenient::Number(value) => format!("{value}{}", $symbol),
+                        };
+                        super::deserialize(text.into_deserializer())
+                    }
+
+                    #[cfg(feature = "schemars")]
+                    pub fn json_schema(
+                        gen: &mut ::schemars::SchemaGenerator,
+                    ) -> ::schemars::schema::Schema {
+                        $crate::combinators::lenient_schema(super::json_schema(gen))
+                    }
+
+                    unit_combinators!($ty);
+                }
+            }
+        )*
+    };
+    ($ty:ty; $($name:ident => $symbol:literal),* $(,)?) => {
+        /// Formats the value in the unit of a pinned submodule.
+        fn format_pinned(
+            value: &$ty,
+            symbol: &str,
+        ) -> Result<String, $crate::unit::ParseUnitError> {
+            PARSER.format_in(value, symbol)
+        }
+
+        pinned_units!($ty => format_pinned; $($name => $symbol),*);
+    };
+}
+
+/// Generates the schema of a value or `null`.
+#[cfg(feature = "schemars")]
+pub(crate) fn nullable_schema(schema: Schema) -> Schema {
+    any_of_schema(vec![
+        schema,
+        SchemaObject {
+            instance_type: Some(InstanceType::Null.into()),
+            ..Default::default()
+        }
+        .into(),
+    ])
+}
+
+/// Generates the schema of an array of values.
+#[cfg(feature = "schemars")]
+pub(crate) fn array_schema(schema: Schema) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(schema.into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Generates the schema of a map with string keys to values.
+#[cfg(feature = "schemars")]
+pub(crate) fn map_schema(schema: Schema) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(ObjectValidation {
+            additional_properties: Some(Box::new(schema)),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Generates the schema of the `lenient` helper modules, which also
+/// accept bare numbers.
+#[cfg(feature = "schemars")]
+pub(crate) fn lenient_schema(schema: Schema) -> Schema {
+    any_of_schema(vec![
+        SchemaObject {
+            instance_type: Some(InstanceType::Number.into()),
+            ..Default::default()
+        }
+        .into(),
+        schema,
+    ])
+}
+
+/// Generates the schema matching any of the schemas.
+#[cfg(feature = "schemars")]
+pub(crate) fn any_of_schema(schemas: Vec<Schema>) -> Schema {
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(schemas),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// A string with units or a bare number, which is accepted by the
+/// `lenient` helper modules.
+pub(crate) enum Lenient {
+    Text(String),
+    Number(f64),
+}
+
+impl<'de> Deserialize<'de> for Lenient {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LenientVisitor;
+
+        impl<'de> Visitor<'de> for LenientVisitor {
+            type Value = Lenient;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a number or a string with units")
+            }
+
+            fn visit_str<E>(self, text: &str) -> Result<Lenient, E>
+            where
+                E: Error,
+            {
+                Ok(Lenient::Text(text.to_string()))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Lenient, E>
+            where
+                E: Error,
+            {
+                if !value.is_finite() {
+                    return Err(E::custom(format!("{value} is not a finite number")));
+                }
+                Ok(Lenient::Number(value))
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Lenient, E>
+            where
+                E: Error,
+            {
+                Ok(Lenient::Number(value as f64))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Lenient, E>
+            where
+                E: Error,
+            {
+                Ok(Lenient::Number(value as f64))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Lenient, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let object = ValueUnit::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(Lenient::Text(object.to_text()))
+            }
+        }
+
+        deserializer.deserialize_any(LenientVisitor)
+    }
+}
+
+/// A quantity in the object form, like `{ "value": 3.5, "unit": "m" }`.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ValueUnit {
+    pub value: f64,
+    pub unit: String,
+}
+
+impl ValueUnit {
+    /// Splits a number-unit string at the longest prefix that is a
+    /// number.
+    pub(crate) fn from_text(text: &str) -> Option<Self> {
+        let text = text.trim();
+        text.char_indices()
+            .map(|(index, _)| index)
+            .chain([text.len()])
+            .rev()
+            .find_map(|index| {
+                let value: f64 = text[..index].trim().parse().ok()?;
+                let unit = text[index..].trim().to_string();
+                Some(Self { value, unit })
+            })
+    }
+
+    /// Joins the number and the unit into a string.
+    pub(crate) fn to_text(&self) -> String {
+        let Self { value, unit } = self;
+        format!("{value}{unit}")
+    }
+}
+
+/// Deserializes a string with units, or an object with a value and a
+/// unit in human-readable formats, to the string form.
+pub(crate) fn deserialize_text<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct TextVisitor;
+
+    impl<'de> Visitor<'de> for TextVisitor {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter
+                .write_str(r#"a string with units or an object like { "value": 3.5, "unit": "m" }"#)
+        }
+
+        fn visit_str<E>(self, text: &str) -> Result<String, E>
+        where
+            E: Error,
+        {
+            Ok(text.to_string())
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<String, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let object = ValueUnit::deserialize(MapAccessDeserializer::new(map))?;
+            Ok(object.to_text())
+        }
+    }
+
+    if !deserializer.is_human_readable() {
+        return String::deserialize(deserializer);
+    }
+    deserializer.deserialize_any(TextVisitor)
+}
+
+/// The string or the number written by a helper module.
+pub(crate) enum Captured {
+    Text(String),
+    Number(f64),
+}
+
+/// Captures the string or the number written by a helper module, in
+/// the way of a human-readable format.
+pub(crate) fn capture<T>(value: &T) -> Result<Captured, CaptureError>
+where
+    T: Serialize,
+{
+    value.serialize(Capture)
+}
+
+#[derive(Debug)]
+pub(crate) struct CaptureError(String);
+
+impl Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl ser::Error for CaptureError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        Self(msg.to_string())
+    }
+}
+
+struct Capture;
+
+impl Capture {
+    fn unsupported(kind: &str) -> CaptureError {
+        CaptureError(format!("expect a string or a number, but get {kind}"))
+    }
+}
+
+macro_rules! capture_number {
+    ($($method:ident: $ty:ty),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Captured, CaptureError> {
+                Ok(Captured::Number(value as f64))
+            }
+        )*
+    };
+}
+
+impl Serializer for Capture {
+    type Ok = Captured;
+    type Error = CaptureError;
+    type SerializeSeq = Impossible<Captured, CaptureError>;
+    type SerializeTuple = Impossible<Captured, CaptureError>;
+    type SerializeTupleStruct = Impossible<Captured, CaptureError>;
+    type SerializeTupleVariant = Impossible<Captured, CaptureError>;
+    type SerializeMap = Impossible<Captured, CaptureError>;
+    type SerializeStruct = Impossible<Captured, CaptureError>;
+    type SerializeStructVariant = Impossible<Captured, CaptureError>;
+
+    capture_number! {
+        serialize_i8: i8, serialize_i16: i16, serialize_i32: i32, serialize_i64: i64,
+        serialize_u8: u8, serialize_u16: u16, serialize_u32: u32, serialize_u64: u64,
+        serialize_f32: f32, serialize_f64: f64
+    }
+
+    fn serialize_str(self, text: &str) -> Result<Captured, CaptureError> {
+        Ok(Captured::Text(text.to_string()))
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<Captured, CaptureError> {
+        Err(Self::unsupported("a boolean"))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Captured, CaptureError> {
+        Ok(Captured::Text(value.to_string()))
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Captured, CaptureError> {
+        Err(Self::unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<Captured, CaptureError> {
+        Err(Self::unsupported("none"))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Captured, CaptureError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Captured, CaptureError> {
+        Err(Self::unsupported("a unit"))
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Captured, CaptureError> {
+        Err(Self::unsupported("a unit struct"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Captured, CaptureError> {
+        Ok(Captured::Text(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Captured, CaptureError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Captured, CaptureError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Self::unsupported("an enum variant"))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, CaptureError> {
+        Err(Self::unsupported("a sequence"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, CaptureError> {
+        Err(Self::unsupported("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, CaptureError> {
+        Err(Self::unsupported("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, CaptureError> {
+        Err(Self::unsupported("an enum variant"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, CaptureError> {
+        Err(Self::unsupported("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, CaptureError> {
+        Err(Self::unsupported("a struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, CaptureError> {
+        Err(Self::unsupported("an enum variant"))
+    }
+}
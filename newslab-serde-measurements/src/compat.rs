@@ -0,0 +1,77 @@
+//! Representation versions and the policy to emit older ones.
+//!
+//! The representations of the helper modules are frozen at
+//! [Representation::V1]. Every earlier representation keeps being
+//! accepted, which is checked against the fixture corpus in the
+//! `newslab-serde` crate. The policy is shared with the other helper
+//! crates through `newslab_serde_common::compat`.
+//!
+//! Human-readable formats accept all versions at once. Formats that
+//! are not human-readable, such as bincode, are not self-describing,
+//! so the version must be chosen by [with_representation] to read or
+//! write the data of older versions.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::{compat::{self, Representation}, length};
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Length;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyMount {
+//!     #[serde(with = "length")]
+//!     height: Length,
+//! }
+//!
+//! let mount = MyMount { height: Length::from_meters(1.5) };
+//! assert_eq!(bincode::serialize(&mount).unwrap(), 1.5f64.to_le_bytes());
+//!
+//! // V0 writes strings with units in all formats.
+//! let bytes = compat::with_representation(Representation::V0, || bincode::serialize(&mount))
+//!     .unwrap();
+//! assert_eq!(&bytes[8..], b"1.5m");
+//!
+//! let MyMount { height } = compat::with_representation(Representation::V0, || {
+//!     bincode::deserialize::<MyMount>(&bytes)
+//! })
+//! .unwrap();
+//! assert_eq!(height.as_meters(), 1.5);
+//! ```
+
+pub use newslab_serde_common::compat::{representation, with_representation, Representation};
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+/// Checks if quantities are stored as bare numbers, given whether
+/// the format is human-readable.
+pub(crate) fn stores_bare_number(is_human_readable: bool) -> bool {
+    !is_human_readable && representation() >= Representation::V1
+}
+
+/// Deserializes a bare number stored by [stores_bare_number], which
+/// fails if it is not finite like the numbers in strings.
+///
+/// ```rust
+/// # use newslab_serde_measurements::{angle, length};
+/// # use serde::{Serialize, Deserialize};
+/// # use measurements::{Angle, Length};
+/// #[derive(Serialize, Deserialize)]
+/// struct MyMount {
+///     #[serde(with = "length")]
+///     height: Length,
+///     #[serde(with = "angle::in_degrees")]
+///     yaw: Angle,
+/// }
+///
+/// for values in [(f64::NAN, 0.0), (0.0, f64::INFINITY), (f64::NEG_INFINITY, 0.0)] {
+///     let bytes = bincode::serialize(&values).unwrap();
+///     assert!(bincode::deserialize::<MyMount>(&bytes).is_err());
+/// }
+/// ```
+pub(crate) fn deserialize_bare_number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    if !value.is_finite() {
+        return Err(D::Error::custom(format!("{value} is not a finite number")));
+    }
+    Ok(value)
+}
@@ -0,0 +1,525 @@
+//! Schemas of CSV files, such as sensor logs, telling the name, the
+//! type and the unit of each column.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::csv_schema::{CsvSchema, CsvValue};
+//! # use serde::Deserialize;
+//! let json = r#"{
+//!     "columns": [
+//!         { "name": "stamp", "type": "float", "unit": "ms" },
+//!         { "name": "range", "type": "float", "unit": "mm" },
+//!         { "name": "yaw", "type": "float", "unit": "deg" },
+//!         { "name": "frame_id", "type": "string" },
+//!         { "name": "valid", "type": "bool" }
+//!     ]
+//! }"#;
+//! let schema: CsvSchema = serde_json::from_str(json).unwrap();
+//! schema.validate_header("stamp, range, yaw, frame_id, valid").unwrap();
+//! assert!(schema.validate_header("stamp,yaw,range,frame_id,valid").is_err());
+//!
+//! // Values with units are converted to SI units.
+//! let row = schema.parse_row("1500, 250, 90, lidar, true").unwrap();
+//! assert_eq!(row[0], CsvValue::Float(1.5));
+//! assert_eq!(row[1], CsvValue::Float(0.25));
+//! assert_eq!(row[3], CsvValue::String("lidar".to_string()));
+//!
+//! #[derive(Deserialize)]
+//! struct Sample {
+//!     stamp: f64,
+//!     range: Option<f64>,
+//!     frame_id: String,
+//!     valid: bool,
+//! }
+//!
+//! // Columns missing in the struct are skipped, and empty cells
+//! // become `None`.
+//! let sample: Sample = schema.deserialize_row("1500,,90,lidar,0").unwrap();
+//! assert_eq!(sample.stamp, 1.5);
+//! assert_eq!(sample.range, None);
+//! assert!(!sample.valid);
+//!
+//! // The cell is not a boolean.
+//! assert!(schema.parse_row("1500,250,90,lidar,yes").is_err());
+//!
+//! // Units are only allowed in float columns.
+//! let json = r#"{ "columns": [{ "name": "count", "type": "integer", "unit": "m" }] }"#;
+//! assert!(serde_json::from_str::<CsvSchema>(json).is_err());
+//! ```
+
+use crate::unit::{ParseUnitError, Unit};
+use measurements::{
+    Acceleration, Angle, Area, Frequency, Length, Mass, Measurement, Pressure, Speed, Temperature,
+    Volume,
+};
+use serde::{
+    de::{self, value::MapDeserializer, DeserializeOwned, Error as _, IntoDeserializer, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// The schema of a CSV file, which is an ordered list of columns.
+///
+/// The column names must be unique and not empty. The delimiter
+/// defaults to a comma. Cells are trimmed, and quoted cells are not
+/// supported.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "CsvSchemaUnchecked", into = "CsvSchemaUnchecked")]
+pub struct CsvSchema {
+    delimiter: char,
+    columns: Vec<CsvColumn>,
+}
+
+/// A column in a [CsvSchema].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: ColumnType,
+    /// The unit of the values, which is only allowed in float
+    /// columns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<ColumnUnit>,
+}
+
+/// The type of values in a [CsvColumn].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Float,
+    Integer,
+    String,
+    /// Booleans written as `true`/`false` or `1`/`0`.
+    Bool,
+}
+
+/// A typed cell parsed by [CsvSchema::parse_row].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CsvValue {
+    /// An empty cell in a non-string column.
+    Empty,
+    /// A float, which is in the SI unit if the column has a unit.
+    Float(f64),
+    Integer(i64),
+    String(String),
+    Bool(bool),
+}
+
+impl CsvSchema {
+    /// Creates a schema with comma-separated columns. It fails if a
+    /// name is empty or repeated, or if a unit is given to a
+    /// non-float column.
+    pub fn new(columns: Vec<CsvColumn>) -> Result<Self, CsvSchemaError> {
+        Self::with_delimiter(columns, ',')
+    }
+
+    /// Creates a schema with the columns separated by the delimiter.
+    pub fn with_delimiter(
+        columns: Vec<CsvColumn>,
+        delimiter: char,
+    ) -> Result<Self, CsvSchemaError> {
+        let invalid = |message: String| Err(CsvSchemaError::InvalidSchema(message));
+
+        if matches!(delimiter, '"' | '\n' | '\r') {
+            return invalid(format!("{delimiter:?} cannot be the delimiter"));
+        }
+        for (index, column) in columns.iter().enumerate() {
+            let CsvColumn { name, ty, unit } = column;
+            if name.is_empty() {
+                return invalid(format!("the name of column {index} is empty"));
+            }
+            if name.contains(delimiter) {
+                return invalid(format!(
+                    "the column name '{name}' contains the delimiter {delimiter:?}"
+                ));
+            }
+            if columns[..index].iter().any(|prev| prev.name == *name) {
+                return invalid(format!("the column name '{name}' is given more than once"));
+            }
+            if let (Some(unit), ColumnType::Integer | ColumnType::String | ColumnType::Bool) =
+                (unit, ty)
+            {
+                return invalid(format!(
+                    "the column '{name}' has the unit '{}', but only float columns can have units",
+                    unit.symbol()
+                ));
+            }
+        }
+
+        Ok(Self { delimiter, columns })
+    }
+
+    pub fn delimiter(&self) -> char {
+        self.delimiter
+    }
+
+    /// Gets the columns in order.
+    pub fn columns(&self) -> &[CsvColumn] {
+        &self.columns
+    }
+
+    pub fn column(&self, name: &str) -> Option<&CsvColumn> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Gets the index of the column.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name == name)
+    }
+
+    /// Checks that the header line lists the column names in order.
+    /// A leading byte order mark is ignored.
+    pub fn validate_header(&self, header: &str) -> Result<(), CsvSchemaError> {
+        let header = header.strip_prefix('\u{feff}').unwrap_or(header);
+        let names: Vec<_> = self.split(header).collect();
+        let mismatch = |message: String| Err(CsvSchemaError::InvalidHeader(message));
+
+        for (index, column) in self.columns.iter().enumerate() {
+            match names.get(index) {
+                Some(&name) if name == column.name => {}
+                Some(&name) => {
+                    return mismatch(format!(
+                        "column {index} is expected to be '{}', but get '{name}'",
+                        column.name
+                    ))
+                }
+                None => return mismatch(format!("the column '{}' is missing", column.name)),
+            }
+        }
+        if let Some(name) = names.get(self.columns.len()) {
+            return mismatch(format!("the column '{name}' is not in the schema"));
+        }
+        Ok(())
+    }
+
+    /// Parses a data line to typed values, one for each column.
+    pub fn parse_row(&self, line: &str) -> Result<Vec<CsvValue>, CsvSchemaError> {
+        let cells: Vec<_> = self.split(line).collect();
+        if cells.len() != self.columns.len() {
+            return Err(CsvSchemaError::InvalidRow(format!(
+                "expect {} cells, but get {}",
+                self.columns.len(),
+                cells.len()
+            )));
+        }
+
+        self.columns
+            .iter()
+            .zip(cells)
+            .map(|(column, cell)| column.parse(cell))
+            .collect()
+    }
+
+    /// Deserializes a data line to a type with fields named after
+    /// the columns, such as a struct. Empty cells can be
+    /// deserialized to `None`.
+    pub fn deserialize_row<T>(&self, line: &str) -> Result<T, CsvSchemaError>
+    where
+        T: DeserializeOwned,
+    {
+        let values = self.parse_row(line)?;
+        let entries = self
+            .columns
+            .iter()
+            .zip(values)
+            .map(|(column, value)| (column.name.as_str(), ValueDeserializer(value)));
+        T::deserialize(MapDeserializer::new(entries))
+    }
+
+    fn split<'a>(&self, line: &'a str) -> impl Iterator<Item = &'a str> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        line.split(self.delimiter).map(str::trim)
+    }
+}
+
+impl CsvColumn {
+    fn parse(&self, cell: &str) -> Result<CsvValue, CsvSchemaError> {
+        let invalid = || {
+            CsvSchemaError::InvalidRow(format!(
+                "'{cell}' in the column '{}' is not a valid {}",
+                self.name,
+                self.ty.as_str()
+            ))
+        };
+
+        let value = match self.ty {
+            ColumnType::String => CsvValue::String(cell.to_string()),
+            _ if cell.is_empty() => CsvValue::Empty,
+            ColumnType::Float => {
+                let value: f64 = cell.parse().map_err(|_| invalid())?;
+                match &self.unit {
+                    Some(unit) => CsvValue::Float(unit.to_base(value)),
+                    None => CsvValue::Float(value),
+                }
+            }
+            ColumnType::Integer => CsvValue::Integer(cell.parse().map_err(|_| invalid())?),
+            ColumnType::Bool => match cell {
+                "1" => CsvValue::Bool(true),
+                "0" => CsvValue::Bool(false),
+                _ if cell.eq_ignore_ascii_case("true") => CsvValue::Bool(true),
+                _ if cell.eq_ignore_ascii_case("false") => CsvValue::Bool(false),
+                _ => return Err(invalid()),
+            },
+        };
+        Ok(value)
+    }
+}
+
+impl ColumnType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColumnType::Float => "float",
+            ColumnType::Integer => "integer",
+            ColumnType::String => "string",
+            ColumnType::Bool => "bool",
+        }
+    }
+}
+
+macro_rules! define_column_unit {
+    ($($variant:ident: $ty:ty => $module:ident,)*) => {
+        /// The unit of a float [CsvColumn], which is written as the
+        /// unit symbol like `"mm"`. The quantity is told by the
+        /// symbol, and ambiguous symbols are rejected.
+        ///
+        /// ```rust
+        /// # use newslab_serde_measurements::csv_schema::ColumnUnit;
+        /// let unit: ColumnUnit = "km/h".parse().unwrap();
+        /// assert_eq!(unit.quantity(), "speed");
+        /// assert!((unit.to_base(36.0) - 10.0).abs() < 1e-12);
+        ///
+        /// let unit: ColumnUnit = "µs".parse().unwrap();
+        /// assert_eq!(unit.quantity(), "duration");
+        /// assert_eq!(unit.to_base(2.0), 2e-6);
+        ///
+        /// assert!("parsec".parse::<ColumnUnit>().is_err());
+        /// ```
+        #[derive(Clone, Copy)]
+        pub enum ColumnUnit {
+            $($variant(&'static Unit<$ty>),)*
+        }
+
+        impl ColumnUnit {
+            /// Gets the name of the quantity, like `"length"`.
+            pub fn quantity(&self) -> &'static str {
+                match self {
+                    $(ColumnUnit::$variant(_) => crate::$module::PARSER.quantity(),)*
+                }
+            }
+
+            /// Gets the canonical symbol of the unit.
+            pub fn symbol(&self) -> &'static str {
+                match self {
+                    $(ColumnUnit::$variant(unit) => unit.symbol(),)*
+                }
+            }
+
+            /// Finds the units having the symbol, in all quantities.
+            fn find_all(symbol: &str) -> Vec<Self> {
+                let symbol = symbol.trim();
+                [$(crate::$module::PARSER.find_unit(symbol).map(ColumnUnit::$variant),)*]
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            }
+        }
+
+        impl PartialEq for ColumnUnit {
+            fn eq(&self, other: &Self) -> bool {
+                match (self, other) {
+                    $((ColumnUnit::$variant(lhs), ColumnUnit::$variant(rhs)) => {
+                        std::ptr::eq(*lhs, *rhs)
+                    })*
+                    _ => false,
+                }
+            }
+        }
+    };
+}
+
+define_column_unit! {
+    Acceleration: Acceleration => acceleration,
+    Angle: Angle => angle,
+    Area: Area => area,
+    Duration: f64 => duration,
+    Frequency: Frequency => frequency,
+    Length: Length => length,
+    Mass: Mass => mass,
+    Pressure: Pressure => pressure,
+    Speed: Speed => speed,
+    Temperature: Temperature => temperature,
+    Volume: Volume => volume,
+}
+
+impl ColumnUnit {
+    /// Converts a value in the unit to the SI unit of the quantity,
+    /// such as meters, radians, seconds or kelvins.
+    pub fn to_base(&self, value: f64) -> f64 {
+        fn base<Q: Measurement>(unit: &Unit<Q>, value: f64) -> f64 {
+            (unit.from_value)(value).as_base_units()
+        }
+
+        match self {
+            ColumnUnit::Acceleration(unit) => base(unit, value),
+            ColumnUnit::Angle(unit) => base(unit, value),
+            ColumnUnit::Area(unit) => base(unit, value),
+            // The duration parser works in nanoseconds.
+            ColumnUnit::Duration(unit) => (unit.from_value)(value) / 1e9,
+            ColumnUnit::Frequency(unit) => base(unit, value),
+            ColumnUnit::Length(unit) => base(unit, value),
+            ColumnUnit::Mass(unit) => base(unit, value),
+            ColumnUnit::Pressure(unit) => base(unit, value),
+            ColumnUnit::Speed(unit) => base(unit, value),
+            ColumnUnit::Temperature(unit) => base(unit, value),
+            ColumnUnit::Volume(unit) => base(unit, value),
+        }
+    }
+}
+
+impl fmt::Debug for ColumnUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ColumnUnit({}, {:?})", self.quantity(), self.symbol())
+    }
+}
+
+impl FromStr for ColumnUnit {
+    type Err = ParseUnitError;
+
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        let mut units = Self::find_all(symbol);
+        match units.len() {
+            0 => Err(ParseUnitError::UnknownUnit {
+                quantity: "unit",
+                text: symbol.to_string(),
+                example: "mm",
+            }),
+            1 => Ok(units.remove(0)),
+            _ => Err(ParseUnitError::AmbiguousUnit {
+                text: symbol.to_string(),
+                candidates: units.iter().map(ColumnUnit::quantity).collect(),
+            }),
+        }
+    }
+}
+
+impl Serialize for ColumnUnit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.symbol())
+    }
+}
+
+impl<'de> Deserialize<'de> for ColumnUnit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let symbol = String::deserialize(deserializer)?;
+        symbol.parse().map_err(D::Error::custom)
+    }
+}
+
+impl TryFrom<CsvSchemaUnchecked> for CsvSchema {
+    type Error = CsvSchemaError;
+
+    fn try_from(from: CsvSchemaUnchecked) -> Result<Self, Self::Error> {
+        let CsvSchemaUnchecked { delimiter, columns } = from;
+        Self::with_delimiter(columns, delimiter)
+    }
+}
+
+impl From<CsvSchema> for CsvSchemaUnchecked {
+    fn from(from: CsvSchema) -> Self {
+        let CsvSchema { delimiter, columns } = from;
+        Self { delimiter, columns }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CsvSchemaUnchecked {
+    #[serde(default = "default_delimiter")]
+    delimiter: char,
+    columns: Vec<CsvColumn>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+/// Feeds a [CsvValue] to a visitor in [CsvSchema::deserialize_row].
+struct ValueDeserializer(CsvValue);
+
+impl<'de> IntoDeserializer<'de, CsvSchemaError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = CsvSchemaError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            CsvValue::Empty => visitor.visit_unit(),
+            CsvValue::Float(value) => visitor.visit_f64(value),
+            CsvValue::Integer(value) => visitor.visit_i64(value),
+            CsvValue::String(value) => visitor.visit_string(value),
+            CsvValue::Bool(value) => visitor.visit_bool(value),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            CsvValue::Empty => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// The error returned by [CsvSchema] methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvSchemaError {
+    InvalidSchema(String),
+    InvalidHeader(String),
+    InvalidRow(String),
+}
+
+impl Display for CsvSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvSchemaError::InvalidSchema(message) => write!(f, "Invalid CSV schema: {message}"),
+            CsvSchemaError::InvalidHeader(message) => write!(f, "Invalid CSV header: {message}"),
+            CsvSchemaError::InvalidRow(message) => write!(f, "Invalid CSV row: {message}"),
+        }
+    }
+}
+
+impl StdError for CsvSchemaError {}
+
+impl de::Error for CsvSchemaError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        CsvSchemaError::InvalidRow(msg.to_string())
+    }
+}
@@ -0,0 +1,341 @@
+//! Serialization helper to en/decode a [Duration] with units.
+//!
+//! Accepted units are `ns`, `µs` (or `us`), `ms`, `s`, `min` and `h`.
+//! Durations are serialized exactly in the unit that fits the
+//! magnitude best. Negative durations are rejected.
+//!
+//! Formats that are not human-readable, such as bincode, store the
+//! [Duration] as it is instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::duration;
+//! # use serde::{Serialize, Deserialize};
+//! # use std::time::Duration;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyDriver {
+//!     #[serde(with = "duration")]
+//!     timeout: Duration,
+//!     #[serde(with = "duration")]
+//!     interval: Duration,
+//!     #[serde(with = "duration")]
+//!     settle: Duration,
+//!     #[serde(with = "duration")]
+//!     log_rotation: Duration,
+//! }
+//!
+//! let json = r#"{
+//!     "timeout": "100ms",
+//!     "interval": "30us",
+//!     "settle": "2.5s",
+//!     "log_rotation": "1min"
+//! }"#;
+//! let driver: MyDriver = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(driver.timeout, Duration::from_millis(100));
+//! assert_eq!(driver.interval, Duration::from_micros(30));
+//! assert_eq!(driver.settle, Duration::from_millis(2500));
+//! assert_eq!(driver.log_rotation, Duration::from_secs(60));
+//!
+//! let json = serde_json::to_string(&driver).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"timeout":"100ms","interval":"30µs","settle":"2.5s","log_rotation":"60s"}"#
+//! );
+//!
+//! // Durations must not be negative.
+//! let json = r#"{ "timeout": "-1s", "interval": "1s", "settle": "1s", "log_rotation": "1s" }"#;
+//! assert!(serde_json::from_str::<MyDriver>(json).is_err());
+//! ```
+//!
+//! Every duration round-trips without losing nanoseconds.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::{compat::{self, Representation}, duration};
+//! # use serde::{Serialize, Deserialize};
+//! # use std::time::Duration;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct MyTimeout {
+//!     #[serde(with = "duration")]
+//!     timeout: Duration,
+//! }
+//!
+//! let days = MyTimeout { timeout: Duration::from_secs(200 * 86400) + Duration::from_nanos(1) };
+//! let json = serde_json::to_string(&days).unwrap();
+//! assert_eq!(json, r#"{"timeout":"17280000.000000001s"}"#);
+//! assert_eq!(serde_json::from_str::<MyTimeout>(&json).unwrap(), days);
+//!
+//! let hour = MyTimeout { timeout: Duration::from_secs(3600) };
+//! assert_eq!(serde_json::to_string(&hour).unwrap(), r#"{"timeout":"1h"}"#);
+//! let hours = MyTimeout { timeout: Duration::from_secs(5400) };
+//! assert_eq!(serde_json::to_string(&hours).unwrap(), r#"{"timeout":"1.5h"}"#);
+//!
+//! let max = MyTimeout { timeout: Duration::MAX };
+//! let json = serde_json::to_string(&max).unwrap();
+//! assert_eq!(json, r#"{"timeout":"18446744073709551615.999999999s"}"#);
+//! assert_eq!(serde_json::from_str::<MyTimeout>(&json).unwrap(), max);
+//! let bytes = bincode::serialize(&max).unwrap();
+//! assert_eq!(bincode::deserialize::<MyTimeout>(&bytes).unwrap(), max);
+//!
+//! // A nanosecond more is out of range.
+//! let json = r#"{ "timeout": "18446744073709551616s" }"#;
+//! assert!(serde_json::from_str::<MyTimeout>(json).is_err());
+//!
+//! // The 1.0 representation stores floating seconds, which cannot
+//! // hold the largest durations.
+//! assert!(compat::with_representation(Representation::V1, || bincode::serialize(&max)).is_err());
+//! ```
+
+use crate::{
+    compat::Representation,
+    unit::{ParseUnitError, Unit, UnitParser},
+};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+/// The parser works on durations in nanoseconds, so that negative
+/// and overflowing values can be reported instead of panicking.
+pub(crate) static PARSER: UnitParser<f64> = UnitParser::new(
+    "duration",
+    "100ms",
+    &[
+        Unit::new(&["ns"], |val| val, |&nanos| nanos),
+        Unit::new(&["µs", "μs", "us"], |val| val * 1e3, |&nanos| nanos / 1e3),
+        Unit::new(&["ms"], |val| val * 1e6, |&nanos| nanos / 1e6),
+        Unit::new(&["s"], |val| val * 1e9, |&nanos| nanos / 1e9),
+        Unit::new(&["min"], |val| val * 60e9, |&nanos| nanos / 60e9),
+        Unit::new(&["h"], |val| val * 3600e9, |&nanos| nanos / 3600e9),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["ns", "µs", "ms", "s", "min", "h"];
+
+/// The nanoseconds of [Duration::MAX].
+const MAX_NANOS: u128 = u64::MAX as u128 * 1_000_000_000 + 999_999_999;
+
+/// The fractional digits of a number that are kept in parsing, which
+/// is far below a nanosecond in every unit.
+const FRACTION_DIGITS: usize = 18;
+
+/// Parses a duration string like `"2.5s"`. The number is converted
+/// to nanoseconds exactly, rounding half up.
+pub(crate) fn parse(text: &str) -> Result<Duration, String> {
+    let (number, unit) = PARSER.split_unit(text).map_err(|err| err.to_string())?;
+    let nanos = to_nanos(number.trim(), unit_nanos(unit), text)?;
+    Ok(Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    ))
+}
+
+/// Converts a decimal number in the unit to nanoseconds. The `text`
+/// is the original value shown in error messages.
+fn to_nanos(number: &str, unit_nanos: u128, text: &str) -> Result<u128, String> {
+    let invalid = || {
+        ParseUnitError::InvalidNumber {
+            text: number.to_string(),
+        }
+        .to_string()
+    };
+    let out_of_range = || format!("duration '{}' is out of range", text.trim());
+
+    let (is_negative, unsigned) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().map_err(|_| invalid())?),
+        None => (unsigned, 0),
+    };
+    let (int_digits, frac_digits) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let is_digits = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+    if int_digits.len() + frac_digits.len() == 0
+        || !is_digits(int_digits)
+        || !is_digits(frac_digits)
+    {
+        return Err(invalid());
+    }
+
+    // The number is `0.digits * 10^point` without leading zeros.
+    let all_digits = format!("{int_digits}{frac_digits}");
+    let digits = all_digits.trim_start_matches('0');
+    let point = int_digits.len() as i64 + exponent - (all_digits.len() - digits.len()) as i64;
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    if point > 39 {
+        return Err(out_of_range());
+    }
+
+    let (int_part, frac_part) = if point <= 0 {
+        let zeros = "0".repeat((-point).min(FRACTION_DIGITS as i64) as usize);
+        (String::new(), zeros + digits)
+    } else if point as usize >= digits.len() {
+        (
+            digits.to_string() + &"0".repeat(point as usize - digits.len()),
+            String::new(),
+        )
+    } else {
+        let (int_part, frac_part) = digits.split_at(point as usize);
+        (int_part.to_string(), frac_part.to_string())
+    };
+
+    // Dropping the digits beyond the kept ones never changes the
+    // rounding of the nanoseconds.
+    let frac_part: String = frac_part
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(FRACTION_DIGITS)
+        .collect();
+
+    let int_part: u128 = match int_part.as_str() {
+        "" => 0,
+        int_part => int_part.parse().map_err(|_| out_of_range())?,
+    };
+    let frac_part: u128 = frac_part.parse().map_err(|_| invalid())?;
+    let scale = 10u128.pow(FRACTION_DIGITS as u32);
+    let nanos = int_part
+        .checked_mul(unit_nanos)
+        .and_then(|nanos| nanos.checked_add((frac_part * unit_nanos + scale / 2) / scale))
+        .filter(|&nanos| nanos <= MAX_NANOS)
+        .ok_or_else(out_of_range)?;
+
+    if is_negative && nanos != 0 {
+        return Err(format!("duration '{}' must not be negative", text.trim()));
+    }
+    Ok(nanos)
+}
+
+/// Gets the nanoseconds in one of the unit, which are integers for
+/// every duration unit.
+fn unit_nanos(unit: &Unit<f64>) -> u128 {
+    (unit.from_value)(1.0) as u128
+}
+
+/// Formats a duration like `"2.5s"` exactly. Among the units from the
+/// [LADDER] where the duration is not less than one and its number
+/// has finite decimal digits, the one writing the shortest text is
+/// chosen, preferring larger units. One hour is written as `"1h"`
+/// and 90 seconds as `"90s"`.
+pub(crate) fn format(duration: &Duration) -> String {
+    format_nanos(duration.as_nanos())
+}
+
+/// Formats the nanoseconds of a duration in the way of [format].
+pub(crate) fn format_nanos(nanos: u128) -> String {
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    LADDER
+        .iter()
+        .rev()
+        .map(|symbol| PARSER.find_unit(symbol).unwrap())
+        .filter(|unit| nanos >= unit_nanos(unit))
+        .filter_map(|unit| Some(decimal(nanos, unit_nanos(unit))? + unit.symbol()))
+        .min_by_key(|text| text.chars().count())
+        .unwrap_or_else(|| format!("{nanos}ns"))
+}
+
+/// Formats a duration exactly in the unit with the symbol. Units
+/// where the number has infinite decimal digits fall back to the
+/// nearest floating number.
+///
+/// # Panics
+/// The function panics if the symbol is unknown.
+pub(crate) fn format_in(duration: &Duration, symbol: &str) -> Result<String, ParseUnitError> {
+    let unit = PARSER
+        .find_unit(symbol)
+        .unwrap_or_else(|| panic!("unknown duration unit '{symbol}'"));
+    let nanos = duration.as_nanos();
+    match decimal(nanos, unit_nanos(unit)) {
+        Some(number) => Ok(number + unit.symbol()),
+        None => PARSER.format(&(nanos as f64), unit),
+    }
+}
+
+/// Writes `nanos / unit_nanos` as a decimal number, or returns `None`
+/// if its decimal digits do not terminate.
+fn decimal(nanos: u128, unit_nanos: u128) -> Option<String> {
+    let mut text = (nanos / unit_nanos).to_string();
+    let mut rem = nanos % unit_nanos;
+    if rem == 0 {
+        return Some(text);
+    }
+
+    // The units have at most 13 factors of 2 or 5, so the digits of
+    // every terminating number fit in 16.
+    text.push('.');
+    for _ in 0..16 {
+        rem *= 10;
+        text.push(char::from(b'0' + (rem / unit_nanos) as u8));
+        rem %= unit_nanos;
+        if rem == 0 {
+            return Some(text);
+        }
+    }
+    None
+}
+
+/// Converts the seconds stored by [Representation::V1] to a duration.
+fn from_secs_f64(secs: f64) -> Result<Duration, String> {
+    let nanos = (secs * 1e9).round();
+    if nanos < 0.0 {
+        return Err(format!("duration '{secs}s' must not be negative"));
+    }
+    if !nanos.is_finite() || nanos >= MAX_NANOS as f64 {
+        return Err(format!("duration '{secs}s' is out of range"));
+    }
+    let nanos = nanos as u128;
+    Ok(Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    ))
+}
+
+pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        if crate::compat::representation() >= Representation::V2 {
+            return duration.serialize(serializer);
+        }
+        let secs = duration.as_secs_f64();
+        from_secs_f64(secs).map_err(serde::ser::Error::custom)?;
+        return secs.serialize(serializer);
+    }
+    format(duration).serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        if crate::compat::representation() >= Representation::V2 {
+            return Duration::deserialize(deserializer);
+        }
+        let secs = crate::compat::deserialize_bare_number(deserializer)?;
+        return from_secs_f64(secs).map_err(D::Error::custom);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "duration::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.non_negative_json_schema()
+}
+
+unit_combinators!(Duration);
+
+pinned_units! {
+    Duration => format_in;
+    in_nanoseconds => "ns",
+    in_microseconds => "µs",
+    in_milliseconds => "ms",
+    in_seconds => "s",
+}
@@ -0,0 +1,580 @@
+use crate::{angle, unit::ParseUnitError};
+use measurements::Angle;
+use nalgebra::{Rotation3, UnitQuaternion, Vector3};
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// The roll, pitch and yaw angles about the x, y and z axes, along
+/// with the order the rotations are applied.
+///
+/// The `order` field is optional in the serialized form and defaults
+/// to [RotationOrder::ZyxIntrinsic], which is the convention of ROS
+/// and nalgebra.
+///
+/// ```rust
+/// # use newslab_serde_measurements::{EulerAngles, RotationOrder};
+/// # use nalgebra::UnitQuaternion;
+/// let json = r#"{ "roll": "0.1rad", "pitch": "0.2rad", "yaw": "0.3rad" }"#;
+/// let angles: EulerAngles = serde_json::from_str(json).unwrap();
+/// assert_eq!(angles.order, RotationOrder::ZyxIntrinsic);
+///
+/// let expect = UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3);
+/// assert!(angles.to_unit_quaternion().angle_to(&expect) < 1e-12);
+///
+/// // The same rotation in another convention.
+/// let other = EulerAngles::from_unit_quaternion(&expect, RotationOrder::XyzIntrinsic);
+/// assert!(other.to_unit_quaternion().angle_to(&expect) < 1e-12);
+///
+/// let json = serde_json::to_string(&other).unwrap();
+/// assert!(json.ends_with(r#""order":"xyz_intrinsic"}"#));
+/// ```
+///
+/// The angles can also be written compactly as `"roll pitch yaw"`,
+/// optionally followed by the order, which is the form of [Display]
+/// and [FromStr]. Human-readable formats accept it in place of the
+/// map.
+///
+/// ```rust
+/// # use newslab_serde_measurements::{EulerAngles, RotationOrder};
+/// let angles: EulerAngles = "10deg 0deg 90deg".parse().unwrap();
+/// assert_eq!(angles, EulerAngles::from_degrees(10.0, 0.0, 90.0).unwrap());
+/// assert_eq!(angles.to_string(), "10deg 0deg 90deg");
+///
+/// let angles: EulerAngles = serde_json::from_str(r#""0.1rad 0deg 0deg xyz_extrinsic""#).unwrap();
+/// assert_eq!(angles.order, RotationOrder::XyzExtrinsic);
+/// assert_eq!(angles.to_string(), "5.729577951308232deg 0deg 0deg xyz_extrinsic");
+///
+/// assert!("10deg 0deg".parse::<EulerAngles>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EulerAngles {
+    #[serde(with = "angle")]
+    pub roll: Angle,
+    #[serde(with = "angle")]
+    pub pitch: Angle,
+    #[serde(with = "angle")]
+    pub yaw: Angle,
+    #[serde(default, skip_serializing_if = "RotationOrder::is_default")]
+    pub order: RotationOrder,
+}
+
+impl Display for EulerAngles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            roll,
+            pitch,
+            yaw,
+            order,
+        } = self;
+        let unit = &angle::PARSER.units()[0];
+        let [roll, pitch, yaw] =
+            [roll, pitch, yaw].map(|angle| angle::PARSER.format_lossy(angle, unit));
+        write!(f, "{roll} {pitch} {yaw}")?;
+        if !order.is_default() {
+            write!(f, " {order}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for EulerAngles {
+    type Err = ParseEulerAnglesError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<_> = text.split_whitespace().collect();
+        let (angles, order) = match tokens.as_slice() {
+            [roll, pitch, yaw] => ([roll, pitch, yaw], RotationOrder::default()),
+            [roll, pitch, yaw, order] => {
+                let order = order.parse()?;
+                ([roll, pitch, yaw], order)
+            }
+            _ => return Err(ParseEulerAnglesError::InvalidFormat(text.to_string())),
+        };
+        let [roll, pitch, yaw] = angles.map(|angle| angle::PARSER.parse(angle));
+
+        Ok(Self {
+            roll: roll?,
+            pitch: pitch?,
+            yaw: yaw?,
+            order,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for EulerAngles {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "EulerAngles")]
+        struct Fields {
+            #[serde(with = "angle")]
+            roll: Angle,
+            #[serde(with = "angle")]
+            pitch: Angle,
+            #[serde(with = "angle")]
+            yaw: Angle,
+            #[serde(default)]
+            order: RotationOrder,
+        }
+
+        /// Accepts the compact string or the map of fields.
+        struct EulerAnglesVisitor;
+
+        impl<'de> Visitor<'de> for EulerAnglesVisitor {
+            type Value = Fields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of angles or a string like '10deg 0deg 90deg'")
+            }
+
+            fn visit_str<E>(self, text: &str) -> Result<Fields, E>
+            where
+                E: de::Error,
+            {
+                let EulerAngles {
+                    roll,
+                    pitch,
+                    yaw,
+                    order,
+                } = text.parse().map_err(E::custom)?;
+                Ok(Fields {
+                    roll,
+                    pitch,
+                    yaw,
+                    order,
+                })
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Fields, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                Fields::deserialize(MapAccessDeserializer::new(map))
+            }
+        }
+
+        let fields = if deserializer.is_human_readable() {
+            deserializer.deserialize_any(EulerAnglesVisitor)?
+        } else {
+            Fields::deserialize(deserializer)?
+        };
+        let Fields {
+            roll,
+            pitch,
+            yaw,
+            order,
+        } = fields;
+        Ok(Self {
+            roll,
+            pitch,
+            yaw,
+            order,
+        })
+    }
+}
+
+/// The error returned by the [FromStr] implementations of
+/// [EulerAngles] and [RotationOrder].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseEulerAnglesError {
+    InvalidFormat(String),
+    InvalidAngle(ParseUnitError),
+    UnknownOrder(String),
+}
+
+impl From<ParseUnitError> for ParseEulerAnglesError {
+    fn from(error: ParseUnitError) -> Self {
+        Self::InvalidAngle(error)
+    }
+}
+
+impl Display for ParseEulerAnglesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseEulerAnglesError::InvalidFormat(text) => write!(
+                f,
+                "Unable to parse '{text}' as Euler angles. \
+                 It must be roll, pitch and yaw angles optionally followed by the order, \
+                 for example, '10deg 0deg 90deg'."
+            ),
+            ParseEulerAnglesError::InvalidAngle(error) => error.fmt(f),
+            ParseEulerAnglesError::UnknownOrder(text) => write!(
+                f,
+                "'{text}' is not a rotation order. It must be one of {}.",
+                RotationOrder::ALL.map(|order| order.as_str()).join(", ")
+            ),
+        }
+    }
+}
+
+impl StdError for ParseEulerAnglesError {}
+
+/// The order of the rotations about the axes.
+///
+/// Intrinsic orders rotate about the axes of the rotating frame,
+/// while extrinsic orders rotate about the axes of the fixed frame.
+/// For example, intrinsic z-y'-x'' is the same as extrinsic x-y-z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RotationOrder {
+    XyzIntrinsic,
+    XzyIntrinsic,
+    YxzIntrinsic,
+    YzxIntrinsic,
+    ZxyIntrinsic,
+    #[default]
+    ZyxIntrinsic,
+    XyzExtrinsic,
+    XzyExtrinsic,
+    YxzExtrinsic,
+    YzxExtrinsic,
+    ZxyExtrinsic,
+    ZyxExtrinsic,
+}
+
+impl RotationOrder {
+    /// All the orders.
+    pub const ALL: [Self; 12] = [
+        Self::XyzIntrinsic,
+        Self::XzyIntrinsic,
+        Self::YxzIntrinsic,
+        Self::YzxIntrinsic,
+        Self::ZxyIntrinsic,
+        Self::ZyxIntrinsic,
+        Self::XyzExtrinsic,
+        Self::XzyExtrinsic,
+        Self::YxzExtrinsic,
+        Self::YzxExtrinsic,
+        Self::ZxyExtrinsic,
+        Self::ZyxExtrinsic,
+    ];
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Gets the name in the serialized form, like `"zyx_intrinsic"`.
+    pub fn as_str(&self) -> &'static str {
+        use RotationOrder as R;
+
+        match self {
+            R::XyzIntrinsic => "xyz_intrinsic",
+            R::XzyIntrinsic => "xzy_intrinsic",
+            R::YxzIntrinsic => "yxz_intrinsic",
+            R::YzxIntrinsic => "yzx_intrinsic",
+            R::ZxyIntrinsic => "zxy_intrinsic",
+            R::ZyxIntrinsic => "zyx_intrinsic",
+            R::XyzExtrinsic => "xyz_extrinsic",
+            R::XzyExtrinsic => "xzy_extrinsic",
+            R::YxzExtrinsic => "yxz_extrinsic",
+            R::YzxExtrinsic => "yzx_extrinsic",
+            R::ZxyExtrinsic => "zxy_extrinsic",
+            R::ZyxExtrinsic => "zyx_extrinsic",
+        }
+    }
+
+    /// Gets the axis indices of the equivalent intrinsic order.
+    fn intrinsic_axes(&self) -> [usize; 3] {
+        use RotationOrder as R;
+
+        match self {
+            R::XyzIntrinsic | R::ZyxExtrinsic => [0, 1, 2],
+            R::XzyIntrinsic | R::YzxExtrinsic => [0, 2, 1],
+            R::YxzIntrinsic | R::ZxyExtrinsic => [1, 0, 2],
+            R::YzxIntrinsic | R::XzyExtrinsic => [1, 2, 0],
+            R::ZxyIntrinsic | R::YxzExtrinsic => [2, 0, 1],
+            R::ZyxIntrinsic | R::XyzExtrinsic => [2, 1, 0],
+        }
+    }
+}
+
+impl Display for RotationOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RotationOrder {
+    type Err = ParseEulerAnglesError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.as_str() == text)
+            .ok_or_else(|| ParseEulerAnglesError::UnknownOrder(text.to_string()))
+    }
+}
+
+/// Generates finite angles in any rotation order, which always
+/// serialize to valid documents.
+///
+/// ```rust
+/// # use arbitrary::{Arbitrary, Unstructured};
+/// # use newslab_serde_measurements::EulerAngles;
+/// let bytes: Vec<u8> = (0..64u8).map(|ix| ix.wrapping_mul(151)).collect();
+/// let angles = EulerAngles::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+///
+/// let json = serde_json::to_string(&angles).unwrap();
+/// let other: EulerAngles = serde_json::from_str(&json).unwrap();
+/// assert_eq!(other.order, angles.order);
+/// ```
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for EulerAngles {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {
+            roll: Angle::from_radians(crate::arbitrary_finite(u)?),
+            pitch: Angle::from_radians(crate::arbitrary_finite(u)?),
+            yaw: Angle::from_radians(crate::arbitrary_finite(u)?),
+            order: u.arbitrary()?,
+        })
+    }
+}
+
+/// Accepts the map of angles, or the compact string of angles
+/// optionally followed by the order.
+///
+/// ```rust
+/// # use newslab_serde_measurements::EulerAngles;
+/// # use schemars::schema_for;
+/// let schema = serde_json::to_value(schema_for!(EulerAngles)).unwrap();
+/// let object = &schema["anyOf"][0];
+/// assert_eq!(object["required"], serde_json::json!(["pitch", "roll", "yaw"]));
+/// assert_eq!(object["properties"]["order"]["$ref"], "#/definitions/RotationOrder");
+/// assert!(schema["anyOf"][1]["pattern"].as_str().unwrap().contains("zyx_intrinsic"));
+/// ```
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for EulerAngles {
+    fn schema_name() -> String {
+        "EulerAngles".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{InstanceType, ObjectValidation, SchemaObject, StringValidation};
+
+        let properties = [
+            ("roll", angle::json_schema(gen)),
+            ("pitch", angle::json_schema(gen)),
+            ("yaw", angle::json_schema(gen)),
+            ("order", gen.subschema_for::<RotationOrder>()),
+        ];
+        let object = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                properties: properties
+                    .into_iter()
+                    .map(|(name, schema)| (name.to_string(), schema))
+                    .collect(),
+                required: ["roll", "pitch", "yaw"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        // The angles are separated by whitespace, which is not
+        // allowed within each angle.
+        let angle = format!(
+            r"[-+]?{}{}",
+            crate::unit::NUMBER_PATTERN,
+            angle::PARSER.symbol_pattern()
+        );
+        let orders = RotationOrder::ALL.map(|order| order.as_str()).join("|");
+        let text = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(format!(
+                    r"^\s*{angle}\s+{angle}\s+{angle}(\s+({orders}))?\s*$"
+                )),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        crate::combinators::any_of_schema(vec![object.into(), text.into()])
+    }
+}
+
+impl EulerAngles {
+    /// Creates angles in degrees, which returns `None` if a number
+    /// cannot be cast to `f64` or overflows to infinity.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::EulerAngles;
+    /// let angles = EulerAngles::from_degrees(10, -20, 90).unwrap();
+    /// assert_eq!(angles.to_degrees::<i32>(), Some([10, -20, 90]));
+    ///
+    /// // The largest integers are rounded, but they do not overflow.
+    /// let angles = EulerAngles::from_degrees(u128::MAX, 0, 0).unwrap();
+    /// assert_eq!(angles.roll.as_degrees(), u128::MAX as f64);
+    /// ```
+    pub fn from_degrees<T>(roll: T, pitch: T, yaw: T) -> Option<Self>
+    where
+        T: num::NumCast,
+    {
+        Some(Self::from_radians_f64([
+            Angle::from_degrees(cast(roll)?).as_radians(),
+            Angle::from_degrees(cast(pitch)?).as_radians(),
+            Angle::from_degrees(cast(yaw)?).as_radians(),
+        ]))
+    }
+
+    /// Creates angles in radians, which returns `None` if a number
+    /// cannot be cast to `f64` or overflows to infinity.
+    pub fn from_radians<T>(roll: T, pitch: T, yaw: T) -> Option<Self>
+    where
+        T: num::NumCast,
+    {
+        Some(Self::from_radians_f64([
+            cast(roll)?,
+            cast(pitch)?,
+            cast(yaw)?,
+        ]))
+    }
+
+    /// Gets the (roll, pitch, yaw) angles in degrees, which returns
+    /// `None` if an angle does not fit in `T`, including finite angles
+    /// that would overflow to infinity.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::EulerAngles;
+    /// let angles = EulerAngles::from_degrees(200.0, 0.0, -1e300).unwrap();
+    /// assert_eq!(angles.to_degrees::<u8>(), None);
+    /// assert_eq!(angles.to_degrees::<f32>(), None);
+    /// assert_eq!(angles.to_degrees::<i64>(), None);
+    ///
+    /// let angles = EulerAngles::from_degrees(200.0, 0.0, -90.0).unwrap();
+    /// assert_eq!(angles.to_degrees::<i16>(), Some([200, 0, -90]));
+    /// assert_eq!(angles.to_degrees::<i8>(), None);
+    /// ```
+    pub fn to_degrees<T>(&self) -> Option<[T; 3]>
+    where
+        T: num::NumCast,
+    {
+        let Self {
+            roll, pitch, yaw, ..
+        } = *self;
+        Some([
+            cast(roll.as_degrees())?,
+            cast(pitch.as_degrees())?,
+            cast(yaw.as_degrees())?,
+        ])
+    }
+
+    /// Gets the (roll, pitch, yaw) angles in radians, which returns
+    /// `None` if an angle does not fit in `T` like
+    /// [to_degrees](Self::to_degrees).
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::EulerAngles;
+    /// let angles = EulerAngles::from_radians(0.5, 1e300, 0.0).unwrap();
+    /// assert_eq!(angles.to_radians::<f64>(), Some([0.5, 1e300, 0.0]));
+    /// assert_eq!(angles.to_radians::<f32>(), None);
+    /// ```
+    pub fn to_radians<T>(&self) -> Option<[T; 3]>
+    where
+        T: num::NumCast,
+    {
+        let [roll, pitch, yaw] = self.radians();
+        Some([cast(roll)?, cast(pitch)?, cast(yaw)?])
+    }
+
+    /// Creates angles from radians that are already `f64`.
+    fn from_radians_f64([roll, pitch, yaw]: [f64; 3]) -> Self {
+        EulerAngles {
+            roll: Angle::from_radians(roll),
+            pitch: Angle::from_radians(pitch),
+            yaw: Angle::from_radians(yaw),
+            order: RotationOrder::default(),
+        }
+    }
+
+    /// Gets the (roll, pitch, yaw) angles in radians as `f64`.
+    fn radians(&self) -> [f64; 3] {
+        let Self {
+            roll, pitch, yaw, ..
+        } = *self;
+        [roll.as_radians(), pitch.as_radians(), yaw.as_radians()]
+    }
+
+    /// Sets the rotation order without changing the angles.
+    pub fn with_order(self, order: RotationOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Finds the angles of the rotation in the order.
+    ///
+    /// In the gimbal lock case, where the middle rotation is ±90°,
+    /// the last rotation is set to zero.
+    pub fn from_rotation3(rotation: &Rotation3<f64>, order: RotationOrder) -> Self {
+        let [i, j, k] = order.intrinsic_axes();
+        let m = rotation.matrix();
+
+        // The sign is positive if the axes are in cyclic order.
+        let sign = if (j + 3 - i) % 3 == 1 { 1.0 } else { -1.0 };
+
+        let sin_mid = sign * m[(i, k)];
+        let cos_mid = m[(i, i)].hypot(m[(i, j)]);
+        let mid = sin_mid.atan2(cos_mid);
+        let (first, last) = if cos_mid > 1e-12 {
+            let first = (-sign * m[(j, k)]).atan2(m[(k, k)]);
+            let last = (-sign * m[(i, j)]).atan2(m[(i, i)]);
+            (first, last)
+        } else {
+            let first = (sign * m[(k, j)]).atan2(m[(j, j)]);
+            (first, 0.0)
+        };
+
+        let mut radians = [0.0; 3];
+        radians[i] = first;
+        radians[j] = mid;
+        radians[k] = last;
+        Self::from_radians_f64(radians).with_order(order)
+    }
+
+    /// Finds the angles of the rotation in the order like
+    /// [from_rotation3](Self::from_rotation3).
+    pub fn from_unit_quaternion(rotation: &UnitQuaternion<f64>, order: RotationOrder) -> Self {
+        Self::from_rotation3(&rotation.to_rotation_matrix(), order)
+    }
+
+    /// Composes the rotations in the order.
+    pub fn to_rotation3(&self) -> Rotation3<f64> {
+        let radians = self.radians();
+        let [first, mid, last] = self
+            .order
+            .intrinsic_axes()
+            .map(|axis| Rotation3::from_axis_angle(&Vector3::ith_axis(axis), radians[axis]));
+        first * mid * last
+    }
+
+    /// Composes the rotations in the order like
+    /// [to_rotation3](Self::to_rotation3).
+    pub fn to_unit_quaternion(&self) -> UnitQuaternion<f64> {
+        UnitQuaternion::from_rotation_matrix(&self.to_rotation3())
+    }
+}
+
+/// Casts a number like [num::cast], which also fails if a finite
+/// number overflows to infinity, as casts between floats saturate.
+fn cast<T, U>(value: T) -> Option<U>
+where
+    T: num::NumCast,
+    U: num::NumCast,
+{
+    let finite = value.to_f64()?.is_finite();
+    let output: U = num::cast(value)?;
+    (output.to_f64()?.is_finite() == finite).then_some(output)
+}
@@ -0,0 +1,574 @@
+//! Quantity fields with a pinned unit and a range of accepted values,
+//! which are given as strings with units. It backs the `#[unit]` and
+//! `#[measurement]` attributes of the `newslab-serde-derive` crate,
+//! and can also be used to write helper modules by hand.
+//!
+//! ```rust
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! mod yaw {
+//!     use newslab_serde_measurements::field::{FieldSpec, Quantity};
+//!     use serde::{Deserializer, Serializer};
+//!
+//!     const SPEC: FieldSpec<'static> = FieldSpec {
+//!         unit: Some("deg"),
+//!         lenient: true,
+//!         range: Some("0deg..360deg"),
+//!     };
+//!
+//!     pub fn serialize<Q, S>(value: &Q, serializer: S) -> Result<S::Ok, S::Error>
+//!     where
+//!         Q: Quantity,
+//!         S: Serializer,
+//!     {
+//!         SPEC.serialize(value, serializer)
+//!     }
+//!
+//!     pub fn deserialize<'de, Q, D>(deserializer: D) -> Result<Q, D::Error>
+//!     where
+//!         Q: Quantity,
+//!         D: Deserializer<'de>,
+//!     {
+//!         SPEC.deserialize(deserializer)
+//!     }
+//! }
+//!
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct MyMount {
+//!     #[serde(with = "yaw")]
+//!     yaw: Angle,
+//! }
+//!
+//! let mount: MyMount = serde_json::from_str(r#"{ "yaw": 90 }"#).unwrap();
+//! assert_eq!(mount.yaw.as_degrees(), 90.0);
+//! assert_eq!(serde_json::to_string(&mount).unwrap(), r#"{"yaw":"90deg"}"#);
+//!
+//! let err = serde_json::from_str::<MyMount>(r#"{ "yaw": "7rad" }"#).unwrap_err();
+//! assert!(err.to_string().contains("out of the range 0deg..360deg"));
+//! ```
+
+use crate::combinators::Lenient;
+use measurements::{
+    Acceleration, Angle, Area, Frequency, Length, Mass, Pressure, Speed, Temperature, Volume,
+};
+use serde::{
+    de::{Error as _, Visitor},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    str::FromStr,
+    time::Duration,
+};
+
+/// A quantity type with a unit helper module.
+pub trait Quantity: PartialOrd + Sized + 'static {
+    /// The name of the quantity, like `"angle"`, which is also the
+    /// name of the helper module.
+    const NAME: &'static str;
+
+    /// Serializes the quantity like the helper module.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+
+    /// Deserializes the quantity like the helper module.
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+
+    /// Parses a number-unit string, like `"90deg"`.
+    fn parse(text: &str) -> Result<Self, String>;
+
+    /// Formats the quantity like the helper module, which fails if
+    /// it is not finite in the unit.
+    fn format(&self) -> Result<String, String>;
+
+    /// Formats the quantity in the unit with the symbol. It fails if
+    /// the unit is unknown or the quantity is not finite in the unit.
+    fn format_in(&self, symbol: &str) -> Result<String, String>;
+
+    /// Checks if the symbol names a unit of the quantity.
+    fn has_unit(symbol: &str) -> bool;
+}
+
+macro_rules! impl_quantity {
+    (@format $value:expr, $module:ident) => {
+        crate::$module::PARSER
+            .format($value, &crate::$module::PARSER.units()[0])
+            .map_err(|err| err.to_string())
+    };
+    (@format $value:expr, $module:ident, $ladder:ident) => {
+        crate::$module::PARSER
+            .format_prefixed($value, crate::$module::$ladder)
+            .map_err(|err| err.to_string())
+    };
+    ($($ty:ty => $module:ident $(with $ladder:ident)?,)*) => {
+        $(
+            impl Quantity for $ty {
+                const NAME: &'static str = stringify!($module);
+
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    crate::$module::serialize(self, serializer)
+                }
+
+                fn deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    crate::$module::deserialize(deserializer)
+                }
+
+                fn parse(text: &str) -> Result<Self, String> {
+                    crate::$module::PARSER.parse(text).map_err(|err| err.to_string())
+                }
+
+                fn format(&self) -> Result<String, String> {
+                    impl_quantity!(@format self, $module $(, $ladder)?)
+                }
+
+                fn format_in(&self, symbol: &str) -> Result<String, String> {
+                    let parser = &crate::$module::PARSER;
+                    let unit = parser
+                        .find_unit(symbol)
+                        .ok_or_else(|| format!("unknown {} unit '{symbol}'", Self::NAME))?;
+                    parser.format(self, unit).map_err(|err| err.to_string())
+                }
+
+                fn has_unit(symbol: &str) -> bool {
+                    crate::$module::PARSER.find_unit(symbol).is_some()
+                }
+            }
+        )*
+    };
+}
+
+impl_quantity! {
+    Acceleration => acceleration,
+    Angle => angle,
+    Area => area with LADDER,
+    Frequency => frequency with LADDER,
+    Length => length with LADDER,
+    Mass => mass with LADDER,
+    Pressure => pressure with LADDER,
+    Speed => speed,
+    Temperature => temperature,
+    Volume => volume with LADDER,
+}
+
+/// The duration parser works on nanoseconds, which are converted
+/// from and to durations here.
+impl Quantity for Duration {
+    const NAME: &'static str = "duration";
+
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        crate::duration::serialize(self, serializer)
+    }
+
+    fn deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        crate::duration::deserialize(deserializer)
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        crate::duration::parse(text)
+    }
+
+    fn format(&self) -> Result<String, String> {
+        Ok(crate::duration::format(self))
+    }
+
+    fn format_in(&self, symbol: &str) -> Result<String, String> {
+        if crate::duration::PARSER.find_unit(symbol).is_none() {
+            return Err(format!("unknown {} unit '{symbol}'", Self::NAME));
+        }
+        crate::duration::format_in(self, symbol).map_err(|err| err.to_string())
+    }
+
+    fn has_unit(symbol: &str) -> bool {
+        crate::duration::PARSER.find_unit(symbol).is_some()
+    }
+}
+
+/// The validations of the fields of each quantity by name.
+type Validate = fn(&FieldSpec, Option<&str>) -> Result<(), String>;
+
+const QUANTITIES: &[(&str, Validate)] = &[
+    (Acceleration::NAME, |spec, default| {
+        spec.validate::<Acceleration>(default)
+    }),
+    (Angle::NAME, |spec, default| spec.validate::<Angle>(default)),
+    (Area::NAME, |spec, default| spec.validate::<Area>(default)),
+    (Duration::NAME, |spec, default| {
+        spec.validate::<Duration>(default)
+    }),
+    (Frequency::NAME, |spec, default| {
+        spec.validate::<Frequency>(default)
+    }),
+    (Length::NAME, |spec, default| {
+        spec.validate::<Length>(default)
+    }),
+    (Mass::NAME, |spec, default| spec.validate::<Mass>(default)),
+    (Pressure::NAME, |spec, default| {
+        spec.validate::<Pressure>(default)
+    }),
+    (Speed::NAME, |spec, default| spec.validate::<Speed>(default)),
+    (Temperature::NAME, |spec, default| {
+        spec.validate::<Temperature>(default)
+    }),
+    (Volume::NAME, |spec, default| {
+        spec.validate::<Volume>(default)
+    }),
+];
+
+/// Checks if a quantity has the name, like `"angle"`.
+pub fn is_quantity(name: &str) -> bool {
+    QUANTITIES.iter().any(|&(quantity, _)| quantity == name)
+}
+
+/// Validates the options of a field of the named quantity in the way
+/// of [FieldSpec::validate]. It fails if the quantity is unknown.
+///
+/// ```rust
+/// # use newslab_serde_measurements::field::{self, FieldSpec};
+/// let spec = FieldSpec {
+///     unit: Some("mm"),
+///     range: Some("0m..=2m"),
+///     ..FieldSpec::default()
+/// };
+/// assert!(field::validate_named("length", &spec, Some("1.5m")).is_ok());
+/// assert!(field::validate_named("length", &spec, Some("3m")).is_err());
+/// assert!(field::validate_named("angle", &spec, None).is_err());
+/// assert!(field::validate_named("height", &spec, None).is_err());
+/// ```
+pub fn validate_named(
+    quantity: &str,
+    spec: &FieldSpec,
+    default: Option<&str>,
+) -> Result<(), String> {
+    let Some(&(_, validate)) = QUANTITIES.iter().find(|&&(name, _)| name == quantity) else {
+        let names: Vec<_> = QUANTITIES.iter().map(|&(name, _)| name).collect();
+        return Err(format!(
+            "unknown quantity '{quantity}'. It must be one of {}.",
+            names.join(", ")
+        ));
+    };
+    validate(spec, default)
+}
+
+/// Panics if the name of the quantity is not `name`. It is intended
+/// to be evaluated in constants, so that a field of a wrong quantity
+/// fails the compilation.
+pub const fn assert_quantity<Q: Quantity>(name: &str) {
+    let (lhs, rhs) = (Q::NAME.as_bytes(), name.as_bytes());
+    let mut same = lhs.len() == rhs.len();
+    let mut index = 0;
+    while same && index < lhs.len() {
+        same = lhs[index] == rhs[index];
+        index += 1;
+    }
+    if !same {
+        panic!("the type of the field is not the quantity given in the attribute");
+    }
+}
+
+/// The options of a quantity field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FieldSpec<'a> {
+    /// The symbol of the unit values are written in. Values are
+    /// written like the helper module if it is `None`.
+    pub unit: Option<&'a str>,
+    /// Also accepts bare numbers in `unit` in human-readable formats.
+    pub lenient: bool,
+    /// The range of accepted values, like `"0deg..360deg"`, in the
+    /// format of [QuantityRange].
+    pub range: Option<&'a str>,
+}
+
+impl FieldSpec<'_> {
+    /// Serializes the value in the unit, or like the helper module
+    /// if no unit is given.
+    pub fn serialize<Q, S>(&self, value: &Q, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        Q: Quantity,
+        S: Serializer,
+    {
+        let Some(symbol) = self.unit else {
+            return Quantity::serialize(value, serializer);
+        };
+        if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+            return Quantity::serialize(value, serializer);
+        }
+        value
+            .format_in(symbol)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+
+    /// Deserializes the value and checks that it is in the range.
+    pub fn deserialize<'de, Q, D>(&self, deserializer: D) -> Result<Q, D::Error>
+    where
+        Q: Quantity,
+        D: Deserializer<'de>,
+    {
+        let value = match self.unit {
+            Some(symbol) if self.lenient && deserializer.is_human_readable() => {
+                let text = match Lenient::deserialize(deserializer)? {
+                    Lenient::Text(text) => text,
+                    Lenient::Number(value) => format!("{value}{symbol}"),
+                };
+                Q::parse(&text).map_err(D::Error::custom)?
+            }
+            _ => Quantity::deserialize(deserializer)?,
+        };
+        self.check(&value).map_err(D::Error::custom)?;
+        Ok(value)
+    }
+
+    /// Serializes an optional value, where `None` becomes `null`.
+    pub fn serialize_opt<Q, S>(&self, value: &Option<Q>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        Q: Quantity,
+        S: Serializer,
+    {
+        /// Serializes the value with the options.
+        struct SerializeWith<'a, Q>(&'a FieldSpec<'a>, &'a Q);
+
+        impl<Q: Quantity> Serialize for SerializeWith<'_, Q> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.0.serialize(self.1, serializer)
+            }
+        }
+
+        match value {
+            Some(value) => serializer.serialize_some(&SerializeWith(self, value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes an optional value, where `null` becomes `None`.
+    pub fn deserialize_opt<'de, Q, D>(&self, deserializer: D) -> Result<Option<Q>, D::Error>
+    where
+        Q: Quantity,
+        D: Deserializer<'de>,
+    {
+        struct OptionVisitor<'a, Q>(&'a FieldSpec<'a>, PhantomData<Q>);
+
+        impl<'de, Q: Quantity> Visitor<'de> for OptionVisitor<'_, Q> {
+            type Value = Option<Q>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "an optional {}", Q::NAME)
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                self.0.deserialize(deserializer).map(Some)
+            }
+        }
+
+        deserializer.deserialize_option(OptionVisitor(self, PhantomData))
+    }
+
+    /// Checks if the value is in the range.
+    pub fn check<Q: Quantity>(&self, value: &Q) -> Result<(), String> {
+        let Some(range) = self.range else {
+            return Ok(());
+        };
+        let range: QuantityRange<Q> = range
+            .parse()
+            .map_err(|err: ParseRangeError| err.to_string())?;
+        if !range.contains(value) {
+            return Err(format!(
+                "The {} '{}' is out of the range {range}.",
+                Q::NAME,
+                value.format()?
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parses the default value of the field.
+    ///
+    /// # Panics
+    /// The function panics if the value is invalid or out of the
+    /// range, which [FieldSpec::validate] reports beforehand.
+    pub fn default_value<Q: Quantity>(&self, text: &str) -> Q {
+        let value = Q::parse(text).and_then(|value| {
+            self.check(&value)?;
+            Ok(value)
+        });
+        value.unwrap_or_else(|err| panic!("invalid default {} '{text}': {err}", Q::NAME))
+    }
+
+    /// Checks that the unit is known, the range is valid and the
+    /// default value, if any, is in the range.
+    pub fn validate<Q: Quantity>(&self, default: Option<&str>) -> Result<(), String> {
+        match self.unit {
+            Some(symbol) if !Q::has_unit(symbol) => {
+                return Err(format!("unknown {} unit '{symbol}'", Q::NAME));
+            }
+            None if self.lenient => {
+                return Err("bare numbers can only be accepted along with a unit".to_string());
+            }
+            _ => {}
+        }
+        if let Some(range) = self.range {
+            range
+                .parse::<QuantityRange<Q>>()
+                .map_err(|err| err.to_string())?;
+        }
+        if let Some(text) = default {
+            let value = Q::parse(text)?;
+            self.check(&value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A range of quantities in the syntax of Rust ranges, like
+/// `"0deg..360deg"`, `"0.5m..=120m"`, `"1ms.."` or `"..=100kg"`.
+///
+/// ```rust
+/// # use newslab_serde_measurements::field::QuantityRange;
+/// # use measurements::Length;
+/// # use std::ops::RangeBounds;
+/// let range: QuantityRange<Length> = "0.5m..=120m".parse().unwrap();
+/// assert!(range.contains(&Length::from_meters(120.0)));
+/// assert!(!range.contains(&Length::from_centimeters(10.0)));
+///
+/// let range: QuantityRange<Length> = "..1km".parse().unwrap();
+/// assert!(range.contains(&Length::from_meters(-5.0)));
+/// assert!(!range.contains(&Length::from_meters(1000.0)));
+///
+/// assert!("2m..1m".parse::<QuantityRange<Length>>().is_err());
+/// assert!("0m..=".parse::<QuantityRange<Length>>().is_err());
+/// assert!("0deg..1deg".parse::<QuantityRange<Length>>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantityRange<Q> {
+    start: Bound<Q>,
+    end: Bound<Q>,
+    text: String,
+}
+
+impl<Q> RangeBounds<Q> for QuantityRange<Q> {
+    fn start_bound(&self) -> Bound<&Q> {
+        self.start.as_ref()
+    }
+
+    fn end_bound(&self) -> Bound<&Q> {
+        self.end.as_ref()
+    }
+}
+
+impl<Q> Display for QuantityRange<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl<Q: Quantity> FromStr for QuantityRange<Q> {
+    type Err = ParseRangeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let error = |reason: String| ParseRangeError {
+            text: text.to_string(),
+            reason,
+        };
+
+        let (start, rest) = text
+            .split_once("..")
+            .ok_or_else(|| error("it must be like 'start..end' or 'start..=end'".to_string()))?;
+        let (end, inclusive) = match rest.strip_prefix('=') {
+            Some(end) => (end, true),
+            None => (rest, false),
+        };
+        let parse = |bound: &str| {
+            let bound = bound.trim();
+            if bound.is_empty() {
+                return Ok(None);
+            }
+            Q::parse(bound).map(Some).map_err(error)
+        };
+
+        let start = match parse(start)? {
+            Some(start) => Bound::Included(start),
+            None => Bound::Unbounded,
+        };
+        let end = match (parse(end)?, inclusive) {
+            (Some(end), true) => Bound::Included(end),
+            (Some(end), false) => Bound::Excluded(end),
+            (None, false) => Bound::Unbounded,
+            (None, true) => return Err(error("an inclusive range must have an end".to_string())),
+        };
+        if let (Bound::Included(start), Bound::Included(end) | Bound::Excluded(end)) =
+            (&start, &end)
+        {
+            if start > end {
+                return Err(error("the start is greater than the end".to_string()));
+            }
+        }
+
+        Ok(Self {
+            start,
+            end,
+            text: text.to_string(),
+        })
+    }
+}
+
+/// The error returned when a [QuantityRange] fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRangeError {
+    text: String,
+    reason: String,
+}
+
+impl ParseRangeError {
+    /// Gets the text that fails to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for ParseRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, reason } = self;
+        write!(f, "'{text}' is not a valid range: {reason}")
+    }
+}
+
+impl StdError for ParseRangeError {}
@@ -0,0 +1,102 @@
+//! Serialization helper to en/decode a frequency value with units.
+//!
+//! Accepted units are `mHz`, `Hz`, `kHz`, `MHz`, `GHz` and `THz`.
+//! Frequencies are serialized in the SI prefix that fits the
+//! magnitude best.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in hertz instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::frequency;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Frequency;
+//! #[derive(Serialize, Deserialize)]
+//! struct MySensor {
+//!     #[serde(with = "frequency")]
+//!     lidar_rate: Frequency,
+//!     #[serde(with = "frequency")]
+//!     imu_rate: Frequency,
+//!     #[serde(with = "frequency")]
+//!     radar_band: Frequency,
+//! }
+//!
+//! let json = r#"{ "lidar_rate": "10Hz", "imu_rate": "2.4kHz", "radar_band": "77GHz" }"#;
+//! let sensor: MySensor = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(sensor.lidar_rate.as_hertz(), 10.0);
+//! assert_eq!(sensor.imu_rate.as_hertz(), 2400.0);
+//! assert_eq!(sensor.radar_band.as_gigahertz(), 77.0);
+//!
+//! let json = serde_json::to_string(&sensor).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"lidar_rate":"10Hz","imu_rate":"2.4kHz","radar_band":"77GHz"}"#
+//! );
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Frequency;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Frequency> = UnitParser::new(
+    "frequency",
+    "10Hz",
+    &[
+        Unit::new(
+            &["mHz"],
+            Frequency::from_millihertz,
+            Frequency::as_millihertz,
+        ),
+        Unit::new(&["Hz"], Frequency::from_hertz, Frequency::as_hertz),
+        Unit::new(&["kHz"], Frequency::from_kilohertz, Frequency::as_kilohertz),
+        Unit::new(&["MHz"], Frequency::from_megahertz, Frequency::as_megahertz),
+        Unit::new(&["GHz"], Frequency::from_gigahertz, Frequency::as_gigahertz),
+        Unit::new(&["THz"], Frequency::from_terahertz, Frequency::as_terahertz),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["mHz", "Hz", "kHz", "MHz", "GHz", "THz"];
+
+pub fn serialize<S>(freq: &Frequency, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return freq.as_hertz().serialize(serializer);
+    }
+    PARSER
+        .format_prefixed(freq, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Frequency, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Frequency::from_hertz);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "frequency::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Frequency);
+
+pinned_units! {
+    Frequency;
+    in_hertz => "Hz",
+    in_kilohertz => "kHz",
+    in_megahertz => "MHz",
+    in_gigahertz => "GHz",
+}
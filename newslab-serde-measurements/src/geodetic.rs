@@ -0,0 +1,280 @@
+//! Serialization helpers to en/decode latitudes and longitudes.
+//!
+//! The [latitude] and [longitude] helper modules accept decimal
+//! degrees, either as numbers or strings, and degrees-minutes-seconds
+//! strings. Strings can end with a hemisphere letter, `N`/`S` for
+//! latitudes and `E`/`W` for longitudes, in place of the sign.
+//! Coordinates are serialized as numbers in decimal degrees, rounded
+//! to 1e-9 degrees.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::geodetic;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Angle;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyAntenna {
+//!     #[serde(with = "geodetic::latitude")]
+//!     latitude: Angle,
+//!     #[serde(with = "geodetic::longitude")]
+//!     longitude: Angle,
+//! }
+//!
+//! let json = r#"{ "latitude": "25.0330N", "longitude": "121.5654E" }"#;
+//! let antenna: MyAntenna = serde_json::from_str(json).unwrap();
+//! assert_eq!(
+//!     serde_json::to_string(&antenna).unwrap(),
+//!     r#"{"latitude":25.033,"longitude":121.5654}"#
+//! );
+//!
+//! let json = r#"{ "latitude": "25°1'58.8\"N", "longitude": -121.5654 }"#;
+//! let antenna: MyAntenna = serde_json::from_str(json).unwrap();
+//! assert!((antenna.latitude.as_degrees() - 25.033).abs() < 1e-9);
+//! assert!((antenna.longitude.as_degrees() + 121.5654).abs() < 1e-9);
+//!
+//! let json = r#"{ "latitude": "91N", "longitude": "121.5654E" }"#;
+//! assert!(serde_json::from_str::<MyAntenna>(json).is_err());
+//! ```
+
+use crate::combinators::Lenient;
+use measurements::Angle;
+#[cfg(feature = "schemars")]
+use schemars::schema::{InstanceType, Metadata, NumberValidation, Schema, SchemaObject};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialized coordinates are rounded to the reciprocal of the scale
+/// in degrees.
+const SCALE: f64 = 1e9;
+
+/// The symbols of degrees, minutes and seconds in order.
+const DMS_SYMBOLS: [&[char]; 3] = [&['°'], &['\'', '′'], &['"', '″']];
+
+/// The kind of a geodetic coordinate.
+struct Axis {
+    name: &'static str,
+    max_degrees: f64,
+    positive: char,
+    negative: char,
+    example: &'static str,
+}
+
+const LATITUDE: Axis = Axis {
+    name: "latitude",
+    max_degrees: 90.0,
+    positive: 'N',
+    negative: 'S',
+    example: "25.0330N",
+};
+
+const LONGITUDE: Axis = Axis {
+    name: "longitude",
+    max_degrees: 180.0,
+    positive: 'E',
+    negative: 'W',
+    example: "121.5654E",
+};
+
+/// Serialization helper for latitudes in the range [-90°, 90°].
+///
+/// ```rust
+/// # use newslab_serde_measurements::geodetic;
+/// # use serde::{Serialize, Deserialize};
+/// # use measurements::Angle;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyFix {
+///     #[serde(with = "geodetic::latitude")]
+///     latitude: Angle,
+/// }
+///
+/// let parse = |text: &str| {
+///     let json = format!(r#"{{ "latitude": {text:?} }}"#);
+///     serde_json::from_str::<MyFix>(&json).map(|fix| fix.latitude.as_degrees())
+/// };
+/// assert!((parse("33°52′4″ S").unwrap() + 33.867_777_8).abs() < 1e-6);
+/// assert!((parse("-12.5").unwrap() + 12.5).abs() < 1e-9);
+/// assert!(parse("-12.5N").is_err());
+/// assert!(parse("12°60'N").is_err());
+/// assert!(parse("12.5E").is_err());
+/// ```
+pub mod latitude {
+    use super::*;
+
+    pub fn serialize<S>(latitude: &Angle, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::serialize(latitude, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Angle, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(&LATITUDE, deserializer)
+    }
+
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        super::json_schema(&LATITUDE)
+    }
+
+    unit_combinators!(Angle);
+}
+
+/// Serialization helper for longitudes in the range [-180°, 180°].
+pub mod longitude {
+    use super::*;
+
+    pub fn serialize<S>(longitude: &Angle, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::serialize(longitude, serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Angle, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(&LONGITUDE, deserializer)
+    }
+
+    #[cfg(feature = "schemars")]
+    pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        super::json_schema(&LONGITUDE)
+    }
+
+    unit_combinators!(Angle);
+}
+
+fn serialize<S>(angle: &Angle, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let degrees = (angle.as_degrees() * SCALE).round() / SCALE;
+    degrees.serialize(serializer)
+}
+
+fn deserialize<'de, D>(axis: &Axis, deserializer: D) -> Result<Angle, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let (degrees, text) = if deserializer.is_human_readable() {
+        match Lenient::deserialize(deserializer)? {
+            Lenient::Number(degrees) => (degrees, degrees.to_string()),
+            Lenient::Text(text) => (parse(axis, &text).map_err(D::Error::custom)?, text),
+        }
+    } else {
+        let degrees = f64::deserialize(deserializer)?;
+        (degrees, degrees.to_string())
+    };
+
+    if degrees.is_nan() || degrees.abs() > axis.max_degrees {
+        return Err(D::Error::custom(format!(
+            "The {} '{text}' is out of the range [-{max}°, {max}°].",
+            axis.name,
+            max = axis.max_degrees
+        )));
+    }
+    Ok(Angle::from_degrees(degrees))
+}
+
+/// Generates the JSON schema of the coordinates, which are numbers
+/// in the range of the axis or strings.
+#[cfg(feature = "schemars")]
+fn json_schema(axis: &Axis) -> Schema {
+    let number = SchemaObject {
+        instance_type: Some(InstanceType::Number.into()),
+        number: Some(Box::new(NumberValidation {
+            minimum: Some(-axis.max_degrees),
+            maximum: Some(axis.max_degrees),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    let text = SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(format!(
+                "Decimal degrees or degrees-minutes-seconds with an optional hemisphere, \
+                 for example, '{}'.",
+                axis.example
+            )),
+            ..Default::default()
+        })),
+        instance_type: Some(InstanceType::String.into()),
+        ..Default::default()
+    };
+    crate::combinators::any_of_schema(vec![number.into(), text.into()])
+}
+
+/// Parses a coordinate string to decimal degrees.
+fn parse(axis: &Axis, text: &str) -> Result<f64, String> {
+    let invalid = || {
+        format!(
+            "Unable to parse '{text}' as a {}. It must be decimal degrees or \
+             degrees-minutes-seconds with an optional hemisphere, for example, '{}'.",
+            axis.name, axis.example
+        )
+    };
+    let trimmed = text.trim();
+
+    let (body, hemisphere) = match trimmed.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some(c) if c == axis.positive => (&trimmed[..trimmed.len() - 1], Some(1.0)),
+        Some(c) if c == axis.negative => (&trimmed[..trimmed.len() - 1], Some(-1.0)),
+        _ => (trimmed, None),
+    };
+    let body = body.trim();
+
+    let signed = match (body.strip_prefix('-'), body.strip_prefix('+')) {
+        (Some(rest), _) => Some((-1.0, rest)),
+        (_, Some(rest)) => Some((1.0, rest)),
+        _ => None,
+    };
+    let (sign, body) = match (hemisphere, signed) {
+        // A hemisphere letter replaces the sign.
+        (Some(_), Some(_)) => return Err(invalid()),
+        (Some(sign), None) => (sign, body),
+        (None, Some(signed)) => signed,
+        (None, None) => (1.0, body),
+    };
+
+    let degrees = parse_dms(body).ok_or_else(invalid)?;
+    Ok(sign * degrees)
+}
+
+/// Parses unsigned decimal degrees or degrees-minutes-seconds.
+fn parse_dms(text: &str) -> Option<f64> {
+    let is_number = |text: &str| text.starts_with(|c: char| c.is_ascii_digit() || c == '.');
+
+    if !DMS_SYMBOLS.iter().any(|symbols| text.contains(*symbols)) {
+        return is_number(text).then(|| text.parse().ok()).flatten();
+    }
+
+    let mut fields = [None; 3];
+    let mut rest = text;
+    for (field, symbols) in fields.iter_mut().zip(DMS_SYMBOLS) {
+        let Some(end) = rest.find(symbols) else {
+            continue;
+        };
+        let number = rest[..end].trim();
+        if !is_number(number) {
+            return None;
+        }
+        *field = Some(number.parse::<f64>().ok()?);
+
+        let symbol_len = rest[end..].chars().next()?.len_utf8();
+        rest = &rest[end + symbol_len..];
+    }
+    if !rest.trim().is_empty() {
+        return None;
+    }
+
+    let [Some(degrees), minutes, seconds] = fields else {
+        return None;
+    };
+    let minutes = minutes.unwrap_or(0.0);
+    let seconds = seconds.unwrap_or(0.0);
+    if minutes >= 60.0 || seconds >= 60.0 {
+        return None;
+    }
+    Some(degrees + minutes / 60.0 + seconds / 3600.0)
+}
@@ -0,0 +1,325 @@
+//! Golden-file snapshot assertions for serialized values.
+//!
+//! A [Golden] file stores the expected serialization of a value in
+//! pretty-printed JSON. The assertion serializes the value and
+//! compares it with the file. Numbers are compared within a
+//! tolerance, and strings with units, such as `"3deg"` or `"1.5m"`,
+//! are compared as quantities within the tolerance of their kind, so
+//! that rounding noise in the last digits does not fail the test.
+//!
+//! Golden files are written, or rewritten, instead of compared when
+//! the `NEWSLAB_SERDE_UPDATE_GOLDEN` environment variable is set.
+//!
+//! It requires the `test-util` feature.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::{angle, golden::Golden, length};
+//! # use serde::Serialize;
+//! # use measurements::{Angle, Length};
+//! #[derive(Serialize)]
+//! struct MyMount {
+//!     #[serde(with = "angle")]
+//!     yaw: Angle,
+//!     #[serde(with = "length")]
+//!     height: Length,
+//!     gain: f64,
+//! }
+//!
+//! let path = std::env::temp_dir().join("newslab-serde-golden-doc.json");
+//! std::fs::write(
+//!     &path,
+//!     r#"{ "yaw": "3.0000000000001deg", "height": "1500mm", "gain": 0.30000000000000004 }"#,
+//! )
+//! .unwrap();
+//!
+//! let mount = MyMount {
+//!     yaw: Angle::from_degrees(3.0),
+//!     height: Length::from_meters(1.5),
+//!     gain: 0.3,
+//! };
+//! Golden::new(&path).assert(&mount);
+//!
+//! // A larger difference fails the assertion.
+//! let mount = MyMount {
+//!     yaw: Angle::from_degrees(3.1),
+//!     ..mount
+//! };
+//! let result = std::panic::catch_unwind(|| Golden::new(&path).assert(&mount));
+//! assert!(result.is_err());
+//!
+//! // The tolerance of a kind of quantity is given in any of its units.
+//! Golden::new(&path).with_tolerance("0.2deg").assert(&mount);
+//! ```
+
+use crate::{
+    acceleration, angle, area, duration, frequency, length, mass, pressure, speed, temperature,
+    volume,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// The environment variable to regenerate golden files.
+pub const UPDATE_ENV: &str = "NEWSLAB_SERDE_UPDATE_GOLDEN";
+
+/// The default tolerance of bare numbers.
+const NUMBER_TOLERANCE: f64 = 1e-9;
+
+/// The maximum number of differences shown in a failure.
+const MAX_REPORTED: usize = 20;
+
+/// A kind of quantity recognized in strings with units.
+struct Quantity {
+    /// Parses the string to the value in the base unit.
+    parse: fn(&str) -> Option<f64>,
+    /// The default tolerance in the base unit.
+    tolerance: f64,
+}
+
+const QUANTITIES: &[Quantity] = &[
+    Quantity {
+        parse: |text| Some(angle::PARSER.parse(text).ok()?.as_radians()),
+        tolerance: 1e-9 * std::f64::consts::PI / 180.0,
+    },
+    Quantity {
+        parse: |text| Some(length::PARSER.parse(text).ok()?.as_meters()),
+        tolerance: 1e-9,
+    },
+    Quantity {
+        parse: |text| duration::PARSER.parse(text).ok(),
+        tolerance: 1.0,
+    },
+    Quantity {
+        parse: |text| Some(frequency::PARSER.parse(text).ok()?.as_hertz()),
+        tolerance: 1e-9,
+    },
+    Quantity {
+        parse: |text| Some(mass::PARSER.parse(text).ok()?.as_kilograms()),
+        tolerance: 1e-9,
+    },
+    Quantity {
+        parse: |text| Some(pressure::PARSER.parse(text).ok()?.as_pascals()),
+        tolerance: 1e-6,
+    },
+    Quantity {
+        parse: |text| Some(speed::PARSER.parse(text).ok()?.as_meters_per_second()),
+        tolerance: 1e-9,
+    },
+    Quantity {
+        parse: |text| {
+            Some(
+                acceleration::PARSER
+                    .parse(text)
+                    .ok()?
+                    .as_meters_per_second_per_second(),
+            )
+        },
+        tolerance: 1e-9,
+    },
+    Quantity {
+        parse: |text| Some(temperature::PARSER.parse(text).ok()?.as_kelvin()),
+        tolerance: 1e-9,
+    },
+    Quantity {
+        parse: |text| Some(area::PARSER.parse(text).ok()?.as_square_meters()),
+        tolerance: 1e-12,
+    },
+    Quantity {
+        parse: |text| Some(volume::PARSER.parse(text).ok()?.as_cubic_meters()),
+        tolerance: 1e-12,
+    },
+];
+
+/// A golden file and the tolerances of the comparison.
+#[derive(Debug, Clone)]
+pub struct Golden {
+    path: PathBuf,
+    number_tolerance: f64,
+    tolerances: Vec<f64>,
+}
+
+impl Golden {
+    /// Creates the assertion on the golden file with the default
+    /// tolerances, which are 1e-9 for numbers, 1e-9 degrees for
+    /// angles, 1 nm for lengths and 1 ns for durations.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            number_tolerance: NUMBER_TOLERANCE,
+            tolerances: QUANTITIES
+                .iter()
+                .map(|quantity| quantity.tolerance)
+                .collect(),
+        }
+    }
+
+    /// Sets the tolerance of bare numbers.
+    pub fn with_number_tolerance(mut self, tolerance: f64) -> Self {
+        self.number_tolerance = tolerance;
+        self
+    }
+
+    /// Sets the tolerance for the kind of quantity of the value, like
+    /// `"1e-6deg"` for angles or `"1um"` for lengths.
+    ///
+    /// # Panics
+    /// The function panics if the value is not a known quantity.
+    pub fn with_tolerance(mut self, tolerance: &str) -> Self {
+        let (index, value) = QUANTITIES
+            .iter()
+            .enumerate()
+            .find_map(|(index, quantity)| Some((index, (quantity.parse)(tolerance)?)))
+            .unwrap_or_else(|| panic!("'{tolerance}' is not a quantity with a known unit"));
+        self.tolerances[index] = value.abs();
+        self
+    }
+
+    /// Gets the path to the golden file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Asserts that the value serializes to the content of the
+    /// golden file. If [UPDATE_ENV] is set, the file is written
+    /// instead.
+    ///
+    /// # Panics
+    /// The function panics if the serialization differs, or if the
+    /// golden file cannot be read or written.
+    pub fn assert<T>(&self, value: &T)
+    where
+        T: Serialize + ?Sized,
+    {
+        let path = &self.path;
+        let actual = serde_json::to_value(value)
+            .unwrap_or_else(|err| panic!("unable to serialize the value: {err}"));
+
+        if env::var_os(UPDATE_ENV).is_some_and(|value| !value.is_empty()) {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).unwrap_or_else(|err| {
+                    panic!("unable to create the directory {}: {err}", dir.display())
+                });
+            }
+            let text = serde_json::to_string_pretty(&actual).unwrap() + "\n";
+            fs::write(path, text)
+                .unwrap_or_else(|err| panic!("unable to write {}: {err}", path.display()));
+            return;
+        }
+
+        let text = fs::read_to_string(path).unwrap_or_else(|err| {
+            panic!(
+                "unable to read the golden file {}: {err}\n\
+                 Set {UPDATE_ENV}=1 to generate it.",
+                path.display()
+            )
+        });
+        let expected: Value = serde_json::from_str(&text).unwrap_or_else(|err| {
+            panic!(
+                "the golden file {} is not valid JSON: {err}",
+                path.display()
+            )
+        });
+
+        let mut diffs = vec![];
+        self.compare(&mut String::new(), &expected, &actual, &mut diffs);
+        if diffs.is_empty() {
+            return;
+        }
+
+        let count = diffs.len();
+        diffs.truncate(MAX_REPORTED);
+        panic!(
+            "the value differs from the golden file {} at {count} place(s):\n  {}\n\
+             Set {UPDATE_ENV}=1 to update it.",
+            path.display(),
+            diffs.join("\n  ")
+        );
+    }
+
+    /// Compares the values and records the differences by their JSON
+    /// pointers.
+    fn compare(
+        &self,
+        pointer: &mut String,
+        expected: &Value,
+        actual: &Value,
+        diffs: &mut Vec<String>,
+    ) {
+        let is_equal = match (expected, actual) {
+            (Value::Number(lhs), Value::Number(rhs)) => match (lhs.as_f64(), rhs.as_f64()) {
+                (Some(lhs), Some(rhs)) => (lhs - rhs).abs() <= self.number_tolerance,
+                _ => lhs == rhs,
+            },
+            (Value::String(lhs), Value::String(rhs)) => {
+                lhs == rhs || self.is_same_quantity(lhs, rhs)
+            }
+            (Value::Array(lhs), Value::Array(rhs)) if lhs.len() == rhs.len() => {
+                for (index, (lhs, rhs)) in lhs.iter().zip(rhs).enumerate() {
+                    self.descend(pointer, &index.to_string(), lhs, rhs, diffs);
+                }
+                true
+            }
+            (Value::Object(lhs), Value::Object(rhs)) => {
+                for (key, lhs) in lhs {
+                    match rhs.get(key) {
+                        Some(rhs) => self.descend(pointer, key, lhs, rhs, diffs),
+                        None => diffs.push(format!("{pointer}/{key}: missing, expected {lhs}")),
+                    }
+                }
+                for (key, rhs) in rhs {
+                    if !lhs.contains_key(key) {
+                        diffs.push(format!("{pointer}/{key}: unexpected {rhs}"));
+                    }
+                }
+                true
+            }
+            _ => expected == actual,
+        };
+
+        if !is_equal {
+            let pointer = if pointer.is_empty() { "/" } else { pointer };
+            diffs.push(format!("{pointer}: expected {expected}, found {actual}"));
+        }
+    }
+
+    fn descend(
+        &self,
+        pointer: &mut String,
+        token: &str,
+        expected: &Value,
+        actual: &Value,
+        diffs: &mut Vec<String>,
+    ) {
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+        self.compare(pointer, expected, actual, diffs);
+        pointer.truncate(len);
+    }
+
+    /// Checks if both strings are quantities of the same kind within
+    /// the tolerance.
+    fn is_same_quantity(&self, lhs: &str, rhs: &str) -> bool {
+        QUANTITIES
+            .iter()
+            .zip(&self.tolerances)
+            .find_map(|(quantity, &tolerance)| {
+                let lhs = (quantity.parse)(lhs)?;
+                let rhs = (quantity.parse)(rhs)?;
+                Some((lhs - rhs).abs() <= tolerance)
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// Asserts that the value serializes to the content of the golden
+/// file with the default tolerances. See [Golden::assert].
+pub fn assert_golden<T>(path: impl AsRef<Path>, value: &T)
+where
+    T: Serialize + ?Sized,
+{
+    Golden::new(path).assert(value);
+}
@@ -0,0 +1,429 @@
+//! Serialization helper to en/decode an length value with units.
+//!
+//! Accepted units are `nm`, `µm` (or `um`), `mm`, `cm`, `dm`, `m`,
+//! `hm`, `km`, `in`, `ft`, `yd`, `furlong` and `mi`. Lengths are
+//! serialized in the metric unit that fits the magnitude best.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in meters instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::length;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Length;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyLength {
+//!     #[serde(with = "length")]
+//!     len1: Length,
+//!     #[serde(with = "length")]
+//!     len2: Length,
+//! }
+//!
+//! let json = r#"{ "len1": "2m", "len2": "-0.4 mm" }"#;
+//! let my_length: MyLength = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(my_length.len1.as_meters(), 2.0);
+//! assert_eq!(my_length.len2.as_millimeters(), -0.4);
+//!
+//! let json = serde_json::to_string(&my_length).unwrap();
+//! assert_eq!(json, r#"{"len1":"2m","len2":"-400µm"}"#);
+//! ```
+//!
+//! Optional values are handled by the [opt] submodule, which every
+//! unit helper module provides.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::length;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Length;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyLidar {
+//!     #[serde(with = "length::opt", default)]
+//!     min_range: Option<Length>,
+//!     #[serde(with = "length::opt", default)]
+//!     max_range: Option<Length>,
+//! }
+//!
+//! let json = r#"{ "min_range": null }"#;
+//! let lidar: MyLidar = serde_json::from_str(json).unwrap();
+//! assert!(lidar.min_range.is_none());
+//! assert!(lidar.max_range.is_none());
+//!
+//! let json = r#"{ "min_range": "0.5m", "max_range": "120m" }"#;
+//! let lidar: MyLidar = serde_json::from_str(json).unwrap();
+//! assert_eq!(lidar.max_range.unwrap().as_meters(), 120.0);
+//! assert_eq!(
+//!     serde_json::to_string(&lidar).unwrap(),
+//!     r#"{"min_range":"500mm","max_range":"120m"}"#
+//! );
+//! ```
+//!
+//! The `in_*` submodules pin the unit written in serialization, such
+//! as [in_millimeters]. They accept any unit in deserialization and
+//! provide the same combinator submodules.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::length;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Length;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyMount {
+//!     #[serde(with = "length::in_millimeters")]
+//!     offset: Length,
+//!     #[serde(with = "length::in_millimeters::opt", default)]
+//!     baseline: Option<Length>,
+//! }
+//!
+//! let json = r#"{ "offset": "0.8m", "baseline": "12cm" }"#;
+//! let mount: MyMount = serde_json::from_str(json).unwrap();
+//! assert_eq!(
+//!     serde_json::to_string(&mount).unwrap(),
+//!     r#"{"offset":"800mm","baseline":"120mm"}"#
+//! );
+//! ```
+//!
+//! The `lenient` submodule of a pinned unit also accepts bare
+//! numbers in that unit, such as [in_meters::lenient].
+//!
+//! ```rust
+//! # use newslab_serde_measurements::length;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Length;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyLidar {
+//!     #[serde(with = "length::in_meters::lenient")]
+//!     height: Length,
+//!     #[serde(with = "length::in_meters::lenient::vec")]
+//!     ranges: Vec<Length>,
+//! }
+//!
+//! let json = r#"{ "height": 1.5, "ranges": [0.5, "120m"] }"#;
+//! let lidar: MyLidar = serde_json::from_str(json).unwrap();
+//! assert_eq!(lidar.height.as_meters(), 1.5);
+//! assert_eq!(
+//!     serde_json::to_string(&lidar).unwrap(),
+//!     r#"{"height":"1.5m","ranges":["0.5m","120m"]}"#
+//! );
+//!
+//! // Other JSON values are rejected.
+//! let json = r#"{ "height": true, "ranges": [] }"#;
+//! assert!(serde_json::from_str::<MyLidar>(json).is_err());
+//! ```
+//!
+//! Quantities are also accepted as objects like
+//! `{ "value": 3.5, "unit": "m" }`. The `object` submodule of every
+//! helper, such as [object] and [in_millimeters::object], writes
+//! them in this form.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::length;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Length;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyMount {
+//!     #[serde(with = "length")]
+//!     height: Length,
+//!     #[serde(with = "length::in_millimeters::object")]
+//!     offset: Length,
+//! }
+//!
+//! let json = r#"{ "height": { "value": 1.5, "unit": "m" }, "offset": "0.25m" }"#;
+//! let mount: MyMount = serde_json::from_str(json).unwrap();
+//! assert_eq!(mount.height.as_meters(), 1.5);
+//! assert_eq!(
+//!     serde_json::to_string(&mount).unwrap(),
+//!     r#"{"height":"1.5m","offset":{"value":250.0,"unit":"mm"}}"#
+//! );
+//!
+//! let json = r#"{ "height": { "value": 1.5 }, "offset": "0.25m" }"#;
+//! assert!(serde_json::from_str::<MyMount>(json).is_err());
+//! ```
+
+use crate::unit::{ParseUnitError, Unit, UnitParser};
+use measurements::Length;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Length> = UnitParser::new(
+    "length",
+    "10.0m",
+    &[
+        Unit::new(&["nm"], Length::from_nanometers, Length::as_nanometers),
+        Unit::new(
+            &["µm", "μm", "um"],
+            Length::from_micrometers,
+            Length::as_micrometers,
+        ),
+        Unit::new(&["mm"], Length::from_millimeters, Length::as_millimeters),
+        Unit::new(&["cm"], Length::from_centimeters, Length::as_centimeters),
+        Unit::new(&["dm"], Length::from_decimeters, Length::as_decimeters),
+        Unit::new(&["m"], Length::from_meters, Length::as_meters),
+        Unit::new(&["hm"], Length::from_hectometers, Length::as_hectometers),
+        Unit::new(&["km"], Length::from_kilometers, Length::as_kilometers),
+        Unit::new(&["in"], Length::from_inches, Length::as_inches),
+        Unit::new(&["ft"], Length::from_feet, Length::as_feet),
+        Unit::new(&["yd"], Length::from_yards, Length::as_yards),
+        Unit::new(&["furlong"], Length::from_furlongs, Length::as_furlongs),
+        Unit::new(&["mi"], Length::from_miles, Length::as_miles),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["nm", "µm", "mm", "m", "km"];
+
+pub fn serialize<S>(len: &Length, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return len.as_meters().serialize(serializer);
+    }
+    PARSER
+        .format_prefixed(len, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Length, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Length::from_meters);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Converts lengths to `f64` numbers in the unit like
+/// [to_f32_slice].
+pub fn to_f64_slice(lengths: &[Length], unit: &str) -> Result<Vec<f64>, ParseUnitError> {
+    let factor = PARSER.factor(unit, &Length::from_meters(1.0))?;
+    Ok(lengths
+        .iter()
+        .map(|value| value.as_meters() * factor)
+        .collect())
+}
+
+/// Converts lengths to `f32` numbers in the unit, such as `"cm"`,
+/// which fails if the unit is unknown. It is meant for large tables
+/// like the beam tables of lidars.
+///
+/// ```rust
+/// # use newslab_serde_measurements::length;
+/// # use measurements::Length;
+/// let ranges: Vec<_> = (0..10).map(|ix| Length::from_meters(ix as f64)).collect();
+/// let ranges = length::to_f32_slice(&ranges, "cm").unwrap();
+/// assert_eq!(ranges[3], 300.0);
+///
+/// assert!(length::to_f32_slice(&[], "deg").is_err());
+/// ```
+pub fn to_f32_slice(lengths: &[Length], unit: &str) -> Result<Vec<f32>, ParseUnitError> {
+    let factor = PARSER.factor(unit, &Length::from_meters(1.0))?;
+    Ok(lengths
+        .iter()
+        .map(|value| (value.as_meters() * factor) as f32)
+        .collect())
+}
+
+/// Returns a zero length. It is intended to be used in
+/// `#[serde(default = "length::default_zero")]`.
+pub fn default_zero() -> Length {
+    Length::from_meters(0.0)
+}
+
+/// Returns a length of `N` meters. It is intended to be used in
+/// `#[serde(default = "length::default_meters::<5>")]`.
+pub fn default_meters<const N: i64>() -> Length {
+    Length::from_meters(N as f64)
+}
+
+/// Returns a length of `N` centimeters.
+pub fn default_centimeters<const N: i64>() -> Length {
+    Length::from_centimeters(N as f64)
+}
+
+/// Returns a length of `N` millimeters.
+pub fn default_millimeters<const N: i64>() -> Length {
+    Length::from_millimeters(N as f64)
+}
+
+/// Returns a length of `N` kilometers.
+pub fn default_kilometers<const N: i64>() -> Length {
+    Length::from_kilometers(N as f64)
+}
+
+/// Serialization helper for `[Length; N]` arrays written with a
+/// shared unit, like `"[1.5, 0.3, -0.002] m"`. The unit fits the
+/// component with the largest magnitude. The sequence of lengths with
+/// units, like `["1.5m", "30cm", "-2mm"]`, is also accepted.
+///
+/// Formats that are not human-readable store a sequence of bare
+/// numbers in meters instead.
+///
+/// ```rust
+/// # use newslab_serde_measurements::length;
+/// # use serde::{Serialize, Deserialize};
+/// # use measurements::Length;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyMount {
+///     #[serde(with = "length::array")]
+///     translation: [Length; 3],
+/// }
+///
+/// let json = r#"{ "translation": "[1.0, 2.5, -0.2] m" }"#;
+/// let mount: MyMount = serde_json::from_str(json).unwrap();
+/// assert_eq!(mount.translation[1].as_meters(), 2.5);
+/// assert_eq!(
+///     serde_json::to_string(&mount).unwrap(),
+///     r#"{"translation":"[1, 2.5, -0.2] m"}"#
+/// );
+///
+/// let json = r#"{ "translation": ["1.5m", "30cm", "-2mm"] }"#;
+/// let mount: MyMount = serde_json::from_str(json).unwrap();
+/// assert_eq!(mount.translation[2].as_millimeters(), -2.0);
+///
+/// let json = r#"{ "translation": "[1.0, 2.5] m" }"#;
+/// assert!(serde_json::from_str::<MyMount>(json).is_err());
+/// ```
+pub mod array {
+    use super::*;
+
+    pub fn serialize<S, const N: usize>(
+        lens: &[Length; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_slice(lens, serializer)
+    }
+
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<[Length; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let lens = deserialize_vec(deserializer)?;
+        let len = lens.len();
+        lens.try_into()
+            .map_err(|_| D::Error::custom(format!("expect a vector of {N} lengths, but get {len}")))
+    }
+}
+
+/// Serialization helper for [Vector3](nalgebra::Vector3) in meters
+/// written with a shared unit, like `"[1.5, 0.3, -0.002] m"`, in the
+/// way of the [array] helper.
+///
+/// ```rust
+/// # use newslab_serde_measurements::length;
+/// # use serde::{Serialize, Deserialize};
+/// # use nalgebra::Vector3;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyMount {
+///     #[serde(with = "length::vector3")]
+///     translation: Vector3<f64>,
+/// }
+///
+/// let json = r#"{ "translation": "[12, 0, -3.5] cm" }"#;
+/// let mount: MyMount = serde_json::from_str(json).unwrap();
+/// assert!((mount.translation - Vector3::new(0.12, 0.0, -0.035)).norm() < 1e-12);
+/// assert_eq!(
+///     serde_json::to_string(&mount).unwrap(),
+///     r#"{"translation":"[120, 0, -35] mm"}"#
+/// );
+/// ```
+pub mod vector3 {
+    use super::*;
+    use nalgebra::Vector3;
+
+    pub fn serialize<S>(vector: &Vector3<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let lens = vector.map(Length::from_meters);
+        serialize_slice(lens.as_slice(), serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vector3<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let [x, y, z] = array::deserialize(deserializer)?;
+        Ok(Vector3::new(x.as_meters(), y.as_meters(), z.as_meters()))
+    }
+}
+
+fn serialize_slice<S>(lens: &[Length], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return serializer.collect_seq(lens.iter().map(Length::as_meters));
+    }
+    PARSER
+        .format_vector_prefixed(lens, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+fn deserialize_vec<'de, D>(deserializer: D) -> Result<Vec<Length>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::{SeqAccess, Visitor};
+    use std::fmt;
+
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        let values = Vec::<f64>::deserialize(deserializer)?;
+        return Ok(values.into_iter().map(Length::from_meters).collect());
+    }
+
+    struct VectorVisitor;
+
+    impl<'de> Visitor<'de> for VectorVisitor {
+        type Value = Vec<Length>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a vector like \"[1.0, 2.5, -0.2] m\" or a sequence of lengths")
+        }
+
+        fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            PARSER.parse_vector(text).map_err(E::custom)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut lens = vec![];
+            while let Some(DeserializeWith(len)) = seq.next_element()? {
+                lens.push(len);
+            }
+            Ok(lens)
+        }
+    }
+
+    deserializer.deserialize_any(VectorVisitor)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "length::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Length);
+
+pinned_units! {
+    Length;
+    in_nanometers => "nm",
+    in_micrometers => "µm",
+    in_millimeters => "mm",
+    in_centimeters => "cm",
+    in_meters => "m",
+    in_kilometers => "km",
+}
@@ -0,0 +1,102 @@
+//! Serialization helper to en/decode a mass value with units.
+//!
+//! Accepted units are `µg` (or `ug`), `mg`, `g`, `kg`, `t`, `oz`, `lb`
+//! (or `lbs`) and `st`. Masses are serialized in the metric unit that
+//! fits the magnitude best.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in kilograms instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::mass;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Mass;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyPayload {
+//!     #[serde(with = "mass")]
+//!     sensor: Mass,
+//!     #[serde(with = "mass")]
+//!     mount: Mass,
+//!     #[serde(with = "mass")]
+//!     vehicle: Mass,
+//! }
+//!
+//! let json = r#"{ "sensor": "250g", "mount": "3lb", "vehicle": "2t" }"#;
+//! let mut payload: MyPayload = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(payload.sensor.as_grams(), 250.0);
+//! assert_eq!(payload.mount.as_pounds(), 3.0);
+//! assert_eq!(payload.vehicle.as_kilograms(), 2000.0);
+//!
+//! payload.mount = Mass::from_grams(1500.0);
+//! let json = serde_json::to_string(&payload).unwrap();
+//! assert_eq!(json, r#"{"sensor":"250g","mount":"1.5kg","vehicle":"2t"}"#);
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Mass;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Mass> = UnitParser::new(
+    "mass",
+    "1.5kg",
+    &[
+        Unit::new(
+            &["µg", "μg", "ug"],
+            Mass::from_micrograms,
+            Mass::as_micrograms,
+        ),
+        Unit::new(&["mg"], Mass::from_milligrams, Mass::as_milligrams),
+        Unit::new(&["g"], Mass::from_grams, Mass::as_grams),
+        Unit::new(&["kg"], Mass::from_kilograms, Mass::as_kilograms),
+        Unit::new(&["t"], Mass::from_metric_tons, Mass::as_metric_tons),
+        Unit::new(&["oz"], Mass::from_ounces, Mass::as_ounces),
+        Unit::new(&["lb", "lbs"], Mass::from_pounds, Mass::as_pounds),
+        Unit::new(&["st"], Mass::from_stones, Mass::as_stones),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["µg", "mg", "g", "kg", "t"];
+
+pub fn serialize<S>(mass: &Mass, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return mass.as_kilograms().serialize(serializer);
+    }
+    PARSER
+        .format_prefixed(mass, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Mass, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Mass::from_kilograms);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "mass::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Mass);
+
+pinned_units! {
+    Mass;
+    in_milligrams => "mg",
+    in_grams => "g",
+    in_kilograms => "kg",
+    in_tonnes => "t",
+}
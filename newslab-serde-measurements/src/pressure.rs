@@ -0,0 +1,134 @@
+//! Serialization helper to en/decode a pressure value with units.
+//!
+//! Accepted units are `Pa`, `hPa`, `kPa`, `MPa`, `mbar`, `bar`,
+//! `psi`, `atm`, `Torr`, `mTorr` and `mmHg`. Pressures are serialized
+//! in the pascal unit that fits the magnitude best.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in pascals instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::pressure;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Pressure;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyBarometer {
+//!     #[serde(with = "pressure")]
+//!     sea_level: Pressure,
+//!     #[serde(with = "pressure")]
+//!     max: Pressure,
+//!     #[serde(with = "pressure")]
+//!     tire: Pressure,
+//!     #[serde(with = "pressure")]
+//!     reference: Pressure,
+//! }
+//!
+//! let json = r#"{
+//!     "sea_level": "101.3kPa",
+//!     "max": "1.0bar",
+//!     "tire": "14.7psi",
+//!     "reference": "760mmHg"
+//! }"#;
+//! let baro: MyBarometer = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(baro.sea_level.as_kilopascals(), 101.3);
+//! assert_eq!(baro.max.as_pascals(), 100_000.0);
+//! assert_eq!(baro.tire.as_psi(), 14.7);
+//! assert!((baro.reference.as_atmospheres() - 1.0).abs() < 1e-6);
+//!
+//! let json = serde_json::to_string(&baro).unwrap();
+//! assert!(json.contains(r#""sea_level":"101.3kPa""#));
+//! assert!(json.contains(r#""max":"100kPa""#));
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Pressure;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Pressure> = UnitParser::new(
+    "pressure",
+    "101.3kPa",
+    &[
+        Unit::new(&["Pa"], Pressure::from_pascals, Pressure::as_pascals),
+        Unit::new(
+            &["hPa"],
+            Pressure::from_hectopascals,
+            Pressure::as_hectopascals,
+        ),
+        Unit::new(
+            &["kPa"],
+            Pressure::from_kilopascals,
+            Pressure::as_kilopascals,
+        ),
+        Unit::new(
+            &["MPa"],
+            |val| Pressure::from_pascals(val * 1e6),
+            |pressure| pressure.as_pascals() / 1e6,
+        ),
+        Unit::new(&["mbar"], Pressure::from_millibars, Pressure::as_millibars),
+        Unit::new(&["bar"], Pressure::from_bars, Pressure::as_bars),
+        Unit::new(&["psi"], Pressure::from_psi, Pressure::as_psi),
+        Unit::new(
+            &["atm"],
+            Pressure::from_atmospheres,
+            Pressure::as_atmospheres,
+        ),
+        Unit::new(&["Torr"], Pressure::from_torrs, Pressure::as_torrs),
+        Unit::new(
+            &["mTorr"],
+            Pressure::from_millitorrs,
+            Pressure::as_millitorrs,
+        ),
+        Unit::new(
+            &["mmHg"],
+            Pressure::from_millimeter_mercury,
+            Pressure::as_millimeter_mercury,
+        ),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["Pa", "kPa", "MPa"];
+
+pub fn serialize<S>(pressure: &Pressure, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return pressure.as_pascals().serialize(serializer);
+    }
+    PARSER
+        .format_prefixed(pressure, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Pressure, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Pressure::from_pascals);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "pressure::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Pressure);
+
+pinned_units! {
+    Pressure;
+    in_pascals => "Pa",
+    in_hectopascals => "hPa",
+    in_kilopascals => "kPa",
+    in_megapascals => "MPa",
+    in_bars => "bar",
+}
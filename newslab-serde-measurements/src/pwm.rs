@@ -0,0 +1,123 @@
+use measurements::Frequency;
+use newslab_serde_num::Fraction;
+use serde::{Deserialize, Serialize, Serializer};
+use std::time::Duration;
+
+/// The configuration of a PWM signal with a frequency and a duty
+/// cycle.
+///
+/// The frequency must be positive, and the duty cycle must be within
+/// 0 and 1. The duty cycle accepts a [Fraction] like `"1/4"` or a
+/// percentage like `"25%"`, and it is written as a percentage if it
+/// is exact.
+///
+/// ```rust
+/// # use newslab_serde_measurements::PwmConfig;
+/// # use newslab_serde_num::Fraction;
+/// # use measurements::Frequency;
+/// # use std::time::Duration;
+/// let json = r#"{ "frequency": "50Hz", "duty": "7.5%" }"#;
+/// let pwm: PwmConfig = serde_json::from_str(json).unwrap();
+/// assert_eq!(pwm.period(), Duration::from_millis(20));
+/// assert_eq!(pwm.on_time(), Duration::from_micros(1500));
+/// assert_eq!(serde_json::to_string(&pwm).unwrap(), r#"{"frequency":"50Hz","duty":"7.5%"}"#);
+///
+/// // Duty cycles without a short percentage are written as fractions.
+/// let duty = Fraction::new(1, 1 << 62).unwrap();
+/// let pwm = PwmConfig::new(Frequency::from_hertz(50.0), duty).unwrap();
+/// let json = serde_json::to_string(&pwm).unwrap();
+/// assert_eq!(json, r#"{"frequency":"50Hz","duty":"1/4611686018427387904"}"#);
+/// assert_eq!(serde_json::from_str::<PwmConfig>(&json).unwrap().duty(), duty);
+///
+/// // The percent sign is misplaced, so the duty cycle is 75 rather than 0.75.
+/// let json = r#"{ "frequency": "50Hz", "duty": "75/1" }"#;
+/// assert!(serde_json::from_str::<PwmConfig>(json).is_err());
+///
+/// let json = r#"{ "frequency": "0Hz", "duty": "50%" }"#;
+/// assert!(serde_json::from_str::<PwmConfig>(json).is_err());
+///
+/// // The period would overflow a Duration.
+/// let err = PwmConfig::new(Frequency::from_hertz(1e-300), Fraction::ONE).unwrap_err();
+/// assert!(err.ends_with("is too long"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PwmConfigUnchecked", into = "PwmConfigUnchecked")]
+pub struct PwmConfig {
+    frequency: Frequency,
+    duty: Fraction,
+}
+
+impl PwmConfig {
+    /// Creates a configuration. It fails if the frequency is not
+    /// positive, if it is so low that the period overflows
+    /// [Duration], or if the duty cycle is out of 0 and 1.
+    pub fn new(frequency: Frequency, duty: Fraction) -> Result<Self, String> {
+        let hertz = frequency.as_hertz();
+        if !(hertz.is_finite() && hertz > 0.0) {
+            return Err(format!("the frequency {hertz}Hz must be positive"));
+        }
+        if Duration::try_from_secs_f64(1.0 / hertz).is_err() {
+            return Err(format!("the period of the frequency {hertz}Hz is too long"));
+        }
+        if duty.is_negative() || duty.num() > duty.deno().get() {
+            return Err(format!(
+                "the duty cycle {} must be within 0% and 100%",
+                duty.display_percent()
+            ));
+        }
+        Ok(Self { frequency, duty })
+    }
+
+    pub fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    /// Gets the duty cycle within 0 and 1.
+    pub fn duty(&self) -> Fraction {
+        self.duty
+    }
+
+    /// Gets the duration of a cycle.
+    pub fn period(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.frequency.as_hertz())
+    }
+
+    /// Gets the duration that the signal is on in a cycle.
+    pub fn on_time(&self) -> Duration {
+        Duration::from_secs_f64(self.duty.to_f64() / self.frequency.as_hertz())
+    }
+}
+
+impl TryFrom<PwmConfigUnchecked> for PwmConfig {
+    type Error = String;
+
+    fn try_from(from: PwmConfigUnchecked) -> Result<Self, Self::Error> {
+        let PwmConfigUnchecked { frequency, duty } = from;
+        Self::new(frequency, duty)
+    }
+}
+
+impl From<PwmConfig> for PwmConfigUnchecked {
+    fn from(from: PwmConfig) -> Self {
+        let PwmConfig { frequency, duty } = from;
+        Self { frequency, duty }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PwmConfigUnchecked {
+    #[serde(with = "crate::frequency")]
+    frequency: Frequency,
+    #[serde(serialize_with = "serialize_duty")]
+    duty: Fraction,
+}
+
+fn serialize_duty<S>(duty: &Fraction, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        return duty.serialize(serializer);
+    }
+    serializer.collect_str(&duty.display_percent())
+}
@@ -0,0 +1,21 @@
+/// The decimal exponent of a finite number written as
+/// `significand * 10^exponent`, where the magnitude of the
+/// significand is within [1, 10).
+pub(crate) struct ScientificNotation {
+    pub exponent: i32,
+}
+
+impl ScientificNotation {
+    /// Splits the number. Zero and non-finite numbers have a zero
+    /// exponent.
+    pub fn from_float(value: f64) -> ScientificNotation {
+        if !value.is_finite() || value == 0.0 {
+            return ScientificNotation { exponent: 0 };
+        }
+
+        // The exponents of finite numbers are within [-324, 308], so
+        // the conversion never saturates.
+        let exponent = value.abs().log10().floor() as i32;
+        ScientificNotation { exponent }
+    }
+}
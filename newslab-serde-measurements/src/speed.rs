@@ -0,0 +1,117 @@
+//! Serialization helper to en/decode a speed value with units.
+//!
+//! Accepted units are `m/s`, `km/h` (or `kph`), `mph`, `ft/s` and
+//! `kn` (or `kt`). Speeds are serialized in meters per second.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in meters per second instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::speed;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Speed;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyVehicle {
+//!     #[serde(with = "speed")]
+//!     cruise: Speed,
+//!     #[serde(with = "speed")]
+//!     max: Speed,
+//!     #[serde(with = "speed")]
+//!     boat: Speed,
+//! }
+//!
+//! let json = r#"{ "cruise": "3.2m/s", "max": "72km/h", "boat": "10kn" }"#;
+//! let vehicle: MyVehicle = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(vehicle.cruise.as_meters_per_second(), 3.2);
+//! assert_eq!(vehicle.max.as_kilometers_per_hour(), 72.0);
+//!
+//! let json = serde_json::to_string(&vehicle).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"cruise":"3.2m/s","max":"20m/s","boat":"5.144444444444445m/s"}"#
+//! );
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Speed;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+/// One knot in meters per second.
+const KNOT: f64 = 1852.0 / 3600.0;
+
+/// One foot per second in meters per second.
+const FOOT_PER_SECOND: f64 = 0.3048;
+
+pub(crate) static PARSER: UnitParser<Speed> = UnitParser::new(
+    "speed",
+    "10.0m/s",
+    &[
+        Unit::new(
+            &["m/s"],
+            Speed::from_meters_per_second,
+            Speed::as_meters_per_second,
+        ),
+        Unit::new(
+            &["km/h", "kph"],
+            Speed::from_kilometers_per_hour,
+            Speed::as_kilometers_per_hour,
+        ),
+        Unit::new(
+            &["mph"],
+            Speed::from_miles_per_hour,
+            Speed::as_miles_per_hour,
+        ),
+        Unit::new(
+            &["ft/s"],
+            |val| Speed::from_meters_per_second(val * FOOT_PER_SECOND),
+            |speed| speed.as_meters_per_second() / FOOT_PER_SECOND,
+        ),
+        Unit::new(
+            &["kn", "kt"],
+            |val| Speed::from_meters_per_second(val * KNOT),
+            |speed| speed.as_meters_per_second() / KNOT,
+        ),
+    ],
+);
+
+pub fn serialize<S>(speed: &Speed, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return speed.as_meters_per_second().serialize(serializer);
+    }
+    PARSER
+        .format(speed, &PARSER.units()[0])
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Speed, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer)
+            .map(Speed::from_meters_per_second);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "speed::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Speed);
+
+pinned_units! {
+    Speed;
+    in_meters_per_second => "m/s",
+    in_kilometers_per_hour => "km/h",
+}
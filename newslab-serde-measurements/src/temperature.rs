@@ -0,0 +1,150 @@
+//! Serialization helper to en/decode a temperature value with units.
+//!
+//! Accepted units are `C`, `F`, `K` and `R`. Degree units tolerate
+//! the spellings found in data sheets, such as `"25°C"`, `"25° C"`,
+//! `"25 C"`, `"25degC"` and `"25℃"`. Temperatures are serialized in
+//! the canonical form in degrees Celsius, like `"25C"`.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in kelvins instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::temperature;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Temperature;
+//! #[derive(Serialize, Deserialize)]
+//! struct MySensor {
+//!     #[serde(with = "temperature")]
+//!     min_temp: Temperature,
+//!     #[serde(with = "temperature")]
+//!     max_temp: Temperature,
+//!     #[serde(with = "temperature")]
+//!     nominal_temp: Temperature,
+//! }
+//!
+//! let json = r#"{ "min_temp": "-40F", "max_temp": "358.15K", "nominal_temp": "25.5C" }"#;
+//! let sensor: MySensor = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(sensor.min_temp.as_celsius(), -40.0);
+//! assert_eq!(sensor.max_temp.as_kelvin(), 358.15);
+//! assert_eq!(sensor.nominal_temp.as_celsius(), 25.5);
+//!
+//! let json = serde_json::to_string(&sensor).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"min_temp":"-40C","max_temp":"85C","nominal_temp":"25.5C"}"#
+//! );
+//! ```
+//!
+//! All spellings of a unit parse to the same temperature. They are
+//! the unit letter alone, the letter after `°`, `º` or `deg` with an
+//! optional space, like `"25° C"`, and the `℃` and `℉` glyphs.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::temperature;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Temperature;
+//! #[derive(Serialize, Deserialize)]
+//! struct Wrapper(#[serde(with = "temperature")] Temperature);
+//!
+//! let units: [(char, Option<char>, fn(&Temperature) -> f64); 3] = [
+//!     ('C', Some('℃'), Temperature::as_celsius),
+//!     ('F', Some('℉'), Temperature::as_fahrenheit),
+//!     ('R', None, Temperature::as_rankine),
+//! ];
+//!
+//! for (letter, glyph, as_unit) in units {
+//!     let mut spellings = vec![letter.to_string()];
+//!     for degree in ["°", "º", "deg"] {
+//!         for space in ["", " "] {
+//!             spellings.push(format!("{degree}{space}{letter}"));
+//!         }
+//!     }
+//!     spellings.extend(glyph.map(String::from));
+//!
+//!     for value in [-40.0, 0.0, 25.0, 37.5, 125.0] {
+//!         for separator in ["", " "] {
+//!             for spelling in &spellings {
+//!                 let json = format!(r#""{value}{separator}{spelling}""#);
+//!                 let Wrapper(temp) = serde_json::from_str(&json).unwrap();
+//!                 assert!((as_unit(&temp) - value).abs() < 1e-9, "{json}");
+//!
+//!                 let json = serde_json::to_string(&Wrapper(temp)).unwrap();
+//!                 let Wrapper(back) = serde_json::from_str(&json).unwrap();
+//!                 assert!((as_unit(&back) - value).abs() < 1e-9, "{json}");
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let Wrapper(temp) = serde_json::from_str(r#""25° C""#).unwrap();
+//! assert_eq!(serde_json::to_string(&Wrapper(temp)).unwrap(), r#""25C""#);
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Temperature;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Temperature> = UnitParser::new(
+    "temperature",
+    "25.0C",
+    &[
+        Unit::new(
+            &["C", "°C", "° C", "ºC", "º C", "degC", "deg C", "℃"],
+            Temperature::from_celsius,
+            Temperature::as_celsius,
+        ),
+        Unit::new(
+            &["F", "°F", "° F", "ºF", "º F", "degF", "deg F", "℉"],
+            Temperature::from_fahrenheit,
+            Temperature::as_fahrenheit,
+        ),
+        Unit::new(&["K"], Temperature::from_kelvin, Temperature::as_kelvin),
+        Unit::new(
+            &["R", "°R", "° R", "ºR", "º R", "degR", "deg R"],
+            Temperature::from_rankine,
+            Temperature::as_rankine,
+        ),
+    ],
+);
+
+pub fn serialize<S>(temp: &Temperature, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return temp.as_kelvin().serialize(serializer);
+    }
+    PARSER
+        .format(temp, &PARSER.units()[0])
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Temperature, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Temperature::from_kelvin);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "temperature::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Temperature);
+
+pinned_units! {
+    Temperature;
+    in_celsius => "C",
+    in_fahrenheit => "F",
+    in_kelvin => "K",
+}
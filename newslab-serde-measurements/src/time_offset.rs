@@ -0,0 +1,299 @@
+use crate::duration::{self, PARSER};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+/// A signed time difference with nanosecond resolution, written as
+/// a number with a time unit like `"-12.5ms"`. Formats that are not
+/// human-readable store the integer nanoseconds instead.
+///
+/// ```rust
+/// # use newslab_serde_measurements::TimeOffset;
+/// let offset: TimeOffset = serde_json::from_str(r#" "-12.5ms" "#).unwrap();
+/// assert_eq!(offset.as_nanos(), -12_500_000);
+/// assert_eq!(serde_json::to_string(&offset).unwrap(), r#""-12.5ms""#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct TimeOffset {
+    nanos: i64,
+}
+
+impl TimeOffset {
+    pub const ZERO: Self = Self { nanos: 0 };
+
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self { nanos }
+    }
+
+    pub fn from_secs_f64(secs: f64) -> Self {
+        Self::from_scaled(secs, 1e9)
+    }
+
+    /// Builds a positive offset from a duration, saturating at the
+    /// largest representable offset.
+    pub fn from_duration(duration: Duration) -> Self {
+        let nanos = duration.as_nanos().min(i64::MAX as u128) as i64;
+        Self { nanos }
+    }
+
+    pub fn as_nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    pub fn as_secs_f64(&self) -> f64 {
+        self.nanos as f64 / 1e9
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.nanos < 0
+    }
+
+    /// Gets the magnitude of the offset.
+    pub fn abs(&self) -> Duration {
+        Duration::from_nanos(self.nanos.unsigned_abs())
+    }
+
+    /// Shifts the time point by the offset. It returns `None` if the
+    /// result is not representable.
+    pub fn apply(&self, time: SystemTime) -> Option<SystemTime> {
+        if self.is_negative() {
+            time.checked_sub(self.abs())
+        } else {
+            time.checked_add(self.abs())
+        }
+    }
+
+    /// Shifts a timestamp, which is a duration since some epoch, by
+    /// the offset. It returns `None` if the result is negative or
+    /// overflows.
+    pub fn apply_to_duration(&self, timestamp: Duration) -> Option<Duration> {
+        if self.is_negative() {
+            timestamp.checked_sub(self.abs())
+        } else {
+            timestamp.checked_add(self.abs())
+        }
+    }
+
+    fn from_scaled(value: f64, nanos_per_unit: f64) -> Self {
+        // The float-to-int cast saturates at the boundaries.
+        let nanos = (value * nanos_per_unit).round() as i64;
+        Self { nanos }
+    }
+}
+
+impl FromStr for TimeOffset {
+    type Err = crate::unit::ParseUnitError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        PARSER
+            .parse(text)
+            .map(|nanos| Self::from_scaled(nanos, 1.0))
+    }
+}
+
+impl Display for TimeOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        f.write_str(&duration::format_nanos(self.nanos.unsigned_abs().into()))
+    }
+}
+
+impl Serialize for TimeOffset {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+            return self.nanos.serialize(serializer);
+        }
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOffset {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+            return i64::deserialize(deserializer).map(Self::from_nanos);
+        }
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
+
+/// The time offsets of sensor clocks relative to a master clock.
+///
+/// Each offset is the amount added to a sensor timestamp to obtain
+/// the time on the master clock. Offsets are audited to be within
+/// `max_offset` in magnitude during ser/deserialization, which
+/// defaults to one second.
+///
+/// ```rust
+/// # use newslab_serde_measurements::TimeOffsets;
+/// # use std::time::Duration;
+/// let json = r#"{
+///     "master": "gnss",
+///     "max_offset": "100ms",
+///     "offsets": {
+///         "camera_front": "-12.5ms",
+///         "lidar_top": "3ms"
+///     }
+/// }"#;
+/// let offsets: TimeOffsets = serde_json::from_str(json).unwrap();
+///
+/// let time = offsets
+///     .apply_to_duration("lidar_top", Duration::from_secs(10))
+///     .unwrap();
+/// assert_eq!(time, Duration::from_millis(10_003));
+///
+/// // The offset exceeds the maximum.
+/// let json = r#"{
+///     "master": "gnss",
+///     "offsets": { "camera_front": "1.5s" }
+/// }"#;
+/// assert!(serde_json::from_str::<TimeOffsets>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "TimeOffsetsUnchecked", into = "TimeOffsetsUnchecked")]
+pub struct TimeOffsets {
+    master: String,
+    max_offset: TimeOffset,
+    offsets: BTreeMap<String, TimeOffset>,
+}
+
+impl TimeOffsets {
+    /// Creates an empty collection of offsets.
+    pub fn new(master: impl Into<String>, max_offset: Duration) -> Self {
+        Self {
+            master: master.into(),
+            max_offset: TimeOffset::from_duration(max_offset),
+            offsets: BTreeMap::new(),
+        }
+    }
+
+    /// Gets the name of the master clock.
+    pub fn master(&self) -> &str {
+        &self.master
+    }
+
+    /// Gets the largest allowed magnitude of offsets.
+    pub fn max_offset(&self) -> Duration {
+        self.max_offset.abs()
+    }
+
+    /// Gets the offset of a sensor. The offset of the master clock is
+    /// always zero.
+    pub fn get(&self, sensor: &str) -> Option<TimeOffset> {
+        if sensor == self.master {
+            return Some(TimeOffset::ZERO);
+        }
+        self.offsets.get(sensor).copied()
+    }
+
+    /// Sets the offset of a sensor. It fails if the offset exceeds
+    /// the maximum or the sensor is the master clock.
+    pub fn insert(&mut self, sensor: impl Into<String>, offset: TimeOffset) -> Result<(), String> {
+        let sensor = sensor.into();
+        check_offset(&self.master, self.max_offset, &sensor, offset)?;
+        self.offsets.insert(sensor, offset);
+        Ok(())
+    }
+
+    /// Iterates over sensor names and offsets.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, TimeOffset)> {
+        self.offsets
+            .iter()
+            .map(|(sensor, &offset)| (sensor.as_str(), offset))
+    }
+
+    /// Converts a sensor time point to the master clock.
+    pub fn apply(&self, sensor: &str, time: SystemTime) -> Option<SystemTime> {
+        self.get(sensor)?.apply(time)
+    }
+
+    /// Converts a sensor timestamp, which is a duration since some
+    /// epoch, to the master clock.
+    pub fn apply_to_duration(&self, sensor: &str, timestamp: Duration) -> Option<Duration> {
+        self.get(sensor)?.apply_to_duration(timestamp)
+    }
+}
+
+fn check_offset(
+    master: &str,
+    max_offset: TimeOffset,
+    sensor: &str,
+    offset: TimeOffset,
+) -> Result<(), String> {
+    if sensor == master {
+        return Err(format!(
+            "the master clock '{master}' must not have an offset"
+        ));
+    }
+    if offset.abs() > max_offset.abs() {
+        return Err(format!(
+            "the offset {offset} of '{sensor}' exceeds the maximum {max_offset}"
+        ));
+    }
+    Ok(())
+}
+
+fn default_max_offset() -> TimeOffset {
+    TimeOffset::from_nanos(1_000_000_000)
+}
+
+impl TryFrom<TimeOffsetsUnchecked> for TimeOffsets {
+    type Error = String;
+
+    fn try_from(from: TimeOffsetsUnchecked) -> Result<Self, Self::Error> {
+        let TimeOffsetsUnchecked {
+            master,
+            max_offset,
+            offsets,
+        } = from;
+
+        if max_offset.is_negative() {
+            return Err(format!("max_offset {max_offset} must not be negative"));
+        }
+        for (sensor, &offset) in &offsets {
+            check_offset(&master, max_offset, sensor, offset)?;
+        }
+
+        Ok(Self {
+            master,
+            max_offset,
+            offsets,
+        })
+    }
+}
+
+impl From<TimeOffsets> for TimeOffsetsUnchecked {
+    fn from(from: TimeOffsets) -> Self {
+        let TimeOffsets {
+            master,
+            max_offset,
+            offsets,
+        } = from;
+        Self {
+            master,
+            max_offset,
+            offsets,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TimeOffsetsUnchecked {
+    master: String,
+    #[serde(default = "default_max_offset")]
+    max_offset: TimeOffset,
+    #[serde(default)]
+    offsets: BTreeMap<String, TimeOffset>,
+}
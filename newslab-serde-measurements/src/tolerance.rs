@@ -0,0 +1,225 @@
+//! Comparisons of quantities within tolerances with units, like
+//! `"1mm"`, so that a tolerance is never given in a wrong unit.
+//!
+//! Tolerances are accepted for lengths, angles, areas, volumes,
+//! masses, speeds, accelerations, pressures and frequencies.
+//! Temperatures are not included, since `"1C"` would be an absolute
+//! temperature rather than a difference. Angles are compared without
+//! wrapping around.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::tolerance::{self, Tolerance};
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::{Angle, Length};
+//! let a = Length::from_meters(1.5);
+//! let b = Length::from_millimeters(1500.8);
+//! assert!(tolerance::approx_eq_within(&a, &b, "1mm").unwrap());
+//! assert!(!tolerance::approx_eq_within(&a, &b, "0.5mm").unwrap());
+//!
+//! // The tolerance must be a quantity of the same kind.
+//! assert!(tolerance::approx_eq_within(&a, &b, "1deg").is_err());
+//! assert!(tolerance::approx_eq_within(&a, &b, "-1mm").is_err());
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct MyRule {
+//!     max_yaw_error: Tolerance<Angle>,
+//! }
+//!
+//! let rule: MyRule = serde_json::from_str(r#"{ "max_yaw_error": "0.5deg" }"#).unwrap();
+//! assert!(rule
+//!     .max_yaw_error
+//!     .approx_eq(&Angle::from_degrees(90.0), &Angle::from_degrees(90.3)));
+//!
+//! // The tolerance is written in the unit as it is given.
+//! let rule = MyRule { max_yaw_error: "0.01rad".parse().unwrap() };
+//! assert_eq!(serde_json::to_string(&rule).unwrap(), r#"{"max_yaw_error":"0.01rad"}"#);
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::{
+    Acceleration, Angle, Area, Frequency, Length, Mass, Measurement, Pressure, Speed, Volume,
+};
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A quantity that can be given as a tolerance.
+pub trait Quantity: Measurement + Copy + 'static {
+    /// Gets the parser of the quantity.
+    fn parser() -> &'static UnitParser<Self>;
+}
+
+macro_rules! impl_quantity {
+    ($($ty:ty => $module:ident,)*) => {
+        $(
+            impl Quantity for $ty {
+                fn parser() -> &'static UnitParser<Self> {
+                    &crate::$module::PARSER
+                }
+            }
+        )*
+    };
+}
+
+impl_quantity! {
+    Acceleration => acceleration,
+    Angle => angle,
+    Area => area,
+    Frequency => frequency,
+    Length => length,
+    Mass => mass,
+    Pressure => pressure,
+    Speed => speed,
+    Volume => volume,
+}
+
+/// Checks if the quantities differ by no more than the tolerance.
+pub fn approx_eq<Q>(lhs: &Q, rhs: &Q, tolerance: &Q) -> bool
+where
+    Q: Measurement,
+{
+    (lhs.as_base_units() - rhs.as_base_units()).abs() <= tolerance.as_base_units()
+}
+
+/// Checks if the quantities differ by no more than the tolerance
+/// string with units, like `"1mm"`.
+pub fn approx_eq_within<Q>(lhs: &Q, rhs: &Q, tolerance: &str) -> Result<bool, ParseToleranceError>
+where
+    Q: Quantity,
+{
+    let tolerance: Tolerance<Q> = tolerance.parse()?;
+    Ok(tolerance.approx_eq(lhs, rhs))
+}
+
+/// A non-negative tolerance of a quantity, which keeps the unit it
+/// is given in.
+#[derive(Clone, Copy)]
+pub struct Tolerance<Q: Quantity> {
+    value: Q,
+    unit: &'static Unit<Q>,
+}
+
+impl<Q: Quantity> Tolerance<Q> {
+    /// Creates a tolerance written in the unit with the symbol. It
+    /// fails if the unit is unknown or the value is negative.
+    pub fn new(value: Q, symbol: &str) -> Result<Self, String> {
+        let parser = Q::parser();
+        let unit = parser
+            .find_unit(symbol)
+            .ok_or_else(|| format!("unknown {} unit '{symbol}'", parser.quantity()))?;
+        let base = value.as_base_units();
+        if !(base >= 0.0 && base.is_finite()) {
+            return Err(format!(
+                "the tolerance {} must be non-negative",
+                parser.format_lossy(&value, unit)
+            ));
+        }
+        Ok(Self { value, unit })
+    }
+
+    pub fn value(&self) -> Q {
+        self.value
+    }
+
+    /// Checks if the quantities differ by no more than the tolerance.
+    pub fn approx_eq(&self, lhs: &Q, rhs: &Q) -> bool {
+        approx_eq(lhs, rhs, &self.value)
+    }
+}
+
+impl<Q: Quantity> fmt::Debug for Tolerance<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Tolerance").field(&self.to_string()).finish()
+    }
+}
+
+impl<Q: Quantity> PartialEq for Tolerance<Q> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.as_base_units() == other.value.as_base_units()
+    }
+}
+
+impl<Q: Quantity> Display for Tolerance<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Q::parser().format_lossy(&self.value, self.unit))
+    }
+}
+
+impl<Q: Quantity> FromStr for Tolerance<Q> {
+    type Err = ParseToleranceError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let error = |reason: String| ParseToleranceError {
+            text: text.to_string(),
+            reason,
+        };
+        let parser = Q::parser();
+        let (value, unit) = parser
+            .parse_with_unit(text)
+            .map_err(|err| error(err.to_string()))?;
+        Self::new((unit.from_value)(value), unit.symbol()).map_err(error)
+    }
+}
+
+/// Tolerances are written in the unit as they are given in
+/// human-readable formats, and as bare numbers in the base unit
+/// otherwise.
+impl<Q: Quantity> Serialize for Tolerance<Q> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+            return self.value.as_base_units().serialize(serializer);
+        }
+        Q::parser()
+            .format(&self.value, self.unit)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de, Q: Quantity> Deserialize<'de> for Tolerance<Q> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+            let value = Q::from_base_units(crate::compat::deserialize_bare_number(deserializer)?);
+            let parser = Q::parser();
+            let symbol = match parser.find_unit(value.get_base_units_name()) {
+                Some(unit) => unit.symbol(),
+                None => parser.units()[0].symbol(),
+            };
+            return Self::new(value, symbol).map_err(D::Error::custom);
+        }
+        let text = crate::combinators::deserialize_text(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
+
+/// The error returned when a tolerance fails to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseToleranceError {
+    text: String,
+    reason: String,
+}
+
+impl ParseToleranceError {
+    /// Gets the text that fails to parse.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Display for ParseToleranceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { text, reason } = self;
+        write!(f, "'{text}' is not a valid tolerance: {reason}")
+    }
+}
+
+impl StdError for ParseToleranceError {}
@@ -0,0 +1,684 @@
+//! The unit-string parsing engine shared by the measurement helpers.
+//!
+//! A quantity is described by a [UnitParser] holding a table of
+//! [Unit]s. Each unit lists its accepted symbols and the conversion
+//! functions to and from the quantity type. The parser accepts a
+//! number followed by a unit symbol, optionally separated by
+//! whitespace, like `"10m"` or `"10 m"`.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::unit::{Unit, UnitParser};
+//! # use measurements::Length;
+//! static PARSER: UnitParser<Length> = UnitParser::new(
+//!     "length",
+//!     "10.0m",
+//!     &[
+//!         Unit::new(&["m"], Length::from_meters, Length::as_meters),
+//!         Unit::new(&["ft"], Length::from_feet, Length::as_feet),
+//!     ],
+//! );
+//!
+//! let len = PARSER.parse(" 3.5 ft ").unwrap();
+//! assert_eq!(len.as_feet(), 3.5);
+//! assert_eq!(PARSER.format(&len, PARSER.find_unit("m").unwrap()).unwrap(), "1.0668m");
+//! assert!(PARSER.parse("3.5").is_err());
+//! ```
+
+use crate::ScientificNotation;
+#[cfg(feature = "schemars")]
+use schemars::schema::{
+    InstanceType, Metadata, NumberValidation, ObjectValidation, Schema, SchemaObject,
+    StringValidation, SubschemaValidation,
+};
+use std::fmt::{self, Display};
+
+/// A unit of measure for the quantity type `Q`.
+pub struct Unit<Q: 'static> {
+    /// Accepted symbols of the unit. The first one is used in
+    /// serialization.
+    pub symbols: &'static [&'static str],
+    /// Builds a quantity from a value in this unit.
+    pub from_value: fn(f64) -> Q,
+    /// Gets the value of a quantity in this unit.
+    pub to_value: fn(&Q) -> f64,
+}
+
+impl<Q> Unit<Q> {
+    pub const fn new(
+        symbols: &'static [&'static str],
+        from_value: fn(f64) -> Q,
+        to_value: fn(&Q) -> f64,
+    ) -> Self {
+        Self {
+            symbols,
+            from_value,
+            to_value,
+        }
+    }
+
+    /// Gets the canonical symbol of the unit.
+    pub fn symbol(&self) -> &'static str {
+        self.symbols[0]
+    }
+}
+
+/// The parser and formatter for a quantity with a table of units.
+pub struct UnitParser<Q: 'static> {
+    quantity: &'static str,
+    example: &'static str,
+    units: &'static [Unit<Q>],
+}
+
+impl<Q> UnitParser<Q> {
+    /// Creates a parser for a quantity.
+    ///
+    /// The `quantity` name and the `example` string are shown in
+    /// error messages.
+    pub const fn new(
+        quantity: &'static str,
+        example: &'static str,
+        units: &'static [Unit<Q>],
+    ) -> Self {
+        Self {
+            quantity,
+            example,
+            units,
+        }
+    }
+
+    /// Gets the name of the quantity.
+    pub fn quantity(&self) -> &'static str {
+        self.quantity
+    }
+
+    /// Gets the example string shown in error messages.
+    pub fn example(&self) -> &'static str {
+        self.example
+    }
+
+    /// Gets the list of known units.
+    pub fn units(&self) -> &'static [Unit<Q>] {
+        self.units
+    }
+
+    /// Finds the unit with the symbol.
+    ///
+    /// Symbols are case-sensitive, since SI prefixes differing only in
+    /// case, such as `m` and `M`, differ by orders of magnitude.
+    pub fn find_unit(&self, symbol: &str) -> Option<&'static Unit<Q>> {
+        let symbol = symbol.trim();
+        self.units
+            .iter()
+            .find(|unit| unit.symbols.contains(&symbol))
+    }
+
+    /// Parses a number-unit string to a quantity.
+    ///
+    /// Numbers must be finite, and it fails if the quantity
+    /// overflows in the conversion between units.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::unit::{ParseUnitError, Unit, UnitParser};
+    /// # use measurements::Length;
+    /// static PARSER: UnitParser<Length> = UnitParser::new(
+    ///     "length",
+    ///     "10.0m",
+    ///     &[Unit::new(&["km"], Length::from_kilometers, Length::as_kilometers)],
+    /// );
+    ///
+    /// assert_eq!(PARSER.parse("1e305km").unwrap().as_kilometers(), 1e305);
+    /// assert!(matches!(PARSER.parse("1e308km"), Err(ParseUnitError::OutOfRange { .. })));
+    /// assert!(matches!(PARSER.parse("-1e308km"), Err(ParseUnitError::OutOfRange { .. })));
+    /// assert!(matches!(PARSER.parse("1e309km"), Err(ParseUnitError::InvalidNumber { .. })));
+    /// assert!(matches!(PARSER.parse("NaN km"), Err(ParseUnitError::InvalidNumber { .. })));
+    /// assert!(matches!(PARSER.parse("-inf km"), Err(ParseUnitError::InvalidNumber { .. })));
+    /// assert!(PARSER.parse_vector("[1, 1e308] km").is_err());
+    ///
+    /// // Subnormal numbers are formatted without overflow.
+    /// let tiny = Length::from_kilometers(5e-324);
+    /// assert_eq!(PARSER.format_prefixed(&tiny, &["km"]).unwrap(), "5e-324km");
+    /// ```
+    pub fn parse(&self, text: &str) -> Result<Q, ParseUnitError> {
+        let (value, unit) = self.parse_with_unit(text)?;
+        self.convert(value, unit, text)
+    }
+
+    /// Parses a number-unit string and returns the number along with
+    /// the matched unit.
+    pub fn parse_with_unit(&self, text: &str) -> Result<(f64, &'static Unit<Q>), ParseUnitError> {
+        let (prefix, unit) = self.split_unit(text)?;
+        let value = parse_number(prefix)?;
+        Ok((value, unit))
+    }
+
+    /// Splits a number-unit string into the number text and the unit,
+    /// for quantities that parse the number by themselves.
+    pub(crate) fn split_unit<'a>(
+        &self,
+        text: &'a str,
+    ) -> Result<(&'a str, &'static Unit<Q>), ParseUnitError> {
+        let text = text.trim();
+        self.match_suffix(text)?
+            .ok_or_else(|| ParseUnitError::UnknownUnit {
+                quantity: self.quantity,
+                text: text.to_string(),
+                example: self.example,
+            })
+    }
+
+    /// Formats the quantity in the specified unit.
+    ///
+    /// Values with very large or small magnitudes are written in
+    /// scientific notation like `"1e-5deg"`. It fails if the value
+    /// in the unit is not a finite number, which cannot be parsed
+    /// back.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::unit::{ParseUnitError, Unit, UnitParser};
+    /// # use measurements::Length;
+    /// static PARSER: UnitParser<Length> = UnitParser::new(
+    ///     "length",
+    ///     "10.0m",
+    ///     &[
+    ///         Unit::new(&["m"], Length::from_meters, Length::as_meters),
+    ///         Unit::new(&["mm"], Length::from_millimeters, Length::as_millimeters),
+    ///     ],
+    /// );
+    ///
+    /// let len = Length::from_meters(1e307);
+    /// assert_eq!(PARSER.format_in(&len, "m").unwrap(), "1e307m");
+    /// assert!(matches!(PARSER.format_in(&len, "mm"), Err(ParseUnitError::OutOfRange { .. })));
+    /// assert!(PARSER.format_in(&Length::from_meters(f64::NAN), "m").is_err());
+    /// ```
+    pub fn format(&self, value: &Q, unit: &Unit<Q>) -> Result<String, ParseUnitError> {
+        self.format_value((unit.to_value)(value), unit)
+    }
+
+    /// Formats a number that is already in the unit with its symbol,
+    /// which fails if the number is not finite.
+    pub fn format_value(&self, value: f64, unit: &Unit<Q>) -> Result<String, ParseUnitError> {
+        let text = format!("{}{}", format_number(value), unit.symbol());
+        if !value.is_finite() {
+            return Err(ParseUnitError::OutOfRange {
+                quantity: self.quantity,
+                text,
+            });
+        }
+        Ok(text)
+    }
+
+    /// Formats the quantity in the specified unit even if it is not
+    /// finite, for error messages and debug output.
+    pub(crate) fn format_lossy(&self, value: &Q, unit: &Unit<Q>) -> String {
+        format!("{}{}", format_number((unit.to_value)(value)), unit.symbol())
+    }
+
+    /// Formats the quantity in the unit with the symbol.
+    ///
+    /// # Panics
+    /// The function panics if the symbol is unknown.
+    pub fn format_in(&self, value: &Q, symbol: &str) -> Result<String, ParseUnitError> {
+        self.format(value, self.expect_unit(symbol))
+    }
+
+    /// Formats the quantity in the most appropriate unit from the
+    /// `ladder`, which is a list of unit symbols ordered from the
+    /// smallest to the largest unit.
+    ///
+    /// The largest unit where the value is not less than one is
+    /// chosen. It falls back to the smallest unit if none applies.
+    /// Zero and subnormal values have no magnitude to fit, and are
+    /// written in the unit of the example instead.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::unit::{Unit, UnitParser};
+    /// # use measurements::Length;
+    /// static PARSER: UnitParser<Length> = UnitParser::new(
+    ///     "length",
+    ///     "10.0m",
+    ///     &[
+    ///         Unit::new(&["mm"], Length::from_millimeters, Length::as_millimeters),
+    ///         Unit::new(&["m"], Length::from_meters, Length::as_meters),
+    ///         Unit::new(&["km"], Length::from_kilometers, Length::as_kilometers),
+    ///     ],
+    /// );
+    /// const LADDER: &[&str] = &["mm", "m", "km"];
+    ///
+    /// let format = |meters| PARSER.format_prefixed(&Length::from_meters(meters), LADDER).unwrap();
+    /// assert_eq!(format(1500.0), "1.5km");
+    /// assert_eq!(format(0.002), "2mm");
+    /// assert_eq!(format(1e-6), "0.001mm");
+    /// assert_eq!(format(0.0), "0m");
+    /// assert_eq!(format(5e-324), "5e-324m");
+    /// ```
+    ///
+    /// # Panics
+    /// The function panics if the ladder is empty or contains
+    /// unknown symbols.
+    pub fn format_prefixed(&self, value: &Q, ladder: &[&str]) -> Result<String, ParseUnitError> {
+        self.format(value, self.prefixed_unit(value, ladder))
+    }
+
+    /// Parses a vector of numbers sharing a unit, like
+    /// `"[1.0, 2.5, -0.2] m"`.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::unit::{Unit, UnitParser};
+    /// # use measurements::Length;
+    /// static PARSER: UnitParser<Length> = UnitParser::new(
+    ///     "length",
+    ///     "10.0m",
+    ///     &[
+    ///         Unit::new(&["m"], Length::from_meters, Length::as_meters),
+    ///         Unit::new(&["cm"], Length::from_centimeters, Length::as_centimeters),
+    ///     ],
+    /// );
+    ///
+    /// let lens = PARSER.parse_vector("[1.0, 2.5, -20] cm").unwrap();
+    /// assert_eq!(lens[2].as_centimeters(), -20.0);
+    /// assert_eq!(PARSER.format_vector(&lens, PARSER.find_unit("m").unwrap()).unwrap(), "[0.01, 0.025, -0.2] m");
+    /// assert!(PARSER.parse_vector("[1.0, 2.5]").is_err());
+    /// assert!(PARSER.parse_vector("1.0m").is_err());
+    /// ```
+    pub fn parse_vector(&self, text: &str) -> Result<Vec<Q>, ParseUnitError> {
+        let text = text.trim();
+        let (items, symbol) = text
+            .strip_prefix('[')
+            .and_then(|rest| rest.rsplit_once(']'))
+            .ok_or_else(|| ParseUnitError::InvalidVector {
+                text: text.to_string(),
+            })?;
+        let unit = self
+            .find_unit(symbol)
+            .ok_or_else(|| ParseUnitError::UnknownUnit {
+                quantity: self.quantity,
+                text: text.to_string(),
+                example: self.example,
+            })?;
+
+        if items.trim().is_empty() {
+            return Ok(vec![]);
+        }
+        items
+            .split(',')
+            .map(|item| self.convert(parse_number(item)?, unit, text))
+            .collect()
+    }
+
+    /// Converts the number in the unit to a quantity, which fails if
+    /// it overflows.
+    fn convert(&self, value: f64, unit: &Unit<Q>, text: &str) -> Result<Q, ParseUnitError> {
+        let quantity = (unit.from_value)(value);
+        if !(unit.to_value)(&quantity).is_finite() {
+            return Err(ParseUnitError::OutOfRange {
+                quantity: self.quantity,
+                text: text.trim().to_string(),
+            });
+        }
+        Ok(quantity)
+    }
+
+    /// Formats the quantities in the specified unit as a vector, like
+    /// `"[1, 2.5, -0.2] m"`.
+    pub fn format_vector(&self, values: &[Q], unit: &Unit<Q>) -> Result<String, ParseUnitError> {
+        let text = self.format_vector_lossy(values, unit);
+        if !values
+            .iter()
+            .all(|value| (unit.to_value)(value).is_finite())
+        {
+            return Err(ParseUnitError::OutOfRange {
+                quantity: self.quantity,
+                text,
+            });
+        }
+        Ok(text)
+    }
+
+    /// Formats the quantities as a vector even if some are not
+    /// finite, in the way of [UnitParser::format_lossy].
+    pub(crate) fn format_vector_lossy(&self, values: &[Q], unit: &Unit<Q>) -> String {
+        let values: Vec<_> = values
+            .iter()
+            .map(|value| format_number((unit.to_value)(value)))
+            .collect();
+        format!("[{}] {}", values.join(", "), unit.symbol())
+    }
+
+    /// Formats the quantities as a vector in the unit from the
+    /// `ladder` that fits the largest magnitude, in the way of
+    /// [UnitParser::format_prefixed].
+    ///
+    /// # Panics
+    /// The function panics if the ladder is empty or contains
+    /// unknown symbols.
+    pub fn format_vector_prefixed(
+        &self,
+        values: &[Q],
+        ladder: &[&str],
+    ) -> Result<String, ParseUnitError> {
+        let base = self.expect_unit(ladder[0]);
+        let largest = values.iter().max_by(|lhs, rhs| {
+            let lhs = (base.to_value)(lhs).abs();
+            let rhs = (base.to_value)(rhs).abs();
+            lhs.total_cmp(&rhs)
+        });
+        let unit = match largest {
+            Some(value) => self.prefixed_unit(value, ladder),
+            None => base,
+        };
+        self.format_vector(values, unit)
+    }
+
+    /// Chooses the largest unit from the ladder where the value is not
+    /// less than one, or the smallest unit if none applies. Zero and
+    /// values that are not normal numbers use the unit of the example.
+    fn prefixed_unit(&self, value: &Q, ladder: &[&str]) -> &'static Unit<Q> {
+        let canonical = match self.match_suffix(self.example) {
+            Ok(Some((_, unit))) => unit,
+            _ => self.expect_unit(ladder[0]),
+        };
+        if !(canonical.to_value)(value).is_normal() {
+            return canonical;
+        }
+
+        let unit = ladder
+            .iter()
+            .rev()
+            .map(|symbol| self.expect_unit(symbol))
+            .find(|unit| {
+                let value = (unit.to_value)(value);
+                let ScientificNotation { exponent } = ScientificNotation::from_float(value);
+                value.is_normal() && exponent >= 0
+            });
+        match unit {
+            Some(unit) => unit,
+            None => self.expect_unit(ladder[0]),
+        }
+    }
+
+    /// Gets the value of `one` base unit in the unit with the
+    /// symbol, which fails if the symbol is unknown. The units of the
+    /// quantity must be proportional to each other.
+    pub(crate) fn factor(&self, symbol: &str, one: &Q) -> Result<f64, ParseUnitError> {
+        let unit = self
+            .find_unit(symbol)
+            .ok_or_else(|| ParseUnitError::UnknownUnit {
+                quantity: self.quantity,
+                text: symbol.to_string(),
+                example: self.example,
+            })?;
+        Ok((unit.to_value)(one))
+    }
+
+    /// Finds the unit with the symbol, or panics if it is unknown.
+    fn expect_unit(&self, symbol: &str) -> &'static Unit<Q> {
+        self.find_unit(symbol)
+            .unwrap_or_else(|| panic!("unknown {} unit '{symbol}'", self.quantity))
+    }
+
+    /// Finds the longest unit symbol that is a suffix of the text.
+    fn match_suffix<'a>(
+        &self,
+        text: &'a str,
+    ) -> Result<Option<(&'a str, &'static Unit<Q>)>, ParseUnitError> {
+        let mut best: Option<(usize, &'static Unit<Q>)> = None;
+        let mut ambiguous = vec![];
+
+        for unit in self.units {
+            for &sym in unit.symbols {
+                let Some(split) = text.len().checked_sub(sym.len()) else {
+                    continue;
+                };
+                if !text.is_char_boundary(split) || sym != &text[split..] {
+                    continue;
+                }
+
+                match best {
+                    Some((len, other)) if len == sym.len() && !std::ptr::eq(other, unit) => {
+                        ambiguous.push(sym);
+                    }
+                    Some((len, _)) if len >= sym.len() => {}
+                    _ => {
+                        best = Some((sym.len(), unit));
+                        ambiguous.clear();
+                    }
+                }
+            }
+        }
+
+        let Some((len, unit)) = best else {
+            return Ok(None);
+        };
+        if !ambiguous.is_empty() {
+            ambiguous.insert(0, unit.symbol());
+            return Err(ParseUnitError::AmbiguousUnit {
+                text: text.to_string(),
+                candidates: ambiguous,
+            });
+        }
+
+        let prefix = &text[..(text.len() - len)];
+        Ok(Some((prefix, unit)))
+    }
+}
+
+/// The finite numbers accepted by [parse_number] without the sign,
+/// in the regular expression dialect of JSON schemas.
+#[cfg(feature = "schemars")]
+pub(crate) const NUMBER_PATTERN: &str = r"(\d+(\.\d*)?|\.\d+)([eE][-+]?\d+)?";
+
+#[cfg(feature = "schemars")]
+impl<Q> UnitParser<Q> {
+    /// Generates the JSON schema of the values accepted by the
+    /// helper modules, which are number-unit strings or objects like
+    /// `{ "value": 3.5, "unit": "m" }`.
+    ///
+    /// The unit symbols are listed as they are, since the parser
+    /// matches them case-sensitively, so `"10M"` is rejected by both.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::unit::{Unit, UnitParser};
+    /// # use measurements::Length;
+    /// static PARSER: UnitParser<Length> = UnitParser::new(
+    ///     "length",
+    ///     "10.0m",
+    ///     &[
+    ///         Unit::new(&["m"], Length::from_meters, Length::as_meters),
+    ///         Unit::new(&["ft"], Length::from_feet, Length::as_feet),
+    ///     ],
+    /// );
+    ///
+    /// let schema = serde_json::to_value(PARSER.json_schema()).unwrap();
+    /// assert_eq!(
+    ///     schema["anyOf"][0]["pattern"],
+    ///     r"^\s*[-+]?(\d+(\.\d*)?|\.\d+)([eE][-+]?\d+)?\s*(m|ft)\s*$"
+    /// );
+    /// assert_eq!(schema["anyOf"][1]["properties"]["unit"]["enum"], serde_json::json!(["m", "ft"]));
+    /// assert!(PARSER.parse("10M").is_err());
+    /// ```
+    pub fn json_schema(&self) -> Schema {
+        self.signed_json_schema(true)
+    }
+
+    /// Generates the JSON schema in the way of
+    /// [UnitParser::json_schema], except that negative values are
+    /// rejected.
+    pub(crate) fn non_negative_json_schema(&self) -> Schema {
+        self.signed_json_schema(false)
+    }
+
+    /// Gets the pattern matching any of the unit symbols, like
+    /// `(m|ft)`.
+    pub(crate) fn symbol_pattern(&self) -> String {
+        let symbols: Vec<_> = self
+            .units
+            .iter()
+            .flat_map(|unit| unit.symbols)
+            .map(|symbol| escape_pattern(symbol))
+            .collect();
+        format!("({})", symbols.join("|"))
+    }
+
+    fn signed_json_schema(&self, signed: bool) -> Schema {
+        let sign = if signed { "[-+]?" } else { r"\+?" };
+        let text = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(format!(
+                    r"^\s*{sign}{NUMBER_PATTERN}\s*{}\s*$",
+                    self.symbol_pattern()
+                )),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let value = SchemaObject {
+            instance_type: Some(InstanceType::Number.into()),
+            number: (!signed).then(|| {
+                Box::new(NumberValidation {
+                    minimum: Some(0.0),
+                    ..Default::default()
+                })
+            }),
+            ..Default::default()
+        };
+        let unit = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(
+                self.units
+                    .iter()
+                    .flat_map(|unit| unit.symbols)
+                    .map(|&symbol| symbol.into())
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let object = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(ObjectValidation {
+                properties: [("value", value), ("unit", unit)]
+                    .into_iter()
+                    .map(|(name, schema)| (name.to_string(), schema.into()))
+                    .collect(),
+                required: ["value", "unit"].into_iter().map(String::from).collect(),
+                additional_properties: Some(Box::new(Schema::Bool(false))),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        let Self {
+            quantity, example, ..
+        } = self;
+        let mut description = format!("{quantity} with units, for example, '{example}'.");
+        description[..1].make_ascii_uppercase();
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(description),
+                examples: vec![(*example).into()],
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![text.into(), object.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Escapes the special characters of regular expressions.
+#[cfg(feature = "schemars")]
+pub(crate) fn escape_pattern(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if r"\^$.|?*+()[]{}".contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Formats a number, in scientific notation if the magnitude is very
+/// large or small.
+fn format_number(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() || (1e-3..=1e3).contains(&value.abs()) {
+        format!("{value}")
+    } else {
+        format!("{value:e}")
+    }
+}
+
+/// Parses a finite floating number with surrounding whitespace.
+pub(crate) fn parse_number(text: &str) -> Result<f64, ParseUnitError> {
+    let text = text.trim();
+    let invalid = || ParseUnitError::InvalidNumber {
+        text: text.to_string(),
+    };
+    let value: f64 = text.parse().map_err(|_| invalid())?;
+    if !value.is_finite() {
+        return Err(invalid());
+    }
+    Ok(value)
+}
+
+/// The error returned by [UnitParser].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseUnitError {
+    InvalidNumber {
+        text: String,
+    },
+    UnknownUnit {
+        quantity: &'static str,
+        text: String,
+        example: &'static str,
+    },
+    AmbiguousUnit {
+        text: String,
+        candidates: Vec<&'static str>,
+    },
+    InvalidVector {
+        text: String,
+    },
+    OutOfRange {
+        quantity: &'static str,
+        text: String,
+    },
+}
+
+impl Display for ParseUnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseUnitError::InvalidNumber { text } => {
+                write!(f, "'{text}' is not a valid number")
+            }
+            ParseUnitError::UnknownUnit {
+                quantity,
+                text,
+                example,
+            } => write!(
+                f,
+                "Unable to parse '{text}' as a {quantity} measure. \
+                 It must be a floating number plus a {quantity} unit, for example, '{example}'."
+            ),
+            ParseUnitError::AmbiguousUnit { text, candidates } => write!(
+                f,
+                "The unit in '{text}' is ambiguous. It can be one of {}.",
+                candidates.join(", ")
+            ),
+            ParseUnitError::OutOfRange { quantity, text } => {
+                write!(f, "The {quantity} '{text}' is out of the range of finite numbers.")
+            }
+            ParseUnitError::InvalidVector { text } => write!(
+                f,
+                "'{text}' is not a valid vector. \
+                 It must be comma-separated numbers in brackets plus a unit, for example, '[1.0, 2.5, -0.2] m'."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseUnitError {}
@@ -0,0 +1,122 @@
+//! Serialization helper to en/decode a volume value with units.
+//!
+//! Accepted units are `mL`, `L`, `kL`, `cm^3` (or `cc`), `m^3`, `in^3`,
+//! `ft^3` and `gal`. The cubic units are also written as `m3` or
+//! `m³`. Volumes are serialized in the liter unit that fits the
+//! magnitude best.
+//!
+//! Formats that are not human-readable, such as bincode, store a bare
+//! number in cubic meters instead.
+//!
+//! ```rust
+//! # use newslab_serde_measurements::volume;
+//! # use serde::{Serialize, Deserialize};
+//! # use measurements::Volume;
+//! #[derive(Serialize, Deserialize)]
+//! struct MyTank {
+//!     #[serde(with = "volume")]
+//!     coolant: Volume,
+//!     #[serde(with = "volume")]
+//!     washer: Volume,
+//!     #[serde(with = "volume")]
+//!     cargo: Volume,
+//! }
+//!
+//! let json = r#"{ "coolant": "3L", "washer": "500mL", "cargo": "2m^3" }"#;
+//! let tank: MyTank = serde_json::from_str(json).unwrap();
+//!
+//! assert_eq!(tank.coolant.as_liters(), 3.0);
+//! assert!((tank.washer.as_milliliters() - 500.0).abs() < 1e-9);
+//! assert_eq!(tank.cargo.as_cubic_meters(), 2.0);
+//!
+//! let json = serde_json::to_string(&tank).unwrap();
+//! assert!(json.contains(r#""coolant":"3L""#));
+//! assert!(json.contains(r#""cargo":"2kL""#));
+//! ```
+
+use crate::unit::{Unit, UnitParser};
+use measurements::Volume;
+use serde::{de::Error as _, Deserializer, Serialize, Serializer};
+
+pub(crate) static PARSER: UnitParser<Volume> = UnitParser::new(
+    "volume",
+    "3.0L",
+    &[
+        Unit::new(
+            &["mL", "ml"],
+            Volume::from_milliliters,
+            Volume::as_milliliters,
+        ),
+        Unit::new(&["L", "l"], Volume::from_liters, Volume::as_liters),
+        Unit::new(
+            &["kL", "kl"],
+            Volume::from_cubic_meters,
+            Volume::as_cubic_meters,
+        ),
+        Unit::new(
+            &["cm^3", "cm3", "cm³", "cc"],
+            Volume::from_cubic_centimeters,
+            Volume::as_cubic_centimeters,
+        ),
+        Unit::new(
+            &["m^3", "m3", "m³"],
+            Volume::from_cubic_meters,
+            Volume::as_cubic_meters,
+        ),
+        Unit::new(
+            &["in^3", "in3", "in³"],
+            Volume::from_cubic_inches,
+            Volume::as_cubic_inches,
+        ),
+        Unit::new(
+            &["ft^3", "ft3", "ft³"],
+            Volume::from_cubic_feet,
+            Volume::as_cubic_feet,
+        ),
+        Unit::new(&["gal"], Volume::from_gallons, Volume::as_gallons),
+    ],
+);
+
+/// The units chosen in serialization, from the smallest to the largest.
+pub(crate) const LADDER: &[&str] = &["mL", "L", "kL"];
+
+pub fn serialize<S>(volume: &Volume, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if crate::compat::stores_bare_number(serializer.is_human_readable()) {
+        return volume.as_cubic_meters().serialize(serializer);
+    }
+    PARSER
+        .format_prefixed(volume, LADDER)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Volume, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if crate::compat::stores_bare_number(deserializer.is_human_readable()) {
+        return crate::compat::deserialize_bare_number(deserializer).map(Volume::from_cubic_meters);
+    }
+    let text = crate::combinators::deserialize_text(deserializer)?;
+    PARSER.parse(&text).map_err(D::Error::custom)
+}
+
+/// Generates the JSON schema of the values accepted by this module.
+/// It is intended to be used in
+/// `#[schemars(schema_with = "volume::json_schema")]`.
+#[cfg(feature = "schemars")]
+pub fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+    PARSER.json_schema()
+}
+
+unit_combinators!(Volume);
+
+pinned_units! {
+    Volume;
+    in_milliliters => "mL",
+    in_liters => "L",
+    in_cubic_meters => "m^3",
+}
@@ -0,0 +1,189 @@
+//! Wrapper types serialized in the format of the unit helper modules.
+
+use measurements::{
+    Acceleration, Angle, Area, Frequency, Length, Mass, Pressure, Speed, Temperature, Volume,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+/// Defines a wrapper type around a quantity that is serialized by
+/// the helper module.
+macro_rules! serde_wrapper {
+    ($(#[$attr:meta])* $name:ident($ty:ty) with $module:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name(pub $ty);
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                crate::$module::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                crate::$module::deserialize(deserializer).map(Self)
+            }
+        }
+
+        #[cfg(feature = "schemars")]
+        impl schemars::JsonSchema for $name {
+            fn schema_name() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn json_schema(gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+                crate::$module::json_schema(gen)
+            }
+        }
+
+        impl Deref for $name {
+            type Target = $ty;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl From<$ty> for $name {
+            fn from(value: $ty) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $ty {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+/// Implements `Arbitrary` for wrapper types, which are built from
+/// finite values in the base unit.
+#[cfg(feature = "arbitrary")]
+macro_rules! arbitrary_wrapper {
+    ($($name:ident => $from_base:path,)*) => {
+        $(
+            impl<'a> arbitrary::Arbitrary<'a> for $name {
+                fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                    crate::arbitrary_finite(u).map(|value| Self($from_base(value)))
+                }
+            }
+        )*
+    };
+}
+
+serde_wrapper! {
+    /// An [Acceleration] serialized like the [acceleration](crate::acceleration) module.
+    SerdeAcceleration(Acceleration) with acceleration
+}
+
+serde_wrapper! {
+    /// An [Angle] serialized like the [angle](crate::angle) module.
+    ///
+    /// Unlike `#[serde(with = "angle")]`, it works inside any
+    /// container and enum.
+    ///
+    /// ```rust
+    /// # use newslab_serde_measurements::SerdeAngle;
+    /// # use serde::{Serialize, Deserialize};
+    /// # use std::collections::HashMap;
+    /// #[derive(Serialize, Deserialize)]
+    /// enum MyLimit {
+    ///     Symmetric(SerdeAngle),
+    ///     Range { min: SerdeAngle, max: SerdeAngle },
+    /// }
+    ///
+    /// let json = r#"{ "pan": { "Symmetric": "3deg" }, "tilt": { "Range": { "min": "-1rad", "max": "0rad" } } }"#;
+    /// let limits: HashMap<String, MyLimit> = serde_json::from_str(json).unwrap();
+    ///
+    /// let MyLimit::Symmetric(pan) = &limits["pan"] else { panic!() };
+    /// assert_eq!(pan.as_degrees(), 3.0);
+    ///
+    /// let offsets: Vec<Option<SerdeAngle>> = serde_json::from_str(r#"["3deg", null]"#).unwrap();
+    /// assert_eq!(serde_json::to_string(&offsets).unwrap(), r#"["3deg",null]"#);
+    /// ```
+    SerdeAngle(Angle) with angle
+}
+
+serde_wrapper! {
+    /// An [Area] serialized like the [area](crate::area) module.
+    SerdeArea(Area) with area
+}
+
+serde_wrapper! {
+    /// A [Duration] serialized like the [duration](crate::duration) module.
+    SerdeDuration(Duration) with duration
+}
+
+serde_wrapper! {
+    /// A [Frequency] serialized like the [frequency](crate::frequency) module.
+    SerdeFrequency(Frequency) with frequency
+}
+
+serde_wrapper! {
+    /// A [Length] serialized like the [length](crate::length) module.
+    SerdeLength(Length) with length
+}
+
+serde_wrapper! {
+    /// A [Mass] serialized like the [mass](crate::mass) module.
+    SerdeMass(Mass) with mass
+}
+
+serde_wrapper! {
+    /// A [Pressure] serialized like the [pressure](crate::pressure) module.
+    SerdePressure(Pressure) with pressure
+}
+
+serde_wrapper! {
+    /// A [Speed] serialized like the [speed](crate::speed) module.
+    SerdeSpeed(Speed) with speed
+}
+
+serde_wrapper! {
+    /// A [Temperature] serialized like the [temperature](crate::temperature) module.
+    SerdeTemperature(Temperature) with temperature
+}
+
+serde_wrapper! {
+    /// A [Volume] serialized like the [volume](crate::volume) module.
+    SerdeVolume(Volume) with volume
+}
+
+#[cfg(feature = "arbitrary")]
+arbitrary_wrapper! {
+    SerdeAcceleration => Acceleration::from_meters_per_second_per_second,
+    SerdeAngle => Angle::from_radians,
+    SerdeArea => Area::from_square_meters,
+    SerdeFrequency => Frequency::from_hertz,
+    SerdeLength => Length::from_meters,
+    SerdeMass => Mass::from_kilograms,
+    SerdePressure => Pressure::from_pascals,
+    SerdeSpeed => Speed::from_meters_per_second,
+    SerdeTemperature => Temperature::from_kelvin,
+    SerdeVolume => Volume::from_cubic_meters,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for SerdeDuration {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(Duration::from_nanos(u.arbitrary()?)))
+    }
+}
@@ -0,0 +1,199 @@
+//! Reader and writer for the [g2o](https://github.com/RainerKuemmerle/g2o)
+//! text format.
+//!
+//! Only 3D pose graphs are supported, which consist of
+//! `VERTEX_SE3:QUAT`, `EDGE_SE3:QUAT` and `FIX` records. A pose is
+//! written as `x y z qx qy qz qw` and an information matrix as the 21
+//! values of its upper triangle in row-major order.
+//!
+//! ```rust
+//! # use newslab_serde_nalgebra::g2o::{self, Record};
+//! let text = "\
+//! ## A single edge graph
+//! VERTEX_SE3:QUAT 0 0 0 0 0 0 0 1
+//! VERTEX_SE3:QUAT 1 1 0 0 0 0 0 1
+//! FIX 0
+//! EDGE_SE3:QUAT 0 1 1 0 0 0 0 0 1 1 0 0 0 0 0 1 0 0 0 0 1 0 0 0 1 0 0 1 0 1
+//! ";
+//! let records = g2o::parse(text).unwrap();
+//! assert_eq!(records.len(), 4);
+//! assert_eq!(records[2], Record::Fix { ids: vec![0] });
+//! assert_eq!(g2o::parse(&g2o::write(&records)).unwrap(), records);
+//!
+//! // Vertex ids become node names.
+//! let graph = g2o::to_graph(&records).unwrap();
+//! assert!(graph.node("1").is_some());
+//! ```
+
+use crate::{
+    records::{parse_lines, write_values, Tokens},
+    PoseEdge, PoseGraph, PoseNode,
+};
+use anyhow::Result;
+use nalgebra::{Isometry3, Matrix6, Quaternion, Translation3, UnitQuaternion};
+use std::{collections::HashMap, fmt::Write as _};
+
+/// A record in a g2o file.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Record {
+    /// A `VERTEX_SE3:QUAT` record.
+    Vertex { id: u64, pose: Isometry3<f64> },
+    /// An `EDGE_SE3:QUAT` record.
+    Edge {
+        from: u64,
+        to: u64,
+        measurement: Isometry3<f64>,
+        information: Matrix6<f64>,
+    },
+    /// A `FIX` record which holds vertices fixed in optimization.
+    Fix { ids: Vec<u64> },
+}
+
+/// Parses g2o text into records. Empty lines and comments are
+/// skipped.
+pub fn parse(text: &str) -> Result<Vec<Record>> {
+    parse_lines("g2o", text, |tag, tokens| {
+        let record = match tag {
+            "VERTEX_SE3:QUAT" => Record::Vertex {
+                id: tokens.id()?,
+                pose: parse_pose(tokens)?,
+            },
+            "EDGE_SE3:QUAT" => Record::Edge {
+                from: tokens.id()?,
+                to: tokens.id()?,
+                measurement: parse_pose(tokens)?,
+                information: parse_information(tokens)?,
+            },
+            "FIX" => Record::Fix { ids: tokens.ids()? },
+            _ => return Ok(None),
+        };
+        Ok(Some(record))
+    })
+}
+
+/// Renders records in g2o text format, one record per line.
+pub fn write(records: &[Record]) -> String {
+    let mut text = String::new();
+
+    for record in records {
+        match record {
+            Record::Vertex { id, pose } => {
+                write!(text, "VERTEX_SE3:QUAT {id}").unwrap();
+                write_pose(&mut text, pose);
+            }
+            Record::Edge {
+                from,
+                to,
+                measurement,
+                information,
+            } => {
+                write!(text, "EDGE_SE3:QUAT {from} {to}").unwrap();
+                write_pose(&mut text, measurement);
+                write_information(&mut text, information);
+            }
+            Record::Fix { ids } => {
+                text.push_str("FIX");
+                for id in ids {
+                    write!(text, " {id}").unwrap();
+                }
+            }
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Converts a pose graph to records. g2o identifies vertices by
+/// integers, so nodes are numbered by their order in the graph and
+/// their names are not preserved.
+pub fn from_graph(graph: &PoseGraph) -> Vec<Record> {
+    let index: HashMap<&str, u64> = graph
+        .nodes()
+        .iter()
+        .zip(0..)
+        .map(|(node, id)| (node.name.as_str(), id))
+        .collect();
+
+    let vertices = graph
+        .nodes()
+        .iter()
+        .zip(0..)
+        .map(|(node, id)| Record::Vertex {
+            id,
+            pose: node.pose,
+        });
+    let edges = graph.edges().iter().map(|edge| Record::Edge {
+        from: index[edge.from.as_str()],
+        to: index[edge.to.as_str()],
+        measurement: edge.measurement,
+        information: edge.information,
+    });
+
+    vertices.chain(edges).collect()
+}
+
+/// Builds a pose graph from records. Vertex ids become node names
+/// and `FIX` records are ignored.
+pub fn to_graph(records: &[Record]) -> Result<PoseGraph> {
+    let mut nodes = vec![];
+    let mut edges = vec![];
+
+    for record in records {
+        match record {
+            Record::Vertex { id, pose } => nodes.push(PoseNode {
+                name: id.to_string(),
+                pose: *pose,
+            }),
+            Record::Edge {
+                from,
+                to,
+                measurement,
+                information,
+            } => edges.push(PoseEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                measurement: *measurement,
+                information: *information,
+            }),
+            Record::Fix { .. } => {}
+        }
+    }
+
+    PoseGraph::new(nodes, edges)
+}
+
+fn parse_pose(tokens: &mut Tokens<'_>) -> Result<Isometry3<f64>> {
+    let [x, y, z, qx, qy, qz, qw] = tokens.values()?;
+
+    Ok(Isometry3::from_parts(
+        Translation3::new(x, y, z),
+        UnitQuaternion::from_quaternion(Quaternion::new(qw, qx, qy, qz)),
+    ))
+}
+
+fn write_pose(text: &mut String, pose: &Isometry3<f64>) {
+    let t = &pose.translation.vector;
+    let q = &pose.rotation.coords;
+    write_values(text, [t.x, t.y, t.z, q.x, q.y, q.z, q.w]);
+}
+
+/// Parses the upper triangle of a 6x6 symmetric matrix.
+pub(crate) fn parse_information(tokens: &mut Tokens<'_>) -> Result<Matrix6<f64>> {
+    let mut information = Matrix6::zeros();
+    for row in 0..6 {
+        for col in row..6 {
+            let value = tokens.value()?;
+            information[(row, col)] = value;
+            information[(col, row)] = value;
+        }
+    }
+    Ok(information)
+}
+
+/// Writes the upper triangle of a 6x6 symmetric matrix.
+pub(crate) fn write_information(text: &mut String, information: &Matrix6<f64>) {
+    let values = (0..6).flat_map(|row| (row..6).map(move |col| information[(row, col)]));
+    write_values(text, values);
+}
@@ -1,8 +1,59 @@
 //! Data ser/deserialization library for [nalgebra] crate.
 
+pub use pose_graph::{PoseEdge, PoseGraph, PoseNode};
+mod pose_graph;
+
+pub use sparsity_pattern::{SparseBlock, SparsityPattern};
+mod sparsity_pattern;
+
+pub use state_layout::{StateBlock, StateKind, StateLayout};
+mod state_layout;
+
+pub mod g2o;
+mod records;
+pub mod toro;
+
+/// Casts a number to `f64` for serialization, which fails if the
+/// number is not representable.
+fn to_f64<T, E>(value: T) -> Result<f64, E>
+where
+    T: num::NumCast,
+    E: serde::ser::Error,
+{
+    num::cast(value).ok_or_else(|| E::custom("the number is not representable as f64"))
+}
+
+/// Creates Euler angles from radians for serialization, which fails
+/// if an angle is not representable as `f64`.
+fn euler_angles<T, E>(
+    roll: T,
+    pitch: T,
+    yaw: T,
+) -> Result<newslab_serde_measurements::EulerAngles, E>
+where
+    T: num::NumCast,
+    E: serde::ser::Error,
+{
+    newslab_serde_measurements::EulerAngles::from_radians(roll, pitch, yaw)
+        .ok_or_else(|| E::custom("the rotation is not representable as f64"))
+}
+
+/// Casts a deserialized `f64` to the scalar type, which fails if the
+/// number is not representable or a finite number overflows to
+/// infinity, as casts to `f32` saturate.
+fn from_f64<T, E>(value: f64) -> Result<T, E>
+where
+    T: num::NumCast,
+    E: serde::de::Error,
+{
+    num::cast::<_, T>(value)
+        .filter(|output| output.to_f64().map(f64::is_finite) == Some(value.is_finite()))
+        .ok_or_else(|| E::custom(format!("{value} is not representable in the scalar type")))
+}
 
 /// Serialize [Isometry3](nalgebra::Isometry3) as a (x, y, z) position
-/// and a triple of (roll, pitch, yaw) angles.
+/// and a triple of (roll, pitch, yaw) angles. The rotation order of
+/// [EulerAngles](newslab_serde_measurements::EulerAngles) is honored.
 ///
 /// ```rust
 /// # use serde::{Deserialize, Serialize};
@@ -28,9 +79,7 @@
 /// let _: MyRotation = serde_json::from_str(json).unwrap();
 /// ```
 pub mod isometry3_as_euler_angles {
-    use nalgebra::{
-        coordinates::XYZ, Isometry3, RealField, SimdRealField, Translation3, UnitQuaternion,
-    };
+    use nalgebra::{coordinates::XYZ, Isometry3, RealField, SimdRealField, Translation3};
     use newslab_serde_measurements::EulerAngles;
     use num::NumCast;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -53,10 +102,11 @@ pub mod isometry3_as_euler_angles {
         } = rot;
         let XYZ { x, y, z } = (**translation).clone();
         let (roll, pitch, yaw) = rotation.euler_angles();
+        let rotation = crate::euler_angles::<_, S::Error>(roll, pitch, yaw)?;
 
         EulerIsometry3 {
             translation: [x, y, z],
-            rotation: EulerAngles::from_radians(roll, pitch, yaw),
+            rotation,
         }
         .serialize(serializer)
     }
@@ -70,10 +120,114 @@ pub mod isometry3_as_euler_angles {
             translation: [x, y, z],
             rotation: angles,
         } = EulerIsometry3::deserialize(deserializer)?;
-        let [roll, pitch, yaw] = angles.to_radians::<T>();
-
         let translation = Translation3::new(x, y, z);
-        let rotation = UnitQuaternion::from_euler_angles(roll, pitch, yaw);
+        let rotation = angles.to_unit_quaternion().cast::<T>();
+        let isometry = Isometry3 {
+            translation,
+            rotation,
+        };
+        Ok(isometry)
+    }
+}
+
+/// Serialize [Isometry3](nalgebra::Isometry3) in meters as a (x, y, z)
+/// position with length units and a triple of (roll, pitch, yaw)
+/// angles, in the way of [isometry3_as_euler_angles]. Each component
+/// of the position can have a different unit.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::{Isometry3, Vector3};
+/// # use newslab_serde_nalgebra::isometry3_with_units;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyMount {
+///     #[serde(with = "isometry3_with_units")]
+///     pose: Isometry3<f64>,
+/// }
+///
+/// let json = r#"{
+///     "pose": {
+///         "translation": ["1.5m", "30cm", "-2mm"],
+///         "rotation": {
+///             "roll": "0.0deg",
+///             "pitch": "0.0deg",
+///             "yaw": "90.0deg"
+///         }
+///     }
+/// }"#;
+///
+/// let mount: MyMount = serde_json::from_str(json).unwrap();
+/// let translation = mount.pose.translation.vector;
+/// assert!((translation - Vector3::new(1.5, 0.3, -0.002)).norm() < 1e-12);
+///
+/// let json = serde_json::to_value(&mount).unwrap();
+/// assert_eq!(json["pose"]["translation"], serde_json::json!(["1.5m", "300mm", "-2mm"]));
+///
+/// // Positions that overflow the scalar type are rejected.
+/// #[derive(Deserialize)]
+/// struct MySmallMount {
+///     #[serde(with = "isometry3_with_units")]
+///     pose: Isometry3<f32>,
+/// }
+///
+/// let json = r#"{
+///     "pose": {
+///         "translation": ["1e300m", "0m", "0m"],
+///         "rotation": { "roll": "0deg", "pitch": "0deg", "yaw": "0deg" }
+///     }
+/// }"#;
+/// assert!(serde_json::from_str::<MySmallMount>(json).is_err());
+/// ```
+pub mod isometry3_with_units {
+    use measurements::Length;
+    use nalgebra::{coordinates::XYZ, Isometry3, RealField, SimdRealField, Translation3};
+    use newslab_serde_measurements::{EulerAngles, SerdeLength};
+    use num::NumCast;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct UnitIsometry3 {
+        pub translation: [SerdeLength; 3],
+        pub rotation: EulerAngles,
+    }
+
+    pub fn serialize<S, T>(pose: &Isometry3<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: SimdRealField + RealField + NumCast,
+        T::Element: SimdRealField,
+        S: Serializer,
+    {
+        let Isometry3 {
+            translation,
+            rotation,
+        } = pose;
+        let XYZ { x, y, z } = (**translation).clone();
+        let to_length = |meters: T| -> Result<_, S::Error> {
+            Ok(SerdeLength(Length::from_meters(crate::to_f64(meters)?)))
+        };
+        let (roll, pitch, yaw) = rotation.euler_angles();
+        let rotation = crate::euler_angles::<_, S::Error>(roll, pitch, yaw)?;
+
+        UnitIsometry3 {
+            translation: [to_length(x)?, to_length(y)?, to_length(z)?],
+            rotation,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Isometry3<T>, D::Error>
+    where
+        T: SimdRealField + RealField + NumCast,
+        D: Deserializer<'de>,
+    {
+        let UnitIsometry3 {
+            translation: [x, y, z],
+            rotation: angles,
+        } = UnitIsometry3::deserialize(deserializer)?;
+        let to_meters =
+            |len: SerdeLength| -> Result<T, D::Error> { crate::from_f64(len.as_meters()) };
+        let translation = Translation3::new(to_meters(x)?, to_meters(y)?, to_meters(z)?);
+        let rotation = angles.to_unit_quaternion().cast::<T>();
         let isometry = Isometry3 {
             translation,
             rotation,
@@ -83,7 +237,8 @@ pub mod isometry3_as_euler_angles {
 }
 
 /// Serialize [UnitQuaternion](nalgebra::UnitQuaternion) as the triple
-/// of (roll, pitch, yaw) angles.
+/// of (roll, pitch, yaw) angles. The rotation order of
+/// [EulerAngles](newslab_serde_measurements::EulerAngles) is honored.
 ///
 /// ```rust
 /// # use serde::{Deserialize, Serialize};
@@ -117,7 +272,7 @@ pub mod unit_quaternion_as_euler_angles {
         S: Serializer,
     {
         let (roll, pitch, yaw) = rot.euler_angles();
-        EulerAngles::from_radians(roll, pitch, yaw).serialize(serializer)
+        crate::euler_angles::<_, S::Error>(roll, pitch, yaw)?.serialize(serializer)
     }
 
     pub fn deserialize<'de, D, T>(deserializer: D) -> Result<UnitQuaternion<T>, D::Error>
@@ -126,8 +281,956 @@ pub mod unit_quaternion_as_euler_angles {
         D: Deserializer<'de>,
     {
         let angles = EulerAngles::deserialize(deserializer)?;
-        let [roll, pitch, yaw] = angles.to_radians();
-        let rot = UnitQuaternion::from_euler_angles(roll, pitch, yaw);
-        Ok(rot)
+        Ok(angles.to_unit_quaternion().cast::<T>())
+    }
+}
+
+/// Serialize [Isometry2](nalgebra::Isometry2) as a (x, y) position and
+/// a heading angle.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::{Isometry2, Vector2};
+/// # use newslab_serde_nalgebra::isometry2_as_heading;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyVehicle {
+///     #[serde(with = "isometry2_as_heading")]
+///     pose: Isometry2<f64>,
+/// }
+///
+/// let json = r#"{
+///     "pose": {
+///         "translation": [1.5, -2.0],
+///         "rotation": "90deg"
+///     }
+/// }"#;
+///
+/// let vehicle: MyVehicle = serde_json::from_str(json).unwrap();
+/// assert_eq!(vehicle.pose.translation.vector, Vector2::new(1.5, -2.0));
+/// assert!((vehicle.pose.rotation.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+///
+/// let json = serde_json::to_string(&vehicle).unwrap();
+/// assert_eq!(json, r#"{"pose":{"translation":[1.5,-2.0],"rotation":"90deg"}}"#);
+/// ```
+pub mod isometry2_as_heading {
+    use measurements::Angle;
+    use nalgebra::{Isometry2, RealField, Translation2, UnitComplex};
+    use num::NumCast;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct HeadingIsometry2<T> {
+        pub translation: [T; 2],
+        #[serde(with = "newslab_serde_measurements::angle")]
+        pub rotation: Angle,
+    }
+
+    pub fn serialize<S, T>(pose: &Isometry2<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: RealField + Serialize + NumCast,
+        S: Serializer,
+    {
+        let Isometry2 {
+            translation,
+            rotation,
+        } = pose;
+        let heading: f64 = crate::to_f64::<_, S::Error>(rotation.angle())?;
+
+        HeadingIsometry2 {
+            translation: [translation.x.clone(), translation.y.clone()],
+            rotation: Angle::from_radians(heading),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Isometry2<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        let HeadingIsometry2 {
+            translation: [x, y],
+            rotation,
+        } = HeadingIsometry2::deserialize(deserializer)?;
+        let heading: T = crate::from_f64(rotation.as_radians())?;
+        let isometry = Isometry2 {
+            translation: Translation2::new(x, y),
+            rotation: UnitComplex::new(heading),
+        };
+        Ok(isometry)
+    }
+}
+
+/// Serialize [UnitComplex](nalgebra::UnitComplex) as a 2D rotation
+/// angle, like `"90deg"`.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::UnitComplex;
+/// # use newslab_serde_nalgebra::unit_complex_as_angle;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyHeading {
+///     #[serde(with = "unit_complex_as_angle")]
+///     heading: UnitComplex<f32>,
+/// }
+///
+/// let json = r#"{ "heading": "-0.5rad" }"#;
+/// let heading: MyHeading = serde_json::from_str(json).unwrap();
+/// assert!((heading.heading.angle() + 0.5).abs() < 1e-6);
+/// ```
+pub mod unit_complex_as_angle {
+    use measurements::Angle;
+    use nalgebra::{RealField, UnitComplex};
+    use num::NumCast;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S, T>(rot: &UnitComplex<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: RealField + NumCast,
+        S: Serializer,
+    {
+        let angle: f64 = crate::to_f64::<_, S::Error>(rot.angle())?;
+        newslab_serde_measurements::angle::serialize(&Angle::from_radians(angle), serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<UnitComplex<T>, D::Error>
+    where
+        T: RealField + NumCast,
+        D: Deserializer<'de>,
+    {
+        let angle = newslab_serde_measurements::angle::deserialize(deserializer)?;
+        let angle: T = crate::from_f64(angle.as_radians())?;
+        Ok(UnitComplex::new(angle))
+    }
+}
+
+/// Serialize [UnitQuaternion](nalgebra::UnitQuaternion) as the
+/// `{ "w": .., "x": .., "y": .., "z": .. }` components, which is
+/// lossless unlike Euler angles. The [array](unit_quaternion_as_wxyz::array)
+/// submodule writes `[w, x, y, z]` arrays instead. Both forms are
+/// deserialized.
+///
+/// The norm of a deserialized quaternion must be within
+/// [DEFAULT_TOLERANCE](unit_quaternion_as_wxyz::DEFAULT_TOLERANCE) of one,
+/// and it is renormalized. Use
+/// [deserialize_with_tolerance](unit_quaternion_as_wxyz::deserialize_with_tolerance)
+/// to configure the tolerance.
+///
+/// ```rust
+/// # use serde::{Deserialize, Deserializer, Serialize};
+/// # use nalgebra::UnitQuaternion;
+/// # use newslab_serde_nalgebra::unit_quaternion_as_wxyz;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyImu {
+///     #[serde(with = "unit_quaternion_as_wxyz")]
+///     orientation: UnitQuaternion<f64>,
+///     #[serde(with = "unit_quaternion_as_wxyz::array")]
+///     mounting: UnitQuaternion<f64>,
+/// }
+///
+/// let json = r#"{
+///     "orientation": { "w": 0.7071068, "x": 0.0, "y": 0.0, "z": 0.7071068 },
+///     "mounting": [1.0, 0.0, 0.0, 0.0]
+/// }"#;
+/// let imu: MyImu = serde_json::from_str(json).unwrap();
+/// assert!((imu.orientation.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+/// assert_eq!(
+///     serde_json::to_string(&imu).unwrap(),
+///     r#"{"orientation":{"w":0.7071067811865476,"x":0.0,"y":0.0,"z":0.7071067811865476},"mounting":[1.0,0.0,0.0,0.0]}"#
+/// );
+///
+/// // The quaternion is far from normalized.
+/// let json = r#"{ "orientation": [2.0, 0.0, 0.0, 0.0], "mounting": [1.0, 0.0, 0.0, 0.0] }"#;
+/// assert!(serde_json::from_str::<MyImu>(json).is_err());
+///
+/// fn loose<'de, D>(deserializer: D) -> Result<UnitQuaternion<f64>, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     unit_quaternion_as_wxyz::deserialize_with_tolerance(deserializer, 1e-2)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct MyLooseImu {
+///     #[serde(deserialize_with = "loose")]
+///     orientation: UnitQuaternion<f64>,
+/// }
+///
+/// let json = r#"{ "orientation": [0.995, 0.0, 0.0, 0.0] }"#;
+/// let imu: MyLooseImu = serde_json::from_str(json).unwrap();
+/// assert_eq!(imu.orientation, UnitQuaternion::identity());
+///
+/// let rot = UnitQuaternion::from_euler_angles(0.1f32, 0.2, 0.3);
+/// let bytes = bincode::serialize(&MyImu { orientation: rot.cast(), mounting: rot.cast() }).unwrap();
+/// assert_eq!(bytes.len(), 64);
+/// ```
+pub mod unit_quaternion_as_wxyz {
+    use nalgebra::{Quaternion, RealField, UnitQuaternion};
+    use num::NumCast;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The default tolerance of the deviation of the norm from one.
+    pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+    #[derive(Serialize)]
+    struct Wxyz<T> {
+        w: T,
+        x: T,
+        y: T,
+        z: T,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyWxyz<T> {
+        Object { w: T, x: T, y: T, z: T },
+        Array([T; 4]),
+    }
+
+    pub fn serialize<S, T>(rot: &UnitQuaternion<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: RealField + Serialize,
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return array::serialize(rot, serializer);
+        }
+        let [x, y, z, w] = rot.coords.clone().into();
+        Wxyz { w, x, y, z }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<UnitQuaternion<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        deserialize_with_tolerance(deserializer, DEFAULT_TOLERANCE)
+    }
+
+    /// Deserializes a quaternion whose norm is within the `tolerance`
+    /// of one, and renormalizes it.
+    pub fn deserialize_with_tolerance<'de, D, T>(
+        deserializer: D,
+        tolerance: f64,
+    ) -> Result<UnitQuaternion<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        let [w, x, y, z] = if deserializer.is_human_readable() {
+            match AnyWxyz::deserialize(deserializer)? {
+                AnyWxyz::Object { w, x, y, z } => [w, x, y, z],
+                AnyWxyz::Array(wxyz) => wxyz,
+            }
+        } else {
+            <[T; 4]>::deserialize(deserializer)?
+        };
+
+        let quat = Quaternion::new(w, x, y, z);
+        let norm: f64 = num::cast(quat.norm()).unwrap_or(f64::NAN);
+        if !norm.is_finite() || (norm - 1.0).abs() > tolerance {
+            return Err(D::Error::custom(format!(
+                "the norm {norm} of the quaternion deviates from 1 by more than {tolerance}"
+            )));
+        }
+        Ok(UnitQuaternion::new_normalize(quat))
+    }
+
+    /// Serialize [UnitQuaternion](nalgebra::UnitQuaternion) as a
+    /// `[w, x, y, z]` array. Both forms are deserialized.
+    pub mod array {
+        use super::*;
+
+        pub fn serialize<S, T>(rot: &UnitQuaternion<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: RealField + Serialize,
+            S: Serializer,
+        {
+            let [x, y, z, w] = rot.coords.clone().into();
+            [w, x, y, z].serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D, T>(deserializer: D) -> Result<UnitQuaternion<T>, D::Error>
+        where
+            T: RealField + Deserialize<'de> + NumCast,
+            D: Deserializer<'de>,
+        {
+            super::deserialize(deserializer)
+        }
+    }
+}
+
+/// Serialize [Isometry3](nalgebra::Isometry3) as a 4x4 homogeneous
+/// matrix in row-major nested arrays.
+///
+/// The bottom row of a deserialized matrix must be `[0, 0, 0, 1]`,
+/// and the rotation block must be orthonormal without reflection,
+/// both within
+/// [DEFAULT_TOLERANCE](isometry3_as_matrix4::DEFAULT_TOLERANCE). Use
+/// [deserialize_with_tolerance](isometry3_as_matrix4::deserialize_with_tolerance)
+/// to configure the tolerance, or
+/// [deserialize_orthonormalized](isometry3_as_matrix4::deserialize_orthonormalized)
+/// to re-orthonormalize the rotation block instead of rejecting it.
+///
+/// ```rust
+/// # use serde::{Deserialize, Deserializer, Serialize};
+/// # use nalgebra::{Isometry3, Vector3};
+/// # use newslab_serde_nalgebra::isometry3_as_matrix4;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyExtrinsics {
+///     #[serde(with = "isometry3_as_matrix4")]
+///     cam_to_imu: Isometry3<f64>,
+/// }
+///
+/// let json = r#"{
+///     "cam_to_imu": [
+///         [0.0, -1.0, 0.0, 0.05],
+///         [1.0,  0.0, 0.0, 0.0],
+///         [0.0,  0.0, 1.0, -0.02],
+///         [0.0,  0.0, 0.0, 1.0]
+///     ]
+/// }"#;
+/// let ext: MyExtrinsics = serde_json::from_str(json).unwrap();
+/// assert_eq!(ext.cam_to_imu.translation.vector, Vector3::new(0.05, 0.0, -0.02));
+/// assert!((ext.cam_to_imu.rotation.angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+///
+/// let json = serde_json::to_value(&ext).unwrap();
+/// assert_eq!(json["cam_to_imu"][0][3], 0.05);
+/// assert_eq!(json["cam_to_imu"][3], serde_json::json!([0.0, 0.0, 0.0, 1.0]));
+///
+/// // The rotation block is scaled.
+/// let json = r#"{
+///     "cam_to_imu": [[2, 0, 0, 0], [0, 2, 0, 0], [0, 0, 2, 0], [0, 0, 0, 1]]
+/// }"#;
+/// assert!(serde_json::from_str::<MyExtrinsics>(json).is_err());
+///
+/// // The bottom row is not [0, 0, 0, 1].
+/// let json = r#"{
+///     "cam_to_imu": [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 1, 1]]
+/// }"#;
+/// assert!(serde_json::from_str::<MyExtrinsics>(json).is_err());
+///
+/// // The translation overflows f32.
+/// #[derive(Deserialize)]
+/// struct MyF32Extrinsics {
+///     #[serde(with = "isometry3_as_matrix4")]
+///     cam_to_imu: Isometry3<f32>,
+/// }
+///
+/// let json = r#"{
+///     "cam_to_imu": [[1, 0, 0, 1e300], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]]
+/// }"#;
+/// assert!(serde_json::from_str::<MyF32Extrinsics>(json).is_err());
+///
+/// fn orthonormalized<'de, D>(deserializer: D) -> Result<Isometry3<f64>, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     isometry3_as_matrix4::deserialize_orthonormalized(deserializer, 1e-6)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct MyRoundedExtrinsics {
+///     #[serde(deserialize_with = "orthonormalized")]
+///     cam_to_imu: Isometry3<f64>,
+/// }
+///
+/// // The rotation of 30 degrees about the z axis written with 3 digits.
+/// let json = r#"{
+///     "cam_to_imu": [
+///         [0.866, -0.5,   0.0, 0.0],
+///         [0.5,    0.866, 0.0, 0.0],
+///         [0.0,    0.0,   1.0, 0.0],
+///         [0.0,    0.0,   0.0, 1.0]
+///     ]
+/// }"#;
+/// assert!(serde_json::from_str::<MyExtrinsics>(json).is_err());
+/// let ext: MyRoundedExtrinsics = serde_json::from_str(json).unwrap();
+/// assert!((ext.cam_to_imu.rotation.angle().to_degrees() - 30.0).abs() < 1e-2);
+/// ```
+pub mod isometry3_as_matrix4 {
+    use nalgebra::{
+        Isometry3, Matrix3, Matrix4, Quaternion, RealField, Rotation3, Translation3, UnitQuaternion,
+    };
+    use num::NumCast;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The default tolerance of the bottom row and the orthonormality
+    /// of the rotation block.
+    pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+    pub fn serialize<S, T>(pose: &Isometry3<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: RealField + Serialize,
+        S: Serializer,
+    {
+        let matrix = pose.to_homogeneous();
+        let rows: [[T; 4]; 4] =
+            std::array::from_fn(|row| std::array::from_fn(|col| matrix[(row, col)].clone()));
+        rows.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Isometry3<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        deserialize_with_tolerance(deserializer, DEFAULT_TOLERANCE)
+    }
+
+    /// Deserializes a matrix with the `tolerance` of the bottom row
+    /// and the orthonormality of the rotation block.
+    pub fn deserialize_with_tolerance<'de, D, T>(
+        deserializer: D,
+        tolerance: f64,
+    ) -> Result<Isometry3<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        let matrix = deserialize_matrix::<D, T>(deserializer)?;
+        from_matrix(&matrix, tolerance, false)
+    }
+
+    /// Deserializes a matrix like
+    /// [deserialize_with_tolerance], but the rotation block that is not
+    /// orthonormal within the `tolerance` is replaced by the closest
+    /// rotation using SVD. Reflections are still rejected.
+    pub fn deserialize_orthonormalized<'de, D, T>(
+        deserializer: D,
+        tolerance: f64,
+    ) -> Result<Isometry3<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        let matrix = deserialize_matrix::<D, T>(deserializer)?;
+        from_matrix(&matrix, tolerance, true)
+    }
+
+    fn deserialize_matrix<'de, D, T>(deserializer: D) -> Result<Matrix4<f64>, D::Error>
+    where
+        T: Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        let rows = <[[T; 4]; 4]>::deserialize(deserializer)?;
+        let mut matrix = Matrix4::zeros();
+        for (row, values) in rows.into_iter().enumerate() {
+            for (col, value) in values.into_iter().enumerate() {
+                matrix[(row, col)] = num::cast(value).ok_or_else(|| {
+                    D::Error::custom("the matrix element is not representable as f64")
+                })?;
+            }
+        }
+        Ok(matrix)
+    }
+
+    fn from_matrix<T, E>(
+        matrix: &Matrix4<f64>,
+        tolerance: f64,
+        orthonormalize: bool,
+    ) -> Result<Isometry3<T>, E>
+    where
+        T: RealField + NumCast,
+        E: serde::de::Error,
+    {
+        if matrix.iter().any(|value| !value.is_finite()) {
+            return Err(E::custom("the matrix must be finite"));
+        }
+
+        let bottom = matrix.fixed_view::<1, 4>(3, 0);
+        let deviation = (bottom - nalgebra::RowVector4::new(0.0, 0.0, 0.0, 1.0)).amax();
+        if deviation > tolerance {
+            return Err(E::custom(format_args!(
+                "the bottom row {bottom} must be [0, 0, 0, 1] within the tolerance {tolerance}"
+            )));
+        }
+
+        let block: Matrix3<f64> = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+        if block.determinant() <= 0.0 {
+            return Err(E::custom("the rotation block must have a positive determinant"));
+        }
+        let deviation = (block.transpose() * block - Matrix3::identity()).amax();
+        let block = if deviation <= tolerance {
+            block
+        } else if orthonormalize {
+            let svd = block.svd(true, true);
+            svd.u.unwrap() * svd.v_t.unwrap()
+        } else {
+            return Err(E::custom(format_args!(
+                "the rotation block deviates from an orthonormal matrix by {deviation}, \
+                 which exceeds the tolerance {tolerance}"
+            )));
+        };
+
+        let rotation =
+            UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(block));
+        let [i, j, k, w] = rotation.coords.into();
+        let rotation = UnitQuaternion::new_unchecked(Quaternion::new(
+            crate::from_f64(w)?,
+            crate::from_f64(i)?,
+            crate::from_f64(j)?,
+            crate::from_f64(k)?,
+        ));
+        let translation = Translation3::new(
+            crate::from_f64(matrix[(0, 3)])?,
+            crate::from_f64(matrix[(1, 3)])?,
+            crate::from_f64(matrix[(2, 3)])?,
+        );
+        Ok(Isometry3::from_parts(translation, rotation))
+    }
+}
+
+/// Serialize [UnitQuaternion](nalgebra::UnitQuaternion) as a rotation
+/// axis and an angle, like `{ "axis": [0, 0, 1], "angle": "30deg" }`.
+///
+/// The norm of a deserialized axis must be within
+/// [DEFAULT_TOLERANCE](unit_quaternion_as_axis_angle::DEFAULT_TOLERANCE)
+/// of one. Use
+/// [deserialize_with_tolerance](unit_quaternion_as_axis_angle::deserialize_with_tolerance)
+/// to configure the tolerance. The identity rotation is written with
+/// the z axis.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::{UnitQuaternion, Vector3};
+/// # use newslab_serde_nalgebra::unit_quaternion_as_axis_angle;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyExtrinsics {
+///     #[serde(with = "unit_quaternion_as_axis_angle")]
+///     rotation: UnitQuaternion<f64>,
+/// }
+///
+/// let json = r#"{ "rotation": { "axis": [0.0, 0.0, 1.0], "angle": "30deg" } }"#;
+/// let ext: MyExtrinsics = serde_json::from_str(json).unwrap();
+/// assert!((ext.rotation.angle().to_degrees() - 30.0).abs() < 1e-12);
+/// assert_eq!(ext.rotation.axis().unwrap().into_inner(), Vector3::z());
+///
+/// let json = serde_json::to_value(&ext).unwrap();
+/// assert_eq!(json["rotation"]["axis"], serde_json::json!([0.0, 0.0, 1.0]));
+///
+/// // The axis is not a unit vector.
+/// let json = r#"{ "rotation": { "axis": [0.0, 0.0, 2.0], "angle": "30deg" } }"#;
+/// assert!(serde_json::from_str::<MyExtrinsics>(json).is_err());
+/// let json = r#"{ "rotation": { "axis": [0.0, 0.0, 0.0], "angle": "0deg" } }"#;
+/// assert!(serde_json::from_str::<MyExtrinsics>(json).is_err());
+/// ```
+pub mod unit_quaternion_as_axis_angle {
+    use measurements::Angle;
+    use nalgebra::{RealField, Unit, UnitQuaternion, Vector3};
+    use num::NumCast;
+    use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The default tolerance of the deviation of the axis norm from
+    /// one.
+    pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+    #[derive(Serialize, Deserialize)]
+    struct AxisAngle<T> {
+        pub axis: [T; 3],
+        #[serde(with = "newslab_serde_measurements::angle")]
+        pub angle: Angle,
+    }
+
+    pub fn serialize<S, T>(rot: &UnitQuaternion<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: RealField + Serialize + NumCast,
+        S: Serializer,
+    {
+        let (axis, angle) = match rot.axis_angle() {
+            Some((axis, angle)) => (axis.into_inner(), angle),
+            None => (Vector3::z(), T::zero()),
+        };
+        AxisAngle {
+            axis: axis.into(),
+            angle: Angle::from_radians(crate::to_f64::<_, S::Error>(angle)?),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<UnitQuaternion<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        deserialize_with_tolerance(deserializer, DEFAULT_TOLERANCE)
+    }
+
+    /// Deserializes a rotation whose axis norm is within the
+    /// `tolerance` of one, and renormalizes the axis.
+    pub fn deserialize_with_tolerance<'de, D, T>(
+        deserializer: D,
+        tolerance: f64,
+    ) -> Result<UnitQuaternion<T>, D::Error>
+    where
+        T: RealField + Deserialize<'de> + NumCast,
+        D: Deserializer<'de>,
+    {
+        let AxisAngle { axis, angle } = AxisAngle::<T>::deserialize(deserializer)?;
+        let axis = Vector3::from(axis);
+        let norm: f64 = num::cast(axis.norm()).unwrap_or(f64::NAN);
+        if !norm.is_finite() || (norm - 1.0).abs() > tolerance {
+            return Err(D::Error::custom(format!(
+                "the norm {norm} of the rotation axis deviates from 1 by more than {tolerance}"
+            )));
+        }
+        let angle: T = crate::from_f64(angle.as_radians())?;
+        Ok(UnitQuaternion::from_axis_angle(
+            &Unit::new_normalize(axis),
+            angle,
+        ))
+    }
+}
+
+/// Serializes or deserializes `[x, y, z]` coordinates as objects
+/// with named fields in human-readable formats, and as arrays
+/// otherwise. The array form is also accepted from human-readable
+/// formats.
+mod xyz {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    struct Xyz<T> {
+        x: T,
+        y: T,
+        z: T,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AnyXyz<T> {
+        Object { x: T, y: T, z: T },
+        Array([T; 3]),
+    }
+
+    pub fn serialize<S, T>([x, y, z]: [T; 3], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return [x, y, z].serialize(serializer);
+        }
+        Xyz { x, y, z }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<[T; 3], D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return <[T; 3]>::deserialize(deserializer);
+        }
+        let xyz = match AnyXyz::deserialize(deserializer)? {
+            AnyXyz::Object { x, y, z } => [x, y, z],
+            AnyXyz::Array(xyz) => xyz,
+        };
+        Ok(xyz)
+    }
+}
+
+/// Serialize [Point3](nalgebra::Point3) as a `{ "x": .., "y": ..,
+/// "z": .. }` object. The `[x, y, z]` array form is also accepted.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::Point3;
+/// # use newslab_serde_nalgebra::point3_as_xyz;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyLandmark {
+///     #[serde(with = "point3_as_xyz")]
+///     position: Point3<f64>,
+/// }
+///
+/// let landmark: MyLandmark = serde_json::from_str(r#"{ "position": { "x": 1.0, "y": -2.0, "z": 0.5 } }"#).unwrap();
+/// assert_eq!(landmark.position, Point3::new(1.0, -2.0, 0.5));
+/// assert_eq!(
+///     serde_json::to_string(&landmark).unwrap(),
+///     r#"{"position":{"x":1.0,"y":-2.0,"z":0.5}}"#
+/// );
+///
+/// let landmark: MyLandmark = serde_json::from_str(r#"{ "position": [1.0, -2.0, 0.5] }"#).unwrap();
+/// assert_eq!(landmark.position, Point3::new(1.0, -2.0, 0.5));
+///
+/// assert!(serde_json::from_str::<MyLandmark>(r#"{ "position": { "x": 1.0, "y": -2.0 } }"#).is_err());
+/// ```
+pub mod point3_as_xyz {
+    use nalgebra::{Point3, Scalar};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, T>(point: &Point3<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Scalar + Serialize,
+        S: Serializer,
+    {
+        super::xyz::serialize(point.coords.clone().into(), serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Point3<T>, D::Error>
+    where
+        T: Scalar + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        super::xyz::deserialize(deserializer).map(Point3::from)
+    }
+}
+
+/// Serialize [Vector3](nalgebra::Vector3) as a `{ "x": .., "y": ..,
+/// "z": .. }` object. The `[x, y, z]` array form is also accepted.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::Vector3;
+/// # use newslab_serde_nalgebra::vector3_as_xyz;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyImu {
+///     #[serde(with = "vector3_as_xyz")]
+///     gyro_bias: Vector3<f32>,
+/// }
+///
+/// let imu: MyImu = serde_json::from_str(r#"{ "gyro_bias": { "x": 0.01, "y": 0.0, "z": -0.02 } }"#).unwrap();
+/// assert_eq!(imu.gyro_bias, Vector3::new(0.01, 0.0, -0.02));
+///
+/// let bytes = bincode::serialize(&imu).unwrap();
+/// assert_eq!(bytes.len(), 12);
+/// ```
+pub mod vector3_as_xyz {
+    use nalgebra::{Scalar, Vector3};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, T>(vector: &Vector3<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Scalar + Serialize,
+        S: Serializer,
+    {
+        super::xyz::serialize(vector.clone().into(), serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vector3<T>, D::Error>
+    where
+        T: Scalar + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        super::xyz::deserialize(deserializer).map(Vector3::from)
+    }
+}
+
+/// Serialize [Translation3](nalgebra::Translation3) as a `{ "x": ..,
+/// "y": .., "z": .. }` object. The `[x, y, z]` array form is also
+/// accepted.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::Translation3;
+/// # use newslab_serde_nalgebra::translation3_as_xyz;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyMount {
+///     #[serde(with = "translation3_as_xyz")]
+///     offset: Translation3<f64>,
+/// }
+///
+/// let mount = MyMount { offset: Translation3::new(0.1, 0.0, 1.8) };
+/// assert_eq!(
+///     serde_json::to_string(&mount).unwrap(),
+///     r#"{"offset":{"x":0.1,"y":0.0,"z":1.8}}"#
+/// );
+/// ```
+pub mod translation3_as_xyz {
+    use nalgebra::{Scalar, Translation3};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, T>(trans: &Translation3<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Scalar + Serialize,
+        S: Serializer,
+    {
+        super::xyz::serialize(trans.vector.clone().into(), serializer)
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Translation3<T>, D::Error>
+    where
+        T: Scalar + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        super::xyz::deserialize(deserializer).map(Translation3::from)
+    }
+}
+
+/// Serialize a statically-sized [SMatrix](nalgebra::SMatrix) as
+/// nested row-major arrays, which rejects inputs with wrong row or
+/// column counts.
+///
+/// ```rust
+/// # use serde::{Deserialize, Serialize};
+/// # use nalgebra::{Matrix2x3, Matrix3};
+/// # use newslab_serde_nalgebra::matrix;
+/// #[derive(Serialize, Deserialize)]
+/// struct MyFilter {
+///     #[serde(with = "matrix")]
+///     noise: Matrix3<f64>,
+///     #[serde(with = "matrix")]
+///     observation: Matrix2x3<f64>,
+/// }
+///
+/// let json = r#"{
+///     "noise": [[0.1, 0.0, 0.0], [0.0, 0.1, 0.0], [0.0, 0.0, 0.2]],
+///     "observation": [[1, 0, 0], [0, 1, 0]]
+/// }"#;
+/// let filter: MyFilter = serde_json::from_str(json).unwrap();
+/// assert_eq!(filter.noise[(2, 2)], 0.2);
+/// assert_eq!(filter.observation, Matrix2x3::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0));
+///
+/// let json = serde_json::to_string(&filter).unwrap();
+/// assert!(json.contains(r#""observation":[[1.0,0.0,0.0],[0.0,1.0,0.0]]"#));
+///
+/// let bytes = bincode::serialize(&filter).unwrap();
+/// assert_eq!(bytes.len(), 8 * 15);
+/// let _: MyFilter = bincode::deserialize(&bytes).unwrap();
+///
+/// let json = r#"{
+///     "noise": [[0.1, 0.0, 0.0], [0.0, 0.1, 0.0], [0.0, 0.0, 0.2]],
+///     "observation": [[1, 0], [0, 1]]
+/// }"#;
+/// let err = serde_json::from_str::<MyFilter>(json).err().unwrap();
+/// assert!(err.to_string().starts_with("expected a 2x3 matrix, but row 0 has 2 columns"));
+///
+/// let json = r#"{
+///     "noise": [[0.1, 0.0, 0.0], [0.0, 0.1, 0.0]],
+///     "observation": [[1, 0, 0], [0, 1, 0]]
+/// }"#;
+/// let err = serde_json::from_str::<MyFilter>(json).err().unwrap();
+/// assert!(err.to_string().starts_with("expected a 3x3 matrix, but got 2 rows"));
+/// ```
+pub mod matrix {
+    use nalgebra::{SMatrix, Scalar};
+    use serde::{
+        de::{self, SeqAccess, Visitor},
+        ser::SerializeTuple,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{fmt, marker::PhantomData};
+
+    struct RowRef<'a, T: Scalar, const R: usize, const C: usize> {
+        matrix: &'a SMatrix<T, R, C>,
+        index: usize,
+    }
+
+    impl<T, const R: usize, const C: usize> Serialize for RowRef<'_, T, R, C>
+    where
+        T: Scalar + Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut row = serializer.serialize_tuple(C)?;
+            for value in self.matrix.row(self.index).iter() {
+                row.serialize_element(value)?;
+            }
+            row.end()
+        }
+    }
+
+    /// A row of any number of columns, which are checked later to
+    /// report the row index.
+    struct Row<T, const C: usize>(Vec<T>);
+
+    impl<'de, T, const C: usize> Deserialize<'de> for Row<T, C>
+    where
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_tuple(C, RowVisitor::<T, C>(PhantomData))
+        }
+    }
+
+    struct RowVisitor<T, const C: usize>(PhantomData<T>);
+
+    impl<'de, T, const C: usize> Visitor<'de> for RowVisitor<T, C>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = Row<T, C>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a row of {C} numbers")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut values = Vec::with_capacity(C);
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(Row(values))
+        }
+    }
+
+    struct MatrixVisitor<T, const R: usize, const C: usize>(PhantomData<T>);
+
+    impl<'de, T, const R: usize, const C: usize> Visitor<'de> for MatrixVisitor<T, R, C>
+    where
+        T: Scalar + Deserialize<'de>,
+    {
+        type Value = SMatrix<T, R, C>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a {R}x{C} matrix in row-major nested arrays")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut rows = Vec::with_capacity(R);
+            while let Some(Row::<T, C>(row)) = seq.next_element()? {
+                if row.len() != C {
+                    return Err(de::Error::custom(format!(
+                        "expected a {R}x{C} matrix, but row {} has {} columns",
+                        rows.len(),
+                        row.len()
+                    )));
+                }
+                rows.push(row);
+            }
+            if rows.len() != R {
+                return Err(de::Error::custom(format!(
+                    "expected a {R}x{C} matrix, but got {} rows",
+                    rows.len()
+                )));
+            }
+            Ok(SMatrix::from_row_iterator(rows.into_iter().flatten()))
+        }
+    }
+
+    pub fn serialize<S, T, const R: usize, const C: usize>(
+        matrix: &SMatrix<T, R, C>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: Scalar + Serialize,
+        S: Serializer,
+    {
+        let mut rows = serializer.serialize_tuple(R)?;
+        for index in 0..R {
+            rows.serialize_element(&RowRef { matrix, index })?;
+        }
+        rows.end()
+    }
+
+    pub fn deserialize<'de, D, T, const R: usize, const C: usize>(
+        deserializer: D,
+    ) -> Result<SMatrix<T, R, C>, D::Error>
+    where
+        T: Scalar + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(R, MatrixVisitor::<T, R, C>(PhantomData))
     }
 }
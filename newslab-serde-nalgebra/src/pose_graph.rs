@@ -0,0 +1,227 @@
+use crate::{g2o, toro};
+use anyhow::{bail, ensure, Context, Error, Result};
+use nalgebra::{Isometry3, Matrix6, SymmetricEigen};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A pose graph consisting of named frames and relative pose
+/// constraints among them.
+///
+/// The structure is audited during ser/deserialization. Node names
+/// must be unique, edges must refer to existing nodes, and
+/// information matrices must be symmetric positive semi-definite.
+///
+/// ```rust
+/// # use newslab_serde_nalgebra::PoseGraph;
+/// let json = r#"{
+///     "nodes": [
+///         {
+///             "name": "base",
+///             "pose": {
+///                 "translation": [0.0, 0.0, 0.0],
+///                 "rotation": { "roll": "0deg", "pitch": "0deg", "yaw": "0deg" }
+///             }
+///         },
+///         {
+///             "name": "camera",
+///             "pose": {
+///                 "translation": [1.0, 0.0, 0.5],
+///                 "rotation": { "roll": "0deg", "pitch": "0deg", "yaw": "90deg" }
+///             }
+///         }
+///     ],
+///     "edges": [
+///         {
+///             "from": "base",
+///             "to": "camera",
+///             "measurement": {
+///                 "translation": [1.0, 0.0, 0.5],
+///                 "rotation": { "roll": "0deg", "pitch": "0deg", "yaw": "90deg" }
+///             },
+///             "information": [
+///                 [100.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+///                 [0.0, 100.0, 0.0, 0.0, 0.0, 0.0],
+///                 [0.0, 0.0, 100.0, 0.0, 0.0, 0.0],
+///                 [0.0, 0.0, 0.0, 400.0, 0.0, 0.0],
+///                 [0.0, 0.0, 0.0, 0.0, 400.0, 0.0],
+///                 [0.0, 0.0, 0.0, 0.0, 0.0, 400.0]
+///             ]
+///         }
+///     ]
+/// }"#;
+/// let graph: PoseGraph = serde_json::from_str(json).unwrap();
+///
+/// // Convert to g2o format and back.
+/// let text = graph.to_g2o();
+/// let graph = PoseGraph::from_g2o(&text).unwrap();
+/// assert_eq!(graph.nodes().len(), 2);
+/// assert_eq!(graph.edges()[0].information[(3, 3)], 400.0);
+///
+/// // Convert to TORO format and back.
+/// let text = graph.to_toro();
+/// let graph = PoseGraph::from_toro(&text).unwrap();
+/// assert_eq!(graph.edges()[0].information[(5, 5)], 400.0);
+///
+/// // The edge refers to unknown nodes.
+/// let text = "EDGE_SE3:QUAT 0 1 0 0 0 0 0 0 1 1 0 0 0 0 0 1 0 0 0 0 1 0 0 0 1 0 0 1 0 1";
+/// assert!(PoseGraph::from_g2o(text).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "PoseGraphUnchecked", into = "PoseGraphUnchecked")]
+pub struct PoseGraph {
+    nodes: Vec<PoseNode>,
+    edges: Vec<PoseEdge>,
+}
+
+/// A named frame in a [PoseGraph] with its initial pose.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoseNode {
+    pub name: String,
+    #[serde(with = "crate::isometry3_as_euler_angles")]
+    pub pose: Isometry3<f64>,
+}
+
+/// A relative pose constraint from one frame to another in a
+/// [PoseGraph].
+///
+/// The information matrix is ordered as (x, y, z) translation
+/// followed by (x, y, z) rotation, which is the same as g2o.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoseEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(with = "crate::isometry3_as_euler_angles")]
+    pub measurement: Isometry3<f64>,
+    #[serde(with = "information_matrix")]
+    pub information: Matrix6<f64>,
+}
+
+impl PoseGraph {
+    /// Creates a pose graph after auditing the nodes and edges.
+    pub fn new(nodes: Vec<PoseNode>, edges: Vec<PoseEdge>) -> Result<Self> {
+        PoseGraphUnchecked { nodes, edges }.try_into()
+    }
+
+    pub fn nodes(&self) -> &[PoseNode] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[PoseEdge] {
+        &self.edges
+    }
+
+    pub fn node(&self, name: &str) -> Option<&PoseNode> {
+        self.nodes.iter().find(|node| node.name == name)
+    }
+
+    /// Renders the graph in g2o text format. See [g2o::from_graph]
+    /// for how nodes are numbered.
+    pub fn to_g2o(&self) -> String {
+        g2o::write(&g2o::from_graph(self))
+    }
+
+    /// Parses a graph in g2o text format. Vertex ids become node
+    /// names. Empty lines, comments and `FIX` records are ignored.
+    pub fn from_g2o(text: &str) -> Result<Self> {
+        g2o::to_graph(&g2o::parse(text)?)
+    }
+
+    /// Renders the graph in TORO text format. See
+    /// [toro::from_graph] for how nodes are numbered.
+    pub fn to_toro(&self) -> String {
+        toro::write(&toro::from_graph(self))
+    }
+
+    /// Parses a graph in 2D or 3D TORO text format. Vertex ids become
+    /// node names.
+    pub fn from_toro(text: &str) -> Result<Self> {
+        toro::to_graph(&toro::parse(text)?)
+    }
+}
+
+/// Checks that the matrix is symmetric positive semi-definite.
+fn check_information(info: &Matrix6<f64>) -> Result<()> {
+    let scale = info.amax().max(1.0);
+    let tolerance = scale * 1e-9;
+
+    ensure!(
+        (info - info.transpose()).amax() <= tolerance,
+        "the information matrix is not symmetric"
+    );
+
+    let eigen = SymmetricEigen::new(*info);
+    ensure!(
+        eigen.eigenvalues.iter().all(|&value| value >= -tolerance),
+        "the information matrix is not positive semi-definite"
+    );
+
+    Ok(())
+}
+
+impl TryFrom<PoseGraphUnchecked> for PoseGraph {
+    type Error = Error;
+
+    fn try_from(from: PoseGraphUnchecked) -> Result<Self, Self::Error> {
+        let PoseGraphUnchecked { nodes, edges } = from;
+
+        let mut names = HashMap::new();
+        for (index, node) in nodes.iter().enumerate() {
+            if let Some(prev) = names.insert(node.name.as_str(), index) {
+                bail!(
+                    "node '{}' is defined more than once (at {prev} and {index})",
+                    node.name
+                );
+            }
+        }
+
+        for (index, edge) in edges.iter().enumerate() {
+            for name in [&edge.from, &edge.to] {
+                ensure!(
+                    names.contains_key(name.as_str()),
+                    "edge {index} refers to unknown node '{name}'"
+                );
+            }
+            check_information(&edge.information).with_context(|| {
+                format!("invalid edge {index} from '{}' to '{}'", edge.from, edge.to)
+            })?;
+        }
+
+        Ok(Self { nodes, edges })
+    }
+}
+
+impl From<PoseGraph> for PoseGraphUnchecked {
+    fn from(from: PoseGraph) -> Self {
+        let PoseGraph { nodes, edges } = from;
+        Self { nodes, edges }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PoseGraphUnchecked {
+    nodes: Vec<PoseNode>,
+    #[serde(default)]
+    edges: Vec<PoseEdge>,
+}
+
+/// Serialize a 6x6 matrix as row-major nested arrays.
+mod information_matrix {
+    use nalgebra::Matrix6;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(mat: &Matrix6<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let rows: [[f64; 6]; 6] = mat.transpose().into();
+        rows.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Matrix6<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rows = <[[f64; 6]; 6]>::deserialize(deserializer)?;
+        Ok(Matrix6::from_fn(|row, col| rows[row][col]))
+    }
+}
@@ -0,0 +1,91 @@
+//! Shared routines for line-based graph file formats.
+
+use anyhow::{bail, ensure, Context, Result};
+use std::{fmt::Write as _, str::SplitWhitespace};
+
+/// The whitespace-separated tokens of a record after its tag.
+pub(crate) struct Tokens<'a> {
+    tokens: SplitWhitespace<'a>,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn id(&mut self) -> Result<u64> {
+        let token = self.tokens.next().context("missing vertex id")?;
+        let id = token
+            .parse()
+            .with_context(|| format!("'{token}' is not a valid vertex id"))?;
+        Ok(id)
+    }
+
+    pub fn value(&mut self) -> Result<f64> {
+        let token = self.tokens.next().context("missing values")?;
+        let value = token
+            .parse()
+            .with_context(|| format!("'{token}' is not a valid number"))?;
+        Ok(value)
+    }
+
+    pub fn values<const N: usize>(&mut self) -> Result<[f64; N]> {
+        let mut values = [0.0; N];
+        for value in &mut values {
+            *value = self.value()?;
+        }
+        Ok(values)
+    }
+
+    /// Consumes the remaining tokens as vertex ids.
+    pub fn ids(&mut self) -> Result<Vec<u64>> {
+        let mut ids = vec![];
+        while self.tokens.clone().next().is_some() {
+            ids.push(self.id()?);
+        }
+        Ok(ids)
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        ensure!(self.tokens.next().is_none(), "trailing values");
+        Ok(())
+    }
+}
+
+/// Splits the text into tagged records and feeds them to the
+/// callback. Empty lines and `#` comments are skipped. The callback
+/// returns `Ok(None)` for tags it does not recognize.
+pub(crate) fn parse_lines<T, F>(format: &str, text: &str, mut parse: F) -> Result<Vec<T>>
+where
+    F: FnMut(&str, &mut Tokens<'_>) -> Result<Option<T>>,
+{
+    let mut records = vec![];
+
+    for (lineno, line) in text.lines().enumerate() {
+        let mut parse_line = || -> Result<Option<T>> {
+            let mut tokens = line.split_whitespace();
+            let Some(tag) = tokens.next() else {
+                return Ok(None);
+            };
+            if tag.starts_with('#') {
+                return Ok(None);
+            }
+
+            let mut tokens = Tokens { tokens };
+            let Some(record) = parse(tag, &mut tokens)? else {
+                bail!("unsupported record '{tag}'");
+            };
+            tokens.finish()?;
+            Ok(Some(record))
+        };
+
+        let record = parse_line()
+            .with_context(|| format!("invalid {format} record at line {}", lineno + 1))?;
+        records.extend(record);
+    }
+
+    Ok(records)
+}
+
+/// Appends space-prefixed values to the text.
+pub(crate) fn write_values(text: &mut String, values: impl IntoIterator<Item = f64>) {
+    for value in values {
+        write!(text, " {value}").unwrap();
+    }
+}
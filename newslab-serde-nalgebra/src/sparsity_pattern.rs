@@ -0,0 +1,192 @@
+use anyhow::{bail, ensure, Error, Result};
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+
+/// The positions of structurally non-zero entries in a matrix, such
+/// as the covariance blocks maintained by an estimator.
+///
+/// The entries are kept in row-major order as `[row, col]` pairs.
+/// They must lie within the matrix and must not repeat. Rectangular
+/// blocks of entries can be given in `blocks`, which are expanded
+/// into entries.
+///
+/// ```rust
+/// # use newslab_serde_nalgebra::SparsityPattern;
+/// let json = r#"{
+///     "rows": 6,
+///     "cols": 6,
+///     "entries": [[5, 0], [0, 5]],
+///     "blocks": [
+///         { "row": 0, "col": 0, "rows": 3, "cols": 3 },
+///         { "row": 3, "col": 3, "rows": 3, "cols": 3 }
+///     ]
+/// }"#;
+/// let pattern: SparsityPattern = serde_json::from_str(json).unwrap();
+/// assert_eq!(pattern.entries().len(), 20);
+/// assert_eq!(pattern.entries()[3], (0, 5));
+/// assert!(pattern.contains(4, 5));
+/// assert!(!pattern.contains(0, 4));
+///
+/// let mask = pattern.to_mask();
+/// assert!(mask[(5, 0)] && !mask[(5, 1)]);
+///
+/// // Blocks are written as entries.
+/// let json = serde_json::to_value(&pattern).unwrap();
+/// assert_eq!(json["entries"][0], serde_json::json!([0, 0]));
+/// assert!(json.get("blocks").is_none());
+///
+/// // The entry is out of bounds.
+/// let json = r#"{ "rows": 2, "cols": 2, "entries": [[0, 2]] }"#;
+/// assert!(serde_json::from_str::<SparsityPattern>(json).is_err());
+///
+/// // The entry overlaps with the block.
+/// let json = r#"{
+///     "rows": 2,
+///     "cols": 2,
+///     "entries": [[1, 1]],
+///     "blocks": [{ "row": 0, "col": 0, "rows": 2, "cols": 2 }]
+/// }"#;
+/// assert!(serde_json::from_str::<SparsityPattern>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(
+    try_from = "SparsityPatternUnchecked",
+    into = "SparsityPatternUnchecked"
+)]
+pub struct SparsityPattern {
+    rows: usize,
+    cols: usize,
+    entries: Vec<(usize, usize)>,
+}
+
+/// A rectangular block of entries in a [SparsityPattern].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SparseBlock {
+    /// The row of the top-left entry.
+    pub row: usize,
+    /// The column of the top-left entry.
+    pub col: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl SparsityPattern {
+    /// Creates a pattern from the entries in any order. It fails if
+    /// an entry is out of bounds or given more than once.
+    pub fn new(rows: usize, cols: usize, mut entries: Vec<(usize, usize)>) -> Result<Self> {
+        if let Some(&(row, col)) = entries
+            .iter()
+            .find(|&&(row, col)| row >= rows || col >= cols)
+        {
+            bail!("the entry [{row}, {col}] is out of the {rows}x{cols} matrix");
+        }
+
+        entries.sort_unstable();
+        if let Some(pair) = entries.windows(2).find(|pair| pair[0] == pair[1]) {
+            let (row, col) = pair[0];
+            bail!("the entry [{row}, {col}] is given more than once");
+        }
+
+        Ok(Self {
+            rows,
+            cols,
+            entries,
+        })
+    }
+
+    /// Creates a pattern from the blocks of entries, which must not
+    /// overlap.
+    pub fn from_blocks(rows: usize, cols: usize, blocks: &[SparseBlock]) -> Result<Self> {
+        SparsityPatternUnchecked {
+            rows,
+            cols,
+            entries: vec![],
+            blocks: blocks.to_vec(),
+        }
+        .try_into()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Gets the `(row, col)` entries in row-major order.
+    pub fn entries(&self) -> &[(usize, usize)] {
+        &self.entries
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        self.entries.binary_search(&(row, col)).is_ok()
+    }
+
+    /// Builds a boolean matrix which is `true` at the entries.
+    pub fn to_mask(&self) -> DMatrix<bool> {
+        let mut mask = DMatrix::from_element(self.rows, self.cols, false);
+        for &entry in &self.entries {
+            mask[entry] = true;
+        }
+        mask
+    }
+}
+
+impl TryFrom<SparsityPatternUnchecked> for SparsityPattern {
+    type Error = Error;
+
+    fn try_from(from: SparsityPatternUnchecked) -> Result<Self, Self::Error> {
+        let SparsityPatternUnchecked {
+            rows,
+            cols,
+            mut entries,
+            blocks,
+        } = from;
+
+        for (index, block) in blocks.iter().enumerate() {
+            let SparseBlock {
+                row,
+                col,
+                rows: height,
+                cols: width,
+            } = *block;
+            ensure!(
+                row.checked_add(height).is_some_and(|end| end <= rows)
+                    && col.checked_add(width).is_some_and(|end| end <= cols),
+                "block {index} of {height}x{width} at [{row}, {col}] is out of the {rows}x{cols} matrix"
+            );
+            entries
+                .extend((row..row + height).flat_map(|r| (col..col + width).map(move |c| (r, c))));
+        }
+
+        Self::new(rows, cols, entries)
+    }
+}
+
+impl From<SparsityPattern> for SparsityPatternUnchecked {
+    fn from(from: SparsityPattern) -> Self {
+        let SparsityPattern {
+            rows,
+            cols,
+            entries,
+        } = from;
+        Self {
+            rows,
+            cols,
+            entries,
+            blocks: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct SparsityPatternUnchecked {
+    rows: usize,
+    cols: usize,
+    #[serde(default)]
+    entries: Vec<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    blocks: Vec<SparseBlock>,
+}
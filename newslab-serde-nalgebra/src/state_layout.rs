@@ -0,0 +1,194 @@
+use anyhow::{bail, ensure, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// The layout of a state vector, such as the state of an estimator,
+/// which is an ordered list of named blocks.
+///
+/// Each block has a size and a kind telling the unit of its
+/// elements. The names must be unique and the sizes must be
+/// positive. The size of a quaternion block is 4 and can be left
+/// out. The blocks are placed in order without gaps.
+///
+/// ```rust
+/// # use newslab_serde_nalgebra::{StateKind, StateLayout};
+/// let json = r#"[
+///     { "name": "position", "size": 3, "kind": "length" },
+///     { "name": "velocity", "size": 3, "kind": "speed" },
+///     { "name": "orientation", "kind": "quaternion" },
+///     { "name": "gyro_bias", "size": 3, "kind": "angular_velocity" }
+/// ]"#;
+/// let layout: StateLayout = serde_json::from_str(json).unwrap();
+/// assert_eq!(layout.dim(), 13);
+/// assert_eq!(layout.range("orientation"), Some(6..10));
+/// assert_eq!(layout.index("velocity", 2), Some(5));
+/// assert_eq!(layout.index("velocity", 3), None);
+///
+/// let (block, component) = layout.locate(11).unwrap();
+/// assert_eq!((block.name.as_str(), component), ("gyro_bias", 1));
+/// assert_eq!(block.kind, StateKind::AngularVelocity);
+///
+/// // The sizes are always written.
+/// let json = serde_json::to_value(&layout).unwrap();
+/// assert_eq!(json[2]["size"], 4);
+///
+/// // The name is given twice.
+/// let json = r#"[
+///     { "name": "position", "size": 3, "kind": "length" },
+///     { "name": "position", "size": 2, "kind": "length" }
+/// ]"#;
+/// assert!(serde_json::from_str::<StateLayout>(json).is_err());
+///
+/// // The quaternion has the wrong size.
+/// let json = r#"[{ "name": "orientation", "size": 3, "kind": "quaternion" }]"#;
+/// assert!(serde_json::from_str::<StateLayout>(json).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<StateBlockUnchecked>", into = "Vec<StateBlock>")]
+pub struct StateLayout {
+    blocks: Vec<StateBlock>,
+    /// The offset of each block, with the dimension at the end.
+    offsets: Vec<usize>,
+}
+
+/// A named block of elements in a [StateLayout].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StateBlock {
+    pub name: String,
+    pub size: usize,
+    pub kind: StateKind,
+}
+
+/// The kind of elements in a [StateBlock], which tells the unit of
+/// the elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateKind {
+    /// Lengths in meters.
+    Length,
+    /// Angles in radians.
+    Angle,
+    /// Speeds in meters per second.
+    Speed,
+    /// Accelerations in meters per second squared.
+    Acceleration,
+    /// Angular velocities in radians per second.
+    AngularVelocity,
+    /// Durations in seconds.
+    Duration,
+    /// Unitless values, such as scale factors.
+    Dimensionless,
+    /// A unit quaternion in `[i, j, k, w]` order like nalgebra.
+    Quaternion,
+}
+
+impl StateLayout {
+    /// Creates a layout from the blocks in order. It fails if a name
+    /// is empty or repeated, or if a size is invalid for its kind.
+    pub fn new(blocks: Vec<StateBlock>) -> Result<Self> {
+        let mut offsets = Vec::with_capacity(blocks.len() + 1);
+        let mut offset = 0;
+
+        for (index, block) in blocks.iter().enumerate() {
+            let StateBlock { name, size, kind } = block;
+            ensure!(!name.is_empty(), "the name of block {index} is empty");
+            if blocks[..index].iter().any(|prev| prev.name == *name) {
+                bail!("the block name \"{name}\" is given more than once");
+            }
+            match kind {
+                StateKind::Quaternion => ensure!(
+                    *size == 4,
+                    "the quaternion block \"{name}\" must have size 4, but get {size}"
+                ),
+                _ => ensure!(*size > 0, "the block \"{name}\" has zero size"),
+            }
+
+            offsets.push(offset);
+            offset += size;
+        }
+        offsets.push(offset);
+
+        Ok(Self { blocks, offsets })
+    }
+
+    /// Gets the blocks in order.
+    pub fn blocks(&self) -> &[StateBlock] {
+        &self.blocks
+    }
+
+    /// Gets the total size of the blocks.
+    pub fn dim(&self) -> usize {
+        *self.offsets.last().unwrap()
+    }
+
+    pub fn block(&self, name: &str) -> Option<&StateBlock> {
+        self.position(name).map(|index| &self.blocks[index])
+    }
+
+    /// Gets the offset of the first element of the block.
+    pub fn offset(&self, name: &str) -> Option<usize> {
+        self.position(name).map(|index| self.offsets[index])
+    }
+
+    /// Gets the index range of the elements of the block.
+    pub fn range(&self, name: &str) -> Option<Range<usize>> {
+        self.position(name)
+            .map(|index| self.offsets[index]..self.offsets[index + 1])
+    }
+
+    /// Gets the index of the `component`th element of the block. It
+    /// returns `None` if the component is out of the block.
+    pub fn index(&self, name: &str, component: usize) -> Option<usize> {
+        let range = self.range(name)?;
+        let index = range.start + component;
+        range.contains(&index).then_some(index)
+    }
+
+    /// Finds the block containing the element at `index`, along with
+    /// the component of the element in the block.
+    pub fn locate(&self, index: usize) -> Option<(&StateBlock, usize)> {
+        if index >= self.dim() {
+            return None;
+        }
+        let position = self.offsets.partition_point(|&offset| offset <= index) - 1;
+        Some((&self.blocks[position], index - self.offsets[position]))
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.blocks.iter().position(|block| block.name == name)
+    }
+}
+
+impl TryFrom<Vec<StateBlockUnchecked>> for StateLayout {
+    type Error = Error;
+
+    fn try_from(from: Vec<StateBlockUnchecked>) -> Result<Self, Self::Error> {
+        let blocks: Vec<_> = from
+            .into_iter()
+            .map(|block| {
+                let StateBlockUnchecked { name, size, kind } = block;
+                let size = match (size, kind) {
+                    (Some(size), _) => size,
+                    (None, StateKind::Quaternion) => 4,
+                    (None, _) => bail!("the size of block \"{name}\" is missing"),
+                };
+                Ok(StateBlock { name, size, kind })
+            })
+            .collect::<Result<_>>()?;
+        Self::new(blocks)
+    }
+}
+
+impl From<StateLayout> for Vec<StateBlock> {
+    fn from(from: StateLayout) -> Self {
+        from.blocks
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+struct StateBlockUnchecked {
+    name: String,
+    #[serde(default)]
+    size: Option<usize>,
+    kind: StateKind,
+}
@@ -0,0 +1,271 @@
+//! Reader and writer for the [TORO](https://openslam-org.github.io/toro.html)
+//! text format.
+//!
+//! 2D graphs consist of `VERTEX2 id x y theta` and
+//! `EDGE2 id1 id2 dx dy dtheta I11 I12 I22 I33 I13 I23` records. 3D
+//! graphs consist of `VERTEX3 id x y z roll pitch yaw` and `EDGE3`
+//! records, in which the relative pose is followed by the 21 values
+//! of the upper triangle of the information matrix in row-major
+//! order.
+//!
+//! ```rust
+//! # use newslab_serde_nalgebra::toro;
+//! let text = "\
+//! VERTEX2 0 0 0 0
+//! VERTEX2 1 1 0 1.5707963267948966
+//! EDGE2 0 1 1 0 1.5707963267948966 100 0 100 400 0 0
+//! ";
+//! let records = toro::parse(text).unwrap();
+//! assert_eq!(toro::parse(&toro::write(&records)).unwrap(), records);
+//!
+//! // 2D records are lifted to the xy-plane.
+//! let graph = toro::to_graph(&records).unwrap();
+//! let edge = &graph.edges()[0];
+//! assert_eq!(edge.information[(0, 0)], 100.0);
+//! assert_eq!(edge.information[(5, 5)], 400.0);
+//! assert_eq!(edge.information[(2, 2)], 0.0);
+//!
+//! // Pose graphs are written in 3D records.
+//! let text = toro::write(&toro::from_graph(&graph));
+//! assert!(text.starts_with("VERTEX3 0 "));
+//! ```
+
+use crate::{
+    g2o::{parse_information, write_information},
+    records::{parse_lines, write_values, Tokens},
+    PoseEdge, PoseGraph, PoseNode,
+};
+use anyhow::Result;
+use nalgebra::{Isometry2, Isometry3, Matrix3, Matrix6, Translation3, UnitQuaternion, Vector3};
+use std::{collections::HashMap, fmt::Write as _};
+
+/// A record in a TORO file.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Record {
+    /// A `VERTEX2` record.
+    Vertex2 { id: u64, pose: Isometry2<f64> },
+    /// An `EDGE2` record. The information matrix is ordered as
+    /// (x, y, theta).
+    Edge2 {
+        from: u64,
+        to: u64,
+        measurement: Isometry2<f64>,
+        information: Matrix3<f64>,
+    },
+    /// A `VERTEX3` record.
+    Vertex3 { id: u64, pose: Isometry3<f64> },
+    /// An `EDGE3` record. The information matrix is ordered as
+    /// (x, y, z) translation followed by (roll, pitch, yaw).
+    Edge3 {
+        from: u64,
+        to: u64,
+        measurement: Isometry3<f64>,
+        information: Matrix6<f64>,
+    },
+}
+
+/// Parses TORO text into records. Empty lines and comments are
+/// skipped.
+pub fn parse(text: &str) -> Result<Vec<Record>> {
+    parse_lines("TORO", text, |tag, tokens| {
+        let record = match tag {
+            "VERTEX2" => Record::Vertex2 {
+                id: tokens.id()?,
+                pose: parse_pose2(tokens)?,
+            },
+            "EDGE2" => Record::Edge2 {
+                from: tokens.id()?,
+                to: tokens.id()?,
+                measurement: parse_pose2(tokens)?,
+                information: {
+                    let [xx, xy, yy, tt, xt, yt] = tokens.values()?;
+                    Matrix3::new(xx, xy, xt, xy, yy, yt, xt, yt, tt)
+                },
+            },
+            "VERTEX3" => Record::Vertex3 {
+                id: tokens.id()?,
+                pose: parse_pose3(tokens)?,
+            },
+            "EDGE3" => Record::Edge3 {
+                from: tokens.id()?,
+                to: tokens.id()?,
+                measurement: parse_pose3(tokens)?,
+                information: parse_information(tokens)?,
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(record))
+    })
+}
+
+/// Renders records in TORO text format, one record per line.
+pub fn write(records: &[Record]) -> String {
+    let mut text = String::new();
+
+    for record in records {
+        match record {
+            Record::Vertex2 { id, pose } => {
+                write!(text, "VERTEX2 {id}").unwrap();
+                write_pose2(&mut text, pose);
+            }
+            Record::Edge2 {
+                from,
+                to,
+                measurement,
+                information: m,
+            } => {
+                write!(text, "EDGE2 {from} {to}").unwrap();
+                write_pose2(&mut text, measurement);
+                write_values(
+                    &mut text,
+                    [
+                        m[(0, 0)],
+                        m[(0, 1)],
+                        m[(1, 1)],
+                        m[(2, 2)],
+                        m[(0, 2)],
+                        m[(1, 2)],
+                    ],
+                );
+            }
+            Record::Vertex3 { id, pose } => {
+                write!(text, "VERTEX3 {id}").unwrap();
+                write_pose3(&mut text, pose);
+            }
+            Record::Edge3 {
+                from,
+                to,
+                measurement,
+                information,
+            } => {
+                write!(text, "EDGE3 {from} {to}").unwrap();
+                write_pose3(&mut text, measurement);
+                write_information(&mut text, information);
+            }
+        }
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Converts a pose graph to `VERTEX3` and `EDGE3` records. Nodes are
+/// numbered by their order in the graph and their names are not
+/// preserved.
+pub fn from_graph(graph: &PoseGraph) -> Vec<Record> {
+    let index: HashMap<&str, u64> = graph
+        .nodes()
+        .iter()
+        .zip(0..)
+        .map(|(node, id)| (node.name.as_str(), id))
+        .collect();
+
+    let vertices = graph
+        .nodes()
+        .iter()
+        .zip(0..)
+        .map(|(node, id)| Record::Vertex3 {
+            id,
+            pose: node.pose,
+        });
+    let edges = graph.edges().iter().map(|edge| Record::Edge3 {
+        from: index[edge.from.as_str()],
+        to: index[edge.to.as_str()],
+        measurement: edge.measurement,
+        information: edge.information,
+    });
+
+    vertices.chain(edges).collect()
+}
+
+/// Builds a pose graph from records. Vertex ids become node names.
+/// 2D poses are placed on the xy-plane and rotated about the z-axis.
+pub fn to_graph(records: &[Record]) -> Result<PoseGraph> {
+    let mut nodes = vec![];
+    let mut edges = vec![];
+
+    for record in records {
+        match record {
+            Record::Vertex2 { id, pose } => nodes.push(PoseNode {
+                name: id.to_string(),
+                pose: lift_pose(pose),
+            }),
+            Record::Vertex3 { id, pose } => nodes.push(PoseNode {
+                name: id.to_string(),
+                pose: *pose,
+            }),
+            Record::Edge2 {
+                from,
+                to,
+                measurement,
+                information,
+            } => edges.push(PoseEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                measurement: lift_pose(measurement),
+                information: lift_information(information),
+            }),
+            Record::Edge3 {
+                from,
+                to,
+                measurement,
+                information,
+            } => edges.push(PoseEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                measurement: *measurement,
+                information: *information,
+            }),
+        }
+    }
+
+    PoseGraph::new(nodes, edges)
+}
+
+fn lift_pose(pose: &Isometry2<f64>) -> Isometry3<f64> {
+    let t = &pose.translation.vector;
+    Isometry3::from_parts(
+        Translation3::new(t.x, t.y, 0.0),
+        UnitQuaternion::from_axis_angle(&Vector3::z_axis(), pose.rotation.angle()),
+    )
+}
+
+/// Places the (x, y, theta) information into the x, y and yaw
+/// entries of a 3D information matrix.
+fn lift_information(information: &Matrix3<f64>) -> Matrix6<f64> {
+    const AXES: [usize; 3] = [0, 1, 5];
+    Matrix6::from_fn(|row, col| {
+        match (
+            AXES.iter().position(|&axis| axis == row),
+            AXES.iter().position(|&axis| axis == col),
+        ) {
+            (Some(row), Some(col)) => information[(row, col)],
+            _ => 0.0,
+        }
+    })
+}
+
+fn parse_pose2(tokens: &mut Tokens<'_>) -> Result<Isometry2<f64>> {
+    let [x, y, theta] = tokens.values()?;
+    Ok(Isometry2::new([x, y].into(), theta))
+}
+
+fn write_pose2(text: &mut String, pose: &Isometry2<f64>) {
+    let t = &pose.translation.vector;
+    write_values(text, [t.x, t.y, pose.rotation.angle()]);
+}
+
+fn parse_pose3(tokens: &mut Tokens<'_>) -> Result<Isometry3<f64>> {
+    let [x, y, z, roll, pitch, yaw] = tokens.values()?;
+    Ok(Isometry3::from_parts(
+        Translation3::new(x, y, z),
+        UnitQuaternion::from_euler_angles(roll, pitch, yaw),
+    ))
+}
+
+fn write_pose3(text: &mut String, pose: &Isometry3<f64>) {
+    let t = &pose.translation.vector;
+    let (roll, pitch, yaw) = pose.rotation.euler_angles();
+    write_values(text, [t.x, t.y, t.z, roll, pitch, yaw]);
+}
@@ -0,0 +1,436 @@
+use crate::Fraction;
+use anyhow::{anyhow, bail, ensure};
+use serde::{
+    de::{Error as _, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    num::NonZeroU64,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// An exact decimal number stored as a scaled integer
+/// `mantissa * 10^-scale`.
+///
+/// Decimals are written as exact strings like `"0.010"`, so that the
+/// trailing precision is preserved. Decimals having the same value
+/// compare equal regardless of the scale.
+///
+/// ```rust
+/// # use newslab_serde_num::Decimal;
+/// let current: Decimal = serde_json::from_str(r#" "0.010" "#).unwrap();
+/// assert_eq!((current.mantissa(), current.scale()), (10, 3));
+/// assert_eq!(serde_json::to_string(&current).unwrap(), r#""0.010""#);
+/// assert_eq!(current, "0.01".parse().unwrap());
+///
+/// let total = current + "1.5".parse().unwrap();
+/// assert_eq!(total.to_string(), "1.510");
+/// assert!(total > current);
+///
+/// let prices: Vec<Decimal> = serde_json::from_str(r#"["-12.50", 3, 0.25]"#).unwrap();
+/// assert_eq!(prices[0].to_string(), "-12.50");
+/// assert_eq!(prices[1].to_string(), "3");
+/// assert_eq!(prices[2].to_string(), "0.25");
+///
+/// assert!("1e-3".parse::<Decimal>().is_err());
+/// assert!("1.".parse::<Decimal>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// The maximum number of fractional digits.
+    pub const MAX_SCALE: u32 = 38;
+
+    pub const ZERO: Self = Self {
+        mantissa: 0,
+        scale: 0,
+    };
+
+    pub const ONE: Self = Self {
+        mantissa: 1,
+        scale: 0,
+    };
+
+    /// Creates the decimal `mantissa * 10^-scale`, which fails if the
+    /// scale exceeds [Decimal::MAX_SCALE].
+    pub fn new(mantissa: i128, scale: u32) -> anyhow::Result<Self> {
+        ensure!(
+            scale <= Self::MAX_SCALE,
+            "the scale {scale} must not exceed {}",
+            Self::MAX_SCALE
+        );
+        Ok(Self { mantissa, scale })
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    /// Gets the number of fractional digits.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Removes the trailing zeros in the fractional digits.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Decimal;
+    /// let value: Decimal = "2.500".parse().unwrap();
+    /// assert_eq!(value.normalize().to_string(), "2.5");
+    /// ```
+    pub fn normalize(&self) -> Self {
+        let Self {
+            mut mantissa,
+            mut scale,
+        } = *self;
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        Self { mantissa, scale }
+    }
+
+    /// Increases the number of fractional digits, which returns `None`
+    /// if the scale is smaller than the current one or on overflow.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Decimal;
+    /// let value: Decimal = "2.5".parse().unwrap();
+    /// assert_eq!(value.rescale(3).unwrap().to_string(), "2.500");
+    /// assert!(value.rescale(0).is_none());
+    /// ```
+    pub fn rescale(&self, scale: u32) -> Option<Self> {
+        if scale > Self::MAX_SCALE {
+            return None;
+        }
+        let factor = 10i128.checked_pow(scale.checked_sub(self.scale)?)?;
+        Some(Self {
+            mantissa: self.mantissa.checked_mul(factor)?,
+            scale,
+        })
+    }
+
+    /// Converts to the nearest float.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    /// Adds decimals, which returns `None` on overflow. The scale of
+    /// the result is the larger one of the operands.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let scale = self.scale.max(rhs.scale);
+        let (lhs, rhs) = (self.rescale(scale)?, rhs.rescale(scale)?);
+        Some(Self {
+            mantissa: lhs.mantissa.checked_add(rhs.mantissa)?,
+            scale,
+        })
+    }
+
+    /// Subtracts decimals, which returns `None` on overflow. The scale
+    /// of the result is the larger one of the operands.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(Self {
+            mantissa: rhs.mantissa.checked_neg()?,
+            ..rhs
+        })
+    }
+
+    /// Multiplies decimals, which returns `None` on overflow. The
+    /// scale of the result is the sum of the operands.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Decimal;
+    /// let current: Decimal = "0.150".parse().unwrap();
+    /// let voltage: Decimal = "3.3".parse().unwrap();
+    /// assert_eq!(current.checked_mul(voltage).unwrap().to_string(), "0.4950");
+    /// ```
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let scale = self.scale + rhs.scale;
+        if scale > Self::MAX_SCALE {
+            return None;
+        }
+        Some(Self {
+            mantissa: self.mantissa.checked_mul(rhs.mantissa)?,
+            scale,
+        })
+    }
+
+    /// Splits into the integer part and the non-negative fractional
+    /// digits.
+    fn split(&self) -> (i128, i128) {
+        let factor = 10i128.pow(self.scale);
+        (
+            self.mantissa.div_euclid(factor),
+            self.mantissa.rem_euclid(factor),
+        )
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self { mantissa, scale } = self.normalize();
+        mantissa.hash(state);
+        scale.hash(state);
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The fractional digits are less than 10^38 after alignment,
+        // which always fit in 128 bits.
+        let scale = self.scale.max(other.scale);
+        let (lint, lfrac) = self.split();
+        let (rint, rfrac) = other.split();
+        let lfrac = lfrac * 10i128.pow(scale - self.scale);
+        let rfrac = rfrac * 10i128.pow(scale - other.scale);
+        (lint, lfrac).cmp(&(rint, rfrac))
+    }
+}
+
+impl Add for Decimal {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("overflow when adding decimals")
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting decimals")
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("overflow when multiplying decimals")
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            mantissa: self
+                .mantissa
+                .checked_neg()
+                .expect("overflow when negating a decimal"),
+            ..self
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(from: i64) -> Self {
+        Self {
+            mantissa: from.into(),
+            scale: 0,
+        }
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(from: u64) -> Self {
+        Self {
+            mantissa: from.into(),
+            scale: 0,
+        }
+    }
+}
+
+/// Converts to a fraction reduced to lowest terms, which fails if it
+/// is out of the range of 64 bits.
+///
+/// ```rust
+/// # use newslab_serde_num::{Decimal, Fraction};
+/// let value: Decimal = "-0.250".parse().unwrap();
+/// assert_eq!(Fraction::try_from(value).unwrap(), Fraction::new(-1, 4).unwrap());
+/// ```
+impl TryFrom<Decimal> for Fraction {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Decimal) -> Result<Self, Self::Error> {
+        let Decimal { mantissa, scale } = from.normalize();
+        let err = || anyhow!("the decimal {from} is out of the range of 64 bits");
+        let num = u64::try_from(mantissa.unsigned_abs()).map_err(|_| err())?;
+        let deno = 10u64
+            .checked_pow(scale)
+            .and_then(NonZeroU64::new)
+            .ok_or_else(err)?;
+        Ok(Fraction::new_raw(mantissa < 0, num, deno).reduce())
+    }
+}
+
+/// Parses a decimal like `"-12.50"`. The exponent notation is not
+/// accepted.
+impl FromStr for Decimal {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let err = || anyhow!("Invalid decimal '{}'. It must be like '-12.50'.", text);
+
+        let (is_negative, unsigned) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let (int, frac) = match unsigned.split_once('.') {
+            Some((int, frac)) => (int, frac),
+            None => (unsigned, ""),
+        };
+        let is_digits = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+        if int.is_empty()
+            || !is_digits(int)
+            || !is_digits(frac)
+            || (frac.is_empty() && unsigned.contains('.'))
+        {
+            return Err(err());
+        }
+
+        let Ok(scale) = u32::try_from(frac.len()) else {
+            return Err(err());
+        };
+        if scale > Self::MAX_SCALE {
+            bail!(
+                "Invalid decimal '{}'. It must not have more than {} fractional digits.",
+                text,
+                Self::MAX_SCALE
+            );
+        }
+        let magnitude: i128 = format!("{int}{frac}")
+            .parse()
+            .map_err(|_| anyhow!("The decimal '{}' is out of the range of 128 bits.", text))?;
+        let mantissa = if is_negative { -magnitude } else { magnitude };
+        Ok(Self { mantissa, scale })
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+
+        if scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{int}.{frac}")
+    }
+}
+
+/// Decimals are written as exact strings in human-readable formats,
+/// and as `(mantissa, scale)` tuples otherwise. Numbers are also
+/// accepted in deserialization, but trailing zeros of floats are
+/// lost. Write them as strings to keep the precision.
+///
+/// ```rust
+/// # use newslab_serde_num::Decimal;
+/// let value: Decimal = "-0.010".parse().unwrap();
+/// let bytes = bincode::serialize(&value).unwrap();
+/// assert_eq!(bincode::deserialize::<Decimal>(&bytes).unwrap().to_string(), "-0.010");
+///
+/// let bytes = bincode::serialize(&(1i128, 39u32)).unwrap();
+/// assert!(bincode::deserialize::<Decimal>(&bytes).is_err());
+/// ```
+impl Serialize for Decimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return (self.mantissa, self.scale).serialize(serializer);
+        }
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            let (mantissa, scale) = <(i128, u32)>::deserialize(deserializer)?;
+            return Self::new(mantissa, scale).map_err(D::Error::custom);
+        }
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+struct DecimalVisitor;
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal like \"0.010\" or a number")
+    }
+
+    fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        text.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(value.into())
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(value.into())
+    }
+
+    /// Takes the shortest decimal representation of the float.
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if !value.is_finite() {
+            return Err(E::custom(format!("{value} is not a finite decimal")));
+        }
+        self.visit_str(&value.to_string())
+    }
+}
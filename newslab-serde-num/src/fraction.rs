@@ -1,53 +1,97 @@
-use anyhow::anyhow;
-use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use anyhow::{anyhow, ensure};
+use newslab_serde_common::compat::{self, Representation};
+use num::rational::Ratio;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     cmp::Ordering,
     fmt::{self, Display},
+    hash::{Hash, Hasher},
     num::NonZeroU64,
+    ops::{Add, Div, Mul, Neg, Sub},
     str::FromStr,
+    time::Duration,
 };
 
 /// Represent a rational number in the form if fractions `num/deno`.
 ///
+/// The denominator is never zero. Use [Fraction::new] to construct a
+/// fraction, which is reduced to lowest terms.
+///
 /// ```rust
 /// # use newslab_serde_num::Fraction;
 /// let f1: Fraction = serde_json::from_str(r#" "-3/4" "#).unwrap();
 /// let f2: Fraction = serde_json::from_str(r#" "7/2" "#).unwrap();
+/// assert!(f1.is_negative() && !f2.is_negative());
+/// assert_eq!(f1.to_f64(), -0.75);
+///
+/// let f3 = Fraction::new(-6, 8).unwrap();
+/// assert!(f3.is_negative());
+/// assert_eq!((f3.num(), f3.deno().get()), (3, 4));
+///
+/// assert!(Fraction::new(1, 0).is_err());
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy)]
 pub struct Fraction {
-    pub is_negative: bool,
-    pub num: u64,
-    pub deno: NonZeroU64,
+    is_negative: bool,
+    num: u64,
+    deno: NonZeroU64,
 }
 
-impl PartialOrd for Fraction {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let reverse = match (self.is_negative, other.is_negative) {
-            (true, true) => true,
-            (true, false) => return Some(Ordering::Less),
-            (false, true) => return Some(Ordering::Greater),
-            (false, false) => false,
-        };
+/// Fractions are compared by value, so unreduced fractions from
+/// [Fraction::new_raw] equal their reduced forms and hash the same.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// # use std::{collections::HashSet, num::NonZeroU64};
+/// let half = Fraction::new(1, 2).unwrap();
+/// let two_quarters = Fraction::new_raw(false, 2, NonZeroU64::new(4).unwrap());
+/// assert_eq!(two_quarters, half);
+/// assert_eq!(two_quarters.cmp(&half), std::cmp::Ordering::Equal);
+/// assert_eq!(HashSet::from([half, two_quarters]).len(), 1);
+///
+/// let negative_zero = Fraction::new_raw(true, 0, NonZeroU64::MIN);
+/// assert_eq!(negative_zero, Fraction::ZERO);
+/// assert_eq!(negative_zero.cmp(&Fraction::ZERO), std::cmp::Ordering::Equal);
+/// assert!(negative_zero > -Fraction::ONE);
+/// ```
+impl PartialEq for Fraction {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Fraction {}
 
-        let lhs = self.num * other.deno.get();
-        let rhs = other.num * self.deno.get();
-        let ord = lhs.partial_cmp(&rhs)?;
+impl Hash for Fraction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let reduced = self.reduce();
+        reduced.is_negative.hash(state);
+        reduced.num.hash(state);
+        reduced.deno.hash(state);
+    }
+}
 
-        Some(if reverse { ord.reverse() } else { ord })
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Fraction {
     fn cmp(&self, other: &Self) -> Ordering {
-        let reverse = match (self.is_negative, other.is_negative) {
+        // Zero is neither negative nor positive, whatever its sign.
+        let reverse = match (
+            self.is_negative && self.num != 0,
+            other.is_negative && other.num != 0,
+        ) {
             (true, true) => true,
             (true, false) => return Ordering::Less,
             (false, true) => return Ordering::Greater,
             (false, false) => false,
         };
-        let lhs = self.num * other.deno.get();
-        let rhs = other.num * self.deno.get();
+        // The products of 64-bit integers always fit in 128 bits.
+        let lhs = self.num as u128 * other.deno.get() as u128;
+        let rhs = other.num as u128 * self.deno.get() as u128;
         let ord = lhs.cmp(&rhs);
 
         if reverse {
@@ -58,18 +102,104 @@ impl Ord for Fraction {
     }
 }
 
+/// Generates fractions through [Fraction::new], so they are always
+/// reduced and never have a zero denominator.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// # use arbitrary::{Arbitrary, Unstructured};
+/// let mut u = Unstructured::new(&[0xff; 64]);
+/// let fraction = Fraction::arbitrary(&mut u).unwrap();
+/// assert!(fraction.is_reduced());
+/// ```
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Fraction {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let num: i64 = u.arbitrary()?;
+        let deno: NonZeroU64 = u.arbitrary()?;
+        Self::new(num, deno.get()).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <(i64, NonZeroU64)>::size_hint(depth)
+    }
+}
+
 impl Fraction {
+    pub const ZERO: Self = Self {
+        is_negative: false,
+        num: 0,
+        deno: NonZeroU64::MIN,
+    };
+
+    pub const ONE: Self = Self {
+        is_negative: false,
+        num: 1,
+        deno: NonZeroU64::MIN,
+    };
+
+    /// Creates a fraction reduced to lowest terms, which fails if the
+    /// denominator is zero.
+    pub fn new(num: i64, deno: u64) -> anyhow::Result<Self> {
+        let deno =
+            NonZeroU64::new(deno).ok_or_else(|| anyhow!("the denominator must not be zero"))?;
+        let fraction = Self {
+            is_negative: num < 0,
+            num: num.unsigned_abs(),
+            deno,
+        };
+        Ok(fraction.reduce())
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+
+    /// Gets the absolute value of the numerator.
+    pub fn num(&self) -> u64 {
+        self.num
+    }
+
+    pub fn deno(&self) -> NonZeroU64 {
+        self.deno
+    }
+
+    /// Creates a fraction from the parts as they are, without
+    /// reduction.
+    pub const fn new_raw(is_negative: bool, num: u64, deno: NonZeroU64) -> Self {
+        Self {
+            is_negative,
+            num,
+            deno,
+        }
+    }
+
+    /// Reduces the fraction to lowest terms. Zero is always reduced
+    /// to a non-negative `0/1`.
     pub fn reduce(&self) -> Self {
         let gcd = gcd::binary_u64(self.num, self.deno.get());
         Self {
             num: self.num / gcd,
             deno: NonZeroU64::new(self.deno.get() / gcd).unwrap(),
-            is_negative: self.is_negative,
+            is_negative: self.is_negative && self.num != 0,
         }
     }
 
+    /// Checks if the fraction is in lowest terms as [reduce](Self::reduce)
+    /// returns. Fractions from [Fraction::new], parsing and
+    /// deserialization are always reduced.
+    pub fn is_reduced(&self) -> bool {
+        let reduced = self.reduce();
+        (self.is_negative, self.num, self.deno) == (reduced.is_negative, reduced.num, reduced.deno)
+    }
+
     pub fn to_f64(&self) -> f64 {
-        self.num as f64 / self.deno.get() as f64
+        let value = self.num as f64 / self.deno.get() as f64;
+        if self.is_negative {
+            -value
+        } else {
+            value
+        }
     }
 
     pub fn recip(&self) -> Option<Self> {
@@ -79,38 +209,470 @@ impl Fraction {
             is_negative: self.is_negative,
         })
     }
-}
 
-impl FromStr for Fraction {
-    type Err = anyhow::Error;
+    /// Adds fractions, which returns `None` on overflow. The result is
+    /// reduced to lowest terms.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// let frac = |num, deno| Fraction::new(num, deno).unwrap();
+    /// assert_eq!(frac(1, 6).checked_add(frac(1, 3)), Some(frac(1, 2)));
+    /// assert_eq!(frac(1, 6).checked_add(frac(-1, 3)), Some(frac(-1, 6)));
+    /// assert_eq!(frac(1, 3).checked_add(frac(-1, 3)), Some(Fraction::ZERO));
+    /// assert_eq!(frac(1, u64::MAX).checked_add(frac(1, u64::MAX - 1)), None);
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let lhs_deno = self.deno.get() as u128;
+        let rhs_deno = rhs.deno.get() as u128;
+        let lhs_num = self.num as u128 * rhs_deno;
+        let rhs_num = rhs.num as u128 * lhs_deno;
 
-    fn from_str(text: &str) -> Result<Self, Self::Err> {
-        let (is_negative, text) = match text.strip_prefix('-') {
-            Some(suffix) => (false, suffix),
-            None => (true, text),
+        let (is_negative, num) = if self.is_negative == rhs.is_negative {
+            (self.is_negative, lhs_num.checked_add(rhs_num)?)
+        } else if lhs_num >= rhs_num {
+            (self.is_negative, lhs_num - rhs_num)
+        } else {
+            (rhs.is_negative, rhs_num - lhs_num)
         };
-        let mut tokens = text.split('/');
+        Self::from_wide(is_negative, num, lhs_deno * rhs_deno)
+    }
 
-        let err = || {
-            anyhow!(
-                "Invalid fraction string '{}'. It must be in 'num/deno' format.",
-                text
-            )
-        };
+    /// Subtracts fractions, which returns `None` on overflow.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.checked_add(-rhs)
+    }
 
-        let num = tokens.next().ok_or_else(err)?.parse().map_err(|_| err())?;
-        let deno = tokens.next().ok_or_else(err)?.parse().map_err(|_| err())?;
+    /// Multiplies fractions, which returns `None` on overflow.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// // The period of 30000/1001 Hz for 3 frames.
+    /// let period = Fraction::new(30000, 1001).unwrap().recip().unwrap();
+    /// let span = period.checked_mul(Fraction::new(3, 1).unwrap()).unwrap();
+    /// assert_eq!(span, Fraction::new(1001, 10000).unwrap());
+    /// ```
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::from_wide(
+            self.is_negative != rhs.is_negative,
+            self.num as u128 * rhs.num as u128,
+            self.deno.get() as u128 * rhs.deno.get() as u128,
+        )
+    }
 
-        if tokens.next().is_some() {
-            return Err(err());
+    /// Divides fractions, which returns `None` if the divisor is zero
+    /// or on overflow.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.checked_mul(rhs.recip()?)
+    }
+
+    /// Finds the fraction closest to the value with the denominator
+    /// not exceeding `max_deno`, using continued fractions. It is
+    /// useful to recover timebases reported as floats.
+    ///
+    /// It returns `None` if the value is not finite, the magnitude does
+    /// not fit in `u64` or `max_deno` is zero.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// let rate = Fraction::approximate_f64(29.97002997, 1001).unwrap();
+    /// assert_eq!(rate, Fraction::new(30000, 1001).unwrap());
+    ///
+    /// let pi = Fraction::approximate_f64(-std::f64::consts::PI, 1000).unwrap();
+    /// assert_eq!(pi, Fraction::new(-355, 113).unwrap());
+    ///
+    /// assert_eq!(Fraction::approximate_f64(0.25, 1000), Fraction::new(1, 4).ok());
+    /// assert_eq!(Fraction::approximate_f64(f64::NAN, 1000), None);
+    /// ```
+    pub fn approximate_f64(value: f64, max_deno: u64) -> Option<Self> {
+        if !value.is_finite() || max_deno == 0 {
+            return None;
+        }
+        let target = value.abs();
+        let max_deno = max_deno as u128;
+        let max_num = u64::MAX as u128;
+
+        // The last two convergents h0/k0 and h1/k1.
+        let (mut h0, mut h1, mut k0, mut k1): (u128, u128, u128, u128) = (0, 1, 1, 0);
+        let mut rest = target;
+
+        loop {
+            let term = rest.floor();
+            if term >= u64::MAX as f64 && k1 == 0 {
+                return None;
+            }
+            let term = (term as u128).min(max_num);
+            let h2 = term * h1 + h0;
+            let k2 = term * k1 + k0;
+
+            if k2 > max_deno || h2 > max_num {
+                if k1 == 0 {
+                    return None;
+                }
+                // Take the semiconvergent if it is closer.
+                let mut steps = (max_deno - k0) / k1;
+                if let Some(max_steps) = (max_num - h0).checked_div(h1) {
+                    steps = steps.min(max_steps);
+                }
+                let (hs, ks) = (steps * h1 + h0, steps * k1 + k0);
+                let error = |h: u128, k: u128| (h as f64 / k as f64 - target).abs();
+                if ks > 0 && error(hs, ks) < error(h1, k1) {
+                    (h1, k1) = (hs, ks);
+                }
+                break;
+            }
+
+            (h0, h1, k0, k1) = (h1, h2, k1, k2);
+            let frac = rest - rest.floor();
+            if frac == 0.0 || h1 as f64 / k1 as f64 == target {
+                break;
+            }
+            rest = 1.0 / frac;
+        }
+
+        Self::from_wide(value < 0.0, h1, k1)
+    }
+
+    /// Reduces a fraction of 128-bit parts, which returns `None` if the
+    /// reduced parts do not fit in 64 bits.
+    fn from_wide(is_negative: bool, num: u128, deno: u128) -> Option<Self> {
+        let gcd = gcd::binary_u128(num, deno);
+        Some(Self {
+            is_negative: is_negative && num != 0,
+            num: u64::try_from(num / gcd).ok()?,
+            deno: NonZeroU64::new(u64::try_from(deno / gcd).ok()?)?,
+        })
+    }
+
+    /// Computes the period of a rate in hertz, like 30000/1001 for
+    /// NTSC videos. The period is rounded down to nanoseconds.
+    ///
+    /// It returns `None` if the rate is zero or negative.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// # use std::time::Duration;
+    /// let rate = Fraction::new(30000, 1001).unwrap();
+    /// assert_eq!(rate.to_period(), Some(Duration::from_nanos(33_366_666)));
+    /// ```
+    pub fn to_period(&self) -> Option<Duration> {
+        if self.is_negative || self.num == 0 {
+            return None;
+        }
+        let num = self.num as u128;
+        let deno = self.deno.get() as u128;
+        let nanos = deno * 1_000_000_000 / num;
+        let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+        Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+    }
+
+    /// Converts a rate in hertz to a [Frequency](measurements::Frequency).
+    ///
+    /// It returns `None` if the rate is negative. It requires the
+    /// `with-measurements` feature.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// let rate = Fraction::new(25, 2).unwrap();
+    /// assert_eq!(rate.to_frequency().unwrap().as_hertz(), 12.5);
+    /// ```
+    #[cfg(feature = "with-measurements")]
+    pub fn to_frequency(&self) -> Option<measurements::Frequency> {
+        if self.is_negative && self.num != 0 {
+            return None;
         }
+        Some(measurements::Frequency::from_hertz(self.to_f64()))
+    }
+}
+
+/// The operators panic on overflow or division by zero like integers.
+/// Use the checked variants, such as [Fraction::checked_add], to
+/// handle the failure.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// let frac = |num, deno| Fraction::new(num, deno).unwrap();
+/// assert_eq!(frac(1, 2) + frac(1, 3), frac(5, 6));
+/// assert_eq!(frac(1, 2) - frac(5, 6), frac(-1, 3));
+/// assert_eq!(frac(-2, 3) * frac(-3, 4), frac(1, 2));
+/// assert_eq!(frac(1, 2) / frac(-1, 4), frac(-2, 1));
+/// assert_eq!(-frac(1, 2), frac(-1, 2));
+/// assert_eq!(-Fraction::ZERO, Fraction::ZERO);
+/// ```
+impl Add for Fraction {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .expect("overflow when adding fractions")
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting fractions")
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Self;
 
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("overflow when multiplying fractions")
+    }
+}
+
+impl Div for Fraction {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(rhs.num != 0, "attempt to divide a fraction by zero");
+        self.checked_div(rhs)
+            .expect("overflow when dividing fractions")
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            is_negative: !self.is_negative && self.num != 0,
+            ..self
+        }
+    }
+}
+
+/// Converts a ratio with a non-zero denominator. The fraction is
+/// reduced.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// # use num::rational::Ratio;
+/// let fraction = Fraction::try_from(Ratio::new(-3i64, 4)).unwrap();
+/// assert!(fraction.is_negative());
+/// assert_eq!((fraction.num(), fraction.deno().get()), (3, 4));
+/// assert_eq!(Ratio::<i64>::try_from(fraction).unwrap(), Ratio::new(-3, 4));
+///
+/// let fraction = Fraction::try_from(Ratio::new(30000u64, 1001)).unwrap();
+/// assert_eq!(Ratio::<u64>::try_from(fraction).unwrap(), Ratio::new(30000, 1001));
+///
+/// assert!(Fraction::try_from(Ratio::new_raw(1u64, 0)).is_err());
+/// ```
+impl TryFrom<Ratio<u64>> for Fraction {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Ratio<u64>) -> Result<Self, Self::Error> {
+        let deno = NonZeroU64::new(*from.denom())
+            .ok_or_else(|| anyhow!("the denominator must not be zero"))?;
         Ok(Self {
-            num,
+            is_negative: false,
+            num: *from.numer(),
             deno,
+        }
+        .reduce())
+    }
+}
+
+impl TryFrom<Ratio<i64>> for Fraction {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Ratio<i64>) -> Result<Self, Self::Error> {
+        let numer = *from.numer();
+        let denom = *from.denom();
+        let deno = NonZeroU64::new(denom.unsigned_abs())
+            .ok_or_else(|| anyhow!("the denominator must not be zero"))?;
+        Ok(Self {
+            is_negative: (numer < 0) != (denom < 0),
+            num: numer.unsigned_abs(),
+            deno,
+        }
+        .reduce())
+    }
+}
+
+/// Converts a non-negative fraction. The ratio is reduced.
+impl TryFrom<Fraction> for Ratio<u64> {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Fraction) -> Result<Self, Self::Error> {
+        ensure!(
+            !from.is_negative || from.num == 0,
+            "the negative fraction {from} cannot be converted to an unsigned ratio"
+        );
+        Ok(Ratio::new(from.num, from.deno.get()))
+    }
+}
+
+/// Converts a fraction whose numerator and denominator fit in `i64`.
+/// The ratio is reduced.
+impl TryFrom<Fraction> for Ratio<i64> {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Fraction) -> Result<Self, Self::Error> {
+        let err = || anyhow!("the fraction {from} is out of the range of i64");
+        let num = i64::try_from(from.num).map_err(|_| err())?;
+        let deno = i64::try_from(from.deno.get()).map_err(|_| err())?;
+        let num = if from.is_negative { -num } else { num };
+        Ok(Ratio::new(num, deno))
+    }
+}
+
+impl Fraction {
+    /// Parses a fraction as it is written, without reduction. See
+    /// [FromStr](#impl-FromStr-for-Fraction) for the accepted formats.
+    pub fn from_str_raw(text: &str) -> anyhow::Result<Self> {
+        let (is_negative, num, deno) = parse_parts(text)?;
+        let err = || anyhow!("The fraction '{}' is out of the range of 64 bits.", text);
+        let num = u64::try_from(num).map_err(|_| err())?;
+        let deno = u64::try_from(deno).map_err(|_| err())?;
+
+        Ok(Self {
+            num,
+            deno: NonZeroU64::new(deno)
+                .ok_or_else(|| anyhow!("Invalid fraction '{}'. The denominator is zero.", text))?,
             is_negative,
         })
     }
+
+    /// Formats the fraction as a mixed number, like `"1 1/2"`. Proper
+    /// fractions and whole numbers are written as `num/deno`.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// let ratio: Fraction = "-3/2".parse().unwrap();
+    /// assert_eq!(ratio.display_mixed().to_string(), "-1 1/2");
+    /// assert_eq!(Fraction::new(4, 1).unwrap().display_mixed().to_string(), "4/1");
+    /// ```
+    pub fn display_mixed(&self) -> impl Display {
+        Mixed(*self)
+    }
+
+    /// Formats the fraction as a percentage, like `"12.5%"`. Fractions
+    /// that have no exact decimal percentage, such as 1/3, or whose
+    /// percentage has too many digits are written as `num/deno`.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::Fraction;
+    /// let duty: Fraction = "1/8".parse().unwrap();
+    /// assert_eq!(duty.display_percent().to_string(), "12.5%");
+    /// assert_eq!(Fraction::new(1, 3).unwrap().display_percent().to_string(), "1/3");
+    ///
+    /// let tiny = Fraction::new(1, 1 << 62).unwrap();
+    /// assert_eq!(tiny.display_percent().to_string(), "1/4611686018427387904");
+    /// ```
+    pub fn display_percent(&self) -> impl Display {
+        Percent(*self)
+    }
+}
+
+/// Parses a fraction in the formats of [FromStr] into the sign, the
+/// numerator and the denominator, which can be zero.
+pub(crate) fn parse_parts(text: &str) -> anyhow::Result<(bool, u128, u128)> {
+    let err = || {
+        anyhow!(
+            "Invalid fraction string '{}'. It must be in 'num/deno', 'whole num/deno', 'percent%' format or a decimal number.",
+            text
+        )
+    };
+    let (is_negative, body) = match text.strip_prefix('-') {
+        Some(suffix) => (true, suffix),
+        None => (false, text),
+    };
+
+    let (num, deno) = if let Some(percent) = body.strip_suffix('%') {
+        let (num, scale) = parse_decimal(percent).ok_or_else(err)?;
+        (num, scale.checked_mul(100).ok_or_else(err)?)
+    } else if let Some((whole, frac)) = body.split_once(' ') {
+        let whole = parse_digits(whole).ok_or_else(err)?;
+        let (num, deno) = parse_num_deno(frac).ok_or_else(err)?;
+        if num >= deno {
+            return Err(anyhow!(
+                "Invalid mixed fraction '{}'. The fractional part must be less than one.",
+                text
+            ));
+        }
+        let num = whole
+            .checked_mul(deno)
+            .and_then(|num_whole| num_whole.checked_add(num))
+            .ok_or_else(err)?;
+        (num, deno)
+    } else if body.contains('/') {
+        parse_num_deno(body).ok_or_else(err)?
+    } else {
+        parse_decimal(body).ok_or_else(err)?
+    };
+
+    Ok((is_negative, num, deno))
+}
+
+/// Parses ASCII digits without a sign.
+fn parse_digits(text: &str) -> Option<u128> {
+    if text.is_empty() || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+fn parse_num_deno(text: &str) -> Option<(u128, u128)> {
+    let (num, deno) = text.split_once('/')?;
+    Some((parse_digits(num)?, parse_digits(deno)?))
+}
+
+/// Parses a decimal number like `12.5` into the mantissa and the
+/// power of ten dividing it, like `(125, 10)`.
+fn parse_decimal(text: &str) -> Option<(u128, u128)> {
+    let Some((int, frac)) = text.split_once('.') else {
+        return Some((parse_digits(text)?, 1));
+    };
+    let int = if int.is_empty() {
+        0
+    } else {
+        parse_digits(int)?
+    };
+    let frac_value = parse_digits(frac)?;
+    let scale = 10u128.checked_pow(frac.len() as u32)?;
+    let mantissa = int.checked_mul(scale)?.checked_add(frac_value)?;
+    Some((mantissa, scale))
+}
+
+/// Parses a fraction, which is reduced to lowest terms. These formats
+/// are accepted.
+///
+/// - `num/deno`, like `"-3/4"`.
+/// - A mixed number `whole num/deno`, like `"1 1/2"`.
+/// - A percentage with an optional decimal part, like `"50%"` or
+///   `"12.5%"`.
+/// - An integer or a decimal number, like `"30"` or `"-0.25"`.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// let frac = |num, deno| Fraction::new(num, deno).unwrap();
+/// assert_eq!("-3/4".parse::<Fraction>().unwrap(), frac(-3, 4));
+/// assert_eq!("1 1/2".parse::<Fraction>().unwrap(), frac(3, 2));
+/// assert_eq!("-2 1/3".parse::<Fraction>().unwrap(), frac(-7, 3));
+/// assert_eq!("50%".parse::<Fraction>().unwrap(), frac(1, 2));
+/// assert_eq!("12.5%".parse::<Fraction>().unwrap(), frac(1, 8));
+/// assert_eq!("30".parse::<Fraction>().unwrap(), frac(30, 1));
+/// assert_eq!("-0.25".parse::<Fraction>().unwrap(), frac(-1, 4));
+/// assert_eq!(".5".parse::<Fraction>().unwrap(), frac(1, 2));
+///
+/// assert!("1 3/2".parse::<Fraction>().is_err());
+/// assert!("1/0".parse::<Fraction>().is_err());
+/// assert!("--1/2".parse::<Fraction>().is_err());
+/// assert!("+1/2".parse::<Fraction>().is_err());
+/// assert!("1.".parse::<Fraction>().is_err());
+/// assert!("".parse::<Fraction>().is_err());
+/// ```
+impl FromStr for Fraction {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::from_str_raw(text).map(|fraction| fraction.reduce())
+    }
 }
 
 impl Display for Fraction {
@@ -125,21 +687,294 @@ impl Display for Fraction {
     }
 }
 
+struct Mixed(Fraction);
+
+impl Display for Mixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Fraction {
+            is_negative,
+            num,
+            deno,
+        } = self.0;
+        let whole = num / deno.get();
+        let rest = num % deno.get();
+        if whole == 0 || rest == 0 {
+            return self.0.fmt(f);
+        }
+        let sign = if is_negative { "-" } else { "" };
+        write!(f, "{sign}{whole} {rest}/{deno}")
+    }
+}
+
+struct Percent(Fraction);
+
+impl Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Fraction {
+            is_negative,
+            num,
+            deno,
+        } = self.0;
+        let num = num as u128 * 100;
+        let deno = deno.get() as u128;
+        let gcd = gcd::binary_u128(num, deno);
+        let (num, deno) = (num / gcd, deno / gcd);
+
+        // The decimal is exact only if the reduced denominator has no
+        // prime factors other than 2 and 5.
+        let mut rest = deno;
+        let mut twos = 0;
+        let mut fives = 0;
+        while rest % 2 == 0 {
+            rest /= 2;
+            twos += 1;
+        }
+        while rest % 5 == 0 {
+            rest /= 5;
+            fives += 1;
+        }
+        if rest != 1 {
+            return self.0.fmt(f);
+        }
+
+        // Very small fractions need more digits than 128 bits hold.
+        let digits = twos.max(fives);
+        let Some((scale, scaled)) = 10u128
+            .checked_pow(digits)
+            .and_then(|scale| Some((scale, num.checked_mul(scale / deno)?)))
+        else {
+            return self.0.fmt(f);
+        };
+        let sign = if is_negative && num != 0 { "-" } else { "" };
+        let int = scaled / scale;
+        if digits == 0 {
+            write!(f, "{sign}{int}%")
+        } else {
+            let frac = scaled % scale;
+            let width = digits as usize;
+            write!(f, "{sign}{int}.{frac:0width$}%")
+        }
+    }
+}
+
+/// Fractions are written as `"num/deno"` strings in human-readable
+/// formats, and as `(is_negative, num, deno)` tuples otherwise. They
+/// are reduced to lowest terms on deserialization, while
+/// [raw_fraction] keeps them as they are written.
+///
+/// The tuples are introduced by [Representation::V2]. Older
+/// representations selected by [compat::with_representation] store
+/// the strings in all formats.
+///
+/// Human-readable formats also accept strings in the formats of
+/// [FromStr](#impl-FromStr-for-Fraction) and plain numbers, like `30`
+/// or `0.25`.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// let fraction: Fraction = serde_json::from_str(r#" "30000/1001" "#).unwrap();
+/// let bytes = bincode::serialize(&fraction).unwrap();
+/// assert_eq!(bytes.len(), 17);
+/// assert_eq!(bincode::deserialize::<Fraction>(&bytes).unwrap(), fraction);
+///
+/// let fraction: Fraction = serde_json::from_str(r#" "60000/2002" "#).unwrap();
+/// assert_eq!(fraction.to_string(), "30000/1001");
+/// let fraction: Fraction = serde_json::from_str(r#" "-0/5" "#).unwrap();
+/// assert_eq!(fraction, Fraction::ZERO);
+///
+/// let rates: Vec<Fraction> = serde_json::from_str(r#"[30, -2, 0.25, "0.25"]"#).unwrap();
+/// assert_eq!(rates, ["30/1", "-2/1", "1/4", "1/4"].map(|text| text.parse().unwrap()));
+///
+/// // The denominator is zero.
+/// let bytes = bincode::serialize(&(false, 1u64, 0u64)).unwrap();
+/// assert!(bincode::deserialize::<Fraction>(&bytes).is_err());
+///
+/// // The 1.0 representation writes strings.
+/// # use newslab_serde_common::compat::{self, Representation};
+/// let fraction: Fraction = "-3/4".parse().unwrap();
+/// let bytes = compat::with_representation(Representation::V1, || bincode::serialize(&fraction))
+///     .unwrap();
+/// assert_eq!(&bytes[8..], b"-3/4");
+/// let other = compat::with_representation(Representation::V1, || {
+///     bincode::deserialize::<Fraction>(&bytes)
+/// })
+/// .unwrap();
+/// assert_eq!(other, fraction);
+/// ```
 impl Serialize for Fraction {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
+        if stores_tuple(serializer.is_human_readable()) {
+            return (self.is_negative, self.num, self.deno.get()).serialize(serializer);
+        }
         format!("{}", self).serialize(serializer)
     }
 }
 
+/// Checks if fractions are stored as tuples, given whether the format
+/// is human-readable.
+fn stores_tuple(is_human_readable: bool) -> bool {
+    !is_human_readable && compat::representation() >= Representation::V2
+}
+
 impl<'de> Deserialize<'de> for Fraction {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let text = String::deserialize(deserializer)?;
-        text.parse().map_err(D::Error::custom)
+        raw_fraction::deserialize(deserializer).map(|fraction| fraction.reduce())
+    }
+}
+
+/// Accepts numbers and the string formats of [FromStr]. The
+/// denominator must not be zero, but the pattern does not check that
+/// the fractional part of a mixed number is less than one.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction;
+/// # use schemars::schema_for;
+/// let schema = serde_json::to_value(schema_for!(Fraction)).unwrap();
+/// assert_eq!(schema["anyOf"][0]["type"], "number");
+/// assert_eq!(
+///     schema["anyOf"][1]["pattern"],
+///     r"^-?((\d+ )?\d+/\d*[1-9]\d*|(\d+|\d*\.\d+)%?)$"
+/// );
+/// ```
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Fraction {
+    fn schema_name() -> String {
+        "Fraction".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        use schemars::schema::{
+            InstanceType, Metadata, SchemaObject, StringValidation, SubschemaValidation,
+        };
+
+        let number = SchemaObject {
+            instance_type: Some(InstanceType::Number.into()),
+            ..Default::default()
+        };
+        let text = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(r"^-?((\d+ )?\d+/\d*[1-9]\d*|(\d+|\d*\.\d+)%?)$".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "A fraction like \"3/4\", \"1 1/2\", \"12.5%\" or a number.".to_string(),
+                ),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![number.into(), text.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Serialize or deserialize a [Fraction] as it is written, without
+/// reduction.
+///
+/// ```rust
+/// # use newslab_serde_num::{raw_fraction, Fraction};
+/// # use serde::{Serialize, Deserialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct MyTimebase {
+///     #[serde(with = "raw_fraction")]
+///     rate: Fraction,
+/// }
+///
+/// let json = r#"{ "rate": "60000/2002" }"#;
+/// let timebase: MyTimebase = serde_json::from_str(json).unwrap();
+/// assert!(!timebase.rate.is_reduced());
+/// assert_eq!(serde_json::to_string(&timebase).unwrap(), r#"{"rate":"60000/2002"}"#);
+/// ```
+pub mod raw_fraction {
+    use super::Fraction;
+    use serde::{
+        de::{Error as _, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{fmt, num::NonZeroU64};
+
+    pub fn serialize<S>(fraction: &Fraction, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        fraction.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Fraction, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if super::stores_tuple(deserializer.is_human_readable()) {
+            let (is_negative, num, deno) = <(bool, u64, u64)>::deserialize(deserializer)?;
+            let deno = NonZeroU64::new(deno)
+                .ok_or_else(|| D::Error::custom("the denominator must not be zero"))?;
+            return Ok(Fraction::new_raw(is_negative, num, deno));
+        }
+        if !deserializer.is_human_readable() {
+            let text = String::deserialize(deserializer)?;
+            return Fraction::from_str_raw(&text).map_err(D::Error::custom);
+        }
+        deserializer.deserialize_any(FractionVisitor)
+    }
+
+    struct FractionVisitor;
+
+    impl<'de> Visitor<'de> for FractionVisitor {
+        type Value = Fraction;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a fraction like \"3/4\" or a number")
+        }
+
+        fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Fraction::from_str_raw(text).map_err(E::custom)
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Fraction::new_raw(false, value, NonZeroU64::MIN))
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Fraction::new_raw(
+                value < 0,
+                value.unsigned_abs(),
+                NonZeroU64::MIN,
+            ))
+        }
+
+        /// Takes the shortest decimal representation of the float,
+        /// which is the number as it is written in most formats.
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            if !value.is_finite() {
+                return Err(E::custom(format!("{value} is not a finite fraction")));
+            }
+            self.visit_str(&value.to_string())
+        }
     }
 }
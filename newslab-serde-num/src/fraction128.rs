@@ -0,0 +1,365 @@
+use crate::{fraction::parse_parts, Fraction};
+use anyhow::anyhow;
+use serde::{
+    de::{Error as _, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    num::NonZeroU128,
+    str::FromStr,
+};
+
+/// A rational number like [Fraction] with 128-bit numerator and
+/// denominator, such as timestamps in rational seconds with
+/// nanosecond precision.
+///
+/// It accepts the same string formats as [Fraction], and fractions
+/// are reduced to lowest terms on parsing and deserialization.
+/// Comparisons never overflow.
+///
+/// ```rust
+/// # use newslab_serde_num::{Fraction, Fraction128};
+/// let t1: Fraction128 = "1700000000123456789/1000000000".parse().unwrap();
+/// let t2: Fraction128 = "1700000000123456790/1000000000".parse().unwrap();
+/// assert!(t1 < t2);
+///
+/// let t3: Fraction128 = serde_json::from_str(r#" "-3400000000246913578/2000000000" "#).unwrap();
+/// assert_eq!(t3, -t1);
+///
+/// let rate = Fraction::new(30000, 1001).unwrap();
+/// assert_eq!(Fraction::try_from(Fraction128::from(rate)).unwrap(), rate);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Fraction128 {
+    is_negative: bool,
+    num: u128,
+    deno: NonZeroU128,
+}
+
+/// Generates fractions through [Fraction128::new], so they are always
+/// reduced and never have a zero denominator.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction128;
+/// # use arbitrary::{Arbitrary, Unstructured};
+/// let mut u = Unstructured::new(&[0xff; 64]);
+/// let fraction = Fraction128::arbitrary(&mut u).unwrap();
+/// assert!(fraction.is_reduced());
+/// ```
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Fraction128 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let num: i128 = u.arbitrary()?;
+        let deno: NonZeroU128 = u.arbitrary()?;
+        Self::new(num, deno.get()).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <(i128, NonZeroU128)>::size_hint(depth)
+    }
+}
+
+impl Fraction128 {
+    pub const ZERO: Self = Self {
+        is_negative: false,
+        num: 0,
+        deno: NonZeroU128::MIN,
+    };
+
+    pub const ONE: Self = Self {
+        is_negative: false,
+        num: 1,
+        deno: NonZeroU128::MIN,
+    };
+
+    /// Creates a fraction reduced to lowest terms, which fails if the
+    /// denominator is zero.
+    pub fn new(num: i128, deno: u128) -> anyhow::Result<Self> {
+        let deno =
+            NonZeroU128::new(deno).ok_or_else(|| anyhow!("the denominator must not be zero"))?;
+        Ok(Self::new_raw(num < 0, num.unsigned_abs(), deno).reduce())
+    }
+
+    /// Creates a fraction from the parts as they are, without
+    /// reduction.
+    pub const fn new_raw(is_negative: bool, num: u128, deno: NonZeroU128) -> Self {
+        Self {
+            is_negative,
+            num,
+            deno,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+
+    /// Gets the absolute value of the numerator.
+    pub fn num(&self) -> u128 {
+        self.num
+    }
+
+    pub fn deno(&self) -> NonZeroU128 {
+        self.deno
+    }
+
+    /// Reduces the fraction to lowest terms. Zero is always reduced
+    /// to a non-negative `0/1`.
+    pub fn reduce(&self) -> Self {
+        let gcd = gcd::binary_u128(self.num, self.deno.get());
+        Self {
+            num: self.num / gcd,
+            deno: NonZeroU128::new(self.deno.get() / gcd).unwrap(),
+            is_negative: self.is_negative && self.num != 0,
+        }
+    }
+
+    pub fn is_reduced(&self) -> bool {
+        let reduced = self.reduce();
+        (self.is_negative, self.num, self.deno) == (reduced.is_negative, reduced.num, reduced.deno)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let value = self.num as f64 / self.deno.get() as f64;
+        if self.is_negative {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+impl std::ops::Neg for Fraction128 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            is_negative: !self.is_negative && self.num != 0,
+            ..self
+        }
+    }
+}
+
+/// Multiplies 128-bit integers into the high and low halves of the
+/// 256-bit product.
+fn widening_mul(lhs: u128, rhs: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (l1, l0) = (lhs >> 64, lhs & MASK);
+    let (r1, r0) = (rhs >> 64, rhs & MASK);
+
+    let p00 = l0 * r0;
+    let p01 = l0 * r1;
+    let p10 = l1 * r0;
+    let p11 = l1 * r1;
+
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let low = (p00 & MASK) | (mid << 64);
+    let high = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (high, low)
+}
+
+/// Fractions are compared by value, so unreduced fractions from
+/// [Fraction128::new_raw] equal their reduced forms and hash the same.
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction128;
+/// # use std::{collections::HashSet, num::NonZeroU128};
+/// let half = Fraction128::new(1, 2).unwrap();
+/// let two_quarters = Fraction128::new_raw(false, 2, NonZeroU128::new(4).unwrap());
+/// assert_eq!(two_quarters, half);
+/// assert_eq!(two_quarters.cmp(&half), std::cmp::Ordering::Equal);
+/// assert_eq!(HashSet::from([half, two_quarters]).len(), 1);
+///
+/// let negative_zero = Fraction128::new_raw(true, 0, NonZeroU128::MIN);
+/// assert_eq!(negative_zero, Fraction128::ZERO);
+/// assert_eq!(negative_zero.cmp(&Fraction128::ZERO), std::cmp::Ordering::Equal);
+/// assert!(negative_zero > -Fraction128::ONE);
+/// ```
+impl PartialEq for Fraction128 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Fraction128 {}
+
+impl Hash for Fraction128 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let reduced = self.reduce();
+        reduced.is_negative.hash(state);
+        reduced.num.hash(state);
+        reduced.deno.hash(state);
+    }
+}
+
+impl PartialOrd for Fraction128 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction128 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Zero is neither negative nor positive, whatever its sign.
+        let reverse = match (
+            self.is_negative && self.num != 0,
+            other.is_negative && other.num != 0,
+        ) {
+            (true, true) => true,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => false,
+        };
+        let lhs = widening_mul(self.num, other.deno.get());
+        let rhs = widening_mul(other.num, self.deno.get());
+        let ord = lhs.cmp(&rhs);
+
+        if reverse {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+}
+
+impl From<Fraction> for Fraction128 {
+    fn from(from: Fraction) -> Self {
+        Self {
+            is_negative: from.is_negative(),
+            num: from.num() as u128,
+            deno: from.deno().into(),
+        }
+    }
+}
+
+/// Converts a fraction that fits in 64 bits after reduction.
+impl TryFrom<Fraction128> for Fraction {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Fraction128) -> Result<Self, Self::Error> {
+        let reduced = from.reduce();
+        let err = || anyhow!("the fraction {from} is out of the range of 64 bits");
+        let num = u64::try_from(reduced.num).map_err(|_| err())?;
+        let deno = u64::try_from(reduced.deno.get())
+            .ok()
+            .and_then(std::num::NonZeroU64::new)
+            .ok_or_else(err)?;
+        Ok(Fraction::new_raw(reduced.is_negative, num, deno))
+    }
+}
+
+impl FromStr for Fraction128 {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (is_negative, num, deno) = parse_parts(text)?;
+        let deno = NonZeroU128::new(deno)
+            .ok_or_else(|| anyhow!("Invalid fraction '{}'. The denominator is zero.", text))?;
+        Ok(Self::new_raw(is_negative, num, deno).reduce())
+    }
+}
+
+impl Display for Fraction128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            is_negative,
+            num,
+            deno,
+        } = *self;
+        let sign = if is_negative { "-" } else { "" };
+        write!(f, "{sign}{num}/{deno}")
+    }
+}
+
+/// Fractions are written as `"num/deno"` strings in human-readable
+/// formats, and as `(is_negative, num, deno)` tuples otherwise.
+impl Serialize for Fraction128 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return (self.is_negative, self.num, self.deno.get()).serialize(serializer);
+        }
+        serializer.collect_str(self)
+    }
+}
+
+/// Human-readable formats accept numbers besides the string formats
+/// of [FromStr], like [Fraction].
+///
+/// ```rust
+/// # use newslab_serde_num::Fraction128;
+/// let fraction: Fraction128 = serde_json::from_str("30").unwrap();
+/// assert_eq!(fraction, Fraction128::new(30, 1).unwrap());
+///
+/// let fraction: Fraction128 = serde_json::from_str("-2.5").unwrap();
+/// assert_eq!(fraction, Fraction128::new(-5, 2).unwrap());
+///
+/// let fraction: Fraction128 = serde_json::from_str(r#""30000/1001""#).unwrap();
+/// assert_eq!(fraction, Fraction128::new(30000, 1001).unwrap());
+/// ```
+impl<'de> Deserialize<'de> for Fraction128 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            let (is_negative, num, deno) = <(bool, u128, u128)>::deserialize(deserializer)?;
+            let deno = NonZeroU128::new(deno)
+                .ok_or_else(|| D::Error::custom("the denominator must not be zero"))?;
+            return Ok(Self::new_raw(is_negative, num, deno).reduce());
+        }
+        deserializer.deserialize_any(Fraction128Visitor)
+    }
+}
+
+struct Fraction128Visitor;
+
+impl<'de> Visitor<'de> for Fraction128Visitor {
+    type Value = Fraction128;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a fraction like \"3/4\" or a number")
+    }
+
+    fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        text.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Fraction128::new_raw(false, value.into(), NonZeroU128::MIN))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Fraction128::new_raw(
+            value < 0,
+            value.unsigned_abs().into(),
+            NonZeroU128::MIN,
+        ))
+    }
+
+    /// Takes the shortest decimal representation of the float,
+    /// which is the number as it is written in most formats.
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if !value.is_finite() {
+            return Err(E::custom(format!("{value} is not a finite fraction")));
+        }
+        self.visit_str(&value.to_string())
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{raw_fraction, Fraction};
+use anyhow::{anyhow, bail, ensure};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A positive gear ratio in `"driving:driven"` format, like
+/// `"12:40"`.
+///
+/// The tooth counts are kept as they are written, and they are
+/// reduced only to compare and multiply ratios. Any fraction format is
+/// also accepted, and it is written in the `"a:b"` format.
+///
+/// ```rust
+/// # use newslab_serde_num::{Fraction, GearRatio};
+/// let ratio: GearRatio = serde_json::from_str(r#" "12:40" "#).unwrap();
+/// assert_eq!((ratio.driving(), ratio.driven()), (12, 40));
+/// assert_eq!(ratio.to_fraction(), Fraction::new(3, 10).unwrap());
+/// assert_eq!(ratio, GearRatio::new(3, 10).unwrap());
+/// assert_eq!(serde_json::to_string(&ratio).unwrap(), r#""12:40""#);
+///
+/// let bytes = bincode::serialize(&ratio).unwrap();
+/// assert_eq!(bincode::deserialize::<GearRatio>(&bytes).unwrap().to_string(), "12:40");
+///
+/// let ratio: GearRatio = serde_json::from_str(r#" "5/2" "#).unwrap();
+/// assert_eq!(ratio.to_string(), "5:2");
+///
+/// assert!("0:12".parse::<GearRatio>().is_err());
+/// assert!("-12:40".parse::<GearRatio>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GearRatio(Fraction);
+
+impl GearRatio {
+    /// The ratio of a direct drive.
+    pub const ONE: Self = Self(Fraction::ONE);
+
+    /// Creates a gear ratio from the tooth counts of the driving and
+    /// the driven gears.
+    pub fn new(driving: u64, driven: u64) -> anyhow::Result<Self> {
+        ensure!(
+            driving > 0 && driven > 0,
+            "the tooth counts {driving}:{driven} must be positive"
+        );
+        Ok(Self(Fraction::new_raw(
+            false,
+            driving,
+            driven.try_into().unwrap(),
+        )))
+    }
+
+    /// Creates a gear ratio from a positive fraction, whose numerator
+    /// and denominator are taken as the tooth counts.
+    pub fn from_fraction(fraction: Fraction) -> anyhow::Result<Self> {
+        ensure!(
+            !fraction.is_negative() && fraction.num() > 0,
+            "the gear ratio {fraction} must be positive"
+        );
+        Ok(Self(fraction))
+    }
+
+    /// Gets the tooth count of the driving gear.
+    pub fn driving(&self) -> u64 {
+        self.0.num()
+    }
+
+    /// Gets the tooth count of the driven gear.
+    pub fn driven(&self) -> u64 {
+        self.0.deno().get()
+    }
+
+    /// Gets the ratio reduced to lowest terms.
+    pub fn to_fraction(&self) -> Fraction {
+        self.0.reduce()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64()
+    }
+
+    /// Gets the overall ratio of chained stages, which returns `None`
+    /// on overflow. The overall ratio of no stages is [GearRatio::ONE].
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::GearRatio;
+    /// let stages = ["12:40".parse().unwrap(), "15:45".parse().unwrap()];
+    /// let overall = GearRatio::overall(stages).unwrap();
+    /// assert_eq!(overall, GearRatio::new(1, 10).unwrap());
+    /// ```
+    pub fn overall<I>(stages: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = GearRatio>,
+    {
+        stages
+            .into_iter()
+            .try_fold(Fraction::ONE, |product, stage| {
+                product.checked_mul(stage.to_fraction())
+            })
+            .map(Self)
+    }
+}
+
+impl TryFrom<Fraction> for GearRatio {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Fraction) -> Result<Self, Self::Error> {
+        Self::from_fraction(from)
+    }
+}
+
+impl From<GearRatio> for Fraction {
+    fn from(from: GearRatio) -> Self {
+        from.to_fraction()
+    }
+}
+
+impl FromStr for GearRatio {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let Some((driving, driven)) = text.split_once(':') else {
+            let fraction = Fraction::from_str_raw(text)?;
+            return Self::from_fraction(fraction);
+        };
+        let parse = |count: &str| {
+            count.trim().parse::<u64>().map_err(|_| {
+                anyhow!(
+                    "Invalid gear ratio '{}'. The tooth counts must be integers.",
+                    text
+                )
+            })
+        };
+        let (driving, driven) = (parse(driving)?, parse(driven)?);
+        if driving == 0 || driven == 0 {
+            bail!(
+                "Invalid gear ratio '{}'. The tooth counts must be positive.",
+                text
+            );
+        }
+        Self::new(driving, driven)
+    }
+}
+
+impl Display for GearRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.0.num(), self.0.deno())
+    }
+}
+
+/// Gear ratios are written as `"a:b"` strings in human-readable
+/// formats, and as unreduced fractions otherwise.
+impl Serialize for GearRatio {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return raw_fraction::serialize(&self.0, serializer);
+        }
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GearRatio {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            let fraction = raw_fraction::deserialize(deserializer)?;
+            return Self::from_fraction(fraction).map_err(D::Error::custom);
+        }
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(D::Error::custom)
+    }
+}
+
+/// A chain of gear stages from the input to the output, which has at
+/// least one stage.
+///
+/// ```rust
+/// # use newslab_serde_num::{GearRatio, Transmission};
+/// let trans: Transmission = serde_json::from_str(r#"["12:40", "15:45"]"#).unwrap();
+/// assert_eq!(trans.stages().len(), 2);
+/// assert_eq!(trans.overall().unwrap().to_string(), "1:10");
+///
+/// assert!(serde_json::from_str::<Transmission>("[]").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<GearRatio>", into = "Vec<GearRatio>")]
+pub struct Transmission(Vec<GearRatio>);
+
+impl Transmission {
+    /// Creates a transmission, which fails if there are no stages.
+    pub fn new(stages: Vec<GearRatio>) -> anyhow::Result<Self> {
+        ensure!(
+            !stages.is_empty(),
+            "the transmission must have at least one stage"
+        );
+        Ok(Self(stages))
+    }
+
+    pub fn stages(&self) -> &[GearRatio] {
+        &self.0
+    }
+
+    pub fn into_stages(self) -> Vec<GearRatio> {
+        self.0
+    }
+
+    /// Gets the overall ratio of all stages, which returns `None` on
+    /// overflow.
+    pub fn overall(&self) -> Option<GearRatio> {
+        GearRatio::overall(self.0.iter().copied())
+    }
+}
+
+impl TryFrom<Vec<GearRatio>> for Transmission {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Vec<GearRatio>) -> Result<Self, Self::Error> {
+        Self::new(from)
+    }
+}
+
+impl From<Transmission> for Vec<GearRatio> {
+    fn from(from: Transmission) -> Self {
+        from.0
+    }
+}
@@ -1,4 +1,16 @@
 //! Data ser/deserialization library for numeral types.
 
-pub use fraction::Fraction;
+pub use fraction::{raw_fraction, Fraction};
 mod fraction;
+
+pub use fraction128::Fraction128;
+mod fraction128;
+
+pub use gear_ratio::{GearRatio, Transmission};
+mod gear_ratio;
+
+pub use decimal::Decimal;
+mod decimal;
+
+pub use weighted::{Weighted, WeightedChoices};
+mod weighted;
@@ -0,0 +1,157 @@
+use anyhow::ensure;
+use serde::{Deserialize, Serialize, Serializer};
+
+/// A value with a non-negative weight in [WeightedChoices].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Weighted<T> {
+    pub value: T,
+    pub weight: f64,
+}
+
+/// A list of values with non-negative weights, such as a mix of
+/// behavior policies. The total weight is positive, so that there is
+/// at least one choice of non-zero weight.
+///
+/// ```rust
+/// # use newslab_serde_num::WeightedChoices;
+/// let json = r#"[
+///     { "value": "cruise", "weight": 3 },
+///     { "value": "overtake", "weight": 1 },
+///     { "value": "yield", "weight": 0 }
+/// ]"#;
+/// let mut mix: WeightedChoices<String> = serde_json::from_str(json).unwrap();
+/// assert_eq!(mix.total_weight(), 4.0);
+///
+/// mix.normalize();
+/// assert_eq!(mix.choices()[0].weight, 0.75);
+///
+/// assert!(serde_json::from_str::<WeightedChoices<String>>("[]").is_err());
+/// assert!(serde_json::from_str::<WeightedChoices<String>>(
+///     r#"[{ "value": "cruise", "weight": 0 }]"#
+/// )
+/// .is_err());
+/// assert!(serde_json::from_str::<WeightedChoices<String>>(
+///     r#"[{ "value": "cruise", "weight": -1 }, { "value": "yield", "weight": 2 }]"#
+/// )
+/// .is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "Vec<Weighted<T>>")]
+pub struct WeightedChoices<T> {
+    choices: Vec<Weighted<T>>,
+    total_weight: f64,
+}
+
+impl<T> WeightedChoices<T> {
+    /// Creates a list of choices, which fails if any weight is
+    /// negative or not finite, or the total weight is not positive.
+    pub fn new(choices: Vec<Weighted<T>>) -> anyhow::Result<Self> {
+        for (index, choice) in choices.iter().enumerate() {
+            let weight = choice.weight;
+            ensure!(
+                weight.is_finite() && weight >= 0.0,
+                "the weight {weight} of choice {index} must be non-negative"
+            );
+        }
+        let total_weight: f64 = choices.iter().map(|choice| choice.weight).sum();
+        ensure!(
+            total_weight.is_finite() && total_weight > 0.0,
+            "the total weight {total_weight} of the choices must be positive"
+        );
+        Ok(Self {
+            choices,
+            total_weight,
+        })
+    }
+
+    pub fn choices(&self) -> &[Weighted<T>] {
+        &self.choices
+    }
+
+    pub fn into_choices(self) -> Vec<Weighted<T>> {
+        self.choices
+    }
+
+    pub fn total_weight(&self) -> f64 {
+        self.total_weight
+    }
+
+    /// Gets the probability of the choice at the index, which
+    /// returns `None` if the index is out of range.
+    pub fn probability(&self, index: usize) -> Option<f64> {
+        let choice = self.choices.get(index)?;
+        Some(choice.weight / self.total_weight)
+    }
+
+    /// Scales the weights so that they sum up to one.
+    pub fn normalize(&mut self) {
+        for choice in &mut self.choices {
+            choice.weight /= self.total_weight;
+        }
+        self.total_weight = 1.0;
+    }
+
+    /// Samples a value by the weights. Choices of zero weight are
+    /// never sampled.
+    ///
+    /// ```rust
+    /// # use newslab_serde_num::WeightedChoices;
+    /// # use rand::{rngs::StdRng, SeedableRng};
+    /// let json = r#"[{ "value": 1, "weight": 0 }, { "value": 2, "weight": 1 }]"#;
+    /// let mix: WeightedChoices<u32> = serde_json::from_str(json).unwrap();
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// assert!((0..100).all(|_| *mix.sample(&mut rng) == 2));
+    /// ```
+    #[cfg(feature = "with-rand")]
+    pub fn sample<R>(&self, rng: &mut R) -> &T
+    where
+        R: rand::Rng + ?Sized,
+    {
+        let target = rng.gen::<f64>() * self.total_weight;
+        let mut sum = 0.0;
+        let mut last = None;
+        for choice in &self.choices {
+            if choice.weight == 0.0 {
+                continue;
+            }
+            sum += choice.weight;
+            if target < sum {
+                return &choice.value;
+            }
+            last = Some(&choice.value);
+        }
+
+        // The sum can fall short of the total weight by a rounding
+        // error.
+        last.unwrap()
+    }
+}
+
+impl<T> TryFrom<Vec<Weighted<T>>> for WeightedChoices<T> {
+    type Error = anyhow::Error;
+
+    fn try_from(from: Vec<Weighted<T>>) -> Result<Self, Self::Error> {
+        Self::new(from)
+    }
+}
+
+impl<T> From<WeightedChoices<T>> for Vec<Weighted<T>> {
+    fn from(from: WeightedChoices<T>) -> Self {
+        from.choices
+    }
+}
+
+/// Choices are written as a list of `{ "value": .., "weight": .. }`
+/// objects.
+impl<T> Serialize for WeightedChoices<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.choices.serialize(serializer)
+    }
+}
@@ -3,6 +3,8 @@
 
 pub use newslab_serde_common as common;
 pub use newslab_serde_cv as cv;
+#[cfg(feature = "with-derive")]
+pub use newslab_serde_derive as derive;
 #[cfg(feature = "with-measurements")]
 pub use newslab_serde_measurements as measurements;
 #[cfg(feature = "with-nalgebra")]
@@ -0,0 +1,367 @@
+//! The compatibility test on the fixture corpus in `tests/corpus`.
+//!
+//! Each directory in the corpus holds the fixtures written in one
+//! representation version. Fixtures of every version must keep
+//! deserializing, and fixtures of the frozen version must serialize
+//! back to the same content. Fixtures are never regenerated. A change
+//! of representation adds a new version directory instead.
+
+use measurements::{
+    Acceleration, Angle, Area, Frequency, Length, Mass, Pressure, Speed, Temperature, Volume,
+};
+use newslab_serde_common::{optional_range, range};
+use newslab_serde_cv::{CameraMatrix, DistortionCoefs};
+use newslab_serde_measurements::{
+    acceleration, angle, area,
+    compat::{self, Representation},
+    duration, frequency, length, mass, pressure, speed, temperature, volume, EulerAngles,
+    RotationOrder, TimeOffset,
+};
+use newslab_serde_num::Fraction;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    ops::Bound,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The quantities written by [Representation::V0].
+#[derive(Serialize, Deserialize)]
+struct QuantitiesV0 {
+    #[serde(with = "angle")]
+    angle: Angle,
+    #[serde(with = "angle::in_radians")]
+    angle_in_radians: Angle,
+    #[serde(with = "length")]
+    length: Length,
+    #[serde(with = "length::vec")]
+    lengths: Vec<Length>,
+    #[serde(with = "duration")]
+    duration: Duration,
+    #[serde(with = "frequency")]
+    frequency: Frequency,
+    #[serde(with = "mass")]
+    mass: Mass,
+    #[serde(with = "pressure")]
+    pressure: Pressure,
+    #[serde(with = "speed")]
+    speed: Speed,
+    #[serde(with = "acceleration")]
+    acceleration: Acceleration,
+    #[serde(with = "temperature")]
+    temperature: Temperature,
+    time_offset: TimeOffset,
+}
+
+/// The quantities written by [Representation::V1], which adds areas
+/// and volumes.
+#[derive(Serialize, Deserialize)]
+struct Quantities {
+    #[serde(with = "angle")]
+    angle: Angle,
+    #[serde(with = "angle::in_radians")]
+    angle_in_radians: Angle,
+    #[serde(with = "length")]
+    length: Length,
+    #[serde(with = "length::vec")]
+    lengths: Vec<Length>,
+    #[serde(with = "duration")]
+    duration: Duration,
+    #[serde(with = "frequency")]
+    frequency: Frequency,
+    #[serde(with = "mass")]
+    mass: Mass,
+    #[serde(with = "pressure")]
+    pressure: Pressure,
+    #[serde(with = "speed")]
+    speed: Speed,
+    #[serde(with = "acceleration")]
+    acceleration: Acceleration,
+    #[serde(with = "temperature")]
+    temperature: Temperature,
+    time_offset: TimeOffset,
+    #[serde(with = "area")]
+    area: Area,
+    #[serde(with = "volume")]
+    volume: Volume,
+}
+
+/// The fractions, which are strings in all formats before
+/// [Representation::V2].
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Fractions {
+    rate: Fraction,
+    scale: Fraction,
+}
+
+/// The ranges in the object form of [Representation::V1].
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Ranges {
+    #[serde(with = "range")]
+    range: (Bound<f64>, Bound<f64>),
+    #[serde(with = "optional_range")]
+    optional_range: Option<(Bound<f64>, Bound<f64>)>,
+    #[serde(with = "optional_range")]
+    no_range: Option<(Bound<f64>, Bound<f64>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Camera {
+    camera_matrix: CameraMatrix,
+    distortion_coefs: DistortionCoefs,
+}
+
+/// The values stored in the quantity fixtures of all versions.
+fn quantities() -> Quantities {
+    Quantities {
+        angle: Angle::from_degrees(3.0),
+        angle_in_radians: Angle::from_radians(0.5),
+        length: Length::from_meters(1.5),
+        lengths: vec![
+            Length::from_millimeters(800.0),
+            Length::from_kilometers(2.0),
+        ],
+        duration: Duration::from_millis(250),
+        frequency: Frequency::from_hertz(10.0),
+        mass: Mass::from_kilograms(2.5),
+        pressure: Pressure::from_kilopascals(101.3),
+        speed: Speed::from_meters_per_second(10.0),
+        acceleration: Acceleration::from_meters_per_second_per_second(9.8),
+        temperature: Temperature::from_celsius(25.0),
+        time_offset: TimeOffset::from_nanos(-1_500_000),
+        area: Area::from_hectares(2.0),
+        volume: Volume::from_liters(0.5),
+    }
+}
+
+/// The values stored in the fraction fixtures of all versions.
+fn fractions() -> Fractions {
+    Fractions {
+        rate: "30000/1001".parse().unwrap(),
+        scale: "-3/4".parse().unwrap(),
+    }
+}
+
+/// The values stored in the range fixtures of all versions.
+fn ranges() -> Ranges {
+    Ranges {
+        range: (Bound::Excluded(-10.0), Bound::Included(5.0)),
+        optional_range: Some((Bound::Included(0.0), Bound::Unbounded)),
+        no_range: None,
+    }
+}
+
+/// The values stored in the Euler angle fixtures of all versions.
+fn euler_angles(order: RotationOrder) -> EulerAngles {
+    EulerAngles {
+        roll: Angle::from_degrees(0.0),
+        pitch: Angle::from_degrees(3.0),
+        yaw: Angle::from_degrees(180.0),
+        order,
+    }
+}
+
+#[test]
+fn corpus_v0() {
+    let expect = serde_json::to_value(quantities()).unwrap();
+    let expect_euler = serde_json::to_value(euler_angles(RotationOrder::ZyxIntrinsic)).unwrap();
+
+    for path in fixtures("v0") {
+        let bytes = read(&path);
+
+        match file_name(&path) {
+            "quantities.json" => {
+                let value: QuantitiesV0 = from_json(&path, &bytes);
+                assert_same_fields(&path, &serde_json::to_value(value).unwrap(), &expect);
+            }
+            "quantities.bincode" => {
+                let value: QuantitiesV0 = compat::with_representation(Representation::V0, || {
+                    bincode::deserialize(&bytes)
+                })
+                .unwrap_or_else(|err| panic!("unable to deserialize {}: {err}", path.display()));
+                assert_same_fields(&path, &serde_json::to_value(&value).unwrap(), &expect);
+
+                // The policy still writes the V0 representation.
+                let output =
+                    compat::with_representation(Representation::V0, || bincode::serialize(&value))
+                        .unwrap();
+                assert!(output == bytes, "{} is not written back", path.display());
+            }
+            "euler_angles.json" => {
+                let value: EulerAngles = from_json(&path, &bytes);
+                assert_eq!(serde_json::to_value(value).unwrap(), expect_euler);
+            }
+            "camera.json" => {
+                let value: Camera = from_json(&path, &bytes);
+                assert_json_written_back(&path, &bytes, &value);
+            }
+            _ => panic!("{} is not checked by the test", path.display()),
+        }
+    }
+}
+
+#[test]
+fn corpus_v1() {
+    for path in fixtures("v1") {
+        let bytes = read(&path);
+
+        match file_name(&path) {
+            "quantities.json" => {
+                let value: Quantities = from_json(&path, &bytes);
+                assert_json_written_back(&path, &bytes, &value);
+                assert_json_written_back(&path, &bytes, &quantities());
+            }
+            "quantities.bincode" => {
+                let value: Quantities = compat::with_representation(Representation::V1, || {
+                    bincode::deserialize(&bytes)
+                })
+                .unwrap_or_else(|err| panic!("unable to deserialize {}: {err}", path.display()));
+                let write = |value: &Quantities| {
+                    compat::with_representation(Representation::V1, || bincode::serialize(value))
+                        .unwrap()
+                };
+
+                // The policy still writes the V1 representation.
+                assert!(write(&value) == bytes);
+                assert!(
+                    write(&quantities()) == bytes,
+                    "{} is not written back",
+                    path.display()
+                );
+            }
+            "fractions.json" => {
+                let value: Fractions = from_json(&path, &bytes);
+                assert_eq!(value, fractions());
+                assert_json_written_back(&path, &bytes, &value);
+            }
+            "ranges.json" => {
+                let value: Ranges = from_json(&path, &bytes);
+                assert_eq!(value, ranges());
+                assert_json_written_back(&path, &bytes, &value);
+            }
+            "ranges.bincode" => {
+                let value: Ranges = bincode::deserialize(&bytes).unwrap_or_else(|err| {
+                    panic!("unable to deserialize {}: {err}", path.display())
+                });
+                assert_eq!(value, ranges());
+                assert!(
+                    bincode::serialize(&value).unwrap() == bytes,
+                    "{} is not written back",
+                    path.display()
+                );
+            }
+            "fractions.bincode" => {
+                let value: Fractions = compat::with_representation(Representation::V1, || {
+                    bincode::deserialize(&bytes)
+                })
+                .unwrap_or_else(|err| panic!("unable to deserialize {}: {err}", path.display()));
+                assert_eq!(value, fractions());
+
+                // The policy still writes the V1 representation.
+                let output =
+                    compat::with_representation(Representation::V1, || bincode::serialize(&value))
+                        .unwrap();
+                assert!(output == bytes, "{} is not written back", path.display());
+            }
+            "euler_angles.json" => {
+                let value: EulerAngles = from_json(&path, &bytes);
+                assert_json_written_back(&path, &bytes, &value);
+                assert_json_written_back(&path, &bytes, &euler_angles(RotationOrder::XyzExtrinsic));
+            }
+            _ => panic!("{} is not checked by the test", path.display()),
+        }
+    }
+}
+
+#[test]
+fn corpus_v2() {
+    for path in fixtures("v2") {
+        let bytes = read(&path);
+
+        match file_name(&path) {
+            "quantities.bincode" => {
+                let value: Quantities = bincode::deserialize(&bytes).unwrap_or_else(|err| {
+                    panic!("unable to deserialize {}: {err}", path.display())
+                });
+                assert!(bincode::serialize(&value).unwrap() == bytes);
+                assert!(
+                    bincode::serialize(&quantities()).unwrap() == bytes,
+                    "{} is not written back",
+                    path.display()
+                );
+            }
+            "fractions.bincode" => {
+                let value: Fractions = bincode::deserialize(&bytes).unwrap_or_else(|err| {
+                    panic!("unable to deserialize {}: {err}", path.display())
+                });
+                assert_eq!(value, fractions());
+                assert!(
+                    bincode::serialize(&value).unwrap() == bytes,
+                    "{} is not written back",
+                    path.display()
+                );
+            }
+            _ => panic!("{} is not checked by the test", path.display()),
+        }
+    }
+}
+
+/// Lists the fixtures of the version.
+fn fixtures(version: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("corpus")
+        .join(version);
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .unwrap_or_else(|err| panic!("unable to read {}: {err}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "{} has no fixtures", dir.display());
+    paths
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().unwrap().to_str().unwrap()
+}
+
+fn read(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|err| panic!("unable to read {}: {err}", path.display()))
+}
+
+fn from_json<T>(path: &Path, bytes: &[u8]) -> T
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|err| panic!("unable to deserialize {}: {err}", path.display()))
+}
+
+/// Asserts that the value serializes to the same JSON as the fixture.
+fn assert_json_written_back<T>(path: &Path, bytes: &[u8], value: &T)
+where
+    T: Serialize,
+{
+    let expect: Value = serde_json::from_slice(bytes).unwrap();
+    assert_eq!(
+        serde_json::to_value(value).unwrap(),
+        expect,
+        "{} is not written back",
+        path.display()
+    );
+}
+
+/// Asserts that the fields of the value equal the same fields of the
+/// expected value.
+fn assert_same_fields(path: &Path, value: &Value, expect: &Value) {
+    for (key, value) in value.as_object().unwrap() {
+        assert_eq!(
+            Some(value),
+            expect.get(key),
+            "the field {key} of {} differs",
+            path.display()
+        );
+    }
+}